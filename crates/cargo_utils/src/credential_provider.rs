@@ -0,0 +1,410 @@
+//! Obtain registry tokens from the
+//! [credential providers](https://doc.rust-lang.org/cargo/reference/registry-authentication.html)
+//! configured for cargo.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead as _, BufReader, Write as _},
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::Context as _;
+use secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+
+use crate::registry::{CRATES_IO_REGISTRY, cargo_config_paths};
+
+/// Version of the credential provider protocol.
+/// <https://doc.rust-lang.org/cargo/reference/credential-provider-protocol.html>
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Ask the credential providers configured for `registry` for a token
+/// ([`Option::None`] means crates.io).
+///
+/// Providers are tried in the same order as cargo. `cargo:token` is skipped because
+/// [`crate::registry_token`] already reads the environment and the credentials file.
+/// The providers storing the token in the keychain of the OS aren't supported.
+pub fn registry_token_from_credential_provider(
+    manifest_path: &Path,
+    registry: Option<&str>,
+) -> anyhow::Result<Option<SecretString>> {
+    let mut configs = vec![CredentialConfig::from_env(registry)];
+    for config_path in cargo_config_paths(manifest_path)? {
+        let content =
+            fs_err::read_to_string(&config_path).context("failed to read cargo config file")?;
+        let config = toml::from_str::<CredentialConfig>(&content)
+            .with_context(|| format!("invalid cargo config {}", config_path.display()))?;
+        configs.push(config);
+    }
+    let providers = credential_providers(&configs, registry);
+    if providers.is_empty() {
+        return Ok(None);
+    }
+
+    let index_url = crate::registry_url(manifest_path, registry)?;
+    let registry_name = registry.unwrap_or(CRATES_IO_REGISTRY);
+    for provider in providers {
+        let token = match provider.split_first() {
+            Some((name, args)) if name == "cargo:token-from-stdout" => {
+                token_from_stdout(args, registry_name, index_url.as_str())?
+            }
+            Some((name, _)) if name.starts_with("cargo:") => None,
+            Some((program, args)) => {
+                token_from_plugin(program, args, registry_name, index_url.as_str())?
+            }
+            None => None,
+        };
+        if token.is_some() {
+            return Ok(token);
+        }
+    }
+    Ok(None)
+}
+
+/// Commands of the credential providers of `registry`, from the first to try to the last.
+/// `configs` go from the most to the least specific.
+fn credential_providers(configs: &[CredentialConfig], registry: Option<&str>) -> Vec<Vec<String>> {
+    let aliases: HashMap<&str, &ProviderValue> = configs
+        .iter()
+        .rev()
+        .flat_map(|c| &c.credential_alias)
+        .map(|(name, value)| (name.as_str(), value))
+        .collect();
+    let resolve = |provider: Vec<String>| {
+        if let [name] = provider.as_slice()
+            && let Some(alias) = aliases.get(name.as_str())
+        {
+            return alias.to_args();
+        }
+        provider
+    };
+
+    // A provider set for the registry takes precedence over the global ones.
+    let registry_provider = configs.iter().find_map(|c| match registry {
+        Some(r) => c
+            .registries
+            .get(r)
+            .and_then(|r| r.credential_provider.as_ref()),
+        None => c
+            .registry
+            .as_ref()
+            .and_then(|r| r.credential_provider.as_ref()),
+    });
+    if let Some(provider) = registry_provider {
+        return vec![resolve(provider.to_args())];
+    }
+
+    // Cargo merges the global providers of all the config files, from the least to the
+    // most specific, and tries the last one first.
+    configs
+        .iter()
+        .flat_map(|c| {
+            c.registry
+                .iter()
+                .flat_map(|r| r.global_credential_providers.iter().rev())
+        })
+        .map(|provider| resolve(ProviderValue::String(provider.clone()).to_args()))
+        .collect()
+}
+
+/// Run the command and read the token from its standard output, like `cargo:token-from-stdout`.
+fn token_from_stdout(
+    args: &[String],
+    registry_name: &str,
+    index_url: &str,
+) -> anyhow::Result<Option<SecretString>> {
+    let (program, args) = args
+        .split_first()
+        .context("cargo:token-from-stdout requires a command")?;
+    let output = Command::new(program)
+        .args(args)
+        .env("CARGO_REGISTRY_NAME_OPT", registry_name)
+        .env("CARGO_REGISTRY_INDEX_URL", index_url)
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("failed to run credential provider `{program}`"))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "credential provider `{program}` failed with {}",
+        output.status
+    );
+    let token = String::from_utf8(output.stdout)
+        .with_context(|| format!("credential provider `{program}` returned an invalid token"))?;
+    let token = token.trim();
+    Ok((!token.is_empty()).then(|| token.into()))
+}
+
+/// Get the token from a credential provider plugin, using the
+/// [credential provider protocol](https://doc.rust-lang.org/cargo/reference/credential-provider-protocol.html).
+fn token_from_plugin(
+    program: &str,
+    args: &[String],
+    registry_name: &str,
+    index_url: &str,
+) -> anyhow::Result<Option<SecretString>> {
+    let mut child = Command::new(program)
+        .args(args)
+        .arg("--cargo-plugin")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("failed to run credential provider `{program}`"))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("can't write to credential provider")?;
+    let mut stdout = BufReader::new(
+        child
+            .stdout
+            .take()
+            .context("can't read credential provider")?,
+    );
+    let mut read_message = || -> anyhow::Result<String> {
+        let mut line = String::new();
+        stdout.read_line(&mut line)?;
+        Ok(line)
+    };
+
+    let hello: Hello = serde_json::from_str(&read_message()?)
+        .with_context(|| format!("invalid hello message from credential provider `{program}`"))?;
+    anyhow::ensure!(
+        hello.v.contains(&PROTOCOL_VERSION),
+        "credential provider `{program}` doesn't support protocol version {PROTOCOL_VERSION}"
+    );
+    let request = CredentialRequest {
+        v: PROTOCOL_VERSION,
+        registry: RegistryInfo {
+            index_url,
+            name: registry_name,
+        },
+        kind: "get",
+        operation: "read",
+        args,
+    };
+    let mut request = serde_json::to_string(&request)?;
+    request.push('\n');
+    stdin.write_all(request.as_bytes())?;
+    stdin.flush()?;
+
+    let response = read_message();
+    // The provider exits when its standard input is closed.
+    drop(stdin);
+    child.wait()?;
+    let response: CredentialResponse = serde_json::from_str(&response?)
+        .with_context(|| format!("invalid response from credential provider `{program}`"))?;
+    match response {
+        CredentialResponse::Ok(response) => Ok(Some(response.token.into())),
+        CredentialResponse::Err(CredentialError::Other { message, caused_by }) => {
+            let error = std::iter::once(message)
+                .chain(caused_by)
+                .collect::<Vec<_>>();
+            anyhow::bail!(
+                "credential provider `{program}` failed: {}",
+                error.join(": ")
+            )
+        }
+        CredentialResponse::Err(_) => Ok(None),
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct CredentialConfig {
+    #[serde(default)]
+    registry: Option<RegistryCredentialConfig>,
+    #[serde(default)]
+    registries: HashMap<String, RegistryCredentialConfig>,
+    #[serde(default, rename = "credential-alias")]
+    credential_alias: HashMap<String, ProviderValue>,
+}
+
+impl CredentialConfig {
+    /// Providers set with environment variables, which take precedence over the config files.
+    fn from_env(registry: Option<&str>) -> Self {
+        let env_var = |name: String| std::env::var(name).ok();
+        let registry_config = |provider_env_var: String| RegistryCredentialConfig {
+            credential_provider: env_var(provider_env_var).map(ProviderValue::String),
+            ..Default::default()
+        };
+        let mut config = Self::default();
+        match registry {
+            Some(r) => {
+                let name = r.to_uppercase().replace('-', "_");
+                config.registries.insert(
+                    r.to_string(),
+                    registry_config(format!("CARGO_REGISTRIES_{name}_CREDENTIAL_PROVIDER")),
+                );
+            }
+            None => {
+                config.registry = Some(registry_config(
+                    "CARGO_REGISTRY_CREDENTIAL_PROVIDER".to_string(),
+                ));
+            }
+        }
+        if let Some(providers) = env_var("CARGO_REGISTRY_GLOBAL_CREDENTIAL_PROVIDERS".to_string()) {
+            config
+                .registry
+                .get_or_insert_with(Default::default)
+                .global_credential_providers =
+                providers.split_whitespace().map(String::from).collect();
+        }
+        config
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct RegistryCredentialConfig {
+    credential_provider: Option<ProviderValue>,
+    #[serde(default)]
+    global_credential_providers: Vec<String>,
+}
+
+/// A provider command, written as a string or as a list of arguments.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+enum ProviderValue {
+    String(String),
+    List(Vec<String>),
+}
+
+impl ProviderValue {
+    fn to_args(&self) -> Vec<String> {
+        match self {
+            Self::String(s) => s.split_whitespace().map(String::from).collect(),
+            Self::List(args) => args.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Hello {
+    v: Vec<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct CredentialRequest<'a> {
+    v: u32,
+    registry: RegistryInfo<'a>,
+    kind: &'static str,
+    operation: &'static str,
+    args: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct RegistryInfo<'a> {
+    index_url: &'a str,
+    name: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+enum CredentialResponse {
+    Ok(GetResponse),
+    Err(CredentialError),
+}
+
+#[derive(Debug, Deserialize)]
+struct GetResponse {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum CredentialError {
+    Other {
+        message: String,
+        #[serde(default, rename = "caused-by")]
+        caused_by: Vec<String>,
+    },
+    /// The provider doesn't have a token for the registry.
+    #[serde(other)]
+    NotFound,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(content: &str) -> CredentialConfig {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn registry_provider_takes_precedence_over_global_ones() {
+        let configs = [
+            config(
+                r#"
+                [registries.my-registry]
+                credential-provider = ["my-provider", "--vault", "prod"]
+                "#,
+            ),
+            config(
+                r#"
+                [registry]
+                global-credential-providers = ["cargo:token", "other-provider"]
+                "#,
+            ),
+        ];
+        assert_eq!(
+            credential_providers(&configs, Some("my-registry")),
+            vec![vec!["my-provider", "--vault", "prod"]]
+        );
+        assert_eq!(
+            credential_providers(&configs, None),
+            vec![vec!["other-provider"], vec!["cargo:token"]]
+        );
+    }
+
+    #[test]
+    fn global_providers_are_merged_and_aliases_resolved() {
+        let configs = [
+            config(
+                r#"
+                [registry]
+                global-credential-providers = ["vault"]
+                "#,
+            ),
+            config(
+                r#"
+                [registry]
+                global-credential-providers = ["cargo:token"]
+
+                [credential-alias]
+                vault = "vault-provider --path secret/cargo"
+                "#,
+            ),
+        ];
+        assert_eq!(
+            credential_providers(&configs, None),
+            vec![
+                vec!["vault-provider", "--path", "secret/cargo"],
+                vec!["cargo:token"]
+            ]
+        );
+    }
+
+    #[test]
+    fn credential_response_is_parsed() {
+        let ok: CredentialResponse = serde_json::from_str(
+            r#"{"Ok":{"kind":"get","token":"secret","cache":"session","operation_independent":true}}"#,
+        )
+        .unwrap();
+        assert!(matches!(ok, CredentialResponse::Ok(r) if r.token == "secret"));
+        let not_found: CredentialResponse =
+            serde_json::from_str(r#"{"Err":{"kind":"not-found"}}"#).unwrap();
+        assert!(matches!(
+            not_found,
+            CredentialResponse::Err(CredentialError::NotFound)
+        ));
+        let other: CredentialResponse = serde_json::from_str(
+            r#"{"Err":{"kind":"other","message":"vault locked","caused-by":["timeout"]}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            other,
+            CredentialResponse::Err(CredentialError::Other { message, .. }) if message == "vault locked"
+        ));
+    }
+}
@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use url::Url;
 
 const CRATES_IO_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
-const CRATES_IO_REGISTRY: &str = "crates-io";
+pub(crate) const CRATES_IO_REGISTRY: &str = "crates-io";
 
 /// Read index for a specific registry using environment variables.
 /// <https://doc.rust-lang.org/cargo/reference/environment-variables.html>
@@ -78,33 +78,8 @@ pub fn registry_url(manifest_path: &Path, registry: Option<&str>) -> anyhow::Res
             });
     }
 
-    // ref: https://doc.rust-lang.org/cargo/reference/config.html#hierarchical-structure
-    for work_dir in manifest_path
-        .parent()
-        .expect("there must be a parent directory")
-        .ancestors()
-    {
-        let work_cargo_dir = work_dir.join(".cargo");
-        let config_path = work_cargo_dir.join("config");
-        if config_path.is_file() {
-            read_config(&mut registries, config_path)?;
-        } else {
-            let config_path = work_cargo_dir.join("config.toml");
-            if config_path.is_file() {
-                read_config(&mut registries, config_path)?;
-            }
-        }
-    }
-
-    let default_cargo_home = cargo_home()?;
-    let default_config_path = default_cargo_home.join("config");
-    if default_config_path.is_file() {
-        read_config(&mut registries, default_config_path)?;
-    } else {
-        let default_config_path = default_cargo_home.join("config.toml");
-        if default_config_path.is_file() {
-            read_config(&mut registries, default_config_path)?;
-        }
+    for config_path in cargo_config_paths(manifest_path)? {
+        read_config(&mut registries, config_path)?;
     }
 
     // find head of the relevant linked list
@@ -162,6 +137,27 @@ struct Registry {
     index: Option<String>,
 }
 
+/// Cargo config files applying to `manifest_path`, from the most to the least specific.
+/// ref: <https://doc.rust-lang.org/cargo/reference/config.html#hierarchical-structure>
+pub(crate) fn cargo_config_paths(manifest_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let config_path = |cargo_dir: PathBuf| {
+        let config_path = cargo_dir.join("config");
+        if config_path.is_file() {
+            Some(config_path)
+        } else {
+            Some(cargo_dir.join("config.toml")).filter(|p| p.is_file())
+        }
+    };
+    let mut paths: Vec<PathBuf> = manifest_path
+        .parent()
+        .expect("there must be a parent directory")
+        .ancestors()
+        .filter_map(|work_dir| config_path(work_dir.join(".cargo")))
+        .collect();
+    paths.extend(config_path(cargo_home()?));
+    Ok(paths)
+}
+
 pub fn cargo_home() -> anyhow::Result<PathBuf> {
     let default_cargo_home = dirs::home_dir()
         .map(|x| x.join(".cargo"))
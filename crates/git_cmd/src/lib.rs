@@ -4,7 +4,11 @@ mod cmd;
 #[cfg(feature = "test_fixture")]
 pub mod test_fixture;
 
-use std::{collections::HashSet, path::Path, process::Command};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    process::Command,
+};
 
 use anyhow::{Context, anyhow};
 use camino::{Utf8Path, Utf8PathBuf};
@@ -37,6 +41,15 @@ impl Repo {
         })
     }
 
+    /// Use the remote `remote` instead of the upstream of the current branch,
+    /// e.g. `upstream` in a fork.
+    pub fn with_remote(self, remote: impl Into<String>) -> Self {
+        Self {
+            original_remote: remote.into(),
+            ..self
+        }
+    }
+
     pub fn directory(&self) -> &Utf8Path {
         &self.directory
     }
@@ -127,6 +140,50 @@ impl Repo {
         Ok(changed_files)
     }
 
+    /// Get files changed in the given commit.
+    pub fn files_of_commit(&self, commit: &str) -> anyhow::Result<HashSet<Utf8PathBuf>> {
+        let output = self.git(&["show", "--name-only", "--pretty=format:", commit])?;
+        let changed_files = output
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(Utf8PathBuf::from)
+            .collect();
+        Ok(changed_files)
+    }
+
+    /// Get the files changed by each of the given commits, running git once.
+    /// Merge commits list the files changed compared to their first parent.
+    pub fn files_of_commits(
+        &self,
+        commits: &[&str],
+    ) -> anyhow::Result<HashMap<String, HashSet<Utf8PathBuf>>> {
+        if commits.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let mut args = vec![
+            "log",
+            "--no-walk=unsorted",
+            "--name-only",
+            "--first-parent",
+            "-m",
+            // Separate the commits with a NUL character, which can't appear in file names.
+            "--format=%x00%H",
+        ];
+        args.extend(commits);
+        let output = self.git(&args)?;
+        let mut files_of_commits = HashMap::new();
+        for entry in output.split('\0') {
+            let mut lines = entry.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+            let Some(commit) = lines.next() else {
+                continue;
+            };
+            let files = lines.map(Utf8PathBuf::from).collect();
+            files_of_commits.insert(commit.to_string(), files);
+        }
+        Ok(files_of_commits)
+    }
+
     pub fn changes_except_typechanges(&self) -> anyhow::Result<Vec<String>> {
         self.changes(|line| !line.starts_with("T "))
     }
@@ -160,6 +217,65 @@ impl Repo {
         Ok(())
     }
 
+    /// Fetch the tags of the remote.
+    pub fn fetch_tags(&self) -> anyhow::Result<()> {
+        self.git(&["fetch", &self.original_remote, "--tags"])
+            .context("failed to fetch tags")?;
+        Ok(())
+    }
+
+    /// Fetch the tags of the remote matching the glob `patterns`, e.g. `v*`.
+    /// In repositories with many tags, this is faster than [`Repo::fetch_tags`].
+    pub fn fetch_tags_matching(&self, patterns: &[String]) -> anyhow::Result<()> {
+        let refspecs: Vec<String> = patterns
+            .iter()
+            .map(|pattern| format!("refs/tags/{pattern}:refs/tags/{pattern}"))
+            .collect();
+        let mut args = vec!["fetch", "--no-tags", &self.original_remote];
+        args.extend(refspecs.iter().map(String::as_str));
+        self.git(&args).context("failed to fetch tags")?;
+        Ok(())
+    }
+
+    /// Whether the repository is a shallow clone, e.g. created with `git clone --depth 1`.
+    pub fn is_shallow(&self) -> anyhow::Result<bool> {
+        let output = self.git(&["rev-parse", "--is-shallow-repository"])?;
+        Ok(output.trim() == "true")
+    }
+
+    /// Fetch the full history and the tags of a shallow clone.
+    pub fn unshallow(&self) -> anyhow::Result<()> {
+        self.git(&["fetch", "--unshallow", "--tags", &self.original_remote])
+            .context("failed to fetch the full history of the shallow clone")?;
+        Ok(())
+    }
+
+    /// Whether the repository is a partial clone, e.g. created with `git clone --filter=blob:none`.
+    /// Checking out a commit of a partial clone downloads its missing blobs from the remote.
+    pub fn is_partial_clone(&self) -> bool {
+        // Older git versions only set `extensions.partialClone`.
+        let has_promisor_remote = self
+            .git(&["config", "--get-regexp", r"^remote\..*\.promisor$"])
+            .is_ok_and(|output| output.lines().any(|line| line.ends_with(" true")));
+        has_promisor_remote
+            || self
+                .git(&["config", "--get", "extensions.partialClone"])
+                .is_ok()
+    }
+
+    /// Whether only a subset of the files is checked out, with `git sparse-checkout`.
+    pub fn is_sparse_checkout(&self) -> bool {
+        self.git(&["config", "--get", "core.sparseCheckout"])
+            .is_ok_and(|value| value == "true")
+    }
+
+    /// Whether the repository is a linked worktree, created with `git worktree add`.
+    pub fn is_linked_worktree(&self) -> anyhow::Result<bool> {
+        let git_dir = git_dir(&self.directory)?;
+        let common_dir = git_common_dir(&self.directory)?;
+        Ok(git_dir != common_dir)
+    }
+
     pub fn force_push(&self, obj: &str) -> anyhow::Result<()> {
         // `--force-with-lease` is safer than `--force` because it will not overwrite
         // changes on the remote that you do not have locally.
@@ -352,6 +468,30 @@ impl Repo {
         self.git(&["config", "--get", &param])
     }
 
+    /// Delete a local git tag.
+    pub fn delete_tag(&self, tag: &str) -> anyhow::Result<()> {
+        self.git(&["tag", "--delete", tag])
+            .with_context(|| format!("can't delete tag {tag}"))?;
+        Ok(())
+    }
+
+    pub fn delete_tag_in_remote(&self, tag: &str) -> anyhow::Result<()> {
+        self.push(&format!(":refs/tags/{tag}"))
+            .with_context(|| format!("can't delete tag {tag} in remote"))
+    }
+
+    pub fn remote_tag_exists(&self, tag: &str) -> anyhow::Result<bool> {
+        let output = self
+            .git(&[
+                "ls-remote",
+                "--tags",
+                &self.original_remote,
+                &format!("refs/tags/{tag}"),
+            ])
+            .context("cannot determine if git tag exists in remote")?;
+        Ok(output.lines().count() >= 1)
+    }
+
     pub fn tag_exists(&self, tag: &str) -> anyhow::Result<bool> {
         let output = self
             .git(&["tag", "-l", tag])
@@ -427,6 +567,23 @@ pub fn git_in_dir(dir: &Utf8Path, args: &[&str]) -> anyhow::Result<String> {
 }
 
 /// Get the name of the current branch.
+/// Absolute path of the git directory of the worktree containing `dir`.
+/// For a linked worktree, this is a directory under `.git/worktrees` of the main worktree.
+pub fn git_dir(dir: &Utf8Path) -> anyhow::Result<Utf8PathBuf> {
+    absolute_git_path(dir, "--git-dir")
+}
+
+/// Absolute path of the git directory shared by all the worktrees of the repository
+/// containing `dir`, i.e. the `.git` directory of the main worktree.
+pub fn git_common_dir(dir: &Utf8Path) -> anyhow::Result<Utf8PathBuf> {
+    absolute_git_path(dir, "--git-common-dir")
+}
+
+fn absolute_git_path(dir: &Utf8Path, arg: &str) -> anyhow::Result<Utf8PathBuf> {
+    let path = git_in_dir(dir, &["rev-parse", "--path-format=absolute", arg])?;
+    Ok(Utf8PathBuf::from(path))
+}
+
 fn get_current_branch(directory: impl AsRef<Utf8Path>) -> anyhow::Result<String> {
     git_in_dir(directory.as_ref(), &["rev-parse", "--abbrev-ref", "HEAD"]).map_err(|e| {
         if e.to_string().contains(
@@ -493,6 +650,152 @@ mod tests {
         assert_eq!(repo.current_commit_message().unwrap(), commit_message);
     }
 
+    #[test]
+    fn configured_remote_is_used() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        repo.git(&[
+            "remote",
+            "add",
+            "upstream",
+            "https://github.com/owner/repo.git",
+        ])
+        .unwrap();
+        assert_eq!(repo.original_remote(), "origin");
+        let repo = repo.with_remote("upstream");
+        assert_eq!(
+            repo.original_remote_url().unwrap(),
+            "https://github.com/owner/repo.git"
+        );
+    }
+
+    #[test]
+    fn shallow_clone_is_unshallowed() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        fs_err::write(repository_dir.as_ref().join("file1.txt"), b"Hello, file1!").unwrap();
+        repo.add_all_and_commit("file1").unwrap();
+        assert!(!repo.is_shallow().unwrap());
+
+        let clone_dir = tempdir().unwrap();
+        let clone_dir = Utf8Path::from_path(clone_dir.path()).unwrap();
+        let source_url = format!("file://{}", repo.directory());
+        git_in_dir(clone_dir, &["clone", "--depth", "1", &source_url, "."]).unwrap();
+        let clone = Repo::new(clone_dir).unwrap();
+        assert!(clone.is_shallow().unwrap());
+
+        clone.unshallow().unwrap();
+        assert!(!clone.is_shallow().unwrap());
+    }
+
+    #[test]
+    fn files_of_commit_are_listed() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        let docs = repository_dir.as_ref().join("docs");
+        fs_err::create_dir(&docs).unwrap();
+        fs_err::write(docs.join("guide.md"), b"# Guide").unwrap();
+        repo.add_all_and_commit("docs: add guide").unwrap();
+        let commit = repo.current_commit_hash().unwrap();
+        fs_err::write(repository_dir.as_ref().join("file1.txt"), b"Hello, file1!").unwrap();
+        repo.add_all_and_commit("file1").unwrap();
+
+        let files = repo.files_of_commit(&commit).unwrap();
+        assert_eq!(files, HashSet::from([Utf8PathBuf::from("docs/guide.md")]));
+    }
+
+    #[test]
+    fn files_of_merge_commit_are_listed() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        fs_err::write(repository_dir.as_ref().join("file1.txt"), b"Hello, file1!").unwrap();
+        repo.add_all_and_commit("file1").unwrap();
+        let first = repo.current_commit_hash().unwrap();
+        repo.git(&["checkout", "-b", "feature"]).unwrap();
+        fs_err::write(repository_dir.as_ref().join("file2.txt"), b"Hello, file2!").unwrap();
+        repo.add_all_and_commit("file2").unwrap();
+        repo.git(&["checkout", "-"]).unwrap();
+        repo.git(&["merge", "--no-ff", "-m", "merge feature", "feature"])
+            .unwrap();
+        let merge = repo.current_commit_hash().unwrap();
+
+        let files = repo.files_of_commits(&[&merge, &first]).unwrap();
+        assert_eq!(
+            files,
+            HashMap::from([
+                (merge, HashSet::from([Utf8PathBuf::from("file2.txt")])),
+                (first, HashSet::from([Utf8PathBuf::from("file1.txt")])),
+            ])
+        );
+    }
+
+    #[test]
+    fn only_tags_matching_patterns_are_fetched() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+
+        let clone_dir = tempdir().unwrap();
+        let clone_dir = Utf8Path::from_path(clone_dir.path()).unwrap();
+        let source_url = format!("file://{}", repo.directory());
+        git_in_dir(clone_dir, &["clone", &source_url, "."]).unwrap();
+        let clone = Repo::new(clone_dir).unwrap();
+
+        repo.tag("v1.0.0", "release").unwrap();
+        repo.tag("nightly", "nightly build").unwrap();
+        clone.fetch_tags_matching(&["v*".to_string()]).unwrap();
+        assert_eq!(clone.get_all_tags(), ["v1.0.0"]);
+    }
+
+    #[test]
+    fn partial_clone_is_detected() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        repo.git(&["config", "uploadpack.allowFilter", "true"])
+            .unwrap();
+        assert!(!repo.is_partial_clone());
+        assert!(!repo.is_sparse_checkout());
+
+        let clone_dir = tempdir().unwrap();
+        let clone_dir = Utf8Path::from_path(clone_dir.path()).unwrap();
+        let source_url = format!("file://{}", repo.directory());
+        git_in_dir(
+            clone_dir,
+            &["clone", "--filter=blob:none", "--sparse", &source_url, "."],
+        )
+        .unwrap();
+        let clone = Repo::new(clone_dir).unwrap();
+        assert!(clone.is_partial_clone());
+        assert!(clone.is_sparse_checkout());
+    }
+
+    #[test]
+    fn linked_worktree_is_detected() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        assert!(!repo.is_linked_worktree().unwrap());
+
+        let worktree_parent = tempdir().unwrap();
+        let worktree_dir = Utf8Path::from_path(worktree_parent.path())
+            .unwrap()
+            .join("worktree");
+        repo.git(&["worktree", "add", "-b", "feature", worktree_dir.as_str()])
+            .unwrap();
+        let worktree = Repo::new(&worktree_dir).unwrap();
+        assert!(worktree.is_linked_worktree().unwrap());
+        assert_eq!(worktree.original_branch(), "feature");
+        assert_eq!(
+            git_common_dir(&worktree_dir).unwrap(),
+            git_dir(repo.directory()).unwrap()
+        );
+    }
+
     #[test]
     fn clean_project_is_recognized() {
         test_logs::init();
@@ -553,6 +856,21 @@ D  crates/git_cmd/CHANGELOG.md
         assert!(!repo.tag_exists("v2.0.0").unwrap());
     }
 
+    #[test]
+    fn tag_is_deleted() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        let file1 = repository_dir.as_ref().join("file1.txt");
+        {
+            fs_err::write(file1, b"Hello, file1!").unwrap();
+            repo.add_all_and_commit("file1").unwrap();
+        }
+        repo.tag("v1.0.0", "test").unwrap();
+        repo.delete_tag("v1.0.0").unwrap();
+        assert!(!repo.tag_exists("v1.0.0").unwrap());
+    }
+
     #[test]
     fn tags_are_retrieved() {
         test_logs::init();
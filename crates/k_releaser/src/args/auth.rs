@@ -0,0 +1,55 @@
+use clap::builder::NonEmptyStringValueParser;
+
+use super::GitForgeKind;
+
+#[derive(clap::Parser, Debug)]
+pub struct Auth {
+    #[command(subcommand)]
+    pub subcommand: AuthSubcommand,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum AuthSubcommand {
+    /// Store a token in the system keyring.
+    /// The token is prompted for, or read from stdin when it isn't a terminal,
+    /// e.g. `echo $TOKEN | k-releaser auth login --forge github`.
+    Login(AuthTarget),
+    /// Delete a token from the system keyring.
+    Logout(AuthTarget),
+}
+
+/// Forge or cargo registry the token belongs to.
+#[derive(clap::Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct AuthTarget {
+    /// Git forge where the token is used.
+    #[arg(long, visible_alias = "backend", value_enum)]
+    pub forge: Option<GitForgeKind>,
+
+    /// Cargo registry where the token is used. Use `crates-io` for crates.io.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    pub registry: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    #[test]
+    fn either_forge_or_registry_is_required() {
+        let auth = Auth::try_parse_from(["auth", "login", "--forge", "github"]).unwrap();
+        let AuthSubcommand::Login(target) = auth.subcommand else {
+            panic!("expected the login subcommand");
+        };
+        assert_eq!(target.forge, Some(GitForgeKind::Github));
+        assert!(Auth::try_parse_from(["auth", "logout", "--registry", "crates-io"]).is_ok());
+
+        assert!(Auth::try_parse_from(["auth", "login"]).is_err());
+        assert!(
+            Auth::try_parse_from(["auth", "login", "--forge", "github", "--registry", "my-reg"])
+                .is_err()
+        );
+    }
+}
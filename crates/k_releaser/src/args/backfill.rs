@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use clap::builder::PathBufValueParser;
+use k_releaser_core::BackfillRequest;
+
+use crate::config::Config;
+
+use super::{OutputType, project::ProjectArgs, update::UpdateOptions};
+
+#[derive(clap::Parser, Debug)]
+pub struct Backfill {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Path to the git-cliff configuration file.
+    /// If not provided, `dirs::config_dir()/git-cliff/cliff.toml` is used if present.
+    #[arg(
+        long,
+        env = "GIT_CLIFF_CONFIG",
+        value_name = "PATH",
+        value_parser = PathBufValueParser::new()
+    )]
+    pub changelog_config: Option<PathBuf>,
+    /// Create and push the git tags missing for the past releases.
+    /// The tags are named after the `git_tag_name` or `workspace_tag_name` config.
+    #[arg(long)]
+    pub create_tags: bool,
+    /// Create the forge releases missing for the tags of the past releases,
+    /// with their changelog section as body.
+    #[arg(long)]
+    pub create_releases: bool,
+    /// Print the backfilled changelog without writing it and without creating tags and releases.
+    /// With `--output`, the backfilled releases are printed instead.
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Output format. If specified, prints the backfilled releases.
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputType>,
+}
+
+impl Backfill {
+    pub fn backfill_request(
+        &self,
+        config: &Config,
+        cargo_metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<BackfillRequest> {
+        let options = UpdateOptions {
+            changelog_config: self.changelog_config.clone(),
+            ..Default::default()
+        };
+        let update_request = options.update_request(&self.project, config, cargo_metadata)?;
+        Ok(BackfillRequest::new(update_request)
+            .with_create_tags(self.create_tags)
+            .with_create_releases(self.create_releases)
+            .with_dry_run(self.dry_run))
+    }
+}
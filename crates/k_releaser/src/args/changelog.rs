@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+
+use cargo_metadata::camino::Utf8PathBuf;
+use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
+use k_releaser_core::{CHANGELOG_FILENAME, fs_utils::to_utf8_path};
+
+use super::manifest_command::ManifestCommand;
+
+#[derive(clap::Parser, Debug)]
+pub struct Changelog {
+    #[command(subcommand)]
+    pub subcommand: ChangelogSubcommand,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum ChangelogSubcommand {
+    /// Print the notes of a released version
+    Show(ShowChangelog),
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct ShowChangelog {
+    /// Version to show, e.g. `1.2.3`, or `latest` for the last released version.
+    #[arg(value_parser = NonEmptyStringValueParser::new())]
+    pub version: String,
+
+    /// Path to the Cargo.toml of the project.
+    /// If not provided, k-releaser will use the Cargo.toml of the current directory.
+    #[arg(long, value_parser = PathBufValueParser::new(), alias = "project-manifest")]
+    manifest_path: Option<PathBuf>,
+
+    /// Path to the changelog.
+    /// If not provided, k-releaser will use the CHANGELOG.md next to the Cargo.toml.
+    #[arg(long, value_parser = PathBufValueParser::new())]
+    changelog_path: Option<PathBuf>,
+
+    /// Print the title of the release before the notes.
+    #[arg(long)]
+    pub title: bool,
+}
+
+impl ShowChangelog {
+    pub fn changelog_path(&self) -> anyhow::Result<Utf8PathBuf> {
+        match &self.changelog_path {
+            Some(path) => Ok(to_utf8_path(path)?.to_path_buf()),
+            None => {
+                let manifest_path = self.manifest_path();
+                let manifest_dir = k_releaser_core::manifest_dir(&manifest_path)?;
+                Ok(manifest_dir.join(CHANGELOG_FILENAME))
+            }
+        }
+    }
+}
+
+impl ManifestCommand for ShowChangelog {
+    fn optional_manifest(&self) -> Option<&Path> {
+        self.manifest_path.as_deref()
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
+use k_releaser_core::update_request::UpdateRequest;
+
+use crate::config::Config;
+
+use super::{project::ProjectArgs, update::UpdateOptions};
+
+#[derive(clap::Parser, Debug)]
+pub struct CheckCommit {
+    /// Range of the commits to check, e.g. `origin/main..HEAD`.
+    #[arg(
+        long,
+        required_unless_present = "message_file",
+        conflicts_with = "message_file",
+        value_parser = NonEmptyStringValueParser::new()
+    )]
+    pub range: Option<String>,
+    /// File containing the commit message to check.
+    /// In a `commit-msg` git hook, pass the file received as first argument.
+    #[arg(long, value_parser = PathBufValueParser::new())]
+    pub message_file: Option<PathBuf>,
+    /// Path to the git-cliff configuration file, used to read the custom commit types.
+    /// If not provided, `dirs::config_dir()/git-cliff/cliff.toml` is used if present.
+    #[arg(
+        long,
+        env = "GIT_CLIFF_CONFIG",
+        value_name = "PATH",
+        value_parser = PathBufValueParser::new()
+    )]
+    pub changelog_config: Option<PathBuf>,
+    #[command(flatten)]
+    pub project: ProjectArgs,
+}
+
+impl CheckCommit {
+    /// Update request of the project, whose changelog config lists the custom commit types.
+    pub fn update_request(
+        &self,
+        config: &Config,
+        cargo_metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<UpdateRequest> {
+        let options = UpdateOptions {
+            changelog_config: self.changelog_config.clone(),
+            ..Default::default()
+        };
+        options.update_request(&self.project, config, cargo_metadata)
+    }
+}
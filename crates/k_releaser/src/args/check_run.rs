@@ -0,0 +1,17 @@
+use clap::Args;
+
+/// A clap [`Args`] struct to report the release plan as a check of the current commit.
+#[derive(Debug, Default, Args)]
+pub struct CheckRunArgs {
+    /// Report the release plan, i.e. the next versions, the semver check results and the
+    /// changelog, on the current commit: as a check run on GitHub, as a commit status on
+    /// Gitea and GitLab. Requires the git token.
+    #[arg(long)]
+    check_run: bool,
+}
+
+impl CheckRunArgs {
+    pub fn is_enabled(&self) -> bool {
+        self.check_run
+    }
+}
@@ -0,0 +1,20 @@
+use clap::CommandFactory as _;
+use clap_complete::Shell;
+
+use super::CliArgs;
+
+#[derive(clap::Parser, Debug)]
+pub struct Completions {
+    /// Shell to generate the completions for.
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+impl Completions {
+    /// Print the completion script to stdout.
+    pub fn print(&self) {
+        let mut cmd = CliArgs::command();
+        let bin_name = cmd.get_name().to_string();
+        clap_complete::generate(self.shell, &mut cmd, bin_name, &mut std::io::stdout());
+    }
+}
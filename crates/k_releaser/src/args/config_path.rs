@@ -10,14 +10,21 @@ use tracing::info;
 
 use crate::config::Config;
 
-/// A clap [`Args`] struct that specifies the path to the Cargo.toml file containing k-releaser config.
+/// Standalone config files, looked up next to the Cargo.toml in order of precedence.
+/// `release-plz.toml` is read to ease the migration from release-plz.
+const CONFIG_FILES: [&str; 2] = ["k-releaser.toml", "release-plz.toml"];
+
+/// A clap [`Args`] struct that specifies the path to the file containing k-releaser config.
 #[derive(Debug, Default, Args)]
 pub struct ConfigPath {
-    /// Path to the Cargo.toml file containing k-releaser configuration in [package.metadata.k-releaser].
+    /// Path to the k-releaser configuration. It can be a standalone config file,
+    /// like `k-releaser.toml`, or a Cargo.toml file containing the configuration
+    /// in [workspace.metadata.k-releaser] or [package.metadata.k-releaser].
     ///
-    /// If not specified, looks for ./Cargo.toml in the current directory.
+    /// If not specified, the configuration is read from the first file found among
+    /// ./k-releaser.toml, ./release-plz.toml and ./Cargo.toml.
     ///
-    /// If no config is found in Cargo.toml, the default configuration is used.
+    /// If no config is found, the default configuration is used.
     #[arg(long = "config", value_name = "PATH")]
     path: Option<PathBuf>,
 }
@@ -33,6 +40,9 @@ impl ConfigPath {
     /// This is useful when you want to override the path with a value from another source
     /// (like --manifest-path) without modifying the ConfigPath struct.
     pub fn load_from(&self, path: &Path) -> anyhow::Result<Config> {
+        if let Some(config_file) = find_standalone_config(path) {
+            return load_standalone_config(&config_file);
+        }
         match load_config_from_cargo_toml(path) {
             Ok(Some(config)) => Ok(config),
             Ok(None) => {
@@ -46,12 +56,24 @@ impl ConfigPath {
         }
     }
 
-    /// Load the k-releaser configuration from Cargo.toml [package.metadata.k-releaser] section.
+    /// Load the k-releaser configuration.
     ///
-    /// If a path is specified, it will attempt to load the configuration from that Cargo.toml file.
+    /// If a path is specified, it will attempt to load the configuration from that file,
+    /// either a standalone config file or a Cargo.toml file.
     /// If the file does not exist, it will return an error. If no path is specified, it will check
-    /// for ./Cargo.toml in the current directory.
+    /// for ./k-releaser.toml, ./release-plz.toml and ./Cargo.toml in the current directory.
     pub fn load(&self) -> anyhow::Result<Config> {
+        match self.path.as_deref() {
+            Some(path) if is_standalone_config_name(path) => {
+                return load_standalone_config(path);
+            }
+            Some(_) => {}
+            None => {
+                if let Some(config_file) = find_standalone_config(Path::new("Cargo.toml")) {
+                    return load_standalone_config(&config_file);
+                }
+            }
+        }
         let cargo_toml_path = if let Some(path) = self.path.as_deref() {
             path.to_path_buf()
         } else {
@@ -89,8 +111,55 @@ impl ConfigPath {
     }
 }
 
+/// Standalone config file in the directory of the given Cargo.toml, if any.
+fn find_standalone_config(cargo_toml: &Path) -> Option<PathBuf> {
+    let dir = cargo_toml.parent()?;
+    CONFIG_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn is_standalone_config_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| CONFIG_FILES.contains(&name))
+}
+
+/// Load the configuration from a standalone config file, like `k-releaser.toml`.
+/// The file has the same content as the [workspace.metadata.k-releaser] section of Cargo.toml.
+fn load_standalone_config(path: &Path) -> anyhow::Result<Config> {
+    let contents = read_to_string(path)
+        .with_context(|| format!("failed to read config from {}", path.display()))?;
+    let config = toml::from_str(&contents)
+        .with_context(|| format!("invalid k-releaser configuration in {}", path.display()))?;
+    if path
+        .file_name()
+        .is_some_and(|name| name == "release-plz.toml")
+    {
+        info!(
+            "using k-releaser config from {}. Rename it to k-releaser.toml once the migration from release-plz is done",
+            path.display()
+        );
+    } else {
+        info!("using k-releaser config from {}", path.display());
+    }
+    Ok(config)
+}
+
+/// Whether the toml is a cargo manifest rather than a standalone config file.
+/// In a standalone config file, `package` is an array of tables and `workspace`
+/// only contains k-releaser settings.
+fn is_cargo_manifest(toml: &toml::Value) -> bool {
+    toml.get("package").is_some_and(toml::Value::is_table)
+        || toml
+            .get("workspace")
+            .is_some_and(|w| w.get("members").is_some() || w.get("metadata").is_some())
+}
+
 /// Try to load the configuration from Cargo.toml's [package.metadata.k-releaser] or
 /// [workspace.metadata.k-releaser] section.
+/// If the file isn't a cargo manifest, it's read as a standalone config file.
 ///
 /// Returns `Ok(Some(config))` if the metadata is found and valid, `Ok(None)` if no metadata exists,
 /// and an error if the file exists but is invalid.
@@ -124,6 +193,12 @@ fn load_config_from_cargo_toml(path: &Path) -> anyhow::Result<Option<Config>> {
                     path.display()
                 );
                 Ok(Some(config))
+            } else if !is_cargo_manifest(&cargo_toml) {
+                let config = cargo_toml.try_into().with_context(|| {
+                    format!("invalid k-releaser configuration in {}", path.display())
+                })?;
+                info!("using k-releaser config from {}", path.display());
+                Ok(Some(config))
             } else {
                 Ok(None)
             }
@@ -324,4 +399,54 @@ publish_allow_dirty = true
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Config::default());
     }
+
+    #[test]
+    fn standalone_config_takes_precedence_over_cargo_toml() {
+        let temp_dir = tempdir().unwrap();
+        let cargo_toml_path = temp_dir.path().join("Cargo.toml");
+        let cargo_toml = r#"
+[package]
+name = "test"
+version = "0.1.0"
+
+[package.metadata.k-releaser.workspace]
+pr_draft = false
+"#;
+        fs_err::write(&cargo_toml_path, cargo_toml).unwrap();
+        fs_err::write(
+            temp_dir.path().join("release-plz.toml"),
+            "[workspace]\npr_name = \"release-plz\"\n",
+        )
+        .unwrap();
+        fs_err::write(
+            temp_dir.path().join("k-releaser.toml"),
+            "[workspace]\npr_draft = true\n",
+        )
+        .unwrap();
+
+        let config_path = ConfigPath { path: None };
+        let result = config_path.load_from(&cargo_toml_path).unwrap();
+
+        assert!(result.workspace.pr_draft);
+        assert_eq!(result.workspace.pr_name, None);
+    }
+
+    #[test]
+    fn load_config_from_standalone_file_with_custom_name() {
+        let temp_dir = tempdir().unwrap();
+        let config_file = temp_dir.path().join("ci-release.toml");
+        fs_err::write(
+            &config_file,
+            "[workspace]\npr_draft = true\n\n[[package]]\nname = \"test-package\"\npublish_allow_dirty = true\n",
+        )
+        .unwrap();
+
+        let config_path = ConfigPath {
+            path: Some(config_file),
+        };
+        let result = config_path.load().unwrap();
+
+        assert!(result.workspace.pr_draft);
+        assert!(result.packages().contains_key("test-package"));
+    }
 }
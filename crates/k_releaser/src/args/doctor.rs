@@ -0,0 +1,26 @@
+use clap::builder::NonEmptyStringValueParser;
+
+use super::{CRATES_IO, project::ProjectArgs, registry_token, registry_token_env_var};
+
+#[derive(clap::Parser, Debug)]
+pub struct Doctor {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+
+    /// Registry whose token is checked.
+    /// The registry name needs to be present in the Cargo config.
+    /// If unspecified, crates.io is used.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    registry: Option<String>,
+}
+
+impl Doctor {
+    /// Whether the token of the cargo registry is set in the environment
+    /// or stored in the system keyring.
+    pub fn registry_token_available(&self) -> bool {
+        let registry = self.registry.as_deref();
+        let env_var = registry_token_env_var(registry.unwrap_or(CRATES_IO));
+        let env_token = std::env::var(env_var).is_ok_and(|token| !token.is_empty());
+        env_token || matches!(registry_token(None, None, registry), Ok(Some(_)))
+    }
+}
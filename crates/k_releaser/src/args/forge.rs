@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use clap::{
+    Args,
+    builder::{NonEmptyStringValueParser, PathBufValueParser},
+};
+use k_releaser_core::{GitForge, GitLab, Gitea, RepoUrl};
+
+use super::{GitForgeKind, github_app::GitHubAppArgs, read_token_file};
+
+/// A clap [`Args`] struct with the kind of the git forge and the credentials
+/// used to authenticate to it.
+#[derive(Debug, Default, Args)]
+pub struct ForgeArgs {
+    /// Git token used to authenticate to the git forge.
+    /// If not provided, it's read from the `GITHUB_TOKEN` environment variable.
+    /// On GitLab, `GITLAB_TOKEN` and `CI_JOB_TOKEN` are also read.
+    /// On Gitea, `GITEA_TOKEN` is also read.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new(), visible_alias = "github-token")]
+    git_token: Option<String>,
+
+    /// File containing the git token. Use `-` to read it from stdin.
+    #[arg(long, value_parser = PathBufValueParser::new(), conflicts_with = "git_token")]
+    git_token_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    github_app: GitHubAppArgs,
+
+    /// Kind of git forge where your project is hosted.
+    /// If not provided, it's detected from the repository url.
+    #[arg(long = "forge", visible_alias = "backend", value_enum)]
+    kind: Option<GitForgeKind>,
+}
+
+impl ForgeArgs {
+    /// Whether a git token or the credentials of a GitHub App were provided.
+    pub fn has_git_credentials(&self) -> bool {
+        self.git_token.is_some()
+            || self.git_token_file.is_some()
+            || self.github_app.is_set()
+            || GitForgeKind::git_token_available()
+    }
+
+    /// Read the git token file once, so that the forges of all the workspaces of
+    /// `workspace_roots` use its token, even when it's read from stdin.
+    pub fn read_git_token_file(&mut self) -> anyhow::Result<()> {
+        if let Some(path) = self.git_token_file.take() {
+            self.git_token = Some(read_token_file(&path)?);
+        }
+        Ok(())
+    }
+
+    /// Git forge of `repo`, authenticated with the GitHub App or with the git token.
+    /// Returns [`None`] if no credentials were provided.
+    pub fn git_forge(&self, repo: RepoUrl) -> anyhow::Result<Option<GitForge>> {
+        let forge = self.kind.unwrap_or_else(|| GitForgeKind::detect(&repo));
+        if let Some(forge) = self.github_app.git_forge(forge, &repo)? {
+            return Ok(Some(forge));
+        }
+        let token_arg = match &self.git_token_file {
+            Some(path) => Some(read_token_file(path)?),
+            None => self.git_token.clone(),
+        };
+        let Some(git_token) = forge.git_token(token_arg.as_deref()) else {
+            return Ok(None);
+        };
+        let token = git_token.token;
+        Ok(Some(match forge {
+            GitForgeKind::Github => {
+                anyhow::ensure!(
+                    self.github_app.is_github_repo(&repo),
+                    "The repository is not hosted in GitHub. Please select a different forge."
+                );
+                GitForge::Github(self.github_app.github(&repo, token))
+            }
+            GitForgeKind::Gitea | GitForgeKind::Forgejo => {
+                GitForge::Gitea(Gitea::new(repo, token)?)
+            }
+            GitForgeKind::Gitlab => {
+                GitForge::Gitlab(GitLab::new(repo, token)?.with_job_token(git_token.is_job_token))
+            }
+        }))
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
+use k_releaser_core::GenerateChangelogRequest;
+
+use crate::config::Config;
+
+use super::{project::ProjectArgs, update::UpdateOptions};
+
+#[derive(clap::Parser, Debug)]
+pub struct GenerateChangelog {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Path to the git-cliff configuration file.
+    /// If not provided, `dirs::config_dir()/git-cliff/cliff.toml` is used if present.
+    #[arg(
+        long,
+        env = "GIT_CLIFF_CONFIG",
+        value_name = "PATH",
+        value_parser = PathBufValueParser::new()
+    )]
+    pub changelog_config: Option<PathBuf>,
+    /// Date of the new entry. Format: %Y-%m-%d. It defaults to current Utc date.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    pub release_date: Option<String>,
+    /// Title the new entry `Unreleased` instead of using the version.
+    #[arg(long)]
+    pub unreleased: bool,
+    /// Tag or commit after which commits are included.
+    /// If unspecified, the latest tag before `--to` is used.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    pub since: Option<String>,
+    /// Tag or commit up to which commits are included.
+    /// If it's a tag like `v1.2.3`, its version is used for the new entry.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new(), default_value = "HEAD")]
+    pub to: String,
+    /// Print the entire changelog instead of the new entry alone.
+    #[arg(long)]
+    pub full: bool,
+    /// Write the entire changelog to the changelog file instead of printing it.
+    #[arg(long, conflicts_with("full"))]
+    pub write: bool,
+}
+
+impl GenerateChangelog {
+    pub fn generate_changelog_request(
+        &self,
+        config: &Config,
+        cargo_metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<GenerateChangelogRequest> {
+        let options = UpdateOptions {
+            changelog_config: self.changelog_config.clone(),
+            release_date: self.release_date.clone(),
+            ..Default::default()
+        };
+        let update_request = options.update_request(&self.project, config, cargo_metadata)?;
+        let mut request = GenerateChangelogRequest::new(update_request)
+            .with_to(self.to.clone())
+            .with_unreleased(self.unreleased);
+        if let Some(since) = &self.since {
+            request = request.with_since(since.clone());
+        }
+        Ok(request)
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+use clap::{Args, builder::PathBufValueParser};
+
+use crate::gha;
+
+/// A clap [`Args`] struct to write the results of the command as outputs
+/// of the GitHub Actions step.
+#[derive(Debug, Default, Args)]
+pub struct GhaOutputArgs {
+    /// File where the results are written as GitHub Actions step outputs,
+    /// e.g. `releases_created`, `version` and `tag`.
+    /// When running in GitHub Actions, the `GITHUB_OUTPUT` file is used by default.
+    #[arg(long, value_parser = PathBufValueParser::new())]
+    gha_output: Option<PathBuf>,
+}
+
+impl GhaOutputArgs {
+    /// File where the step outputs are written, if any.
+    pub fn output_file(&self) -> Option<PathBuf> {
+        gha::output_file(self.gha_output.as_deref())
+    }
+}
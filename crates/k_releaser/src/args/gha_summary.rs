@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::gha;
+
+/// A clap [`Args`] struct to write a markdown summary of the command
+/// to the GitHub Actions job summary.
+#[derive(Debug, Default, Args)]
+pub struct GhaSummaryArgs {
+    /// Append a markdown summary of the results, e.g. version bumps and released packages,
+    /// to the GitHub Actions job summary (the `GITHUB_STEP_SUMMARY` file).
+    #[arg(long)]
+    gha_summary: bool,
+}
+
+impl GhaSummaryArgs {
+    /// File where the job summary is written, if enabled.
+    pub fn summary_file(&self) -> Option<PathBuf> {
+        if self.gha_summary {
+            gha::summary_file()
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use anyhow::Context as _;
+use clap::{Args, builder::NonEmptyStringValueParser};
+use k_releaser_core::{GitForge, GitHub, GitHubApp, RepoUrl};
+use secrecy::SecretString;
+use url::Url;
+
+use super::GitForgeKind;
+
+/// A clap [`Args`] struct with the url of the GitHub API and the credentials of a GitHub App.
+///
+/// When set, k-releaser authenticates to GitHub as the app instead of using the git token,
+/// so release PRs trigger CI workflows and are created by the app's bot.
+#[derive(Debug, Default, Args)]
+pub struct GitHubAppArgs {
+    /// ID of the GitHub App used to authenticate to GitHub.
+    /// Takes precedence over the git token.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new(), env = "GITHUB_APP_ID", requires = "github_app_private_key")]
+    github_app_id: Option<String>,
+
+    /// Private key of the GitHub App in PEM format, or path to the PEM file.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new(), env = "GITHUB_APP_PRIVATE_KEY", hide_env_values = true, requires = "github_app_id")]
+    github_app_private_key: Option<String>,
+
+    /// Url of the GitHub API, e.g. `https://ghe.example.com/api/v3` for GitHub Enterprise Server.
+    /// Defaults to `https://api.github.com`.
+    #[arg(long, env = "GITHUB_API_URL")]
+    github_api_url: Option<Url>,
+}
+
+impl GitHubAppArgs {
+    pub fn is_set(&self) -> bool {
+        self.github_app_id.is_some()
+    }
+
+    /// GitHub forge of `repo` authenticated with `token`.
+    pub fn github(&self, repo: &RepoUrl, token: SecretString) -> GitHub {
+        self.with_api_url(GitHub::new(repo.owner.clone(), repo.name.clone(), token))
+    }
+
+    fn with_api_url(&self, github: GitHub) -> GitHub {
+        match &self.github_api_url {
+            Some(api_url) => github.with_base_url(api_url.clone()),
+            None => github,
+        }
+    }
+
+    /// Whether `repo` can be hosted in the GitHub instance.
+    /// The host of GitHub Enterprise Server instances is arbitrary, so it's compared
+    /// with the host of the API url. GitHub Actions always sets `GITHUB_API_URL`,
+    /// so its presence alone doesn't mean the repository is on GitHub.
+    pub fn is_github_repo(&self, repo: &RepoUrl) -> bool {
+        repo.is_on_github()
+            || self
+                .github_api_url
+                .as_ref()
+                .is_some_and(|api_url| api_url.host_str() == Some(repo.host.as_str()))
+    }
+
+    fn github_app(&self) -> anyhow::Result<Option<GitHubApp>> {
+        let (Some(app_id), Some(private_key)) = (&self.github_app_id, &self.github_app_private_key)
+        else {
+            return Ok(None);
+        };
+        let private_key = if private_key.trim_start().starts_with("-----BEGIN") {
+            private_key.clone()
+        } else {
+            fs_err::read_to_string(Path::new(private_key))
+                .context("can't read GitHub App private key")?
+        };
+        Ok(Some(GitHubApp::new(
+            app_id,
+            SecretString::from(private_key),
+        )))
+    }
+
+    /// GitHub forge authenticated as the app, if the app credentials are set.
+    pub fn git_forge(
+        &self,
+        forge: GitForgeKind,
+        repo: &RepoUrl,
+    ) -> anyhow::Result<Option<GitForge>> {
+        let Some(app) = self.github_app()? else {
+            return Ok(None);
+        };
+        anyhow::ensure!(
+            forge == GitForgeKind::Github,
+            "GitHub App authentication is only supported with `--forge github`."
+        );
+        anyhow::ensure!(
+            self.is_github_repo(repo),
+            "Can't authenticate as a GitHub App: the repository is not hosted in GitHub."
+        );
+        Ok(Some(GitForge::Github(self.with_api_url(GitHub::from_app(
+            repo.owner.clone(),
+            repo.name.clone(),
+            app,
+        )))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_args(github_api_url: &str) -> GitHubAppArgs {
+        GitHubAppArgs {
+            github_api_url: Some(Url::parse(github_api_url).unwrap()),
+            ..GitHubAppArgs::default()
+        }
+    }
+
+    #[test]
+    fn repo_on_github_enterprise_server_is_a_github_repo() {
+        let repo = RepoUrl::new("https://ghe.example.com/owner/repo").unwrap();
+        assert!(app_args("https://ghe.example.com/api/v3").is_github_repo(&repo));
+    }
+
+    #[test]
+    fn repo_on_other_host_is_not_a_github_repo() {
+        let repo = RepoUrl::new("https://gitlab.com/owner/repo").unwrap();
+        assert!(!app_args("https://api.github.com").is_github_repo(&repo));
+    }
+}
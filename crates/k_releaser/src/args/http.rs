@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use clap::{
+    Args,
+    builder::{NonEmptyStringValueParser, PathBufValueParser},
+};
+use k_releaser_core::http_client::{self, HttpConfig};
+
+use crate::config::Config;
+
+/// A clap [`Args`] struct with the TLS and proxy options of the http clients
+/// used to reach the git forge and the cargo registry.
+#[derive(Debug, Default, Args)]
+pub struct HttpArgs {
+    /// PEM file with additional root certificates to trust,
+    /// e.g. the certificate of the internal CA of a self-hosted forge.
+    /// Overrides the `ca_cert_path` field of the config.
+    #[arg(long, value_parser = PathBufValueParser::new())]
+    ca_cert_path: Option<PathBuf>,
+
+    /// PEM file with the client certificate and its private key, used for mutual TLS.
+    /// Overrides the `client_cert_path` field of the config.
+    #[arg(long, value_parser = PathBufValueParser::new())]
+    client_cert_path: Option<PathBuf>,
+
+    /// Don't verify the TLS certificates of the servers.
+    /// This is insecure: use it only for testing.
+    #[arg(long)]
+    accept_invalid_certs: bool,
+
+    /// Url of the proxy used to reach the git forge and the cargo registry,
+    /// e.g. `http://proxy.example.com:3128`.
+    /// If not provided, the `HTTPS_PROXY` and `HTTP_PROXY` environment variables are used.
+    /// Overrides the `proxy` field of the config.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    proxy: Option<String>,
+}
+
+impl HttpArgs {
+    fn http_config(&self, config: &Config) -> HttpConfig {
+        let workspace = &config.workspace;
+        HttpConfig {
+            ca_cert_path: self
+                .ca_cert_path
+                .clone()
+                .or_else(|| workspace.ca_cert_path.clone()),
+            accept_invalid_certs: self.accept_invalid_certs || workspace.accept_invalid_certs,
+            client_cert_path: self
+                .client_cert_path
+                .clone()
+                .or_else(|| workspace.client_cert_path.clone()),
+            proxy: self.proxy.clone().or_else(|| workspace.proxy.clone()),
+        }
+    }
+
+    /// Apply the options of the cli and of the config to the http clients.
+    pub fn configure(&self, config: &Config) -> anyhow::Result<()> {
+        http_client::configure(&self.http_config(config))
+    }
+}
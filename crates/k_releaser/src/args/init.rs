@@ -0,0 +1,28 @@
+use std::path::{Path, PathBuf};
+
+use clap::builder::PathBufValueParser;
+
+use super::{GitForgeKind, manifest_command::ManifestCommand};
+
+#[derive(clap::Parser, Debug)]
+pub struct Init {
+    /// Path to the Cargo.toml of the project you want to configure.
+    /// If not provided, k-releaser will use the Cargo.toml of the current directory.
+    #[arg(long, value_parser = PathBufValueParser::new(), alias = "project-manifest")]
+    manifest_path: Option<PathBuf>,
+
+    /// Kind of git forge.
+    /// If not provided, k-releaser detects it from the url of the git remote.
+    #[arg(long, visible_alias = "backend", value_enum)]
+    pub forge: Option<GitForgeKind>,
+
+    /// Don't write the CI workflow file.
+    #[arg(long)]
+    pub no_ci: bool,
+}
+
+impl ManifestCommand for Init {
+    fn optional_manifest(&self) -> Option<&Path> {
+        self.manifest_path.as_deref()
+    }
+}
@@ -0,0 +1,29 @@
+use clap::Args;
+use k_releaser_core::LockMode;
+
+/// A clap [`Args`] struct to lock the repository while k-releaser runs,
+/// so that concurrent runs don't create duplicate PRs, tags or releases.
+#[derive(Debug, Default, Args)]
+pub struct LockArgs {
+    /// Lock the repository with the `k-releaser-lock` branch while k-releaser runs.
+    /// If another run holds the lock, k-releaser fails instead of racing with it.
+    #[arg(long)]
+    lock: bool,
+
+    /// Remove the lock left by a run that crashed, then lock the repository.
+    /// Implies `--lock`.
+    #[arg(long)]
+    force_unlock: bool,
+}
+
+impl LockArgs {
+    pub fn lock_mode(&self) -> LockMode {
+        if self.force_unlock {
+            LockMode::ForceUnlock
+        } else if self.lock {
+            LockMode::Enabled
+        } else {
+            LockMode::Disabled
+        }
+    }
+}
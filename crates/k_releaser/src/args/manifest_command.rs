@@ -2,8 +2,11 @@ use std::path::Path;
 
 use anyhow::Context as _;
 use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use git_cmd::git_in_dir;
 use k_releaser_core::fs_utils::to_utf8_path;
 
+use crate::config::Config;
+
 /// Command that acts on a manifest.
 pub trait ManifestCommand {
     fn optional_manifest(&self) -> Option<&Path>;
@@ -16,6 +19,30 @@ pub trait ManifestCommand {
         super::local_manifest(self.optional_manifest_path())
     }
 
+    /// Branch checked out in the repository of the manifest.
+    /// CI often checks out a detached commit, so the branch is also read from the CI environment.
+    fn current_branch(&self) -> Option<String> {
+        let manifest_path = self.manifest_path();
+        let branch = k_releaser_core::manifest_dir(&manifest_path)
+            .ok()
+            .and_then(|dir| git_in_dir(dir, &["rev-parse", "--abbrev-ref", "HEAD"]).ok())
+            // A detached checkout has no branch.
+            .filter(|branch| branch != "HEAD");
+        branch.or_else(|| {
+            ["GITHUB_REF_NAME", "CI_COMMIT_BRANCH"]
+                .into_iter()
+                .find_map(|var| std::env::var(var).ok())
+        })
+    }
+
+    /// Record the current branch in the config and apply its configuration, if any.
+    fn with_branch_config(&self, config: Config) -> Config {
+        match self.current_branch() {
+            Some(branch) => config.with_branch_overrides(&branch),
+            None => config,
+        }
+    }
+
     fn cargo_metadata(&self) -> anyhow::Result<cargo_metadata::Metadata> {
         let manifest = &self.manifest_path();
         cargo_utils::get_manifest_metadata(manifest).map_err(|e| match e {
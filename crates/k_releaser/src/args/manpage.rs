@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use clap::{CommandFactory as _, builder::PathBufValueParser};
+
+use super::CliArgs;
+
+#[derive(clap::Parser, Debug)]
+pub struct Manpage {
+    /// Directory where the man pages of k-releaser and of all its subcommands are written.
+    /// If not provided, the man page of k-releaser is printed to stdout.
+    #[arg(long, value_parser = PathBufValueParser::new())]
+    out_dir: Option<PathBuf>,
+}
+
+impl Manpage {
+    pub fn generate(&self) -> anyhow::Result<()> {
+        let cmd = CliArgs::command();
+        match &self.out_dir {
+            Some(out_dir) => {
+                fs_err::create_dir_all(out_dir)?;
+                clap_mangen::generate_to(cmd, out_dir)
+                    .with_context(|| format!("can't write man pages to {}", out_dir.display()))?;
+            }
+            None => {
+                clap_mangen::Man::new(cmd)
+                    .render(&mut std::io::stdout())
+                    .context("can't write man page to stdout")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn man_pages_are_written_for_subcommands() {
+        let temp = tempfile::tempdir().unwrap();
+        let manpage = Manpage {
+            out_dir: Some(temp.path().to_path_buf()),
+        };
+        manpage.generate().unwrap();
+        assert!(temp.path().join("k-releaser.1").exists());
+        assert!(temp.path().join("k-releaser-release-pr.1").exists());
+    }
+}
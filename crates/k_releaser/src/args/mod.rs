@@ -1,24 +1,56 @@
+#[cfg(feature = "keyring")]
+pub mod auth;
+mod backfill;
+pub(crate) mod changelog;
+pub(crate) mod check_commit;
+mod check_run;
+mod completions;
 pub mod config;
 mod config_path;
+pub(crate) mod doctor;
+mod forge;
+mod generate_changelog;
+mod gha_output;
+mod gha_summary;
+mod github_app;
+mod http;
+pub(crate) mod init;
+mod lock;
 pub(crate) mod manifest_command;
-mod publish;
+mod manpage;
+pub(crate) mod next_version;
+pub(crate) mod preview;
+pub(crate) mod project;
+pub(crate) mod publish;
+mod publish_drafts;
 mod release;
 mod release_pr;
 pub(crate) mod repo_command;
-mod update;
+mod rollback;
+mod set_version;
+pub(crate) mod update;
+mod update_command;
+mod verify_tags;
 
-use anyhow::bail;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, bail};
 use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
 use cargo_utils::CARGO_TOML;
 use clap::{
     ValueEnum,
     builder::{Styles, styling::AnsiColor},
 };
-use k_releaser_core::fs_utils::current_directory;
-use tracing::level_filters::LevelFilter;
+use k_releaser_core::{RepoUrl, fs_utils::current_directory};
+use secrecy::SecretString;
+use tracing::{level_filters::LevelFilter, warn};
 
 use self::{
-    config::Config, publish::Publish, release::Release, release_pr::ReleasePr, update::Update,
+    backfill::Backfill, changelog::Changelog, check_commit::CheckCommit, completions::Completions,
+    config::Config, doctor::Doctor, generate_changelog::GenerateChangelog, init::Init,
+    manpage::Manpage, next_version::NextVersion, preview::Preview, publish::Publish,
+    publish_drafts::PublishDrafts, release::Release, release_pr::ReleasePr, rollback::Rollback,
+    set_version::SetVersion, update_command::UpdateCommand, verify_tags::VerifyTags,
 };
 
 const MAIN_COLOR: AnsiColor = AnsiColor::Red;
@@ -70,7 +102,7 @@ impl CliArgs {
 #[derive(clap::Subcommand, Debug)]
 pub enum Command {
     /// Update packages version and changelogs based on commit messages.
-    Update(Update),
+    Update(UpdateCommand),
     /// Create a Pull Request representing the next release.
     ///
     /// The Pull request updates the package version and generates a changelog entry for the new
@@ -95,8 +127,98 @@ pub enum Command {
     ///
     /// You can run this command in the CI on every commit in the main branch.
     Release(Release),
+    /// Publish the draft forge releases of the packages.
+    ///
+    /// Find the draft releases created with `git_release_draft = true` whose tag
+    /// is the tag of a release of the packages, and publish them.
+    /// Drafts of other tags are left untouched. GitLab doesn't support draft releases.
+    ///
+    /// Use `--dry-run` to preview which drafts would be published.
+    PublishDrafts(PublishDrafts),
+    /// Undo a release.
+    ///
+    /// Delete the git tag (local and remote) and the forge release of the given
+    /// version or tag. With `--yank`, also yank the released packages from the cargo registry.
+    ///
+    /// Use `--dry-run` to preview what would be deleted.
+    Rollback(Rollback),
+    /// Check that git tags, forge releases and published versions are consistent.
+    ///
+    /// Report tags without a forge release, forge releases without a tag,
+    /// versions published in the cargo registry without a tag and tags pointing
+    /// to a commit not contained in any branch.
+    /// Fail if any inconsistency is found, so that it can be used to gate CI jobs.
+    VerifyTags(VerifyTags),
     /// Show the current configuration.
     Config(Config),
+    /// Read the changelog.
+    Changelog(Changelog),
+    /// Set the version of the packages to the given version.
+    ///
+    /// Update the version in the Cargo.toml files, the version requirements of the
+    /// dependencies between the workspace packages and the Cargo.lock file.
+    /// Use it to jump to a specific version, bypassing the version determined
+    /// from the conventional commits.
+    SetVersion(SetVersion),
+    /// Print the next version of the packages.
+    ///
+    /// The version and the bump kind (major, minor, patch, prerelease or none) are computed
+    /// from the commits since the last tag, without changing the project.
+    /// Use it in build scripts that need the version before the release PR exists.
+    NextVersion(NextVersion),
+    /// Comment on a PR with the next release after merging it.
+    ///
+    /// Compute the next version of the packages, like `next-version`, and post it as a
+    /// comment on the PR, updating the previous comment if present.
+    /// Run it in the CI of feature PRs to catch accidental breaking changes during review.
+    ///
+    /// Use `--dry-run` to print the comment instead of posting it.
+    Preview(Preview),
+    /// Generate the changelog from the commits.
+    ///
+    /// Print the changelog entry of the commits since the last tag, without changing
+    /// the versions or the Cargo.toml files.
+    /// Use it to run k-releaser as a changelog generator only.
+    GenerateChangelog(GenerateChangelog),
+    /// Add the releases made before adopting k-releaser to the changelog.
+    ///
+    /// Find the past releases from the `v<version>` tags and from the commits that
+    /// changed the version in the Cargo.toml, and add a changelog section for each
+    /// release older than the oldest version of the CHANGELOG.md.
+    /// With `--create-tags`, also create the missing git tags.
+    Backfill(Backfill),
+    /// Check that commit messages follow the conventional commits specification.
+    ///
+    /// Commits are checked with the rules used to compute the next version.
+    /// Commit types matched by the commit parsers of the changelog configuration are accepted, too.
+    /// Use it in a `commit-msg` git hook or in a CI job of the pull requests.
+    CheckCommit(CheckCommit),
+    /// Set up k-releaser in the project.
+    ///
+    /// Add a starter k-releaser configuration to the Cargo.toml file and write
+    /// a CI workflow for the git forge (GitHub/GitLab/Gitea) detected from the git remote.
+    Init(Init),
+    /// Check that k-releaser can run in this environment.
+    ///
+    /// Check git, the repository, the remote tags, the forge and registry tokens,
+    /// the cargo metadata and the changelogs, and print a pass/fail report.
+    /// Run it before setting up k-releaser in CI.
+    Doctor(Doctor),
+    /// Manage the tokens stored in the system keyring.
+    ///
+    /// k-releaser uses the stored tokens when the token isn't passed
+    /// via cli argument or environment variable.
+    #[cfg(feature = "keyring")]
+    Auth(auth::Auth),
+    /// Print the shell completion script.
+    ///
+    /// E.g. `k-releaser completions bash > /usr/share/bash-completion/completions/k-releaser`.
+    Completions(Completions),
+    /// Generate the man page.
+    ///
+    /// Print the man page of k-releaser to stdout, or write the man pages of
+    /// k-releaser and of all its subcommands to a directory with `--out-dir`.
+    Manpage(Manpage),
 }
 
 #[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
@@ -111,13 +233,220 @@ pub enum GitForgeKind {
     Github,
     #[value(name = "gitea")]
     Gitea,
+    /// Forgejo, e.g. Codeberg. Uses the Gitea API.
+    #[value(name = "forgejo")]
+    Forgejo,
     #[value(name = "gitlab")]
     Gitlab,
 }
 
+/// GitLab CI/CD job token, available in GitLab pipelines.
+const CI_JOB_TOKEN: &str = "CI_JOB_TOKEN";
+
+/// Token used to authenticate to the git forge.
+pub struct GitToken {
+    pub token: SecretString,
+    /// The token is a GitLab CI/CD job token.
+    pub is_job_token: bool,
+}
+
+impl GitForgeKind {
+    /// Environment variables containing the git token, in order of precedence.
+    fn token_env_vars(self) -> &'static [&'static str] {
+        match self {
+            Self::Github => &["GITHUB_TOKEN"],
+            Self::Gitea => &["GITEA_TOKEN", "GITHUB_TOKEN"],
+            Self::Forgejo => &["FORGEJO_TOKEN", "GITEA_TOKEN", "GITHUB_TOKEN"],
+            Self::Gitlab => &["GITLAB_TOKEN", "GITHUB_TOKEN", CI_JOB_TOKEN],
+        }
+    }
+
+    /// Git token passed via cli argument or, if missing,
+    /// read from the environment variables of the forge.
+    pub fn git_token(self, arg: Option<&str>) -> Option<GitToken> {
+        if let Some(token) = arg {
+            // `--git-token $CI_JOB_TOKEN` is still a job token.
+            let is_job_token = self == Self::Gitlab
+                && std::env::var(CI_JOB_TOKEN).is_ok_and(|job_token| job_token == token);
+            return Some(GitToken {
+                token: token.into(),
+                is_job_token,
+            });
+        }
+        let env_token = self.token_env_vars().iter().find_map(|&var| {
+            let token = std::env::var(var).ok().filter(|t| !t.is_empty())?;
+            Some(GitToken {
+                token: token.into(),
+                is_job_token: var == CI_JOB_TOKEN,
+            })
+        });
+        if env_token.is_some() {
+            return env_token;
+        }
+        #[cfg(feature = "keyring")]
+        if let Some(token) = crate::auth::forge_token(self) {
+            return Some(GitToken {
+                token,
+                is_job_token: false,
+            });
+        }
+        None
+    }
+
+    /// Whether a git token is set in the environment or stored in the keyring.
+    pub fn git_token_available() -> bool {
+        [Self::Github, Self::Gitea, Self::Forgejo, Self::Gitlab]
+            .iter()
+            .any(|forge| forge.git_token(None).is_some())
+    }
+
+    /// Detect the forge from the host of the repository url. Defaults to GitHub.
+    pub fn detect(repo_url: &RepoUrl) -> Self {
+        Self::from_host(&repo_url.host).unwrap_or_else(|| {
+            warn!(
+                "can't detect the forge of {}, defaulting to GitHub. Use --forge to select a different forge.",
+                repo_url.host
+            );
+            Self::Github
+        })
+    }
+
+    fn from_host(host: &str) -> Option<Self> {
+        if host.contains("github") {
+            Some(Self::Github)
+        } else if host.contains("gitlab") {
+            Some(Self::Gitlab)
+        } else if ["codeberg", "forgejo"]
+            .iter()
+            .any(|name| host.contains(name))
+        {
+            Some(Self::Forgejo)
+        } else if host.contains("gitea") {
+            Some(Self::Gitea)
+        } else {
+            None
+        }
+    }
+}
+
+/// Name of the crates.io registry.
+pub const CRATES_IO: &str = "crates-io";
+
+/// Environment variable where cargo reads the token of the registry from.
+pub fn registry_token_env_var(registry: &str) -> String {
+    if registry == CRATES_IO {
+        "CARGO_REGISTRY_TOKEN".to_string()
+    } else {
+        format!(
+            "CARGO_REGISTRIES_{}_TOKEN",
+            registry.to_uppercase().replace('-', "_")
+        )
+    }
+}
+
+/// Read a token from the given file, or from stdin if the path is `-`.
+/// Surrounding whitespace, like the trailing newline, is removed.
+fn read_token_file(path: &Path) -> anyhow::Result<String> {
+    let content = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin()).context("can't read token from stdin")?
+    } else {
+        fs_err::read_to_string(path)?
+    };
+    let token = content.trim();
+    anyhow::ensure!(!token.is_empty(), "token file {} is empty", path.display());
+    Ok(token.to_string())
+}
+
+/// Cargo registry token passed via cli argument or file or, if missing,
+/// stored in the system keyring.
+fn registry_token(
+    token: Option<&str>,
+    token_file: Option<&Path>,
+    #[cfg_attr(not(feature = "keyring"), expect(unused_variables))] registry: Option<&str>,
+) -> anyhow::Result<Option<SecretString>> {
+    if let Some(path) = token_file {
+        return Ok(Some(read_token_file(path)?.into()));
+    }
+    if let Some(token) = token {
+        return Ok(Some(token.into()));
+    }
+    #[cfg(feature = "keyring")]
+    if let Some(token) = crate::auth::registry_token(registry) {
+        return Ok(Some(token));
+    }
+    Ok(None)
+}
+
+/// Workspaces of the `workspace_roots` config to process, in order.
+/// [`Option::None`] stands for the workspace of the manifest, processed alone when
+/// `workspace_roots` is empty or `--manifest-path` is set.
+fn workspace_roots(
+    config_path: &config_path::ConfigPath,
+    manifest_path: Option<&Path>,
+) -> anyhow::Result<Vec<Option<String>>> {
+    if manifest_path.is_some() {
+        return Ok(vec![None]);
+    }
+    let config = config_path.load()?.with_env_overrides()?;
+    if config.workspace.workspace_roots.is_empty()
+        && config.workspace.workspace_discovery_paths.is_empty()
+    {
+        return Ok(vec![None]);
+    }
+    // The workspaces are relative to the repository root, not to the current directory.
+    let repo_root = k_releaser_core::root_repo_path_from_manifest_dir(&current_directory()?)?;
+    let roots = config.workspace_roots(repo_root.as_std_path())?;
+    if roots.is_empty() {
+        return Ok(vec![None]);
+    }
+    Ok(roots
+        .into_iter()
+        .map(|root| Some(repo_root.join(root).to_string()))
+        .collect())
+}
+
+/// Cargo.toml of a workspace of the `workspace_roots` config.
+fn workspace_root_manifest(root: &str) -> PathBuf {
+    Path::new(root).join(CARGO_TOML)
+}
+
 fn local_manifest(manifest_path: Option<&Utf8Path>) -> Utf8PathBuf {
     match manifest_path {
         Some(manifest) => manifest.to_path_buf(),
         None => current_directory().unwrap().join(CARGO_TOML),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forge_is_detected_from_host() {
+        assert_eq!(
+            GitForgeKind::from_host("github.com"),
+            Some(GitForgeKind::Github)
+        );
+        assert_eq!(
+            GitForgeKind::from_host("gitlab.example.com"),
+            Some(GitForgeKind::Gitlab)
+        );
+        assert_eq!(
+            GitForgeKind::from_host("codeberg.org"),
+            Some(GitForgeKind::Forgejo)
+        );
+        assert_eq!(
+            GitForgeKind::from_host("gitea.com"),
+            Some(GitForgeKind::Gitea)
+        );
+        assert_eq!(GitForgeKind::from_host("git.example.com"), None);
+    }
+
+    #[test]
+    fn token_is_read_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        fs_err::write(&path, "my-token\n").unwrap();
+        assert_eq!(read_token_file(&path).unwrap(), "my-token");
+    }
+}
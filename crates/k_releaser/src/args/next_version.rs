@@ -0,0 +1,17 @@
+use clap::builder::NonEmptyStringValueParser;
+
+use super::{OutputType, project::ProjectArgs};
+
+#[derive(clap::Parser, Debug)]
+pub struct NextVersion {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Package to print the next version of.
+    /// If not provided, the next version of every package is printed.
+    #[arg(short, long, value_parser = NonEmptyStringValueParser::new())]
+    pub package: Option<String>,
+    /// Output format. If specified, prints the current version, the next version
+    /// and the bump kind of every package.
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputType>,
+}
@@ -0,0 +1,56 @@
+use clap::builder::NonEmptyStringValueParser;
+
+use super::project::ProjectArgs;
+
+#[derive(clap::Parser, Debug)]
+pub struct Preview {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Package to preview the release of.
+    /// If not provided, the releases of every package are previewed.
+    #[arg(short, long, value_parser = NonEmptyStringValueParser::new())]
+    pub package: Option<String>,
+    /// Number of the PR to comment on.
+    /// If not provided, it's read from the CI environment:
+    /// `GITHUB_REF` on GitHub and Gitea Actions, `CI_MERGE_REQUEST_IID` on GitLab CI.
+    #[arg(long)]
+    pub pr: Option<u64>,
+    /// Print the comment instead of posting it.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl Preview {
+    /// Number of the PR to comment on.
+    pub fn pr_number(&self) -> Option<u64> {
+        self.pr.or_else(pr_number_from_env)
+    }
+}
+
+fn pr_number_from_env() -> Option<u64> {
+    if let Ok(iid) = std::env::var("CI_MERGE_REQUEST_IID") {
+        return iid.parse().ok();
+    }
+    pr_number_from_ref(&std::env::var("GITHUB_REF").ok()?)
+}
+
+/// Number of the PR of a ref like `refs/pull/123/merge`.
+fn pr_number_from_ref(git_ref: &str) -> Option<u64> {
+    git_ref
+        .strip_prefix("refs/pull/")?
+        .split('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pr_number_is_read_from_ref() {
+        assert_eq!(pr_number_from_ref("refs/pull/123/merge"), Some(123));
+        assert_eq!(pr_number_from_ref("refs/heads/main"), None);
+    }
+}
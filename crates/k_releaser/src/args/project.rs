@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+
+use clap::{
+    Args,
+    builder::{NonEmptyStringValueParser, PathBufValueParser},
+};
+
+use crate::config::Config;
+
+use super::{
+    config_path::ConfigPath, forge::ForgeArgs, http::HttpArgs, manifest_command::ManifestCommand,
+    repo_command::RepoCommand,
+};
+
+/// A clap [`Args`] struct with the arguments shared by the commands that act on the project:
+/// its manifest, its repository, the k-releaser config and the git forge.
+#[derive(Debug, Default, Args)]
+pub struct ProjectArgs {
+    /// Path to the Cargo.toml of the project.
+    /// If not provided, k-releaser will use the Cargo.toml of the current directory.
+    /// Both Cargo workspaces and single packages are supported.
+    #[arg(long, value_parser = PathBufValueParser::new(), alias = "project-manifest")]
+    manifest_path: Option<PathBuf>,
+
+    /// Workspace of the `workspace_roots` config being processed, if any.
+    #[arg(skip)]
+    workspace_root: Option<String>,
+
+    /// GitHub/Gitea/GitLab repository url where your project is hosted.
+    /// It is used to generate the changelog release link and to call the forge API.
+    /// It defaults to the url of the default remote.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    repo_url: Option<String>,
+
+    /// Name of the git remote used to derive the repository url, fetch tags and push tags.
+    /// It defaults to the `git_remote` config or to the remote of the current branch.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    git_remote: Option<String>,
+
+    /// Path to the k-releaser config file.
+    #[command(flatten)]
+    pub config: ConfigPath,
+
+    #[command(flatten)]
+    pub forge: ForgeArgs,
+
+    #[command(flatten)]
+    pub http: HttpArgs,
+}
+
+impl RepoCommand for ProjectArgs {
+    fn repo_url(&self) -> Option<&str> {
+        self.repo_url.as_deref()
+    }
+
+    fn git_remote(&self) -> Option<&str> {
+        self.git_remote.as_deref()
+    }
+}
+
+impl ManifestCommand for ProjectArgs {
+    fn optional_manifest(&self) -> Option<&Path> {
+        self.manifest_path.as_deref()
+    }
+}
+
+impl ProjectArgs {
+    /// Load the k-releaser configuration.
+    ///
+    /// This method handles config loading correctly when `--manifest-path` is specified:
+    /// 1. If `--config` is explicitly specified, load from that path
+    /// 2. Otherwise, if `--manifest-path` is specified, load config from that Cargo.toml
+    /// 3. Otherwise, use the default behavior (load from `./Cargo.toml`)
+    /// 4. Apply the `[branch]` configuration of the current branch
+    /// 5. Apply the `K_RELEASER_*` environment variables, which override everything else
+    ///
+    /// The workspaces of `workspace_roots` are always configured next to their Cargo.toml.
+    pub fn load_config(&self) -> anyhow::Result<Config> {
+        let config = if let (Some(root), Some(manifest_path)) =
+            (&self.workspace_root, &self.manifest_path)
+        {
+            self.config
+                .load_from(manifest_path)?
+                .with_workspace_root_defaults(root)
+        } else if self.config.has_explicit_path() {
+            // If explicit config path is specified, use ConfigPath::load()
+            self.config.load()?
+        } else if let Some(manifest_path) = &self.manifest_path {
+            // If manifest_path is specified, load config from that Cargo.toml
+            self.config.load_from(manifest_path)?
+        } else {
+            // Default: load from ./Cargo.toml
+            self.config.load()?
+        };
+        self.with_branch_config(config).with_env_overrides()
+    }
+
+    /// Workspaces of the `workspace_roots` config to process, in order.
+    /// See [`ProjectArgs::set_workspace_root`].
+    pub fn workspace_roots(&self) -> anyhow::Result<Vec<Option<String>>> {
+        super::workspace_roots(&self.config, self.manifest_path.as_deref())
+    }
+
+    /// Process the given workspace of `workspace_roots`, if any, instead of the one of
+    /// the manifest.
+    pub fn set_workspace_root(&mut self, root: Option<String>) {
+        if let Some(root) = &root {
+            self.manifest_path = Some(super::workspace_root_manifest(root));
+        }
+        self.workspace_root = root;
+    }
+
+    /// Set up what the workspaces of `workspace_roots` share, before processing them:
+    /// the http clients, configured with the config of the project, and the git token.
+    pub fn prepare_workspaces(&mut self) -> anyhow::Result<()> {
+        let config = self.load_config()?;
+        self.http.configure(&config)?;
+        self.forge.read_git_token_file()
+    }
+
+    /// Workspace of the `workspace_roots` config being processed, if any.
+    pub fn workspace_root(&self) -> Option<&str> {
+        self.workspace_root.as_deref()
+    }
+}
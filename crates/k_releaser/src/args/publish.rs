@@ -1,20 +1,23 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
-use k_releaser_core::PublishRequest;
-use secrecy::SecretString;
+use anyhow::Context as _;
+use clap::{
+    ValueEnum,
+    builder::{NonEmptyStringValueParser, PathBufValueParser},
+};
+use k_releaser_core::{PublishRequest, fs_utils::to_utf8_path};
 
 use crate::config::Config;
 
-use super::{OutputType, config_path::ConfigPath, manifest_command::ManifestCommand};
+use super::{
+    OutputType, gha_output::GhaOutputArgs, gha_summary::GhaSummaryArgs, project::ProjectArgs,
+    registry_token, repo_command::RepoCommand as _,
+};
 
 #[derive(clap::Parser, Debug)]
 pub struct Publish {
-    /// Path to the Cargo.toml of the project you want to publish.
-    /// If not provided, k-releaser will use the Cargo.toml of the current directory.
-    /// Both Cargo workspaces and single packages are supported.
-    #[arg(long, value_parser = PathBufValueParser::new(), alias = "project-manifest")]
-    manifest_path: Option<PathBuf>,
+    #[command(flatten)]
+    pub project: ProjectArgs,
 
     /// Registry where you want to publish the packages.
     /// The registry name needs to be present in the Cargo config.
@@ -29,6 +32,11 @@ pub struct Publish {
     #[arg(long, value_parser = NonEmptyStringValueParser::new())]
     token: Option<String>,
 
+    /// File containing the token used to publish to the cargo registry.
+    /// Use `-` to read it from stdin.
+    #[arg(long, value_parser = PathBufValueParser::new(), conflicts_with = "token")]
+    registry_token_file: Option<PathBuf>,
+
     /// Perform all checks without uploading.
     #[arg(long)]
     pub dry_run: bool,
@@ -43,14 +51,61 @@ pub struct Publish {
     #[arg(long)]
     pub allow_dirty: bool,
 
+    /// Publish only this package. Can be repeated.
+    /// Packages are still published in dependency order.
+    #[arg(long = "package", value_name = "NAME", value_parser = NonEmptyStringValueParser::new())]
+    pub packages: Vec<String>,
+
+    /// Don't publish this package. Can be repeated.
+    /// Publishing fails if a selected package depends on an excluded package that isn't published yet.
+    #[arg(long = "exclude", value_name = "NAME", value_parser = NonEmptyStringValueParser::new())]
+    pub excluded_packages: Vec<String>,
+
+    /// Don't wait for each published package to be available in the registry.
+    /// Useful for registries that index packages instantly.
+    #[arg(long)]
+    pub no_wait: bool,
+
+    /// Continue an interrupted run, skipping the packages it already published.
+    /// The published packages are recorded in the `target` directory.
+    #[arg(long, conflicts_with = "dry_run")]
+    pub resume: bool,
+
+    /// Directory where the `.crate` files of the packages are copied.
+    /// Overrides the `package_output_dir` config.
+    #[arg(long, value_name = "DIR", value_parser = PathBufValueParser::new())]
+    pub package_output_dir: Option<PathBuf>,
+
+    /// After publishing, add each published package to a new project and run `cargo check`.
+    /// Enabled by the `publish_smoke_test` config, too.
+    #[arg(long, conflicts_with = "dry_run")]
+    pub smoke_test: bool,
+
+    /// Refuse to publish if the commit statuses and check runs of the current commit
+    /// didn't pass.
+    /// Enabled by the `publish_require_checks` config, too.
+    #[arg(long)]
+    pub require_checks: bool,
+
+    /// Publish even if the checks of the current commit didn't pass.
+    #[arg(long)]
+    pub force: bool,
+
     /// Print the order packages would be published in and exit.
     /// Does not actually publish anything.
     #[arg(long)]
     pub print_order: bool,
 
-    /// Path to the k-releaser config file.
+    /// Print the publish order as a dependency graph, with the edges between
+    /// the workspace packages.
+    #[arg(long, value_enum, requires = "print_order", conflicts_with = "output")]
+    pub format: Option<GraphFormat>,
+
     #[command(flatten)]
-    pub config: ConfigPath,
+    pub gha_output: GhaOutputArgs,
+
+    #[command(flatten)]
+    pub gha_summary: GhaSummaryArgs,
 
     /// Output format. If specified, prints the version and the tag of the
     /// published packages.
@@ -58,46 +113,85 @@ pub struct Publish {
     pub output: Option<OutputType>,
 }
 
-impl Publish {
-    /// Load the k-releaser configuration.
-    ///
-    /// If `--manifest-path` is specified but `--config` is not, load config from the manifest path.
-    pub fn load_config(&self) -> anyhow::Result<Config> {
-        if self.config.has_explicit_path() {
-            return self.config.load();
-        }
-        if let Some(manifest_path) = &self.manifest_path {
-            return self.config.load_from(manifest_path);
-        }
-        self.config.load()
-    }
+/// Format of the dependency graph printed by `--print-order`.
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    Dot,
+    /// Mermaid flowchart.
+    Mermaid,
+}
 
+impl Publish {
     pub fn publish_request(
         self,
         config: &Config,
         metadata: cargo_metadata::Metadata,
     ) -> anyhow::Result<PublishRequest> {
-        let mut req = PublishRequest::new(metadata).with_dry_run(self.dry_run);
-
+        let package_output_dir = match &self.package_output_dir {
+            Some(dir) => Some(to_utf8_path(dir)?.to_path_buf()),
+            None => match &config.workspace.package_output_dir {
+                Some(dir) => Some(metadata.workspace_root.join(to_utf8_path(dir)?)),
+                None => None,
+            },
+        };
+        let checks_forge = if self.require_checks || config.workspace.publish_require_checks {
+            let repo_url = self.project.get_repo_url(config)?;
+            let forge =
+                self.project.forge.git_forge(repo_url)?.context(
+                    "a git token is required to verify the checks of the published commit",
+                )?;
+            Some(forge)
+        } else {
+            None
+        };
+        let mut req = PublishRequest::new(metadata)
+            .with_dry_run(self.dry_run)
+            .with_packages(self.packages)
+            .with_excluded_packages(self.excluded_packages)
+            .with_resume(self.resume)
+            .with_no_wait(self.no_wait)
+            .with_smoke_test(self.smoke_test || config.workspace.publish_smoke_test)
+            .with_force(self.force);
+
+        if let Some(token) = registry_token(
+            self.token.as_deref(),
+            self.registry_token_file.as_deref(),
+            self.registry.as_deref(),
+        )? {
+            req = req.with_token(token);
+        }
         if let Some(registry) = self.registry {
             req = req.with_registry(registry);
         }
-        if let Some(token) = self.token {
-            req = req.with_token(SecretString::from(token));
+        if let Some(dir) = package_output_dir {
+            req = req.with_package_output_dir(dir);
+        }
+        if let Some(forge) = checks_forge {
+            req = req.with_commit_checks(forge, config.workspace.required_checks.clone());
+            if let Some(ci_job_name) = &config.workspace.ci_job_name {
+                req = req.with_ci_job_name(ci_job_name.clone());
+            }
         }
 
         req = req.with_publish_timeout(config.workspace.publish_timeout()?);
+        req = req.with_publish_wait_poll_interval(config.workspace.publish_wait_poll_interval()?);
+        if let Some(timeout) = config.workspace.docs_rs_timeout()? {
+            req = req.with_docs_rs_timeout(timeout);
+        }
+        req = req.with_version_mode(config.workspace.version_mode());
+        if let Some(branch) = config.current_branch() {
+            req = req.with_branch(branch.to_string());
+        }
+        if let Some(workspace_tag_name) = config.workspace_tag_name() {
+            req = req.with_workspace_tag_name(workspace_tag_name.to_string());
+        }
+        req = req.with_hooks(config.workspace.hooks());
 
-        req = config.fill_publish_config(self.allow_dirty, self.no_verify, req);
+        req = config.fill_publish_config(self.allow_dirty, self.no_verify, req)?;
 
         req.check_publish_fields()?;
 
         Ok(req)
     }
 }
-
-impl ManifestCommand for Publish {
-    fn optional_manifest(&self) -> Option<&Path> {
-        self.manifest_path.as_deref()
-    }
-}
@@ -0,0 +1,31 @@
+use k_releaser_core::PublishDraftsRequest;
+
+use crate::config::Config;
+
+use super::{OutputType, project::ProjectArgs, release::release_request};
+
+#[derive(clap::Parser, Debug)]
+pub struct PublishDrafts {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+
+    /// Print the draft releases that would be published, without publishing them.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Output format. If specified, prints the published releases.
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputType>,
+}
+
+impl PublishDrafts {
+    pub fn publish_drafts_request(
+        self,
+        config: &Config,
+        metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<PublishDraftsRequest> {
+        let release_request =
+            release_request(&self.project, config, metadata)?.with_dry_run(self.dry_run);
+        Ok(PublishDraftsRequest::new(release_request))
+    }
+}
@@ -1,45 +1,41 @@
-use std::path::{Path, PathBuf};
-
-use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
-use k_releaser_core::{GitForge, GitHub, GitLab, Gitea, ReleaseRequest};
-use secrecy::SecretString;
+use k_releaser_core::ReleaseRequest;
 
 use crate::config::Config;
 
 use super::{
-    GitForgeKind, OutputType, config_path::ConfigPath, manifest_command::ManifestCommand,
-    repo_command::RepoCommand,
+    OutputType, gha_output::GhaOutputArgs, gha_summary::GhaSummaryArgs, lock::LockArgs,
+    project::ProjectArgs, repo_command::RepoCommand as _,
 };
 
 #[derive(clap::Parser, Debug)]
 pub struct Release {
-    /// Path to the Cargo.toml of the project you want to release.
-    /// If not provided, k-releaser will use the Cargo.toml of the current directory.
-    /// Both Cargo workspaces and single packages are supported.
-    #[arg(long, value_parser = PathBufValueParser::new(), alias = "project-manifest")]
-    manifest_path: Option<PathBuf>,
+    #[command(flatten)]
+    pub project: ProjectArgs,
 
     /// Perform all checks without creating git tags/releases.
     #[arg(long)]
     pub dry_run: bool,
 
-    /// GitHub/Gitea/GitLab repository url where your project is hosted.
-    /// It is used to create the git release.
-    /// It defaults to the url of the default remote.
-    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
-    pub repo_url: Option<String>,
+    /// Wait for the commit statuses and check runs of the released commit to pass
+    /// before creating tags and releases.
+    /// Enabled by the `release_wait_for_checks` config, too.
+    #[arg(long)]
+    pub wait_for_checks: bool,
 
-    /// Git token used to publish the GitHub/Gitea/GitLab release.
-    #[arg(long, value_parser = NonEmptyStringValueParser::new(), env = "GITHUB_TOKEN", hide_env_values=true)]
-    pub git_token: Option<String>,
+    /// Move floating tags, like `v1` and `v1.4`, to the released commit,
+    /// unless a greater version of their series is already tagged.
+    /// Enabled by the `release_alias_tags` config, too.
+    #[arg(long)]
+    pub alias_tags: bool,
 
-    /// Kind of git forge
-    #[arg(long, visible_alias = "backend", value_enum, default_value_t = GitForgeKind::Github)]
-    forge: GitForgeKind,
+    #[command(flatten)]
+    pub lock: LockArgs,
+
+    #[command(flatten)]
+    pub gha_output: GhaOutputArgs,
 
-    /// Path to the k-releaser config file.
     #[command(flatten)]
-    pub config: ConfigPath,
+    pub gha_summary: GhaSummaryArgs,
 
     /// Output format. If specified, prints the version and the tag of the
     /// released packages.
@@ -48,70 +44,83 @@ pub struct Release {
 }
 
 impl Release {
-    /// Load the k-releaser configuration.
-    ///
-    /// If `--manifest-path` is specified but `--config` is not, load config from the manifest path.
-    pub fn load_config(&self) -> anyhow::Result<Config> {
-        if self.config.has_explicit_path() {
-            return self.config.load();
-        }
-        if let Some(manifest_path) = &self.manifest_path {
-            return self.config.load_from(manifest_path);
-        }
-        self.config.load()
-    }
-
     pub fn release_request(
-        self,
+        &self,
         config: &Config,
         metadata: cargo_metadata::Metadata,
     ) -> anyhow::Result<ReleaseRequest> {
-        let git_release = if let Some(git_token) = &self.git_token {
-            let git_token = SecretString::from(git_token.clone());
-            let repo_url = self.get_repo_url(config)?;
-            let release = k_releaser_core::GitRelease {
-                forge: match self.forge {
-                    GitForgeKind::Gitea => GitForge::Gitea(Gitea::new(repo_url, git_token)?),
-                    GitForgeKind::Github => {
-                        GitForge::Github(GitHub::new(repo_url.owner, repo_url.name, git_token))
-                    }
-                    GitForgeKind::Gitlab => GitForge::Gitlab(GitLab::new(repo_url, git_token)?),
-                },
-            };
-            Some(release)
-        } else {
-            None
-        };
-        let mut req = ReleaseRequest::new(metadata).with_dry_run(self.dry_run);
-
-        if let Some(repo_url) = self.repo_url {
-            req = req.with_repo_url(repo_url);
-        }
-        if let Some(git_release) = git_release {
-            req = req.with_git_release(git_release);
-        }
-        if let Some(release_always) = config.workspace.release_always {
-            req = req.with_release_always(release_always);
+        let mut req = release_request(&self.project, config, metadata)?
+            .with_dry_run(self.dry_run)
+            .with_lock_mode(self.lock.lock_mode())
+            .with_alias_tags(self.alias_tags || config.workspace.release_alias_tags);
+        if self.wait_for_checks || config.workspace.release_wait_for_checks {
+            req = req
+                .with_required_checks(config.workspace.required_checks.clone())
+                .with_wait_for_checks(config.workspace.checks_timeout()?);
+            if let Some(ci_job_name) = &config.workspace.ci_job_name {
+                req = req.with_ci_job_name(ci_job_name.clone());
+            }
         }
-
-        req = config.fill_release_config(false, false, req);
-
-        req = req.with_branch_prefix(config.workspace.pr_branch_prefix.clone());
-
         Ok(req)
     }
 }
 
-impl RepoCommand for Release {
-    fn repo_url(&self) -> Option<&str> {
-        self.repo_url.as_deref()
+/// Release request of the project built from the configuration alone,
+/// for the commands that act on past releases.
+pub fn release_request(
+    project: &ProjectArgs,
+    config: &Config,
+    metadata: cargo_metadata::Metadata,
+) -> anyhow::Result<ReleaseRequest> {
+    let git_release = if project.forge.has_git_credentials() {
+        let repo_url = project.get_repo_url(config)?;
+        project
+            .forge
+            .git_forge(repo_url)?
+            .map(|forge| k_releaser_core::GitRelease { forge })
+    } else {
+        None
+    };
+    let mut req = ReleaseRequest::new(metadata);
+
+    if let Some(git_remote) = project.user_git_remote(config) {
+        req = req.with_git_remote(git_remote);
     }
-}
+    req = req.with_fetch_depth_strategy(config.workspace.fetch_depth_strategy());
+    if let Some(repo_url) = project.repo_url() {
+        req = req.with_repo_url(repo_url.to_string());
+    }
+    if let Some(git_release) = git_release {
+        req = req.with_git_release(git_release);
+    }
+    if let Some(release_always) = config.workspace.release_always {
+        req = req.with_release_always(release_always);
+    }
+
+    req = config.fill_release_config(false, false, req)?;
 
-impl ManifestCommand for Release {
-    fn optional_manifest(&self) -> Option<&Path> {
-        self.manifest_path.as_deref()
+    req = req.with_branch_prefix(config.workspace.pr_branch_prefix.clone());
+    req = req.with_version_mode(config.workspace.version_mode());
+    if let Some(branch) = config.current_branch() {
+        req = req.with_branch(branch.to_string());
+    }
+    if let Some(workspace_tag_name) = config.workspace_tag_name() {
+        req = req.with_workspace_tag_name(workspace_tag_name.to_string());
+    }
+    req = req.with_hooks(config.workspace.hooks());
+    if let Some(sign_tags) = config.workspace.git_tag_sign {
+        req = req.with_sign_tags(sign_tags);
+    }
+    if let Some(signing_key) = &config.workspace.signing_key {
+        req = req.with_signing_key(signing_key);
+    }
+    if let Some(milestone) = config.workspace.milestone() {
+        req = req.with_milestone(milestone);
     }
+    req = req
+        .with_comment_issues(config.workspace.release_comment_issues)
+        .with_close_issues(config.workspace.release_close_issues);
+    Ok(req)
 }
 
 #[cfg(test)]
@@ -122,12 +131,13 @@ mod tests {
 
     fn default_args() -> Release {
         Release {
-            manifest_path: None,
+            project: ProjectArgs::default(),
             dry_run: false,
-            repo_url: None,
-            git_token: None,
-            forge: GitForgeKind::Github,
-            config: ConfigPath::default(),
+            lock: LockArgs::default(),
+            gha_output: GhaOutputArgs::default(),
+            gha_summary: GhaSummaryArgs::default(),
+            wait_for_checks: false,
+            alias_tags: false,
             output: None,
         }
     }
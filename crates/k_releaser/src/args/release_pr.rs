@@ -2,7 +2,10 @@ use k_releaser_core::ReleasePrRequest;
 
 use crate::config::Config;
 
-use super::{OutputType, update::Update};
+use super::{
+    OutputType, check_run::CheckRunArgs, gha_output::GhaOutputArgs, gha_summary::GhaSummaryArgs,
+    lock::LockArgs, update::Update,
+};
 
 #[derive(clap::Parser, Debug)]
 pub struct ReleasePr {
@@ -10,12 +13,32 @@ pub struct ReleasePr {
     pub update: Update,
     /// Output format. If specified, prints the branch, URL and number of
     /// the release PR, if any.
+    /// With `--dry-run`, prints the list of the PRs that would be opened, one for each
+    /// workspace of `workspace_roots`, with their title, body, version bumps and commits.
     #[arg(short, long, value_enum)]
     pub output: Option<OutputType>,
     /// Dry run mode. Calculate what the PR would contain but don't create it.
     /// Prints the PR title and body to stdout for debugging.
     #[arg(long)]
     pub dry_run: bool,
+    /// Close the opened release PR and delete its branch, then open a new one from the
+    /// current HEAD. Useful when the branch of the release PR diverged or was edited
+    /// into a broken state.
+    #[arg(long, conflicts_with = "dry_run")]
+    pub force_new: bool,
+    /// Update the opened release PR, but never open a new one.
+    /// Useful to keep the release PR fresh from a scheduled job, in repositories
+    /// where release PRs are opened manually.
+    #[arg(long, conflicts_with = "force_new")]
+    pub update_only: bool,
+    #[command(flatten)]
+    pub lock: LockArgs,
+    #[command(flatten)]
+    pub gha_output: GhaOutputArgs,
+    #[command(flatten)]
+    pub gha_summary: GhaSummaryArgs,
+    #[command(flatten)]
+    pub check_run: CheckRunArgs,
 }
 
 impl ReleasePr {
@@ -28,14 +51,31 @@ impl ReleasePr {
         let pr_name = config.workspace.pr_name.clone();
         let pr_body = config.workspace.pr_body.clone();
         let pr_labels = config.workspace.pr_labels.clone();
+        let pr_reviewers = config.workspace.pr_reviewers.clone();
+        let pr_assignees = config.workspace.pr_assignees.clone();
         let pr_draft = config.workspace.pr_draft;
         let update_request = self.update.update_request(config, cargo_metadata)?;
         let request = ReleasePrRequest::new(update_request)
             .mark_as_draft(pr_draft)
             .with_labels(pr_labels)
+            .with_reviewers(pr_reviewers)
+            .with_codeowners_reviewers(config.workspace.pr_reviewers_from_codeowners)
+            .with_assignees(pr_assignees)
+            .with_required_approvals(config.workspace.pr_required_approvals)
+            .with_auto_merge(config.workspace.pr_auto_merge.map(Into::into))
             .with_branch_prefix(pr_branch_prefix)
+            .with_branch_name_template(config.workspace.pr_branch_name.clone())
+            .with_milestone_template(config.workspace.pr_milestone.clone())
+            .with_project(config.workspace.pr_project.clone().map(Into::into))
             .with_pr_name_template(pr_name)
-            .with_pr_body_template(pr_body);
+            .with_pr_body_template(pr_body)
+            .with_pr_split(config.workspace.pr_split())
+            .with_update_strategy(config.workspace.pr_update_strategy())
+            .with_signed_commits(config.workspace.pr_signed_commits)
+            .with_lock_mode(self.lock.lock_mode())
+            .with_check_run(self.check_run.is_enabled())
+            .with_force_new(self.force_new)
+            .with_update_only(self.update_only);
         Ok(request)
     }
 }
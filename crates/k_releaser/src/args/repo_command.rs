@@ -9,13 +9,19 @@ use super::manifest_command::ManifestCommand;
 pub trait RepoCommand: ManifestCommand {
     fn repo_url(&self) -> Option<&str>;
 
+    /// Git remote specified via cli argument.
+    fn git_remote(&self) -> Option<&str>;
+
     fn get_repo_url(&self, config: &Config) -> anyhow::Result<RepoUrl> {
         match &self.user_repo_url(config) {
             Some(url) => RepoUrl::new(url),
             None => {
                 let manifest_path = self.manifest_path();
                 let project_dir = k_releaser_core::manifest_dir(&manifest_path)?;
-                let repo = Repo::new(project_dir)?;
+                let mut repo = Repo::new(project_dir)?;
+                if let Some(remote) = self.user_git_remote(config) {
+                    repo = repo.with_remote(remote);
+                }
                 RepoUrl::from_repo(&repo)
             }
         }
@@ -26,4 +32,9 @@ pub trait RepoCommand: ManifestCommand {
         self.repo_url()
             .or_else(|| config.workspace.repo_url.as_ref().map(|u| u.as_str()))
     }
+
+    /// Git remote specified by user
+    fn user_git_remote<'a>(&'a self, config: &'a Config) -> Option<&'a str> {
+        self.git_remote().or(config.workspace.git_remote.as_deref())
+    }
 }
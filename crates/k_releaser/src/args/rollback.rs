@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
+use k_releaser_core::RollbackRequest;
+
+use crate::config::Config;
+
+use super::{OutputType, project::ProjectArgs, registry_token, release::release_request};
+
+#[derive(clap::Parser, Debug)]
+pub struct Rollback {
+    /// Version (e.g. `1.2.3`) or git tag (e.g. `v1.2.3`) of the release to undo.
+    #[arg(value_name = "VERSION_OR_TAG", value_parser = NonEmptyStringValueParser::new())]
+    pub target: String,
+
+    #[command(flatten)]
+    pub project: ProjectArgs,
+
+    /// Print what would be undone, without deleting tags and releases.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Output format. If specified, prints the undone tags and releases.
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputType>,
+
+    /// Yank the released version of the packages from the cargo registry.
+    #[arg(long)]
+    pub yank: bool,
+
+    /// Registry where the packages are published.
+    /// The registry name needs to be present in the Cargo config.
+    /// If unspecified, crates.io is used.
+    #[arg(long, requires("yank"), value_parser = NonEmptyStringValueParser::new())]
+    registry: Option<String>,
+
+    /// Token used to yank the packages from the cargo registry.
+    /// Override the `CARGO_REGISTRY_TOKEN` environment variable, or the `CARGO_REGISTRIES_<NAME>_TOKEN`
+    /// environment variable, used for registry specified in the `registry` input variable.
+    #[arg(long, requires("yank"), value_parser = NonEmptyStringValueParser::new())]
+    token: Option<String>,
+
+    /// File containing the token used to yank the packages from the cargo registry.
+    /// Use `-` to read it from stdin.
+    #[arg(long, requires("yank"), value_parser = PathBufValueParser::new(), conflicts_with = "token")]
+    registry_token_file: Option<PathBuf>,
+}
+
+impl Rollback {
+    pub fn rollback_request(
+        self,
+        config: &Config,
+        metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<RollbackRequest> {
+        let release_request =
+            release_request(&self.project, config, metadata)?.with_dry_run(self.dry_run);
+        let mut req = RollbackRequest::new(release_request, self.target).with_yank(self.yank);
+        if self.yank
+            && let Some(token) = registry_token(
+                self.token.as_deref(),
+                self.registry_token_file.as_deref(),
+                self.registry.as_deref(),
+            )?
+        {
+            req = req.with_token(token);
+        }
+        if let Some(registry) = self.registry {
+            req = req.with_registry(registry);
+        }
+        Ok(req)
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use cargo_metadata::semver::Version;
+use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
+use k_releaser_core::SetVersionRequest;
+
+use crate::config::Config;
+
+use super::{project::ProjectArgs, update::UpdateOptions};
+
+#[derive(clap::Parser, Debug)]
+pub struct SetVersion {
+    /// Version to set, e.g. `2.0.0`.
+    pub version: Version,
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Package to set the version of. If not provided, the version of every
+    /// package of the workspace is set.
+    #[arg(short, long, value_parser = NonEmptyStringValueParser::new())]
+    pub package: Option<String>,
+    /// Update all the dependencies in the Cargo.lock file by running `cargo update`.
+    /// If this flag is not specified, only update the workspace packages by running `cargo update --workspace`.
+    #[arg(short, long)]
+    pub update_deps: bool,
+    /// Add a changelog entry for the new version containing this message.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    pub changelog_entry: Option<String>,
+    /// Date of the changelog entry. Format: %Y-%m-%d. It defaults to current Utc date.
+    #[arg(
+        long,
+        requires("changelog_entry"),
+        value_parser = NonEmptyStringValueParser::new()
+    )]
+    pub release_date: Option<String>,
+    /// Path to the git-cliff configuration file used to render the changelog entry.
+    /// If not provided, `dirs::config_dir()/git-cliff/cliff.toml` is used if present.
+    #[arg(
+        long,
+        env = "GIT_CLIFF_CONFIG",
+        value_name = "PATH",
+        value_parser = PathBufValueParser::new()
+    )]
+    pub changelog_config: Option<PathBuf>,
+}
+
+impl SetVersion {
+    pub fn set_version_request(
+        &self,
+        config: &Config,
+        cargo_metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<SetVersionRequest> {
+        let options = UpdateOptions {
+            package: self.package.clone(),
+            update_deps: self.update_deps,
+            release_date: self.release_date.clone(),
+            changelog_config: self.changelog_config.clone(),
+            ..Default::default()
+        };
+        let update_request = options.update_request(&self.project, config, cargo_metadata)?;
+        let mut request = SetVersionRequest::new(update_request, self.version.clone());
+        if let Some(changelog_entry) = &self.changelog_entry {
+            request = request.with_changelog_entry(changelog_entry.clone());
+        }
+        Ok(request)
+    }
+}
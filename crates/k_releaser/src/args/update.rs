@@ -3,19 +3,18 @@ use std::path::{Path, PathBuf};
 use anyhow::Context;
 use cargo_metadata::camino::Utf8Path;
 use chrono::NaiveDate;
-use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
-use git_cliff_core::config::Config as GitCliffConfig;
-use k_releaser_core::{
-    ChangelogRequest, GitForge, GitHub, GitLab, Gitea, RepoUrl, fs_utils::to_utf8_path,
-    update_request::UpdateRequest,
+use clap::{
+    ValueEnum,
+    builder::{NonEmptyStringValueParser, PathBufValueParser},
 };
-use secrecy::SecretString;
+use git_cliff_core::config::Config as GitCliffConfig;
+use k_releaser_core::{ChangelogRequest, fs_utils::to_utf8_path, update_request::UpdateRequest};
+use next_version::VersionIncrement;
 
 use crate::{changelog_config, config::Config};
 
 use super::{
-    GitForgeKind, config_path::ConfigPath, manifest_command::ManifestCommand,
-    repo_command::RepoCommand,
+    manifest_command::ManifestCommand as _, project::ProjectArgs, repo_command::RepoCommand as _,
 };
 
 /// Update your project locally, without opening a PR.
@@ -23,12 +22,16 @@ use super::{
 /// link in the changelog.
 #[derive(clap::Parser, Debug)]
 pub struct Update {
-    /// Path to the Cargo.toml of the project you want to update.
-    /// If not provided, k-releaser will use the Cargo.toml of the current directory.
-    /// Both Cargo workspaces and single packages are supported.
-    #[arg(long, value_parser = PathBufValueParser::new(), alias = "project-manifest")]
-    manifest_path: Option<PathBuf>,
+    #[command(flatten)]
+    pub project: ProjectArgs,
 
+    #[command(flatten)]
+    pub options: UpdateOptions,
+}
+
+/// Options of the commands that update the project: `update` and `release-pr`.
+#[derive(clap::Args, Debug, Default)]
+pub struct UpdateOptions {
     /// Path to the Cargo.toml contained in the released version of the project you want to update.
     /// If not provided, the packages of your project will be compared with the
     /// ones published in the cargo registry.
@@ -37,7 +40,7 @@ pub struct Update {
     /// For example, it could be the path to the project with a `git checkout` on its latest tag.
     /// The git history of this project should be behind the one of the project you want to update.
     #[arg(long, value_parser = PathBufValueParser::new(), alias = "registry-project-manifest")]
-    registry_manifest_path: Option<PathBuf>,
+    pub registry_manifest_path: Option<PathBuf>,
 
     /// Package to update. Use it when you want to update a single package rather than all the
     /// packages contained in the workspace.
@@ -46,11 +49,11 @@ pub struct Update {
         long,
         value_parser = NonEmptyStringValueParser::new()
     )]
-    package: Option<String>,
+    pub package: Option<String>,
 
     /// Don't create/update changelog.
     #[arg(long, conflicts_with("release_date"))]
-    no_changelog: bool,
+    pub no_changelog: bool,
 
     /// Date of the release. Format: %Y-%m-%d. It defaults to current Utc date.
     #[arg(
@@ -58,7 +61,7 @@ pub struct Update {
         conflicts_with("no_changelog"),
         value_parser = NonEmptyStringValueParser::new()
     )]
-    release_date: Option<String>,
+    pub release_date: Option<String>,
 
     /// Registry where the packages are stored.
     /// The registry name needs to be present in the Cargo config.
@@ -69,12 +72,12 @@ pub struct Update {
         conflicts_with("registry_manifest_path"),
         value_parser = NonEmptyStringValueParser::new()
     )]
-    registry: Option<String>,
+    pub registry: Option<String>,
 
     /// Update all the dependencies in the Cargo.lock file by running `cargo update`.
     /// If this flag is not specified, only update the workspace packages by running `cargo update --workspace`.
     #[arg(short, long)]
-    update_deps: bool,
+    pub update_deps: bool,
 
     /// Path to the git-cliff configuration file.
     /// If not provided, `dirs::config_dir()/git-cliff/cliff.toml` is used if present.
@@ -85,88 +88,70 @@ pub struct Update {
         conflicts_with("no_changelog"),
         value_parser = PathBufValueParser::new()
     )]
-    changelog_config: Option<PathBuf>,
+    pub changelog_config: Option<PathBuf>,
 
     /// Allow dirty working directories to be updated.
     /// The uncommitted changes will be part of the update.
     #[arg(long)]
-    allow_dirty: bool,
-
-    /// GitHub/Gitea repository url where your project is hosted.
-    /// It is used to generate the changelog release link.
-    /// It defaults to the url of the default remote.
-    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
-    repo_url: Option<String>,
-
-    /// Path to the k-releaser config file.
-    #[command(flatten)]
-    pub config: ConfigPath,
+    pub allow_dirty: bool,
 
-    /// Git token used to create the pull request.
-    #[arg(long, value_parser = NonEmptyStringValueParser::new(), visible_alias = "github-token", env = "GITHUB_TOKEN", hide_env_values=true)]
-    pub git_token: Option<String>,
+    /// Determine the commits of each package by reading the git log, instead of checking out
+    /// each previous commit. Faster in large repositories, and it doesn't download
+    /// missing files in partial clones and sparse checkouts.
+    /// The files of a package are approximated with its directory, excluding nested packages.
+    #[arg(long)]
+    pub no_checkout_diff: bool,
 
-    /// Kind of git host where your project is hosted.
-    #[arg(long, visible_alias = "backend", value_enum, default_value_t = GitForgeKind::Github)]
-    forge: GitForgeKind,
     /// Maximum number of commits to analyze when the package hasn't been published yet.
     /// Default: 1000.
     #[arg(long)]
-    max_analyze_commits: Option<u32>,
+    pub max_analyze_commits: Option<u32>,
+
+    /// Force the version increment instead of determining it from the commits.
+    /// The changelog is still generated from the commits since the last tag.
+    #[arg(long, value_enum)]
+    pub bump: Option<Bump>,
+
+    /// Release a pre-release version of this channel, e.g. `beta` -> `1.4.0-beta.1`.
+    /// Subsequent pre-releases on the same version increment the number, e.g. `1.4.0-beta.2`.
+    /// Overrides the `prerelease_label` configuration.
+    #[arg(long, value_name = "LABEL", value_parser = NonEmptyStringValueParser::new())]
+    pub prerelease: Option<String>,
 }
 
-impl RepoCommand for Update {
-    fn repo_url(&self) -> Option<&str> {
-        self.repo_url.as_deref()
-    }
+/// Part of the version to increment.
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+    /// Increment the pre-release identifier, e.g. `1.0.0-rc.1` -> `1.0.0-rc.2`.
+    Prerelease,
 }
 
-impl ManifestCommand for Update {
-    fn optional_manifest(&self) -> Option<&Path> {
-        self.manifest_path.as_deref()
+impl From<Bump> for VersionIncrement {
+    fn from(bump: Bump) -> Self {
+        match bump {
+            Bump::Major => Self::Major,
+            Bump::Minor => Self::Minor,
+            Bump::Patch => Self::Patch,
+            Bump::Prerelease => Self::Prerelease,
+        }
     }
 }
 
 impl Update {
-    /// Load the k-releaser configuration.
-    ///
-    /// This method handles config loading correctly when `--manifest-path` is specified:
-    /// 1. If `--config` is explicitly specified, load from that path
-    /// 2. Otherwise, if `--manifest-path` is specified, load config from that Cargo.toml
-    /// 3. Otherwise, use the default behavior (load from `./Cargo.toml`)
-    pub fn load_config(&self) -> anyhow::Result<Config> {
-        // If explicit config path is specified, use ConfigPath::load()
-        if self.config.has_explicit_path() {
-            return self.config.load();
-        }
-
-        // If manifest_path is specified, load config from that Cargo.toml
-        if let Some(manifest_path) = &self.manifest_path {
-            return self.config.load_from(manifest_path);
-        }
-
-        // Default: load from ./Cargo.toml
-        self.config.load()
-    }
-
-    pub fn git_forge(&self, repo: RepoUrl) -> anyhow::Result<Option<GitForge>> {
-        let Some(token) = self.git_token.clone() else {
-            return Ok(None);
-        };
-        let token = SecretString::from(token);
-        Ok(Some(match self.forge {
-            GitForgeKind::Github => {
-                anyhow::ensure!(
-                    repo.is_on_github(),
-                    "Can't create PR: the repository is not hosted in GitHub. Please select a different forge."
-                );
-                GitForge::Github(GitHub::new(repo.owner, repo.name, token))
-            }
-            GitForgeKind::Gitea => GitForge::Gitea(Gitea::new(repo, token)?),
-            GitForgeKind::Gitlab => GitForge::Gitlab(GitLab::new(repo, token)?),
-        }))
+    pub fn update_request(
+        &self,
+        config: &Config,
+        cargo_metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<UpdateRequest> {
+        self.options
+            .update_request(&self.project, config, cargo_metadata)
     }
+}
 
+impl UpdateOptions {
     fn dependencies_update(&self, config: &Config) -> bool {
         self.update_deps || config.workspace.dependencies_update == Some(true)
     }
@@ -180,12 +165,14 @@ impl Update {
             .or(config.workspace.max_analyze_commits)
     }
 
+    /// Build the update request of the project with these options.
     pub fn update_request(
         &self,
+        project: &ProjectArgs,
         config: &Config,
         cargo_metadata: cargo_metadata::Metadata,
     ) -> anyhow::Result<UpdateRequest> {
-        let project_manifest = self.manifest_path();
+        let project_manifest = project.manifest_path();
         check_if_cargo_lock_is_ignored_and_committed(&project_manifest)?;
         let mut update = UpdateRequest::new(cargo_metadata)
             .with_context(|| {
@@ -193,8 +180,21 @@ impl Update {
             })?
             .with_dependencies_update(self.dependencies_update(config))
             .with_max_analyze_commits(self.max_analyze_commits(config))
-            .with_allow_dirty(self.allow_dirty(config));
-        match self.get_repo_url(config) {
+            .with_allow_dirty(self.allow_dirty(config))
+            .with_no_checkout_diff(self.no_checkout_diff)
+            .with_workspace_scoped(project.workspace_root().is_some())
+            .with_version_mode(config.workspace.version_mode())
+            .with_fetch_depth_strategy(config.workspace.fetch_depth_strategy());
+        if let Some(branch) = config.current_branch() {
+            update = update.with_branch(branch.to_string());
+        }
+        if let Some(workspace_tag_name) = config.workspace_tag_name() {
+            update = update.with_workspace_tag_name(workspace_tag_name.to_string());
+        }
+        if let Some(git_remote) = project.user_git_remote(config) {
+            update = update.with_git_remote(git_remote.to_string());
+        }
+        match project.get_repo_url(config) {
             Ok(repo_url) => {
                 update = update.with_repo_url(repo_url);
             }
@@ -212,7 +212,7 @@ impl Update {
                     format!("cannot find project manifest {registry_manifest_path:?}")
                 })?;
         }
-        update = config.fill_update_config(self.no_changelog, update);
+        update = config.fill_update_config(self.no_changelog, update)?;
         {
             let release_date = self
                 .release_date
@@ -226,6 +226,7 @@ impl Update {
             let changelog_req = ChangelogRequest {
                 release_date,
                 changelog_config: Some(self.changelog_config(config, pr_link.as_deref())?),
+                group_by_package: config.workspace.changelog_group_by_package,
             };
             update = update.with_changelog_req(changelog_req);
         }
@@ -235,11 +236,31 @@ impl Update {
         if let Some(registry) = &self.registry {
             update = update.with_registry(registry.clone());
         }
+        if let Some(bump) = self.bump {
+            anyhow::ensure!(
+                !config.patch_only() || matches!(bump, Bump::Patch | Bump::Prerelease),
+                "`--bump major` and `--bump minor` aren't allowed because the configuration of the current branch only allows patch releases"
+            );
+            update = update.with_bump(bump.into());
+        }
+        update = update.with_patch_only(config.patch_only());
+        if let Some(prerelease_label) = self
+            .prerelease
+            .as_ref()
+            .or(config.workspace.prerelease_label.as_ref())
+        {
+            update = update.with_prerelease_label(prerelease_label.clone());
+        }
+        update = update.with_hooks(config.workspace.hooks());
+        update = update.with_extra_version_files(config.workspace.extra_version_files()?);
         if let Some(release_commits) = &config.workspace.release_commits {
             update = update.with_release_commits(release_commits)?;
         }
+        if !config.workspace.release_ignore_paths.is_empty() {
+            update = update.with_release_ignore_paths(&config.workspace.release_ignore_paths)?;
+        }
         if let Some(repo) = update.repo_url()
-            && let Some(git_client) = self.git_forge(repo.clone())?
+            && let Some(git_client) = project.forge.git_forge(repo.clone())?
         {
             update = update.with_git_client(git_client);
         }
@@ -316,22 +337,10 @@ mod tests {
     #[test]
     fn input_generates_correct_release_request() {
         let update_args = Update {
-            manifest_path: None,
-            registry_manifest_path: None,
-            package: None,
-            no_changelog: false,
-            release_date: None,
-            registry: None,
-            update_deps: false,
-            changelog_config: None,
-            allow_dirty: false,
-            repo_url: None,
-            config: ConfigPath::default(),
-            forge: GitForgeKind::Github,
-            git_token: None,
-            max_analyze_commits: None,
+            project: ProjectArgs::default(),
+            options: UpdateOptions::default(),
         };
-        let config = update_args.config.load().unwrap();
+        let config = update_args.project.config.load().unwrap();
         let req = update_args
             .update_request(&config, fake_metadata())
             .unwrap();
@@ -0,0 +1,19 @@
+use super::{check_run::CheckRunArgs, gha_summary::GhaSummaryArgs, update::Update};
+
+#[derive(clap::Parser, Debug)]
+pub struct UpdateCommand {
+    #[command(flatten)]
+    pub update: Update,
+    /// Compute the update without changing the project, and print the version bumps.
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Print the unified diff of the files that the update would change,
+    /// e.g. `Cargo.toml`, `Cargo.lock` and `CHANGELOG.md`.
+    #[arg(long, requires = "dry_run")]
+    pub diff: bool,
+
+    #[command(flatten)]
+    pub gha_summary: GhaSummaryArgs,
+    #[command(flatten)]
+    pub check_run: CheckRunArgs,
+}
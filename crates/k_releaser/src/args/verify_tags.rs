@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use clap::builder::{NonEmptyStringValueParser, PathBufValueParser};
+use k_releaser_core::VerifyTagsRequest;
+
+use crate::config::Config;
+
+use super::{OutputType, project::ProjectArgs, registry_token, release::release_request};
+
+#[derive(clap::Parser, Debug)]
+pub struct VerifyTags {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+
+    /// Output format. If specified, prints the inconsistencies found.
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputType>,
+
+    /// Registry where the packages are published.
+    /// The registry name needs to be present in the Cargo config.
+    /// If unspecified, the `publish` field of the package manifest is used.
+    /// If the `publish` field is empty, crates.io is used.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    registry: Option<String>,
+
+    /// Token used to read the cargo registry.
+    /// Override the `CARGO_REGISTRY_TOKEN` environment variable, or the `CARGO_REGISTRIES_<NAME>_TOKEN`
+    /// environment variable, used for registry specified in the `registry` input variable.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    token: Option<String>,
+
+    /// File containing the token used to read the cargo registry.
+    /// Use `-` to read it from stdin.
+    #[arg(long, value_parser = PathBufValueParser::new(), conflicts_with = "token")]
+    registry_token_file: Option<PathBuf>,
+}
+
+impl VerifyTags {
+    pub fn verify_tags_request(
+        self,
+        config: &Config,
+        metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<VerifyTagsRequest> {
+        let release_request = release_request(&self.project, config, metadata)?;
+        let mut req = VerifyTagsRequest::new(release_request);
+        if let Some(token) = registry_token(
+            self.token.as_deref(),
+            self.registry_token_file.as_deref(),
+            self.registry.as_deref(),
+        )? {
+            req = req.with_token(token);
+        }
+        if let Some(registry) = self.registry {
+            req = req.with_registry(registry);
+        }
+        Ok(req)
+    }
+}
@@ -0,0 +1,137 @@
+//! Tokens stored in the system keyring.
+
+use std::io::{BufRead, IsTerminal as _};
+
+use anyhow::Context as _;
+use secrecy::SecretString;
+use tracing::{debug, info};
+
+use crate::args::{
+    CRATES_IO, GitForgeKind,
+    auth::{AuthSubcommand, AuthTarget},
+    registry_token_env_var,
+};
+
+const KEYRING_SERVICE: &str = "k-releaser";
+
+pub fn auth(subcommand: &AuthSubcommand) -> anyhow::Result<()> {
+    match subcommand {
+        AuthSubcommand::Login(target) => {
+            let name = target_name(target)?;
+            let token = if std::io::stdin().is_terminal() {
+                rpassword::prompt_password(format!("Token for {name}: "))
+                    .context("can't read token")?
+            } else {
+                read_token(std::io::stdin().lock())?
+            };
+            let token = token.trim();
+            anyhow::ensure!(!token.is_empty(), "token is empty");
+            entry(&name)?
+                .set_password(token)
+                .context("can't store token in the system keyring")?;
+            info!("stored token for {name} in the system keyring");
+        }
+        AuthSubcommand::Logout(target) => {
+            let name = target_name(target)?;
+            entry(&name)?
+                .delete_credential()
+                .context("can't delete token from the system keyring")?;
+            info!("deleted token for {name} from the system keyring");
+        }
+    }
+    Ok(())
+}
+
+/// Git token of the forge stored in the system keyring.
+pub fn forge_token(forge: GitForgeKind) -> Option<SecretString> {
+    stored_token(&forge_name(forge))
+}
+
+/// Cargo registry token stored in the system keyring.
+/// If `registry` is [`Option::None`], crates.io is used.
+///
+/// Returns [`Option::None`] if the token is set via the cargo environment variables,
+/// so that they take precedence.
+pub fn registry_token(registry: Option<&str>) -> Option<SecretString> {
+    let registry = registry.unwrap_or(CRATES_IO);
+    if std::env::var_os(registry_token_env_var(registry)).is_some() {
+        return None;
+    }
+    stored_token(&registry_name(registry))
+}
+
+fn stored_token(name: &str) -> Option<SecretString> {
+    // The keyring isn't available in most CI environments, so errors aren't fatal.
+    match entry(name).and_then(|e| e.get_password().context("can't read keyring")) {
+        Ok(token) => Some(token.into()),
+        Err(e) => {
+            debug!("no token for {name} in the system keyring: {e:?}");
+            None
+        }
+    }
+}
+
+fn entry(name: &str) -> anyhow::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, name).context("can't access the system keyring")
+}
+
+/// Read the token from the first line of `reader`, e.g. piped to stdin.
+fn read_token(mut reader: impl BufRead) -> anyhow::Result<String> {
+    let mut token = String::new();
+    reader
+        .read_line(&mut token)
+        .context("can't read token from stdin")?;
+    Ok(token)
+}
+
+fn target_name(target: &AuthTarget) -> anyhow::Result<String> {
+    match (&target.forge, &target.registry) {
+        (Some(forge), None) => Ok(forge_name(*forge)),
+        (None, Some(registry)) => Ok(registry_name(registry)),
+        _ => anyhow::bail!("pass either `--forge` or `--registry`"),
+    }
+}
+
+fn forge_name(forge: GitForgeKind) -> String {
+    let forge = match forge {
+        GitForgeKind::Github => "github",
+        GitForgeKind::Gitea => "gitea",
+        GitForgeKind::Forgejo => "forgejo",
+        GitForgeKind::Gitlab => "gitlab",
+    };
+    format!("forge:{forge}")
+}
+
+fn registry_name(registry: &str) -> String {
+    format!("registry:{registry}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_read_from_the_first_line() {
+        let token = read_token("my-token\nnext line\n".as_bytes()).unwrap();
+        assert_eq!(token.trim(), "my-token");
+    }
+
+    #[test]
+    fn keyring_entries_are_named_after_the_target() {
+        let forge = AuthTarget {
+            forge: Some(GitForgeKind::Gitlab),
+            registry: None,
+        };
+        assert_eq!(target_name(&forge).unwrap(), "forge:gitlab");
+        let registry = AuthTarget {
+            forge: None,
+            registry: Some("crates-io".to_string()),
+        };
+        assert_eq!(target_name(&registry).unwrap(), "registry:crates-io");
+        let none = AuthTarget {
+            forge: None,
+            registry: None,
+        };
+        assert!(target_name(&none).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+use anyhow::Context as _;
+
+use crate::args::changelog::ShowChangelog;
+
+/// Print the notes of the requested version of the changelog.
+pub fn show_changelog(args: &ShowChangelog) -> anyhow::Result<()> {
+    let changelog_path = args.changelog_path()?;
+    let changelog = fs_err::read_to_string(&changelog_path)?;
+    let release = k_releaser_core::release_from_str(&changelog, &args.version)
+        .with_context(|| format!("can't parse changelog {changelog_path}"))?
+        .with_context(|| format!("version {} not found in {changelog_path}", args.version))?;
+    if args.title {
+        println!("## {}\n", release.title());
+    }
+    println!("{}", release.notes());
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! Lint of commit messages against the conventional commits used to compute the next version.
+
+use anyhow::Context as _;
+use git_cliff_core::config::Config as GitCliffConfig;
+use git_cmd::Repo;
+use regex::Regex;
+use tracing::{debug, error, info};
+
+use crate::args::{check_commit::CheckCommit, manifest_command::ManifestCommand as _};
+
+/// Commit types of the conventional commits specification.
+const CONVENTIONAL_TYPES: [&str; 11] = [
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "chore", "ci", "build", "revert",
+];
+
+/// Check the commit messages of the range or of the message file.
+/// Fails if any message isn't a valid conventional commit.
+pub fn check_commits(args: &CheckCommit) -> anyhow::Result<()> {
+    let config = args.project.load_config()?;
+    let cargo_metadata = args.project.cargo_metadata()?;
+    let request = args.update_request(&config, cargo_metadata)?;
+    let custom_types = custom_types(request.changelog_req().changelog_config.as_ref());
+
+    let messages = match (&args.range, &args.message_file) {
+        (Some(range), _) => {
+            let repo = Repo::new(request.local_manifest_dir()?)?;
+            range_messages(&repo, range)?
+        }
+        (None, Some(message_file)) => {
+            let message = fs_err::read_to_string(message_file)?;
+            vec![("message".to_string(), strip_comments(&message))]
+        }
+        (None, None) => anyhow::bail!("pass either --range or --message-file"),
+    };
+
+    let mut invalid = 0;
+    for (commit, message) in &messages {
+        match check_message(message, &custom_types) {
+            Ok(()) => debug!("{commit}: valid commit message"),
+            Err(e) => {
+                invalid += 1;
+                error!("{commit}: {e:#}");
+            }
+        }
+    }
+    anyhow::ensure!(
+        invalid == 0,
+        "{invalid} of {} commit messages don't follow the conventional commits specification",
+        messages.len()
+    );
+    info!("{} commit messages checked", messages.len());
+    Ok(())
+}
+
+/// Commit parsers of the changelog configuration, used to accept custom commit types.
+/// Parsers matching any message, like `.*`, are ignored.
+fn custom_types(changelog_config: Option<&GitCliffConfig>) -> Vec<Regex> {
+    changelog_config
+        .map(|c| {
+            c.git
+                .commit_parsers
+                .iter()
+                .filter_map(|p| p.message.clone())
+                .filter(|r| !r.is_match(""))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Short hash and message of the commits of the range.
+fn range_messages(repo: &Repo, range: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let output = repo
+        .git(&["log", range, "--format=%h%n%B%n--END-COMMIT--"])
+        .with_context(|| format!("can't read the commits of {range}"))?;
+    let messages = output
+        .split("--END-COMMIT--")
+        .filter_map(|commit| {
+            let (hash, message) = commit.trim().split_once('\n')?;
+            Some((hash.to_string(), message.to_string()))
+        })
+        .collect();
+    Ok(messages)
+}
+
+/// Remove the lines that git ignores in the message file.
+fn strip_comments(message: &str) -> String {
+    message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn check_message(message: &str, custom_types: &[Regex]) -> anyhow::Result<()> {
+    let message = message.trim();
+    // Messages generated by git.
+    if message.starts_with("Merge ") || message.starts_with("Revert \"") {
+        return Ok(());
+    }
+    let commit = git_conventional::Commit::parse(message)
+        .map_err(|e| anyhow::anyhow!("not a conventional commit: {e}"))?;
+    let commit_type = commit.type_().as_str().to_lowercase();
+    let subject = message.lines().next().unwrap_or_default();
+    anyhow::ensure!(
+        CONVENTIONAL_TYPES.contains(&commit_type.as_str())
+            || custom_types.iter().any(|r| r.is_match(subject)),
+        "unknown commit type `{commit_type}`: use one of {} or add a commit parser for it to the changelog configuration",
+        CONVENTIONAL_TYPES.join(", ")
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conventional_commits_are_valid() {
+        assert!(check_message("feat: add check-commit", &[]).is_ok());
+        assert!(check_message("fix(core)!: handle empty tags\n\nbody", &[]).is_ok());
+        assert!(check_message("Merge branch 'main' into feature", &[]).is_ok());
+    }
+
+    #[test]
+    fn non_conventional_commits_are_invalid() {
+        assert!(check_message("add check-commit", &[]).is_err());
+        assert!(check_message("feature: add check-commit", &[]).is_err());
+    }
+
+    #[test]
+    fn custom_types_are_valid() {
+        let custom_types = [Regex::new("^security").unwrap()];
+        assert!(check_message("security: rotate keys", &custom_types).is_ok());
+    }
+
+    #[test]
+    fn comments_are_stripped() {
+        let message = "feat: add check-commit\n# Please enter the commit message\n";
+        assert_eq!(strip_comments(message), "feat: add check-commit");
+    }
+}
@@ -1,13 +1,17 @@
 use anyhow::Context as _;
-use cargo_metadata::camino::Utf8Path;
-use cargo_utils::to_utf8_pathbuf;
+use cargo_metadata::{Metadata, camino::Utf8Path};
+use cargo_utils::{CARGO_TOML, to_utf8_pathbuf};
 use k_releaser_core::{
-    GitReleaseConfig, PublishRequest, ReleaseRequest,
+    DEFAULT_BRANCH_PREFIX, GitReleaseConfig, Hooks, PublishRequest, ReleaseRequest,
     fs_utils::to_utf8_path,
     update_request::{DEFAULT_MAX_ANALYZE_COMMITS, UpdateRequest},
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use url::Url;
 
 use crate::changelog_config::ChangelogCfg;
@@ -28,9 +32,177 @@ pub struct Config {
     /// Not all settings of `workspace` can be overridden.
     #[serde(default)]
     package: Vec<PackageSpecificConfigWithName>,
+    /// # Registry
+    /// Registry-specific configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    registry: Vec<RegistryConfig>,
+    /// # Branch
+    /// Configuration of maintenance branches, e.g. `[branch."release/1.x"]`.
+    /// It's applied when k-releaser runs on the branch and overrides `workspace`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    branch: BTreeMap<String, BranchConfig>,
+    /// Branch k-releaser runs on, if known.
+    #[serde(skip)]
+    current_branch: Option<String>,
+    /// Template of the unified git tag of the workspace root k-releaser runs on, if any.
+    #[serde(skip)]
+    root_tag_name: Option<String>,
 }
 
+/// Prefix of the environment variables that override the `[workspace]` config,
+/// e.g. `K_RELEASER_PR_DRAFT=true`.
+const ENV_OVERRIDE_PREFIX: &str = "K_RELEASER_";
+/// Environment variables with the [`ENV_OVERRIDE_PREFIX`] that aren't config overrides.
+const NON_CONFIG_ENV_VARS: [&str; 2] = ["K_RELEASER_LOG", "K_RELEASER_TOKEN"];
+
 impl Config {
+    /// Override the `[workspace]` fields with the `K_RELEASER_<FIELD>` environment variables.
+    pub fn with_env_overrides(self) -> anyhow::Result<Self> {
+        self.with_overrides(std::env::vars())
+    }
+
+    /// Override the `[workspace]` fields with the given environment variables.
+    /// Values are parsed as TOML, e.g. `true`, `10` or `["a", "b"]`.
+    /// Values that aren't valid TOML are read as strings.
+    /// Variables that don't match any field are ignored with a warning, because CI
+    /// environments may define other `K_RELEASER_*` variables.
+    fn with_overrides(
+        mut self,
+        env_vars: impl IntoIterator<Item = (String, String)>,
+    ) -> anyhow::Result<Self> {
+        let mut overrides: Vec<(String, String)> = env_vars
+            .into_iter()
+            .filter(|(name, _)| {
+                name.starts_with(ENV_OVERRIDE_PREFIX)
+                    && !NON_CONFIG_ENV_VARS.contains(&name.as_str())
+            })
+            .collect();
+        if overrides.is_empty() {
+            return Ok(self);
+        }
+        // Apply the overrides in a deterministic order.
+        overrides.sort();
+        let mut workspace =
+            toml::Table::try_from(&self.workspace).context("can't serialize workspace config")?;
+        for (name, value) in overrides {
+            let field = name[ENV_OVERRIDE_PREFIX.len()..].to_lowercase();
+            let mut overridden = workspace.clone();
+            overridden.insert(field, parse_env_value(&value));
+            match overridden.clone().try_into() {
+                Ok(overridden_workspace) => {
+                    self.workspace = overridden_workspace;
+                    workspace = overridden;
+                    tracing::info!(
+                        "workspace config overridden by the {name} environment variable"
+                    );
+                }
+                Err(e) if e.message().starts_with("unknown field") => {
+                    tracing::warn!(
+                        "ignoring the {name} environment variable: it doesn't match any field of the workspace config"
+                    );
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!("invalid config override in the {name} environment variable")
+                    });
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Record the current branch and apply its `[branch."<branch>"]` configuration, if any.
+    pub fn with_branch_overrides(mut self, branch: &str) -> Self {
+        self.current_branch = Some(branch.to_string());
+        let Some(branch_config) = self.branch.get(branch).cloned() else {
+            return self;
+        };
+        tracing::info!("applying the configuration of branch {branch}");
+        if let Some(git_tag_name) = branch_config.git_tag_name {
+            self.workspace.packages_defaults.git_tag_name = Some(git_tag_name);
+        }
+        if let Some(git_release_latest) = branch_config.git_release_latest {
+            self.workspace.packages_defaults.git_release_latest = Some(git_release_latest);
+        }
+        let pr_branch_prefix = branch_config.pr_branch_prefix.unwrap_or_else(|| {
+            // Release PRs of different branches must not share the prefix,
+            // otherwise they close each other.
+            let prefix = self
+                .workspace
+                .pr_branch_prefix
+                .as_deref()
+                .unwrap_or(DEFAULT_BRANCH_PREFIX);
+            format!("{}-{prefix}", branch.replace('/', "-"))
+        });
+        self.workspace.pr_branch_prefix = Some(pr_branch_prefix);
+        self
+    }
+
+    /// Apply the defaults of a workspace of `workspace_roots`, so that its release PRs and
+    /// tags don't clash with the ones of the other workspaces of the repository.
+    /// Unless configured, they are named after the directory of the workspace,
+    /// e.g. `agent-v1.2.0` for `rust/agent`.
+    pub fn with_workspace_root_defaults(mut self, root: &str) -> Self {
+        let name = workspace_root_name(root);
+        if self.workspace.pr_branch_prefix.is_none() {
+            self.workspace.pr_branch_prefix = Some(format!("{DEFAULT_BRANCH_PREFIX}{name}/"));
+        }
+        if self.workspace.pr_name.is_none() {
+            self.workspace.pr_name = Some(format!(
+                "chore: release {name}{{% if version is defined %}} v{{{{ version }}}}{{% endif %}}"
+            ));
+        }
+        self.root_tag_name = Some(format!("{name}-v{{{{ version }}}}"));
+        self
+    }
+
+    /// Directories of the Cargo workspaces of the repository: the `workspace_roots`,
+    /// followed by the projects found in the `workspace_discovery_paths`.
+    /// They are relative to `repo_root`.
+    pub fn workspace_roots(&self, repo_root: &Path) -> anyhow::Result<Vec<String>> {
+        let mut roots = self.workspace.workspace_roots.clone();
+        for path in &self.workspace.workspace_discovery_paths {
+            let discovered = discover_cargo_projects(repo_root, Path::new(path))
+                .with_context(|| format!("cannot discover the Cargo projects in {path}"))?;
+            for root in discovered {
+                if !roots.contains(&root) {
+                    roots.push(root);
+                }
+            }
+        }
+        let mut names = HashSet::new();
+        for root in &roots {
+            anyhow::ensure!(
+                names.insert(workspace_root_name(root)),
+                "the workspace roots must have different directory names, but `{root}` is repeated"
+            );
+        }
+        Ok(roots)
+    }
+
+    pub fn current_branch(&self) -> Option<&str> {
+        self.current_branch.as_deref()
+    }
+
+    fn current_branch_config(&self) -> Option<&BranchConfig> {
+        self.current_branch
+            .as_ref()
+            .and_then(|branch| self.branch.get(branch))
+    }
+
+    /// Whether only patch versions can be released from the current branch.
+    pub fn patch_only(&self) -> bool {
+        self.current_branch_config()
+            .is_some_and(|c| c.patch_only != Some(false))
+    }
+
+    /// Template of the workspace git tag of the current branch, used with unified versioning.
+    pub fn workspace_tag_name(&self) -> Option<&str> {
+        self.current_branch_config()
+            .and_then(|c| c.git_tag_name.as_deref())
+            .or(self.root_tag_name.as_deref())
+    }
+
     /// Package-specific configurations.
     /// Returns `<package name, package config>`.
     pub fn packages(&self) -> HashMap<&str, &PackageSpecificConfig> {
@@ -40,26 +212,45 @@ impl Config {
             .collect()
     }
 
+    /// Package-specific configurations, including the packages filtered out by
+    /// `packages_include` and `packages_exclude`, which aren't released.
+    /// Returns `<package name, package config>`.
+    fn filtered_packages(
+        &self,
+        metadata: &Metadata,
+    ) -> anyhow::Result<HashMap<String, PackageSpecificConfig>> {
+        let mut packages: HashMap<String, PackageSpecificConfig> = self
+            .packages()
+            .into_iter()
+            .map(|(name, config)| (name.to_string(), config.clone()))
+            .collect();
+        for name in self.workspace.excluded_packages(metadata)? {
+            packages.entry(name).or_default().release = Some(false);
+        }
+        Ok(packages)
+    }
+
     pub fn fill_update_config(
         &self,
         is_changelog_update_disabled: bool,
         update_request: UpdateRequest,
-    ) -> UpdateRequest {
+    ) -> anyhow::Result<UpdateRequest> {
         let mut default_update_config = self.workspace.packages_defaults.clone();
         if is_changelog_update_disabled {
             default_update_config.changelog_update = false.into();
         }
+        let packages = self.filtered_packages(update_request.cargo_metadata())?;
         let mut update_request =
             update_request.with_default_package_config(default_update_config.into());
-        for (package, config) in self.packages() {
-            let mut update_config = config.clone();
+        for (package, config) in packages {
+            let mut update_config = config;
             update_config = update_config.merge(self.workspace.packages_defaults.clone());
             if is_changelog_update_disabled {
                 update_config.common.changelog_update = false.into();
             }
             update_request = update_request.with_package_config(package, update_config.into());
         }
-        update_request
+        Ok(update_request)
     }
 
     pub fn fill_release_config(
@@ -67,20 +258,27 @@ impl Config {
         allow_dirty: bool,
         no_verify: bool,
         release_request: ReleaseRequest,
-    ) -> ReleaseRequest {
-        let mut default_config = self.workspace.packages_defaults.clone();
+    ) -> anyhow::Result<ReleaseRequest> {
+        let mut packages_defaults = self.workspace.packages_defaults.clone();
+        if self.workspace.prerelease_label.is_some() && packages_defaults.git_release_type.is_none()
+        {
+            // Mark the releases of the pre-release versions as pre-releases.
+            packages_defaults.git_release_type = Some(ReleaseType::Auto);
+        }
+        let mut default_config = packages_defaults.clone();
         if no_verify {
             default_config.publish_no_verify = Some(true);
         }
         if allow_dirty {
             default_config.publish_allow_dirty = Some(true);
         }
+        let packages = self.filtered_packages(release_request.metadata())?;
         let mut release_request =
             release_request.with_default_package_config(default_config.into());
 
-        for (package, config) in self.packages() {
-            let mut release_config = config.clone();
-            release_config = release_config.merge(self.workspace.packages_defaults.clone());
+        for (package, config) in packages {
+            let mut release_config = config;
+            release_config = release_config.merge(packages_defaults.clone());
 
             if no_verify {
                 release_config.common.publish_no_verify = Some(true);
@@ -88,10 +286,13 @@ impl Config {
             if allow_dirty {
                 release_config.common.publish_allow_dirty = Some(true);
             }
-            release_request =
-                release_request.with_package_config(package, release_config.common.into());
+            let is_release_enabled = release_config.is_release_enabled();
+            let package_release_config =
+                k_releaser_core::ReleaseConfig::from(release_config.common)
+                    .with_release(is_release_enabled);
+            release_request = release_request.with_package_config(package, package_release_config);
         }
-        release_request
+        Ok(release_request)
     }
 
     pub fn fill_publish_config(
@@ -99,31 +300,47 @@ impl Config {
         allow_dirty: bool,
         no_verify: bool,
         publish_request: PublishRequest,
-    ) -> PublishRequest {
+    ) -> anyhow::Result<PublishRequest> {
         let mut default_config = self.workspace.packages_defaults.clone();
         if no_verify {
-            default_config.publish_no_verify = Some(true);
+            default_config.force_publish_no_verify();
         }
         if allow_dirty {
             default_config.publish_allow_dirty = Some(true);
         }
+        let packages = self.filtered_packages(publish_request.metadata())?;
         let mut publish_request =
-            publish_request.with_default_package_config(default_config.into());
+            publish_request.with_default_package_config(default_config.try_into()?);
 
-        for (package, config) in self.packages() {
-            let mut publish_config = config.clone();
+        for (package, config) in packages {
+            let mut publish_config = config;
             publish_config = publish_config.merge(self.workspace.packages_defaults.clone());
 
             if no_verify {
-                publish_config.common.publish_no_verify = Some(true);
+                publish_config.common.force_publish_no_verify();
             }
             if allow_dirty {
                 publish_config.common.publish_allow_dirty = Some(true);
             }
-            publish_request =
-                publish_request.with_package_config(package, publish_config.common.into());
+            let is_release_enabled = publish_config.is_release_enabled();
+            let mut package_publish_config: k_releaser_core::PublishPackageConfig =
+                publish_config.common.try_into()?;
+            if !is_release_enabled {
+                package_publish_config = package_publish_config
+                    .with_publish(k_releaser_core::PublishConfig::enabled(false));
+            }
+            publish_request = publish_request.with_package_config(package, package_publish_config);
+        }
+        for registry in &self.registry {
+            if let Some(api_url) = &registry.api_url {
+                publish_request =
+                    publish_request.with_registry_api_url(&registry.name, api_url.clone());
+            }
+            if let Some(kind) = registry.kind {
+                publish_request = publish_request.with_registry_kind(&registry.name, kind.into());
+            }
         }
-        publish_request
+        Ok(publish_request)
     }
 }
 
@@ -141,6 +358,12 @@ pub struct Workspace {
     /// # Changelog Config
     /// Path to the git cliff configuration file. Defaults to the `keep a changelog` configuration.
     pub changelog_config: Option<PathBuf>,
+    /// # Changelog Group By Package
+    /// With unified versioning, group the commits of the changelog by the packages whose
+    /// files they changed. Commits that don't change any package are listed under
+    /// `workspace-wide`. Ignored if the changelog `body` is customized.
+    #[serde(default)]
+    pub changelog_group_by_package: bool,
     /// # Dependencies Update
     /// - If `true`, update all the dependencies in the Cargo.lock file by running `cargo update`.
     /// - If `false` or [`Option::None`], only update the workspace packages by running `cargo update --workspace`.
@@ -153,26 +376,161 @@ pub struct Workspace {
     pub pr_body: Option<String>,
     /// # PR Draft
     /// If `true`, the created release PR will be marked as a draft.
+    /// On GitLab, the draft state of the release MR is also updated when it changes.
     #[serde(default)]
     pub pr_draft: bool,
     /// # PR Labels
-    /// Labels to add to the release PR.
+    /// Labels of the release PR.
+    /// When the release PR is updated, the labels that aren't in this list are removed.
     #[serde(default)]
     pub pr_labels: Vec<String>,
+    /// # PR Reviewers
+    /// Users and teams (`org/team`) requested to review the release PR.
+    /// GitLab doesn't support team reviewers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pr_reviewers: Vec<String>,
+    /// # PR Reviewers From CODEOWNERS
+    /// If `true`, request reviews from the owners of the files changed by the release PR,
+    /// read from the `CODEOWNERS` file of the repository, in addition to `pr_reviewers`.
+    #[serde(default)]
+    pub pr_reviewers_from_codeowners: bool,
+    /// # PR Assignees
+    /// Users assigned to the release PR.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pr_assignees: Vec<String>,
+    /// # PR Required Approvals
+    /// Approvals required to merge the release PR, from `pr_reviewers` if any.
+    /// Only supported by GitLab, with a merge request approval rule.
+    pub pr_required_approvals: Option<u32>,
+    /// # PR Auto Merge
+    /// Merge the release PR with this method once its checks pass.
+    /// Set when the release PR is opened.
+    pub pr_auto_merge: Option<MergeMethod>,
     /// # PR Branch Prefix
     /// Prefix for the PR Branch
     pub pr_branch_prefix: Option<String>,
+    /// # PR Branch Name
+    /// Tera template of the name of the release PR branch, after `pr_branch_prefix`.
+    /// Available variables: `version`, `timestamp` and `packages`.
+    /// Defaults to `{{ timestamp }}`, e.g. `2025-01-26T18-30-09Z`.
+    pub pr_branch_name: Option<String>,
+    /// # PR Milestone
+    /// Milestone of the release PR, created if missing.
+    /// It's a Tera template with the variables `version` and `packages`, e.g. `v{{ version }}`.
+    pub pr_milestone: Option<String>,
+    /// # PR Project
+    /// GitHub Project (v2) where the new release PR is added.
+    pub pr_project: Option<PrProject>,
+    /// # PR Split
+    /// - If `single` or [`Option::None`], one release PR updates all the packages. *(Default)*.
+    /// - If `per-package`, every updated package gets its own release PR.
+    ///   Requires `version_mode = "independent"`.
+    pub pr_split: Option<PrSplit>,
+    /// # PR Update Strategy
+    /// How to update the opened release PR when it contains commits of other contributors,
+    /// or when its branch conflicts with the original branch.
+    /// - If `recreate` or [`Option::None`], the release PR is closed and a new one is opened.
+    ///   *(Default)*.
+    /// - If `rebase`, the release PR branch is rebased, keeping the commits of the other
+    ///   contributors. k-releaser fails on conflicts.
+    /// - If `abort-with-error`, k-releaser fails, leaving the release PR untouched.
+    pub pr_update_strategy: Option<PrUpdateStrategy>,
+    /// # PR Signed Commits
+    /// If `true`, the commits of the release PR are created via the GitLab or Gitea API,
+    /// so that they are signed by the forge.
+    /// On GitHub, release PR commits are always created via the API.
+    #[serde(default)]
+    pub pr_signed_commits: bool,
     /// # Publish Timeout
     /// Timeout for the publishing process
     pub publish_timeout: Option<String>,
+    /// # Publish Wait Poll Interval
+    /// How often the registry is checked while waiting for a published package to be
+    /// available, e.g. `1s` or `10s`. Defaults to `2s`.
+    pub publish_wait_poll_interval: Option<String>,
+    /// # Package Output Dir
+    /// Directory where `publish` copies the `.crate` files of the packages,
+    /// e.g. to archive them in CI or attach them to the git release.
+    /// Relative paths start from the workspace root.
+    pub package_output_dir: Option<PathBuf>,
+    /// # Publish Smoke Test
+    /// If `true`, after publishing, `publish` adds each published package to a new project
+    /// and runs `cargo check`, to catch packages that can't be used as dependencies.
+    #[serde(default)]
+    pub publish_smoke_test: bool,
+    /// # Publish Require Checks
+    /// If `true`, `publish` refuses to upload packages if the commit statuses and check runs
+    /// of the current commit didn't pass. See `required_checks`.
+    #[serde(default)]
+    pub publish_require_checks: bool,
+    /// # Docs.rs Timeout
+    /// If set, after publishing to crates.io, `publish` waits up to this duration for the
+    /// docs.rs builds of the published packages, e.g. `30m`, and fails if a build fails.
+    pub docs_rs_timeout: Option<String>,
     /// # Repo URL
     /// GitHub/Gitea/GitLab repository url where your project is hosted.
     /// It is used to generate the changelog release link.
     /// It defaults to the url of the default remote.
     pub repo_url: Option<Url>,
+    /// # Git Remote
+    /// Name of the git remote used to derive the repository url, fetch tags and push tags,
+    /// e.g. `upstream` when working from a fork.
+    /// It defaults to the remote of the current branch, or `origin`.
+    pub git_remote: Option<String>,
+    /// # Fetch Depth Strategy
+    /// What to do when the repository is a shallow clone, e.g. the default checkout of CI jobs,
+    /// which misses the commits and the tags of the previous releases.
+    pub fetch_depth_strategy: Option<FetchDepthStrategy>,
+    /// # CA Certificate Path
+    /// PEM file with additional root certificates to trust when reaching the git forge
+    /// and the cargo registry, e.g. the certificate of an internal CA.
+    pub ca_cert_path: Option<PathBuf>,
+    /// # Client Certificate Path
+    /// PEM file with the client certificate and its private key, used for mutual TLS.
+    pub client_cert_path: Option<PathBuf>,
+    /// # Accept Invalid Certificates
+    /// If `true`, the TLS certificates of the servers aren't verified.
+    /// This is insecure: use it only for testing.
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// # Proxy
+    /// Url of the proxy used to reach the git forge and the cargo registry.
+    /// Defaults to the `HTTPS_PROXY` and `HTTP_PROXY` environment variables.
+    pub proxy: Option<String>,
     /// # Release Commits
     /// Prepare release only if at least one commit respects this regex.
     pub release_commits: Option<String>,
+    /// # Release Ignore Paths
+    /// Glob patterns of files, relative to the repository root, that don't trigger a release,
+    /// e.g. `["docs/**", "*.md", ".github/**"]`.
+    /// Commits that only change files matching these patterns are excluded from the version
+    /// calculation and from the changelog.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub release_ignore_paths: Vec<String>,
+    /// # Packages Include
+    /// Glob patterns of the packages managed by k-releaser, matched against the package name
+    /// and against the package directory relative to the workspace root,
+    /// e.g. `["crates/public-*"]`. If empty, all the packages are included. *(Default)*.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages_include: Vec<String>,
+    /// # Packages Exclude
+    /// Glob patterns of the packages ignored by k-releaser, matched like `packages_include`.
+    /// The excluded packages aren't released, as if they had `release = false`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages_exclude: Vec<String>,
+    /// # Workspace Roots
+    /// Directories of the Cargo workspaces of the repository, relative to the repository root,
+    /// e.g. `["rust/agent", "rust/cli"]`.
+    /// `update`, `release-pr` and `release` process each workspace with the configuration
+    /// found next to its `Cargo.toml`, as if `--manifest-path` pointed to it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspace_roots: Vec<String>,
+    /// # Workspace Discovery Paths
+    /// Directories, relative to the repository root, searched for Cargo projects that aren't
+    /// members of a workspace, e.g. `["tools"]`.
+    /// Each directory containing a `Cargo.toml` is added to `workspace_roots`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspace_discovery_paths: Vec<String>,
     /// # Release always
     /// - If true, k-releaser release will try to release your packages every time you run it
     ///   (e.g. on every commit in the main branch). *(Default)*.
@@ -183,10 +541,102 @@ pub struct Workspace {
     ///   `k-releaser-`. So if you want to create a PR that should trigger a release
     ///   (e.g. when you fix the CI), use this branch name format (e.g. `k-releaser-fix-ci`).
     pub release_always: Option<bool>,
+    /// # Release Wait For Checks
+    /// If `true`, `k-releaser release` waits for the commit statuses and check runs of the
+    /// released commit to pass before creating tags and releases.
+    #[serde(default)]
+    pub release_wait_for_checks: bool,
+    /// # Release Alias Tags
+    /// If `true`, `k-releaser release` moves floating tags, like `v1` and `v1.4`,
+    /// to the latest release of their series.
+    #[serde(default)]
+    pub release_alias_tags: bool,
+    /// # Git Tag Sign
+    /// If `true`, `k-releaser release` always creates signed tags locally and pushes them.
+    /// If `false`, tags are created via the forge API, even if `tag.gpgSign` is set in the git config.
+    /// If unspecified, tags are signed only if `tag.gpgSign` is set.
+    /// Lightweight tags can't be signed.
+    pub git_tag_sign: Option<bool>,
+    /// # Signing Key
+    /// Key used to sign the tags: a GPG key id, an SSH public key, or the path of an SSH key file.
+    /// If unspecified, git uses `user.signingkey`.
+    pub signing_key: Option<String>,
+    /// # Release Close Milestone
+    /// If `true`, `k-releaser release` closes the forge milestone of the released version.
+    #[serde(default)]
+    pub release_close_milestone: bool,
+    /// # Milestone Title
+    /// Tera template of the title of the milestones, e.g. `"v{{ version }}"`.
+    /// Default: `"{{ version }}"`.
+    pub milestone_title: Option<String>,
+    /// # Milestone Next
+    /// Part of the released version to increment to get the next milestone.
+    /// If set, the next milestone is created if needed, and the open issues
+    /// of the closed milestone are moved to it.
+    pub milestone_next: Option<MilestoneNext>,
+    /// # Release Comment Issues
+    /// If `true`, `k-releaser release` comments "Released in ..." on the issues
+    /// referenced by the released commits with keywords like `fixes #123`.
+    #[serde(default)]
+    pub release_comment_issues: bool,
+    /// # Release Close Issues
+    /// If `true`, `k-releaser release` closes the issues referenced by the released commits.
+    #[serde(default)]
+    pub release_close_issues: bool,
+    /// # Required Checks
+    /// Names of the commit statuses or check runs that must pass before releasing or publishing,
+    /// e.g. `["test", "lint"]`.
+    /// If empty, all the checks must pass, except the CI job running k-releaser.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_checks: Vec<String>,
+    /// # CI Job Name
+    /// Name of the check of the CI job running k-releaser, which is ignored when
+    /// all the checks must pass. Defaults to the `GITHUB_JOB` or `CI_JOB_NAME`
+    /// environment variable. Set it on GitHub if the job has a `name:`, because
+    /// `GITHUB_JOB` is the id of the job, while its check run is named after `name:`.
+    pub ci_job_name: Option<String>,
+    /// # Checks Timeout
+    /// How long to wait for the required checks, e.g. `1h`. Defaults to `30m`.
+    pub checks_timeout: Option<String>,
     /// Maximum number of commits to analyze when the package hasn't been published yet.
     /// Default: 1000.
     #[serde(default = "default_max_analyze_commits")]
     pub max_analyze_commits: Option<u32>,
+    /// # Version Mode
+    /// - If `unified` or [`Option::None`], all packages share the workspace version. *(Default)*.
+    /// - If `independent`, every package gets its own version, changelog and git tag,
+    ///   calculated from the commits that touched the package.
+    pub version_mode: Option<VersionMode>,
+    /// # Pre-release Label
+    /// If set, `update` and `release-pr` produce pre-release versions of this channel,
+    /// e.g. `beta` -> `1.4.0-beta.1`. Releases default to `git_release_type = "auto"`,
+    /// so they are marked as pre-releases.
+    pub prerelease_label: Option<String>,
+    /// # Pre-update Hook
+    /// Shell command to run after the next versions are determined,
+    /// before manifests and changelogs are updated.
+    pub pre_update_hook: Option<String>,
+    /// # Post-update Hook
+    /// Shell command to run after manifests, changelogs and Cargo.lock are updated.
+    /// The files it changes are part of the release PR.
+    pub post_update_hook: Option<String>,
+    /// # Pre-publish Hook
+    /// Shell command to run before publishing each package.
+    pub pre_publish_hook: Option<String>,
+    /// # Post-publish Hook
+    /// Shell command to run after each package is published.
+    pub post_publish_hook: Option<String>,
+    /// # Pre-release Hook
+    /// Shell command to run before creating the git tag of a release.
+    pub pre_release_hook: Option<String>,
+    /// # Post-release Hook
+    /// Shell command to run after creating the git tag and the git release.
+    pub post_release_hook: Option<String>,
+    /// # Extra Version Files
+    /// Files outside of the cargo manifests where k-releaser updates the version,
+    /// e.g. `helm/Chart.yaml` or `package.json`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_version_files: Vec<ExtraVersionFile>,
 }
 
 impl Default for Workspace {
@@ -195,28 +645,198 @@ impl Default for Workspace {
             packages_defaults: PackageConfig::default(),
             allow_dirty: None,
             changelog_config: None,
+            changelog_group_by_package: false,
             dependencies_update: None,
             repo_url: None,
+            git_remote: None,
+            fetch_depth_strategy: None,
+            ca_cert_path: None,
+            client_cert_path: None,
+            accept_invalid_certs: false,
+            proxy: None,
             pr_name: None,
             pr_body: None,
             pr_draft: false,
             pr_labels: Vec::new(),
+            pr_reviewers: Vec::new(),
+            pr_reviewers_from_codeowners: false,
+            pr_assignees: Vec::new(),
+            pr_required_approvals: None,
+            pr_auto_merge: None,
             pr_branch_prefix: None,
+            pr_branch_name: None,
+            pr_milestone: None,
+            pr_project: None,
+            pr_split: None,
+            pr_update_strategy: None,
+            pr_signed_commits: false,
             publish_timeout: None,
+            publish_wait_poll_interval: None,
+            package_output_dir: None,
+            publish_smoke_test: false,
+            publish_require_checks: false,
+            docs_rs_timeout: None,
             release_commits: None,
+            release_ignore_paths: Vec::new(),
+            packages_include: Vec::new(),
+            packages_exclude: Vec::new(),
+            workspace_roots: Vec::new(),
+            workspace_discovery_paths: Vec::new(),
             release_always: None,
+            release_wait_for_checks: false,
+            release_alias_tags: false,
+            git_tag_sign: None,
+            signing_key: None,
+            release_close_milestone: false,
+            milestone_title: None,
+            milestone_next: None,
+            release_comment_issues: false,
+            release_close_issues: false,
+            required_checks: Vec::new(),
+            ci_job_name: None,
+            checks_timeout: None,
             max_analyze_commits: default_max_analyze_commits(),
+            version_mode: None,
+            prerelease_label: None,
+            pre_update_hook: None,
+            post_update_hook: None,
+            pre_publish_hook: None,
+            post_publish_hook: None,
+            pre_release_hook: None,
+            post_release_hook: None,
+            extra_version_files: Vec::new(),
         }
     }
 }
 
 impl Workspace {
+    /// Get the version mode. Defaults to unified versioning.
+    pub fn version_mode(&self) -> k_releaser_core::VersionMode {
+        self.version_mode.unwrap_or_default().into()
+    }
+
+    /// Get how the packages are split across release PRs. Defaults to a single PR.
+    pub fn pr_split(&self) -> k_releaser_core::PrSplit {
+        self.pr_split.unwrap_or_default().into()
+    }
+
+    pub fn pr_update_strategy(&self) -> k_releaser_core::PrUpdateStrategy {
+        self.pr_update_strategy.unwrap_or_default().into()
+    }
+
+    pub fn fetch_depth_strategy(&self) -> k_releaser_core::FetchDepthStrategy {
+        self.fetch_depth_strategy.unwrap_or_default().into()
+    }
+
+    /// Names of the workspace packages filtered out by `packages_include` and `packages_exclude`.
+    pub fn excluded_packages(&self, metadata: &Metadata) -> anyhow::Result<Vec<String>> {
+        let include = glob_patterns(&self.packages_include, "packages_include")?;
+        let exclude = glob_patterns(&self.packages_exclude, "packages_exclude")?;
+        let mut excluded = vec![];
+        for package in metadata.workspace_packages() {
+            let package_dir = package
+                .manifest_path
+                .parent()
+                .and_then(|dir| dir.strip_prefix(&metadata.workspace_root).ok())
+                .map(|dir| dir.as_str())
+                .unwrap_or_default();
+            let matches = |patterns: &[glob::Pattern]| {
+                patterns
+                    .iter()
+                    .any(|p| p.matches(package.name.as_str()) || p.matches(package_dir))
+            };
+            let is_included = include.is_empty() || matches(&include);
+            if !is_included || matches(&exclude) {
+                excluded.push(package.name.to_string());
+            }
+        }
+        Ok(excluded)
+    }
+
+    /// Shell commands to run before and after each stage.
+    pub fn hooks(&self) -> Hooks {
+        Hooks {
+            pre_update: self.pre_update_hook.clone(),
+            post_update: self.post_update_hook.clone(),
+            pre_publish: self.pre_publish_hook.clone(),
+            post_publish: self.post_publish_hook.clone(),
+            pre_release: self.pre_release_hook.clone(),
+            post_release: self.post_release_hook.clone(),
+        }
+    }
+
+    /// Milestone closed by `k-releaser release`, if enabled.
+    pub fn milestone(&self) -> Option<k_releaser_core::MilestoneConfig> {
+        if !self.release_close_milestone {
+            return None;
+        }
+        let mut milestone = k_releaser_core::MilestoneConfig::default()
+            .set_title_template(self.milestone_title.clone());
+        if let Some(next) = self.milestone_next {
+            milestone = milestone.set_next(next.into());
+        }
+        Some(milestone)
+    }
+
+    /// Files outside of the cargo manifests where the version is updated.
+    pub fn extra_version_files(&self) -> anyhow::Result<Vec<k_releaser_core::ExtraVersionFile>> {
+        self.extra_version_files
+            .iter()
+            .map(|file| {
+                let path = to_utf8_pathbuf(file.path.clone())?;
+                let mut extra_file = k_releaser_core::ExtraVersionFile::new(path);
+                if let Some(search) = &file.search {
+                    extra_file = extra_file.with_search(search)?;
+                }
+                if let Some(replace) = &file.replace {
+                    extra_file = extra_file.with_replace(replace);
+                }
+                if let Some(package) = &file.package {
+                    extra_file = extra_file.with_package(package);
+                }
+                Ok(extra_file)
+            })
+            .collect()
+    }
+
     /// Get the publish timeout. Defaults to 30 minutes.
     pub fn publish_timeout(&self) -> anyhow::Result<Duration> {
         let publish_timeout = self.publish_timeout.as_deref().unwrap_or("30m");
         parse_duration(publish_timeout)
             .with_context(|| format!("invalid publish_timeout '{publish_timeout}'"))
     }
+
+    /// Get the interval between registry checks after publishing a package. Defaults to 2 seconds.
+    pub fn publish_wait_poll_interval(&self) -> anyhow::Result<Duration> {
+        let interval = self.publish_wait_poll_interval.as_deref().unwrap_or("2s");
+        parse_duration(interval)
+            .with_context(|| format!("invalid publish_wait_poll_interval '{interval}'"))
+    }
+
+    /// Get how long to wait for the docs.rs builds. [`Option::None`] means docs.rs isn't checked.
+    pub fn docs_rs_timeout(&self) -> anyhow::Result<Option<Duration>> {
+        self.docs_rs_timeout
+            .as_deref()
+            .map(|timeout| {
+                parse_duration(timeout)
+                    .with_context(|| format!("invalid docs_rs_timeout '{timeout}'"))
+            })
+            .transpose()
+    }
+
+    /// Get how long to wait for the required checks. Defaults to 30 minutes.
+    pub fn checks_timeout(&self) -> anyhow::Result<Duration> {
+        let checks_timeout = self.checks_timeout.as_deref().unwrap_or("30m");
+        parse_duration(checks_timeout)
+            .with_context(|| format!("invalid checks_timeout '{checks_timeout}'"))
+    }
+}
+
+fn parse_env_value(value: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {value}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
 }
 
 fn default_max_analyze_commits() -> Option<u32> {
@@ -239,6 +859,27 @@ fn parse_duration(input: &str) -> anyhow::Result<Duration> {
     }
 }
 
+/// Parse a size in bytes, like `500`, `500B`, `10KB`, `10MB` or `1GB`.
+/// Units are multiples of 1024.
+fn parse_size(input: &str) -> anyhow::Result<u64> {
+    let input = input.trim();
+    let number_end = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number_str, unit) = input.split_at(number_end);
+    let number = number_str.parse::<u64>().context("invalid size number")?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" => 1024,
+        "MB" => 1024 * 1024,
+        "GB" => 1024 * 1024 * 1024,
+        unit => anyhow::bail!(
+            "'{unit}' is not a valid size unit. Valid units are: 'B', 'KB', 'MB' and 'GB'"
+        ),
+    };
+    number.checked_mul(multiplier).context("size is too big")
+}
+
 enum DurationUnit {
     Seconds,
     Minutes,
@@ -265,7 +906,7 @@ fn parse_duration_unit(input: &str) -> anyhow::Result<(&str, DurationUnit)> {
 }
 
 /// Config at the `[[package]]` level.
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct PackageSpecificConfig {
     /// Configuration that can be specified at the `[workspace]` level, too.
@@ -278,6 +919,12 @@ pub struct PackageSpecificConfig {
     /// # Version group
     /// The name of a group of packages that needs to have the same version.
     version_group: Option<String>,
+    /// # Release
+    /// - If `true` or [`Option::None`], the package is released. *(Default)*.
+    /// - If `false`, the package is excluded from version bumps, changelogs, git tags,
+    ///   git releases and `cargo publish`, even if its `Cargo.toml` allows publishing it.
+    ///   Useful for internal crates, like test utilities or `xtask`.
+    release: Option<bool>,
 }
 
 impl PackageSpecificConfig {
@@ -287,9 +934,15 @@ impl PackageSpecificConfig {
             common: self.common.merge(default),
             changelog_include: self.changelog_include,
             version_group: self.version_group,
+            release: self.release,
         }
     }
 
+    /// Whether the package is released. See `release`.
+    pub fn is_release_enabled(&self) -> bool {
+        self.release != Some(false)
+    }
+
     /// Get the common package configuration.
     pub fn common(&self) -> &PackageConfig {
         &self.common
@@ -303,6 +956,66 @@ pub struct PackageSpecificConfigWithName {
     pub config: PackageSpecificConfig,
 }
 
+/// Config at the `[branch."<name>"]` level.
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BranchConfig {
+    /// # Patch Only
+    /// If `true` or [`Option::None`], only the patch version is incremented on this branch,
+    /// even if the commits contain features or breaking changes. *(Default)*.
+    pub patch_only: Option<bool>,
+    /// # Git Tag Name
+    /// Tera template of the git tags created from this branch, e.g. `v{{ version }}-lts`.
+    /// Overrides `git_tag_name`, and also applies with unified versioning.
+    pub git_tag_name: Option<String>,
+    /// # Git Release Latest
+    /// Overrides `git_release_latest`. Set it to `false` so that the releases of this branch
+    /// aren't marked as the latest release.
+    pub git_release_latest: Option<bool>,
+    /// # PR Branch Prefix
+    /// Prefix of the release PR branches created from this branch.
+    /// Defaults to the branch name followed by `pr_branch_prefix`, e.g. `release-1.x-k-releaser-`.
+    pub pr_branch_prefix: Option<String>,
+}
+
+/// Config at the `[[registry]]` level.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// # Name
+    /// Name of the registry in the Cargo config. Use `crates-io` for crates.io.
+    pub name: String,
+    /// # API URL
+    /// Url of the web API of the registry, e.g. `https://crates.io`.
+    /// Used to check if a package is published when the registry index can't be read.
+    pub api_url: Option<Url>,
+    /// # Kind
+    /// Kind of registry. With `kellnr`, `api_url` is required and the Kellnr API is used
+    /// to check if a package is published and to validate the token before publishing.
+    pub kind: Option<RegistryKind>,
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryKind {
+    /// # Cargo
+    /// Registry implementing the web API of crates.io.
+    #[default]
+    Cargo,
+    /// # Kellnr
+    /// [Kellnr](https://kellnr.io) registry.
+    Kellnr,
+}
+
+impl From<RegistryKind> for k_releaser_core::RegistryKind {
+    fn from(value: RegistryKind) -> Self {
+        match value {
+            RegistryKind::Cargo => Self::Cargo,
+            RegistryKind::Kellnr => Self::Kellnr,
+        }
+    }
+}
+
 impl From<PackageConfig> for k_releaser_core::ReleaseConfig {
     fn from(value: PackageConfig) -> Self {
         let is_git_tag_enabled = value.git_tag_enable != Some(false);
@@ -311,7 +1024,12 @@ impl From<PackageConfig> for k_releaser_core::ReleaseConfig {
             .with_git_release(git_release(&value))
             .with_git_tag(
                 k_releaser_core::GitTagConfig::enabled(is_git_tag_enabled)
-                    .set_name_template(git_tag_name),
+                    .set_name_template(git_tag_name)
+                    .set_annotated(value.git_tag_annotated != Some(false))
+                    .set_message_template(value.git_tag_message.clone())
+                    .set_extra_name_templates(
+                        value.git_tag_extra_names.clone().unwrap_or_default(),
+                    ),
             );
 
         if let Some(changelog_update) = value.changelog_update {
@@ -336,8 +1054,10 @@ impl From<PackageConfig> for k_releaser_core::ReleaseConfig {
     }
 }
 
-impl From<PackageConfig> for k_releaser_core::PublishPackageConfig {
-    fn from(value: PackageConfig) -> Self {
+impl TryFrom<PackageConfig> for k_releaser_core::PublishPackageConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PackageConfig) -> anyhow::Result<Self> {
         let mut cfg = Self::default();
 
         if let Some(no_verify) = value.publish_no_verify {
@@ -352,7 +1072,68 @@ impl From<PackageConfig> for k_releaser_core::PublishPackageConfig {
         if let Some(allow_dirty) = value.publish_allow_dirty {
             cfg = cfg.with_allow_dirty(allow_dirty);
         }
-        cfg
+        if let Some(registries) = value.publish_registries {
+            let registries = registries
+                .into_iter()
+                .map(k_releaser_core::PublishRegistryConfig::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            cfg = cfg.with_registries(registries);
+        }
+        if let Some(deny_files) = value.publish_deny_files {
+            cfg = cfg.with_deny_files(deny_files);
+        }
+        if let Some(max_size) = value.publish_max_size {
+            let max_size = parse_size(&max_size)
+                .with_context(|| format!("invalid publish_max_size '{max_size}'"))?;
+            cfg = cfg.with_max_size(max_size);
+        }
+        if let Some(owners) = value.crates_io_owners {
+            cfg = cfg.with_crates_io_owners(owners);
+        }
+        Ok(cfg)
+    }
+}
+
+/// Registry where a package is published, with the settings of the package for it.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PublishRegistry {
+    /// # Name
+    /// Name of the registry in the Cargo config. Use `crates-io` for crates.io.
+    pub name: String,
+    /// # Token Env
+    /// Environment variable containing the token used to publish to the registry.
+    pub token_env: Option<String>,
+    /// # Publish Timeout
+    /// Timeout for publishing to the registry. Defaults to `publish_timeout`.
+    pub publish_timeout: Option<String>,
+    /// # No Verify
+    /// If `true`, add the `--no-verify` flag to the `cargo publish` command for this registry.
+    /// Defaults to `publish_no_verify`.
+    pub no_verify: Option<bool>,
+}
+
+impl TryFrom<PublishRegistry> for k_releaser_core::PublishRegistryConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PublishRegistry) -> anyhow::Result<Self> {
+        let mut cfg = Self::new(&value.name);
+        if let Some(token_env) = value.token_env {
+            cfg = cfg.with_token_env(token_env);
+        }
+        if let Some(timeout) = value.publish_timeout {
+            let timeout = parse_duration(&timeout).with_context(|| {
+                format!(
+                    "invalid publish_timeout '{timeout}' of registry {}",
+                    value.name
+                )
+            })?;
+            cfg = cfg.with_publish_timeout(timeout);
+        }
+        if let Some(no_verify) = value.no_verify {
+            cfg = cfg.with_no_verify(no_verify);
+        }
+        Ok(cfg)
     }
 }
 
@@ -365,11 +1146,18 @@ fn git_release(config: &PackageConfig) -> GitReleaseConfig {
     let is_git_release_draft = config.git_release_draft == Some(true);
     let git_release_name = config.git_release_name.clone();
     let git_release_body = config.git_release_body.clone();
+    let git_release_body_source: k_releaser_core::ReleaseBodySource = config
+        .git_release_body_source
+        .map(|body_source| body_source.into())
+        .unwrap_or_default();
+    let git_release_assets = config.git_release_assets.clone().unwrap_or_default();
     let mut git_release = k_releaser_core::GitReleaseConfig::enabled(is_git_release_enabled)
         .set_draft(is_git_release_draft)
         .set_release_type(git_release_type)
         .set_name_template(git_release_name)
-        .set_body_template(git_release_body);
+        .set_body_template(git_release_body)
+        .set_body_source(git_release_body_source)
+        .set_assets(git_release_assets);
 
     if config.git_release_latest == Some(false) {
         git_release = git_release.set_latest(false);
@@ -403,6 +1191,14 @@ pub struct PackageConfig {
     /// # Git Release Body
     /// Tera template of the git release body created by k-releaser.
     pub git_release_body: Option<String>,
+    /// # Git Release Body Source
+    /// Whether the git release body comes from the changelog (default)
+    /// or is generated by the forge from the merged PRs.
+    pub git_release_body_source: Option<ReleaseBodySource>,
+    /// # Git Release Assets
+    /// Glob patterns of the files to upload to the git release, relative to the workspace root.
+    /// Patterns are Tera templates, e.g. `"dist/{{ package }}-v{{ version }}.tar.gz"`.
+    pub git_release_assets: Option<Vec<String>>,
     /// # Git Release Type
     /// Whether to mark the created release as not ready for production.
     pub git_release_type: Option<ReleaseType>,
@@ -411,6 +1207,7 @@ pub struct PackageConfig {
     pub git_release_draft: Option<bool>,
     /// # Git Release Latest
     /// If true, will set the git release as latest.
+    /// Ignored with a warning by Gitea servers older than 1.24.
     pub git_release_latest: Option<bool>,
     /// # Git Release Name
     /// Tera template of the git release name created by k-releaser.
@@ -422,6 +1219,18 @@ pub struct PackageConfig {
     /// # Git Tag Name
     /// Tera template of the git tag name created by k-releaser.
     pub git_tag_name: Option<String>,
+    /// # Git Tag Annotated
+    /// If `true` or [`Option::None`], create annotated git tags. *(Default)*.
+    /// If `false`, create lightweight git tags.
+    pub git_tag_annotated: Option<bool>,
+    /// # Git Tag Message
+    /// Tera template of the message of annotated git tags.
+    /// Defaults to `chore: Release package {{ package }} version {{ version }}`.
+    pub git_tag_message: Option<String>,
+    /// # Git Tag Extra Names
+    /// Tera templates of additional git tags created for the release,
+    /// e.g. `["v{{ version }}"]` to also tag the primary package with a plain version.
+    pub git_tag_extra_names: Option<Vec<String>>,
     /// # Publish Allow Dirty
     /// If `true`, add the `--allow-dirty` flag to the `cargo publish` command.
     pub publish_allow_dirty: Option<bool>,
@@ -434,6 +1243,22 @@ pub struct PackageConfig {
     /// # Publish All Features
     /// If `true`, add the `--all-features` flag to the `cargo publish` command.
     pub publish_all_features: Option<bool>,
+    /// # Publish Registries
+    /// Registries where the package is published, each with its own settings.
+    /// If unspecified, the `publish` field of the package manifest is used.
+    pub publish_registries: Option<Vec<PublishRegistry>>,
+    /// # Publish Deny Files
+    /// Glob patterns of the files that must not be published, e.g. `["*.pem", "tests/fixtures/*"]`.
+    /// `publish` fails before uploading anything if the package contains a matching file.
+    pub publish_deny_files: Option<Vec<String>>,
+    /// # Publish Max Size
+    /// Maximum size of the files of the package, before compression, e.g. `500KB` or `10MB`.
+    /// `publish` fails before uploading anything if the package is bigger.
+    pub publish_max_size: Option<String>,
+    /// # Crates.io Owners
+    /// Owners added to the package after its first publication to crates.io,
+    /// e.g. `["github:my-org:publishers", "user1"]`. Users are invited, teams are added directly.
+    pub crates_io_owners: Option<Vec<String>>,
     /// # Semver Check
     /// Controls when to run cargo-semver-checks.
     /// If unspecified, run cargo-semver-checks if the package is a library.
@@ -448,21 +1273,102 @@ impl From<PackageConfig> for k_releaser_core::UpdateConfig {
             tag_name_template: config.git_tag_name,
             features_always_increment_minor: config.features_always_increment_minor == Some(true),
             changelog_path: config.changelog_path.map(|p| to_utf8_pathbuf(p).unwrap()),
+            release: true,
         }
     }
 }
 
 impl From<PackageSpecificConfig> for k_releaser_core::PackageUpdateConfig {
     fn from(config: PackageSpecificConfig) -> Self {
+        let is_release_enabled = config.is_release_enabled();
         Self {
-            generic: config.common.into(),
+            generic: k_releaser_core::UpdateConfig::from(config.common)
+                .with_release(is_release_enabled),
             changelog_include: config.changelog_include.unwrap_or_default(),
             version_group: config.version_group,
         }
     }
 }
 
+/// Directories under `dir` containing a `Cargo.toml`, sorted by path.
+/// `dir` and the returned directories are relative to `repo_root`.
+/// The directories of a project aren't searched, so that the members of a workspace aren't
+/// returned. Projects that are members of a workspace in a parent directory are skipped too,
+/// because they are released with their workspace. `target` and hidden directories are skipped.
+fn discover_cargo_projects(repo_root: &Path, dir: &Path) -> anyhow::Result<Vec<String>> {
+    let project_dir = repo_root.join(dir);
+    if project_dir.join(CARGO_TOML).is_file() {
+        if !is_workspace_root(&project_dir)? {
+            return Ok(vec![]);
+        }
+        let root = dir
+            .to_str()
+            .with_context(|| format!("path {dir:?} isn't valid UTF-8"))?;
+        return Ok(vec![root.to_string()]);
+    }
+    let mut subdirs = vec![];
+    for entry in fs_err::read_dir(&project_dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let is_skipped = name == "target" || name.to_string_lossy().starts_with('.');
+        if !is_skipped && entry.file_type()?.is_dir() {
+            subdirs.push(dir.join(name));
+        }
+    }
+    subdirs.sort();
+    let mut roots = vec![];
+    for subdir in subdirs {
+        roots.extend(discover_cargo_projects(repo_root, &subdir)?);
+    }
+    Ok(roots)
+}
+
+/// Whether the Cargo project in `dir` is the root of its workspace, i.e. it isn't
+/// a member of a workspace in a parent directory.
+fn is_workspace_root(dir: &Path) -> anyhow::Result<bool> {
+    let manifest = to_utf8_pathbuf(dir.join(CARGO_TOML))?;
+    let metadata = match cargo_utils::get_manifest_metadata(&manifest) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            tracing::warn!("skipping the Cargo project {manifest}: {e}");
+            return Ok(false);
+        }
+    };
+    let workspace_root = fs_err::canonicalize(&metadata.workspace_root)?;
+    let is_root = workspace_root == fs_err::canonicalize(dir)?;
+    if !is_root {
+        tracing::debug!(
+            "skipping the Cargo project {manifest}: it's a member of the workspace {}",
+            metadata.workspace_root
+        );
+    }
+    Ok(is_root)
+}
+
+/// Name of a workspace of `workspace_roots`, i.e. the name of its directory.
+fn workspace_root_name(root: &str) -> &str {
+    Path::new(root)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(root)
+}
+
+fn glob_patterns(patterns: &[String], field: &str) -> anyhow::Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p).with_context(|| format!("invalid {field} glob `{p}`")))
+        .collect()
+}
+
 impl PackageConfig {
+    /// Pass `--no-verify` to `cargo publish`, whatever the registry.
+    fn force_publish_no_verify(&mut self) {
+        self.publish_no_verify = Some(true);
+        for registry in self.publish_registries.iter_mut().flatten() {
+            registry.no_verify = Some(true);
+        }
+    }
+
     /// Merge the package-specific configuration with the global configuration.
     pub fn merge(self, default: Self) -> Self {
         Self {
@@ -478,12 +1384,23 @@ impl PackageConfig {
             git_release_latest: self.git_release_latest.or(default.git_release_latest),
             git_release_name: self.git_release_name.or(default.git_release_name),
             git_release_body: self.git_release_body.or(default.git_release_body),
+            git_release_body_source: self
+                .git_release_body_source
+                .or(default.git_release_body_source),
+            git_release_assets: self.git_release_assets.or(default.git_release_assets),
             publish_allow_dirty: self.publish_allow_dirty.or(default.publish_allow_dirty),
             publish_no_verify: self.publish_no_verify.or(default.publish_no_verify),
             publish_features: self.publish_features.or(default.publish_features),
             publish_all_features: self.publish_all_features.or(default.publish_all_features),
+            publish_registries: self.publish_registries.or(default.publish_registries),
+            publish_deny_files: self.publish_deny_files.or(default.publish_deny_files),
+            publish_max_size: self.publish_max_size.or(default.publish_max_size),
+            crates_io_owners: self.crates_io_owners.or(default.crates_io_owners),
             git_tag_enable: self.git_tag_enable.or(default.git_tag_enable),
             git_tag_name: self.git_tag_name.or(default.git_tag_name),
+            git_tag_annotated: self.git_tag_annotated.or(default.git_tag_annotated),
+            git_tag_message: self.git_tag_message.or(default.git_tag_message),
+            git_tag_extra_names: self.git_tag_extra_names.or(default.git_tag_extra_names),
         }
     }
 
@@ -522,6 +1439,213 @@ impl From<ReleaseType> for k_releaser_core::ReleaseType {
     }
 }
 
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseBodySource {
+    /// # Changelog
+    /// Render `git_release_body` with the changelog of the release.
+    #[default]
+    Changelog,
+    /// # Forge
+    /// Let the forge generate the release notes from the merged PRs.
+    /// Only supported by GitHub: on GitLab and Gitea, the changelog is used.
+    Forge,
+}
+
+impl From<ReleaseBodySource> for k_releaser_core::ReleaseBodySource {
+    fn from(value: ReleaseBodySource) -> Self {
+        match value {
+            ReleaseBodySource::Changelog => Self::Changelog,
+            ReleaseBodySource::Forge => Self::Forge,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionMode {
+    /// # Unified
+    /// All packages share the workspace version, changelog and git tag.
+    #[default]
+    Unified,
+    /// # Independent
+    /// Every package is versioned, changelogged and tagged on its own.
+    Independent,
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrSplit {
+    /// # Single
+    /// One release PR updates all the packages.
+    #[default]
+    Single,
+    /// # Per Package
+    /// Every updated package gets its own release PR.
+    PerPackage,
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrUpdateStrategy {
+    /// # Recreate
+    /// Close the release PR and open a new one.
+    #[default]
+    Recreate,
+    /// # Rebase
+    /// Rebase the commits of the other contributors onto the new changes of k-releaser,
+    /// dropping the previous ones of k-releaser.
+    Rebase,
+    /// # Abort With Error
+    /// Fail, leaving the release PR untouched.
+    AbortWithError,
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchDepthStrategy {
+    /// # Unshallow
+    /// Fetch the full history and the tags from the git remote.
+    #[default]
+    Unshallow,
+    /// # Fail
+    /// Fail, asking to fetch the full history.
+    Fail,
+    /// # Ignore
+    /// Use the history of the shallow clone.
+    Ignore,
+}
+
+impl From<FetchDepthStrategy> for k_releaser_core::FetchDepthStrategy {
+    fn from(value: FetchDepthStrategy) -> Self {
+        match value {
+            FetchDepthStrategy::Unshallow => Self::Unshallow,
+            FetchDepthStrategy::Fail => Self::Fail,
+            FetchDepthStrategy::Ignore => Self::Ignore,
+        }
+    }
+}
+
+/// Method used to merge the release PR.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeMethod {
+    /// # Merge
+    /// Create a merge commit.
+    Merge,
+    /// # Squash
+    /// Squash the commits of the PR into one.
+    Squash,
+    /// # Rebase
+    /// Rebase the commits of the PR onto the base branch.
+    Rebase,
+}
+
+impl From<MergeMethod> for k_releaser_core::MergeMethod {
+    fn from(value: MergeMethod) -> Self {
+        match value {
+            MergeMethod::Merge => Self::Merge,
+            MergeMethod::Squash => Self::Squash,
+            MergeMethod::Rebase => Self::Rebase,
+        }
+    }
+}
+
+/// GitHub Project (v2) where the release PR is added.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrProject {
+    /// # Owner
+    /// User or organization owning the project. Defaults to the owner of the repository.
+    pub owner: Option<String>,
+    /// # Number
+    /// Number of the project, e.g. `3` for `https://github.com/orgs/my-org/projects/3`.
+    pub number: u64,
+    /// # Status
+    /// Value of the `Status` field of the release PR in the project, e.g. `Ready for release`.
+    pub status: Option<String>,
+}
+
+impl From<PrProject> for k_releaser_core::PrProject {
+    fn from(value: PrProject) -> Self {
+        Self {
+            owner: value.owner,
+            number: value.number,
+            status: value.status,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneNext {
+    /// # Major
+    Major,
+    /// # Minor
+    Minor,
+    /// # Patch
+    Patch,
+}
+
+impl From<MilestoneNext> for next_version::VersionIncrement {
+    fn from(value: MilestoneNext) -> Self {
+        match value {
+            MilestoneNext::Major => Self::Major,
+            MilestoneNext::Minor => Self::Minor,
+            MilestoneNext::Patch => Self::Patch,
+        }
+    }
+}
+
+impl From<PrSplit> for k_releaser_core::PrSplit {
+    fn from(value: PrSplit) -> Self {
+        match value {
+            PrSplit::Single => Self::Single,
+            PrSplit::PerPackage => Self::PerPackage,
+        }
+    }
+}
+
+impl From<PrUpdateStrategy> for k_releaser_core::PrUpdateStrategy {
+    fn from(value: PrUpdateStrategy) -> Self {
+        match value {
+            PrUpdateStrategy::Recreate => Self::Recreate,
+            PrUpdateStrategy::Rebase => Self::Rebase,
+            PrUpdateStrategy::AbortWithError => Self::AbortWithError,
+        }
+    }
+}
+
+/// File outside of the cargo manifests where k-releaser updates the version.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExtraVersionFile {
+    /// # Path
+    /// Path of the file, relative to the workspace root.
+    pub path: PathBuf,
+    /// # Search
+    /// Regex matching the text to replace.
+    /// If unspecified, the previous version is replaced, if it appears only once.
+    pub search: Option<String>,
+    /// # Replace
+    /// Tera template of the replacement text. Defaults to `{{ version }}`.
+    /// It can reference the capture groups of `search`, e.g. `${1}`.
+    pub replace: Option<String>,
+    /// # Package
+    /// Package whose version is written.
+    /// If unspecified, the workspace version is written.
+    pub package: Option<String>,
+}
+
+impl From<VersionMode> for k_releaser_core::VersionMode {
+    fn from(value: VersionMode) -> Self {
+        match value {
+            VersionMode::Unified => Self::Unified,
+            VersionMode::Independent => Self::Independent,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -546,8 +1670,15 @@ mod tests {
             workspace: Workspace {
                 dependencies_update: Some(false),
                 changelog_config: Some("../git-cliff.toml".into()),
+                changelog_group_by_package: false,
                 allow_dirty: Some(false),
                 repo_url: Some("https://github.com/k-releaser/k-releaser".parse().unwrap()),
+                git_remote: None,
+                fetch_depth_strategy: None,
+                ca_cert_path: None,
+                client_cert_path: None,
+                accept_invalid_certs: false,
+                proxy: None,
                 packages_defaults: PackageConfig {
                     semver_check: None,
                     changelog_update: None,
@@ -560,13 +1691,59 @@ mod tests {
                 pr_body: None,
                 pr_draft: false,
                 pr_labels: vec![],
+                pr_reviewers: vec![],
+                pr_reviewers_from_codeowners: false,
+                pr_assignees: vec![],
+                pr_required_approvals: None,
+                pr_auto_merge: None,
                 pr_branch_prefix: Some("f-".to_string()),
+                pr_branch_name: None,
+                pr_milestone: None,
+                pr_project: None,
+                pr_split: None,
+                pr_update_strategy: None,
+                pr_signed_commits: false,
                 publish_timeout: Some("10m".to_string()),
+                publish_wait_poll_interval: None,
+                package_output_dir: None,
+                publish_smoke_test: false,
+                publish_require_checks: false,
+                docs_rs_timeout: None,
                 release_commits: Some("^feat:".to_string()),
+                release_ignore_paths: Vec::new(),
+                packages_include: Vec::new(),
+                packages_exclude: Vec::new(),
+                workspace_roots: Vec::new(),
+                workspace_discovery_paths: Vec::new(),
                 release_always: None,
+                release_wait_for_checks: false,
+                release_alias_tags: false,
+                git_tag_sign: None,
+                signing_key: None,
+                release_close_milestone: false,
+                milestone_title: None,
+                milestone_next: None,
+                release_comment_issues: false,
+                release_close_issues: false,
+                required_checks: Vec::new(),
+                ci_job_name: None,
+                checks_timeout: None,
                 max_analyze_commits: default_max_analyze_commits(),
+                version_mode: None,
+                prerelease_label: None,
+                pre_update_hook: None,
+                post_update_hook: None,
+                pre_publish_hook: None,
+                post_publish_hook: None,
+                pre_release_hook: None,
+                post_release_hook: None,
+                extra_version_files: vec![],
             },
             package: [].into(),
+            registry: vec![],
+            branch: BTreeMap::new(),
+            current_branch: None,
+            root_tag_name: None,
         }
     }
 
@@ -592,6 +1769,233 @@ mod tests {
         assert_eq!(config, expected_config);
     }
 
+    #[test]
+    fn registry_is_deserialized() {
+        let config = r#"
+[[registry]]
+name = "my-registry"
+api_url = "https://registry.example.com"
+
+[[registry]]
+name = "kellnr"
+api_url = "https://kellnr.example.com"
+kind = "kellnr"
+"#;
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(
+            config.registry,
+            vec![
+                RegistryConfig {
+                    name: "my-registry".to_string(),
+                    api_url: Some("https://registry.example.com".parse().unwrap()),
+                    kind: None,
+                },
+                RegistryConfig {
+                    name: "kellnr".to_string(),
+                    api_url: Some("https://kellnr.example.com".parse().unwrap()),
+                    kind: Some(RegistryKind::Kellnr),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn publish_registries_are_deserialized() {
+        let config = r#"
+[[package]]
+name = "crate1"
+publish_registries = [
+  { name = "crates-io" },
+  { name = "private", token_env = "PRIVATE_TOKEN", publish_timeout = "5m", no_verify = true },
+]
+"#;
+        let config: Config = toml::from_str(config).unwrap();
+        let registries = config.package[0]
+            .config
+            .common()
+            .publish_registries
+            .clone()
+            .unwrap();
+        let registries: Vec<k_releaser_core::PublishRegistryConfig> = registries
+            .into_iter()
+            .map(|r| r.try_into().unwrap())
+            .collect();
+        assert_eq!(
+            registries,
+            vec![
+                k_releaser_core::PublishRegistryConfig::new("crates-io"),
+                k_releaser_core::PublishRegistryConfig::new("private")
+                    .with_token_env("PRIVATE_TOKEN")
+                    .with_publish_timeout(Duration::from_secs(300))
+                    .with_no_verify(true),
+            ]
+        );
+    }
+
+    #[test]
+    fn crates_io_owners_are_deserialized() {
+        let config = r#"
+[workspace]
+crates_io_owners = ["github:my-org:publishers", "user1"]
+"#;
+        let config: Config = toml::from_str(config).unwrap();
+        let publish_config: k_releaser_core::PublishPackageConfig =
+            config.workspace.packages_defaults.try_into().unwrap();
+        assert_eq!(
+            publish_config,
+            k_releaser_core::PublishPackageConfig::default().with_crates_io_owners(vec![
+                "github:my-org:publishers".to_string(),
+                "user1".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn version_mode_is_deserialized() {
+        let config = r#"
+[workspace]
+version_mode = "independent"
+"#;
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(
+            config.workspace.version_mode,
+            Some(VersionMode::Independent)
+        );
+        assert_eq!(
+            config.workspace.version_mode(),
+            k_releaser_core::VersionMode::Independent
+        );
+
+        let config: Config = toml::from_str("[workspace]").unwrap();
+        assert_eq!(
+            config.workspace.version_mode(),
+            k_releaser_core::VersionMode::Unified
+        );
+    }
+
+    #[test]
+    fn prerelease_releases_default_to_auto_release_type() {
+        let config: Config = toml::from_str(
+            r#"
+[workspace]
+prerelease_label = "beta"
+"#,
+        )
+        .unwrap();
+        let request = config
+            .fill_release_config(
+                false,
+                false,
+                ReleaseRequest::new(fake_package::metadata::fake_metadata()),
+            )
+            .unwrap();
+        let version = cargo_metadata::semver::Version::parse("1.4.0-beta.1").unwrap();
+        assert!(
+            request
+                .get_package_config("aaa")
+                .git_release()
+                .is_pre_release(&version)
+        );
+    }
+
+    #[test]
+    fn git_release_body_source_is_configured() {
+        let config: Config = toml::from_str(
+            r#"
+[workspace]
+git_release_body_source = "forge"
+"#,
+        )
+        .unwrap();
+        let request = config
+            .fill_release_config(
+                false,
+                false,
+                ReleaseRequest::new(fake_package::metadata::fake_metadata()),
+            )
+            .unwrap();
+        assert_eq!(
+            request.get_package_config("aaa").git_release(),
+            &k_releaser_core::GitReleaseConfig::enabled(true)
+                .set_body_source(k_releaser_core::ReleaseBodySource::Forge)
+        );
+    }
+
+    #[test]
+    fn git_tag_message_is_configured() {
+        let config: Config = toml::from_str(
+            r#"
+[workspace]
+git_tag_message = "Release {{ version }}"
+
+[[package]]
+name = "aaa"
+git_tag_annotated = false
+"#,
+        )
+        .unwrap();
+        let request = config
+            .fill_release_config(
+                false,
+                false,
+                ReleaseRequest::new(fake_package::metadata::fake_metadata()),
+            )
+            .unwrap();
+        assert_eq!(
+            request.get_package_config("bbb").git_tag(),
+            &k_releaser_core::GitTagConfig::enabled(true)
+                .set_message_template(Some("Release {{ version }}".to_string()))
+        );
+        assert_eq!(
+            request.get_package_config("aaa").git_tag(),
+            &k_releaser_core::GitTagConfig::enabled(true)
+                .set_annotated(false)
+                .set_message_template(Some("Release {{ version }}".to_string()))
+        );
+    }
+
+    #[test]
+    fn packages_are_filtered_by_globs() {
+        let config: Config = toml::from_str(
+            r#"
+[workspace]
+packages_include = ["crates/k_releaser*"]
+packages_exclude = ["k_releaser_core"]
+"#,
+        )
+        .unwrap();
+        let excluded = config
+            .workspace
+            .excluded_packages(&fake_package::metadata::fake_metadata())
+            .unwrap();
+        assert!(!excluded.contains(&"k-releaser".to_string()));
+        assert!(excluded.contains(&"k_releaser_core".to_string()));
+        assert!(excluded.contains(&"git_cmd".to_string()));
+    }
+
+    #[test]
+    fn package_with_release_disabled_is_excluded() {
+        use k_releaser_core::ReleaseMetadataBuilder as _;
+
+        let config: Config = toml::from_str(
+            r#"
+[[package]]
+name = "aaa"
+release = false
+"#,
+        )
+        .unwrap();
+        let request = config
+            .fill_release_config(
+                false,
+                false,
+                ReleaseRequest::new(fake_package::metadata::fake_metadata()),
+            )
+            .unwrap();
+        assert!(request.get_release_metadata("aaa").is_none());
+        assert!(request.get_release_metadata("bbb").is_some());
+    }
+
     #[test]
     fn config_is_serialized() {
         let config = Config {
@@ -599,13 +2003,31 @@ mod tests {
             workspace: Workspace {
                 dependencies_update: None,
                 changelog_config: Some("../git-cliff.toml".into()),
+                changelog_group_by_package: false,
                 allow_dirty: None,
                 repo_url: Some("https://github.com/k-releaser/k-releaser".parse().unwrap()),
+                git_remote: None,
+                fetch_depth_strategy: None,
+                ca_cert_path: None,
+                client_cert_path: None,
+                accept_invalid_certs: false,
+                proxy: None,
                 pr_name: None,
                 pr_body: None,
                 pr_draft: false,
                 pr_labels: vec!["label1".to_string()],
+                pr_reviewers: vec![],
+                pr_reviewers_from_codeowners: false,
+                pr_assignees: vec![],
+                pr_required_approvals: None,
+                pr_auto_merge: None,
                 pr_branch_prefix: Some("f-".to_string()),
+                pr_branch_name: None,
+                pr_milestone: None,
+                pr_project: None,
+                pr_split: None,
+                pr_update_strategy: None,
+                pr_signed_commits: false,
                 packages_defaults: PackageConfig {
                     semver_check: None,
                     changelog_update: true.into(),
@@ -616,9 +2038,40 @@ mod tests {
                     ..Default::default()
                 },
                 publish_timeout: Some("10m".to_string()),
+                publish_wait_poll_interval: None,
+                package_output_dir: None,
+                publish_smoke_test: false,
+                publish_require_checks: false,
+                docs_rs_timeout: None,
                 release_commits: Some("^feat:".to_string()),
+                release_ignore_paths: Vec::new(),
+                packages_include: Vec::new(),
+                packages_exclude: Vec::new(),
+                workspace_roots: Vec::new(),
+                workspace_discovery_paths: Vec::new(),
                 release_always: None,
+                release_wait_for_checks: false,
+                release_alias_tags: false,
+                git_tag_sign: None,
+                signing_key: None,
+                release_close_milestone: false,
+                milestone_title: None,
+                milestone_next: None,
+                release_comment_issues: false,
+                release_close_issues: false,
+                required_checks: Vec::new(),
+                ci_job_name: None,
+                checks_timeout: None,
                 max_analyze_commits: default_max_analyze_commits(),
+                version_mode: None,
+                prerelease_label: None,
+                pre_update_hook: None,
+                post_update_hook: None,
+                pre_publish_hook: None,
+                post_publish_hook: None,
+                pre_release_hook: None,
+                post_release_hook: None,
+                extra_version_files: vec![],
             },
             package: [PackageSpecificConfigWithName {
                 name: "crate1".to_string(),
@@ -633,9 +2086,14 @@ mod tests {
                     },
                     changelog_include: Some(vec!["pkg1".to_string()]),
                     version_group: None,
+                    release: None,
                 },
             }]
             .into(),
+            registry: vec![],
+            branch: BTreeMap::new(),
+            current_branch: None,
+            root_tag_name: None,
         };
 
         expect_test::expect![[r#"
@@ -646,12 +2104,23 @@ mod tests {
             git_release_type = "prod"
             git_release_draft = false
             changelog_config = "../git-cliff.toml"
+            changelog_group_by_package = false
             pr_draft = false
             pr_labels = ["label1"]
+            pr_reviewers_from_codeowners = false
             pr_branch_prefix = "f-"
+            pr_signed_commits = false
             publish_timeout = "10m"
+            publish_smoke_test = false
+            publish_require_checks = false
             repo_url = "https://github.com/k-releaser/k-releaser"
+            accept_invalid_certs = false
             release_commits = "^feat:"
+            release_wait_for_checks = false
+            release_alias_tags = false
+            release_close_milestone = false
+            release_comment_issues = false
+            release_close_issues = false
             max_analyze_commits = 1000
 
             [changelog]
@@ -678,7 +2147,7 @@ mod tests {
               |
             1 | [unknown]
               |  ^^^^^^^
-            unknown field `unknown`, expected one of `workspace`, `changelog`, `package`
+            unknown field `unknown`, expected one of `workspace`, `changelog`, `package`, `registry`, `branch`
         "]]
         .assert_eq(&error);
     }
@@ -707,6 +2176,7 @@ allow_dirty = true";
         let config = r#"
 [workspace]
 release_commits = "^(feat|fix):"
+release_ignore_paths = ["docs/**", "*.md", ".github/**"]
 changelog_update = true
 changelog_config = ".github/cliff.toml"
 "#;
@@ -720,12 +2190,40 @@ max_analyze_commits = 2000
 "#;
         assert!(toml::from_str::<Config>(config).is_ok());
 
+        // Test example from CONFIGURATION.md - Versioning Mode
+        let config = r#"
+[workspace]
+version_mode = "independent"
+git_tag_name = "{{ package }}-v{{ version }}"
+"#;
+        assert!(toml::from_str::<Config>(config).is_ok());
+
+        // Test example from CONFIGURATION.md - Version Groups
+        let config = r#"
+[[package]]
+name = "my-macros"
+version_group = "core"
+
+[[package]]
+name = "my-core"
+version_group = "core"
+"#;
+        assert!(toml::from_str::<Config>(config).is_ok());
+
+        // Test example from CONFIGURATION.md - Pre-releases
+        let config = r#"
+[workspace]
+prerelease_label = "beta"
+"#;
+        assert!(toml::from_str::<Config>(config).is_ok());
+
         // Test example from CONFIGURATION.md - Git Release Configuration
         let config = r#"
 [workspace]
 git_release_enable = true
 git_release_name = "Release {{ version }}"
 git_release_body = "{{ changelog }}"
+git_release_assets = ["target/package/{{ package }}-{{ version }}.crate", "dist/*.tar.gz"]
 git_release_type = "auto"
 git_release_draft = false
 git_release_latest = true
@@ -743,18 +2241,95 @@ pr_body = """
 """
 pr_draft = false
 pr_labels = ["release", "automated"]
+pr_reviewers = ["alice", "my-org/maintainers"]
+pr_reviewers_from_codeowners = true
+pr_assignees = ["bob"]
+pr_required_approvals = 2
+pr_auto_merge = "squash"
 pr_branch_prefix = "release-"
+pr_milestone = "v{{ version }}"
+pr_project = { owner = "my-org", number = 3, status = "Ready for release" }
+pr_split = "per-package"
+pr_update_strategy = "abort-with-error"
+pr_signed_commits = true
 "#;
-        assert!(toml::from_str::<Config>(config).is_ok());
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(
+            config.workspace.pr_split(),
+            k_releaser_core::PrSplit::PerPackage
+        );
+        assert_eq!(
+            config.workspace.pr_update_strategy(),
+            k_releaser_core::PrUpdateStrategy::AbortWithError
+        );
+        assert!(config.workspace.pr_signed_commits);
+        assert!(config.workspace.pr_reviewers_from_codeowners);
+        assert_eq!(config.workspace.pr_required_approvals, Some(2));
+        assert_eq!(config.workspace.pr_auto_merge, Some(MergeMethod::Squash));
+        assert_eq!(
+            config.workspace.pr_project,
+            Some(PrProject {
+                owner: Some("my-org".to_string()),
+                number: 3,
+                status: Some("Ready for release".to_string()),
+            })
+        );
+        assert_eq!(
+            config.workspace.pr_milestone.as_deref(),
+            Some("v{{ version }}")
+        );
 
         // Test example from CONFIGURATION.md - Repository Settings
         let config = r#"
 [workspace]
 repo_url = "https://github.com/your-org/your-repo"
+git_remote = "upstream"
+fetch_depth_strategy = "fail"
 allow_dirty = false
 dependencies_update = false
+ca_cert_path = "/etc/ssl/certs/internal-ca.pem"
+client_cert_path = "/etc/ssl/private/k-releaser.pem"
+accept_invalid_certs = false
+proxy = "http://proxy.example.com:3128"
 "#;
-        assert!(toml::from_str::<Config>(config).is_ok());
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(config.workspace.git_remote.as_deref(), Some("upstream"));
+        assert_eq!(
+            config.workspace.fetch_depth_strategy(),
+            k_releaser_core::FetchDepthStrategy::Fail
+        );
+        assert_eq!(
+            config.workspace.ca_cert_path,
+            Some(PathBuf::from("/etc/ssl/certs/internal-ca.pem"))
+        );
+
+        // Test example from CONFIGURATION.md - Extra Version Files
+        let config = r#"
+[workspace]
+extra_version_files = [
+  { path = "package.json" },
+  { path = "helm/Chart.yaml", search = '(?m)^appVersion: .*$', replace = 'appVersion: "{{ version }}"' },
+  { path = "Dockerfile", search = 'version="[^"]*"', replace = 'version="{{ version }}"', package = "my-cli" },
+]
+"#;
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(config.workspace.extra_version_files().unwrap().len(), 3);
+
+        // Test example from CONFIGURATION.md - Hooks
+        let config = r#"
+[workspace]
+pre_update_hook = "echo updating to $NEXT_VERSION"
+post_update_hook = "cargo run -p codegen"
+pre_publish_hook = "make dist"
+post_publish_hook = "./scripts/notify.sh published $PACKAGE $NEXT_VERSION"
+pre_release_hook = "./scripts/check-release.sh"
+post_release_hook = "./scripts/notify.sh released $TAG"
+"#;
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(
+            config.workspace.hooks().post_update.as_deref(),
+            Some("cargo run -p codegen")
+        );
 
         // Test example from CONFIGURATION.md - Per-Package Overrides
         let config = r#"
@@ -766,6 +2341,40 @@ publish_allow_dirty = true
 [[package]]
 name = "my-codegen-package"
 semver_check = false
+
+[[package]]
+name = "xtask"
+release = false
+"#;
+        assert!(toml::from_str::<Config>(config).is_ok());
+
+        // Test example from CONFIGURATION.md - Package Filters
+        let config = r#"
+[workspace]
+packages_include = ["crates/public-*"]
+packages_exclude = ["crates/public-internal-*", "*-bench"]
+"#;
+        assert!(toml::from_str::<Config>(config).is_ok());
+
+        // Test example from CONFIGURATION.md - Multiple Workspaces
+        let config = r#"
+[workspace]
+workspace_roots = ["rust/agent", "rust/cli"]
+"#;
+        assert!(toml::from_str::<Config>(config).is_ok());
+
+        // Test example from CONFIGURATION.md - Independent Projects
+        let config = r#"
+[workspace]
+workspace_discovery_paths = ["tools"]
+"#;
+        assert!(toml::from_str::<Config>(config).is_ok());
+
+        // Test example from CONFIGURATION.md - Changelog Grouped by Package
+        let config = r#"
+[workspace]
+changelog_update = true
+changelog_group_by_package = true
 "#;
         assert!(toml::from_str::<Config>(config).is_ok());
 
@@ -873,4 +2482,219 @@ unknown = false"#;
             "invalid duration number"
         );
     }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("500").unwrap(), 500);
+        assert_eq!(parse_size("500B").unwrap(), 500);
+        assert_eq!(parse_size("10KB").unwrap(), 10 * 1024);
+        assert_eq!(parse_size("10 mb").unwrap(), 10 * 1024 * 1024);
+        assert_eq!(parse_size("1GB").unwrap(), 1024 * 1024 * 1024);
+        assert_eq!(
+            parse_size("10TB").unwrap_err().to_string(),
+            "'TB' is not a valid size unit. Valid units are: 'B', 'KB', 'MB' and 'GB'"
+        );
+        assert_eq!(
+            parse_size("MB").unwrap_err().to_string(),
+            "invalid size number"
+        );
+    }
+
+    #[test]
+    fn workspace_config_is_overridden_by_env_vars() {
+        let env_vars = [
+            ("K_RELEASER_PR_DRAFT", "true"),
+            ("K_RELEASER_GIT_RELEASE_ENABLE", "false"),
+            ("K_RELEASER_PR_NAME", "Release {{ version }}"),
+            ("K_RELEASER_PR_LABELS", r#"["release", "automated"]"#),
+            ("K_RELEASER_LOG", "debug"),
+            ("GITHUB_TOKEN", "token"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        let config = create_base_workspace_config()
+            .with_overrides(env_vars)
+            .unwrap();
+        assert!(config.workspace.pr_draft);
+        assert_eq!(
+            config.workspace.packages_defaults.git_release_enable,
+            Some(false)
+        );
+        assert_eq!(
+            config.workspace.pr_name.as_deref(),
+            Some("Release {{ version }}")
+        );
+        assert_eq!(config.workspace.pr_labels, ["release", "automated"]);
+        assert_eq!(config.workspace.pr_branch_prefix.as_deref(), Some("f-"));
+    }
+
+    #[test]
+    fn branch_config_is_applied() {
+        let config = r#"
+[workspace]
+git_release_latest = true
+
+[branch."release/1.x"]
+git_release_latest = false
+git_tag_name = "v{{ version }}-lts"
+"#;
+
+        let main = toml::from_str::<Config>(config)
+            .unwrap()
+            .with_branch_overrides("main");
+        assert!(!main.patch_only());
+        assert_eq!(main.workspace.pr_branch_prefix, None);
+
+        let maintenance = toml::from_str::<Config>(config)
+            .unwrap()
+            .with_branch_overrides("release/1.x");
+        assert!(maintenance.patch_only());
+        assert_eq!(maintenance.workspace_tag_name(), Some("v{{ version }}-lts"));
+        assert_eq!(
+            maintenance
+                .workspace
+                .packages_defaults
+                .git_tag_name
+                .as_deref(),
+            Some("v{{ version }}-lts")
+        );
+        assert_eq!(
+            maintenance.workspace.packages_defaults.git_release_latest,
+            Some(false)
+        );
+        assert_eq!(
+            maintenance.workspace.pr_branch_prefix.as_deref(),
+            Some("release-1.x-k-releaser-")
+        );
+    }
+
+    #[test]
+    fn workspace_root_defaults_are_applied() {
+        let config = Config::default().with_workspace_root_defaults("rust/agent");
+        assert_eq!(config.workspace_tag_name(), Some("agent-v{{ version }}"));
+        assert_eq!(
+            config.workspace.pr_branch_prefix.as_deref(),
+            Some("k-releaser-agent/")
+        );
+        assert_eq!(
+            config.workspace.pr_name.as_deref(),
+            Some("chore: release agent{% if version is defined %} v{{ version }}{% endif %}")
+        );
+
+        let config = r#"
+[workspace]
+pr_branch_prefix = "release-agent-"
+"#;
+        let config = toml::from_str::<Config>(config)
+            .unwrap()
+            .with_workspace_root_defaults("rust/agent");
+        assert_eq!(
+            config.workspace.pr_branch_prefix.as_deref(),
+            Some("release-agent-")
+        );
+    }
+
+    #[test]
+    fn workspace_roots_with_same_name_are_rejected() {
+        let config = r#"
+[workspace]
+workspace_roots = ["rust/agent", "go/agent"]
+"#;
+        let config = toml::from_str::<Config>(config).unwrap();
+        assert_eq!(
+            config
+                .workspace_roots(Path::new("."))
+                .unwrap_err()
+                .to_string(),
+            "the workspace roots must have different directory names, but `go/agent` is repeated"
+        );
+    }
+
+    fn write_package(dir: &Path, name: &str, extra: &str) {
+        fs_err::create_dir_all(dir.join("src")).unwrap();
+        fs_err::write(dir.join("src/lib.rs"), "").unwrap();
+        fs_err::write(
+            dir.join(CARGO_TOML),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n{extra}"
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cargo_projects_are_discovered() {
+        let temp = tempfile::tempdir().unwrap();
+        let tools = temp.path().join("tools");
+        for dir in ["a", "a/member", "group/b", ".hidden/c", "target/d"] {
+            let name = dir.replace(['/', '.'], "-");
+            write_package(
+                &tools.join(dir),
+                name.trim_start_matches('-'),
+                "[workspace]\n",
+            );
+        }
+        fs_err::create_dir_all(tools.join("docs")).unwrap();
+
+        let roots = discover_cargo_projects(temp.path(), Path::new("tools")).unwrap();
+        assert_eq!(roots, ["tools/a", "tools/group/b"]);
+    }
+
+    #[test]
+    fn members_of_another_workspace_are_not_discovered() {
+        let temp = tempfile::tempdir().unwrap();
+        fs_err::write(
+            temp.path().join(CARGO_TOML),
+            "[workspace]\nmembers = [\"tools/member\"]\nexclude = [\"tools/standalone\"]\n",
+        )
+        .unwrap();
+        write_package(&temp.path().join("tools/member"), "member", "");
+        write_package(&temp.path().join("tools/standalone"), "standalone", "");
+
+        let roots = discover_cargo_projects(temp.path(), Path::new("tools")).unwrap();
+        assert_eq!(roots, ["tools/standalone"]);
+    }
+
+    #[test]
+    fn unknown_env_override_is_ignored() {
+        let env_vars = [
+            ("K_RELEASER_PR_DRAFTT".to_string(), "true".to_string()),
+            (
+                "K_RELEASER_PR_LABELS".to_string(),
+                r#"["release"]"#.to_string(),
+            ),
+        ];
+        let config = Config::default().with_overrides(env_vars).unwrap();
+        assert_eq!(config.workspace.pr_labels, ["release"]);
+    }
+
+    #[test]
+    fn invalid_env_override_is_rejected() {
+        let env_vars = [("K_RELEASER_PR_DRAFT".to_string(), "maybe".to_string())];
+        let error = Config::default().with_overrides(env_vars).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid config override in the K_RELEASER_PR_DRAFT environment variable"
+        );
+    }
+
+    #[test]
+    fn env_overrides_are_applied_after_branch_overrides() {
+        let config = r#"
+[branch."release/1.x"]
+pr_branch_prefix = "release-1.x-"
+"#;
+        let env_vars = [(
+            "K_RELEASER_PR_BRANCH_PREFIX".to_string(),
+            "from-env-".to_string(),
+        )];
+        let config = toml::from_str::<Config>(config)
+            .unwrap()
+            .with_branch_overrides("release/1.x")
+            .with_overrides(env_vars)
+            .unwrap();
+        assert_eq!(
+            config.workspace.pr_branch_prefix.as_deref(),
+            Some("from-env-")
+        );
+    }
 }
@@ -20,16 +20,25 @@ pub struct WorkspaceDefaultsDisplay {
     features_always_increment_minor: Option<bool>,
     git_release_enable: Option<bool>,
     git_release_body: Option<String>,
+    git_release_body_source: Option<String>,
+    git_release_assets: Option<Vec<String>>,
     git_release_type: Option<String>,
     git_release_draft: Option<bool>,
     git_release_latest: Option<bool>,
     git_release_name: Option<String>,
     git_tag_enable: Option<bool>,
     git_tag_name: Option<String>,
+    git_tag_annotated: Option<bool>,
+    git_tag_message: Option<String>,
+    git_tag_extra_names: Option<Vec<String>>,
     publish_allow_dirty: Option<bool>,
     publish_no_verify: Option<bool>,
     publish_features: Option<Vec<String>>,
     publish_all_features: Option<bool>,
+    publish_registries: Option<Vec<String>>,
+    publish_deny_files: Option<Vec<String>>,
+    publish_max_size: Option<String>,
+    crates_io_owners: Option<Vec<String>>,
     semver_check: Option<bool>,
 }
 
@@ -37,17 +46,66 @@ pub struct WorkspaceDefaultsDisplay {
 pub struct WorkspaceOverridesDisplay {
     allow_dirty: Option<bool>,
     changelog_config: Option<String>,
+    changelog_group_by_package: bool,
     dependencies_update: Option<bool>,
     pr_name: Option<String>,
     pr_body: Option<String>,
     pr_draft: bool,
     pr_labels: Vec<String>,
+    pr_reviewers: Vec<String>,
+    pr_reviewers_from_codeowners: bool,
+    pr_assignees: Vec<String>,
+    pr_required_approvals: Option<u32>,
+    pr_auto_merge: Option<String>,
     pr_branch_prefix: Option<String>,
+    pr_branch_name: Option<String>,
+    pr_milestone: Option<String>,
+    pr_project: Option<String>,
+    pr_split: Option<String>,
+    pr_update_strategy: Option<String>,
+    pr_signed_commits: bool,
     publish_timeout: Option<String>,
+    publish_wait_poll_interval: Option<String>,
+    package_output_dir: Option<String>,
+    publish_smoke_test: bool,
+    publish_require_checks: bool,
+    docs_rs_timeout: Option<String>,
     repo_url: Option<String>,
+    git_remote: Option<String>,
+    fetch_depth_strategy: Option<String>,
+    ca_cert_path: Option<String>,
+    client_cert_path: Option<String>,
+    accept_invalid_certs: bool,
+    proxy: Option<String>,
     release_commits: Option<String>,
+    release_ignore_paths: Vec<String>,
+    packages_include: Vec<String>,
+    packages_exclude: Vec<String>,
+    workspace_roots: Vec<String>,
+    workspace_discovery_paths: Vec<String>,
     release_always: Option<bool>,
+    release_wait_for_checks: bool,
+    release_alias_tags: bool,
+    git_tag_sign: Option<bool>,
+    signing_key: Option<String>,
+    release_close_milestone: bool,
+    milestone_title: Option<String>,
+    milestone_next: Option<String>,
+    release_comment_issues: bool,
+    release_close_issues: bool,
+    required_checks: Vec<String>,
+    ci_job_name: Option<String>,
+    checks_timeout: Option<String>,
     max_analyze_commits: Option<u32>,
+    version_mode: Option<String>,
+    prerelease_label: Option<String>,
+    pre_update_hook: Option<String>,
+    post_update_hook: Option<String>,
+    pre_publish_hook: Option<String>,
+    post_publish_hook: Option<String>,
+    pre_release_hook: Option<String>,
+    post_release_hook: Option<String>,
+    extra_version_files: Vec<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -118,6 +176,12 @@ fn format_option_fields(defaults: &WorkspaceDefaultsDisplay) -> String {
     if let Some(ref val) = defaults.git_release_body {
         output.push_str(&format!("  git_release_body: {}\n", val));
     }
+    if let Some(ref val) = defaults.git_release_body_source {
+        output.push_str(&format!("  git_release_body_source: {}\n", val));
+    }
+    if let Some(ref val) = defaults.git_release_assets {
+        output.push_str(&format!("  git_release_assets: {:?}\n", val));
+    }
     if let Some(ref val) = defaults.git_release_type {
         output.push_str(&format!("  git_release_type: {}\n", val));
     }
@@ -136,6 +200,15 @@ fn format_option_fields(defaults: &WorkspaceDefaultsDisplay) -> String {
     if let Some(ref val) = defaults.git_tag_name {
         output.push_str(&format!("  git_tag_name: {}\n", val));
     }
+    if let Some(val) = defaults.git_tag_annotated {
+        output.push_str(&format!("  git_tag_annotated: {}\n", val));
+    }
+    if let Some(ref val) = defaults.git_tag_message {
+        output.push_str(&format!("  git_tag_message: {}\n", val));
+    }
+    if let Some(ref val) = defaults.git_tag_extra_names {
+        output.push_str(&format!("  git_tag_extra_names: {:?}\n", val));
+    }
     if let Some(val) = defaults.publish_allow_dirty {
         output.push_str(&format!("  publish_allow_dirty: {}\n", val));
     }
@@ -148,6 +221,18 @@ fn format_option_fields(defaults: &WorkspaceDefaultsDisplay) -> String {
     if let Some(val) = defaults.publish_all_features {
         output.push_str(&format!("  publish_all_features: {}\n", val));
     }
+    if let Some(ref val) = defaults.publish_registries {
+        output.push_str(&format!("  publish_registries: {:?}\n", val));
+    }
+    if let Some(ref val) = defaults.publish_deny_files {
+        output.push_str(&format!("  publish_deny_files: {:?}\n", val));
+    }
+    if let Some(ref val) = defaults.publish_max_size {
+        output.push_str(&format!("  publish_max_size: {}\n", val));
+    }
+    if let Some(ref val) = defaults.crates_io_owners {
+        output.push_str(&format!("  crates_io_owners: {:?}\n", val));
+    }
     if let Some(val) = defaults.semver_check {
         output.push_str(&format!("  semver_check: {}\n", val));
     }
@@ -168,6 +253,9 @@ fn format_workspace_overrides(overrides: &WorkspaceOverridesDisplay) -> String {
     if let Some(ref val) = overrides.changelog_config {
         output.push_str(&format!("  changelog_config: {}\n", val));
     }
+    if overrides.changelog_group_by_package {
+        output.push_str("  changelog_group_by_package: true\n");
+    }
     if let Some(val) = overrides.dependencies_update {
         output.push_str(&format!("  dependencies_update: {}\n", val));
     }
@@ -183,27 +271,192 @@ fn format_workspace_overrides(overrides: &WorkspaceOverridesDisplay) -> String {
     if !overrides.pr_labels.is_empty() {
         output.push_str(&format!("  pr_labels: {:?}\n", overrides.pr_labels));
     }
+    if !overrides.pr_reviewers.is_empty() {
+        output.push_str(&format!("  pr_reviewers: {:?}\n", overrides.pr_reviewers));
+    }
+    if overrides.pr_reviewers_from_codeowners {
+        output.push_str("  pr_reviewers_from_codeowners: true\n");
+    }
+    if !overrides.pr_assignees.is_empty() {
+        output.push_str(&format!("  pr_assignees: {:?}\n", overrides.pr_assignees));
+    }
+    if let Some(val) = overrides.pr_required_approvals {
+        output.push_str(&format!("  pr_required_approvals: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pr_auto_merge {
+        output.push_str(&format!("  pr_auto_merge: {}\n", val));
+    }
     if let Some(ref val) = overrides.pr_branch_prefix {
         output.push_str(&format!("  pr_branch_prefix: {}\n", val));
     }
+    if let Some(ref val) = overrides.pr_branch_name {
+        output.push_str(&format!("  pr_branch_name: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pr_milestone {
+        output.push_str(&format!("  pr_milestone: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pr_project {
+        output.push_str(&format!("  pr_project: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pr_split {
+        output.push_str(&format!("  pr_split: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pr_update_strategy {
+        output.push_str(&format!("  pr_update_strategy: {}\n", val));
+    }
+    if overrides.pr_signed_commits {
+        output.push_str("  pr_signed_commits: true\n");
+    }
     if let Some(ref val) = overrides.publish_timeout {
         output.push_str(&format!("  publish_timeout: {}\n", val));
     }
+    if let Some(ref val) = overrides.publish_wait_poll_interval {
+        output.push_str(&format!("  publish_wait_poll_interval: {}\n", val));
+    }
+    if let Some(ref val) = overrides.package_output_dir {
+        output.push_str(&format!("  package_output_dir: {}\n", val));
+    }
+    if overrides.publish_smoke_test {
+        output.push_str("  publish_smoke_test: true\n");
+    }
+    if overrides.publish_require_checks {
+        output.push_str("  publish_require_checks: true\n");
+    }
+    if let Some(ref val) = overrides.docs_rs_timeout {
+        output.push_str(&format!("  docs_rs_timeout: {}\n", val));
+    }
     if let Some(ref val) = overrides.repo_url {
         output.push_str(&format!("  repo_url: {}\n", val));
     }
+    if let Some(ref val) = overrides.git_remote {
+        output.push_str(&format!("  git_remote: {}\n", val));
+    }
+    if let Some(ref val) = overrides.fetch_depth_strategy {
+        output.push_str(&format!("  fetch_depth_strategy: {}\n", val));
+    }
+    if let Some(ref val) = overrides.ca_cert_path {
+        output.push_str(&format!("  ca_cert_path: {}\n", val));
+    }
+    if let Some(ref val) = overrides.client_cert_path {
+        output.push_str(&format!("  client_cert_path: {}\n", val));
+    }
+    if overrides.accept_invalid_certs {
+        output.push_str("  accept_invalid_certs: true\n");
+    }
+    if let Some(ref val) = overrides.proxy {
+        output.push_str(&format!("  proxy: {}\n", val));
+    }
     if let Some(ref val) = overrides.release_commits {
         output.push_str(&format!("  release_commits: {}\n", val));
     }
+    if !overrides.release_ignore_paths.is_empty() {
+        output.push_str(&format!(
+            "  release_ignore_paths: {:?}\n",
+            overrides.release_ignore_paths
+        ));
+    }
+    if !overrides.packages_include.is_empty() {
+        output.push_str(&format!(
+            "  packages_include: {:?}\n",
+            overrides.packages_include
+        ));
+    }
+    if !overrides.packages_exclude.is_empty() {
+        output.push_str(&format!(
+            "  packages_exclude: {:?}\n",
+            overrides.packages_exclude
+        ));
+    }
+    if !overrides.workspace_roots.is_empty() {
+        output.push_str(&format!(
+            "  workspace_roots: {:?}\n",
+            overrides.workspace_roots
+        ));
+    }
+    if !overrides.workspace_discovery_paths.is_empty() {
+        output.push_str(&format!(
+            "  workspace_discovery_paths: {:?}\n",
+            overrides.workspace_discovery_paths
+        ));
+    }
     if let Some(val) = overrides.release_always {
         output.push_str(&format!("  release_always: {}\n", val));
     }
+    if overrides.release_wait_for_checks {
+        output.push_str("  release_wait_for_checks: true\n");
+    }
+    if overrides.release_alias_tags {
+        output.push_str("  release_alias_tags: true\n");
+    }
+    if let Some(val) = overrides.git_tag_sign {
+        output.push_str(&format!("  git_tag_sign: {}\n", val));
+    }
+    if let Some(ref val) = overrides.signing_key {
+        output.push_str(&format!("  signing_key: {}\n", val));
+    }
+    if overrides.release_close_milestone {
+        output.push_str("  release_close_milestone: true\n");
+    }
+    if let Some(ref val) = overrides.milestone_title {
+        output.push_str(&format!("  milestone_title: {}\n", val));
+    }
+    if let Some(ref val) = overrides.milestone_next {
+        output.push_str(&format!("  milestone_next: {}\n", val));
+    }
+    if overrides.release_comment_issues {
+        output.push_str("  release_comment_issues: true\n");
+    }
+    if overrides.release_close_issues {
+        output.push_str("  release_close_issues: true\n");
+    }
+    if !overrides.required_checks.is_empty() {
+        output.push_str(&format!(
+            "  required_checks: {:?}\n",
+            overrides.required_checks
+        ));
+    }
+    if let Some(ref val) = overrides.ci_job_name {
+        output.push_str(&format!("  ci_job_name: {}\n", val));
+    }
+    if let Some(ref val) = overrides.checks_timeout {
+        output.push_str(&format!("  checks_timeout: {}\n", val));
+    }
     // Don't show max_analyze_commits if it's the default value
     if let Some(val) = overrides.max_analyze_commits
         && val != 1000
     {
         output.push_str(&format!("  max_analyze_commits: {}\n", val));
     }
+    if let Some(ref val) = overrides.version_mode {
+        output.push_str(&format!("  version_mode: {}\n", val));
+    }
+    if let Some(ref val) = overrides.prerelease_label {
+        output.push_str(&format!("  prerelease_label: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pre_update_hook {
+        output.push_str(&format!("  pre_update_hook: {}\n", val));
+    }
+    if let Some(ref val) = overrides.post_update_hook {
+        output.push_str(&format!("  post_update_hook: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pre_publish_hook {
+        output.push_str(&format!("  pre_publish_hook: {}\n", val));
+    }
+    if let Some(ref val) = overrides.post_publish_hook {
+        output.push_str(&format!("  post_publish_hook: {}\n", val));
+    }
+    if let Some(ref val) = overrides.pre_release_hook {
+        output.push_str(&format!("  pre_release_hook: {}\n", val));
+    }
+    if let Some(ref val) = overrides.post_release_hook {
+        output.push_str(&format!("  post_release_hook: {}\n", val));
+    }
+    if !overrides.extra_version_files.is_empty() {
+        output.push_str(&format!(
+            "  extra_version_files: {:?}\n",
+            overrides.extra_version_files
+        ));
+    }
 
     if output.is_empty() {
         output.push_str("  (No workspace-specific settings set)\n");
@@ -218,7 +471,8 @@ pub fn show_config(args: ShowConfig) -> anyhow::Result<()> {
         args.config.load_from(manifest_path)?
     } else {
         args.config.load()?
-    };
+    }
+    .with_env_overrides()?;
     let config_source = determine_config_source(&args);
 
     // Load workspace metadata to get package info
@@ -292,6 +546,11 @@ pub(crate) fn extract_workspace_defaults(defaults: &PackageConfig) -> WorkspaceD
         features_always_increment_minor: defaults.features_always_increment_minor,
         git_release_enable: defaults.git_release_enable,
         git_release_body: defaults.git_release_body.clone(),
+        git_release_body_source: defaults
+            .git_release_body_source
+            .as_ref()
+            .map(|s| format!("{:?}", s)),
+        git_release_assets: defaults.git_release_assets.clone(),
         git_release_type: defaults
             .git_release_type
             .as_ref()
@@ -301,10 +560,17 @@ pub(crate) fn extract_workspace_defaults(defaults: &PackageConfig) -> WorkspaceD
         git_release_name: defaults.git_release_name.clone(),
         git_tag_enable: defaults.git_tag_enable,
         git_tag_name: defaults.git_tag_name.clone(),
+        git_tag_annotated: defaults.git_tag_annotated,
+        git_tag_message: defaults.git_tag_message.clone(),
+        git_tag_extra_names: defaults.git_tag_extra_names.clone(),
         publish_allow_dirty: defaults.publish_allow_dirty,
         publish_no_verify: defaults.publish_no_verify,
         publish_features: defaults.publish_features.clone(),
         publish_all_features: defaults.publish_all_features,
+        publish_registries: registry_names(defaults),
+        publish_deny_files: defaults.publish_deny_files.clone(),
+        publish_max_size: defaults.publish_max_size.clone(),
+        crates_io_owners: defaults.crates_io_owners.clone(),
         semver_check: defaults.semver_check,
     }
 }
@@ -318,17 +584,82 @@ pub(crate) fn extract_workspace_overrides(
             .changelog_config
             .as_ref()
             .map(|p| p.display().to_string()),
+        changelog_group_by_package: workspace.changelog_group_by_package,
         dependencies_update: workspace.dependencies_update,
         pr_name: workspace.pr_name.clone(),
         pr_body: workspace.pr_body.clone(),
         pr_draft: workspace.pr_draft,
         pr_labels: workspace.pr_labels.clone(),
+        pr_reviewers: workspace.pr_reviewers.clone(),
+        pr_reviewers_from_codeowners: workspace.pr_reviewers_from_codeowners,
+        pr_assignees: workspace.pr_assignees.clone(),
+        pr_required_approvals: workspace.pr_required_approvals,
+        pr_auto_merge: workspace.pr_auto_merge.map(|m| format!("{:?}", m)),
         pr_branch_prefix: workspace.pr_branch_prefix.clone(),
+        pr_branch_name: workspace.pr_branch_name.clone(),
+        pr_milestone: workspace.pr_milestone.clone(),
+        pr_project: workspace.pr_project.as_ref().map(|p| format!("{:?}", p)),
+        pr_split: workspace.pr_split.as_ref().map(|s| format!("{:?}", s)),
+        pr_update_strategy: workspace
+            .pr_update_strategy
+            .as_ref()
+            .map(|s| format!("{:?}", s)),
+        pr_signed_commits: workspace.pr_signed_commits,
         publish_timeout: workspace.publish_timeout.clone(),
+        publish_wait_poll_interval: workspace.publish_wait_poll_interval.clone(),
+        package_output_dir: workspace
+            .package_output_dir
+            .as_ref()
+            .map(|p| p.display().to_string()),
+        publish_smoke_test: workspace.publish_smoke_test,
+        publish_require_checks: workspace.publish_require_checks,
+        docs_rs_timeout: workspace.docs_rs_timeout.clone(),
         repo_url: workspace.repo_url.as_ref().map(|u| u.to_string()),
+        git_remote: workspace.git_remote.clone(),
+        fetch_depth_strategy: workspace.fetch_depth_strategy.map(|s| format!("{:?}", s)),
+        ca_cert_path: workspace
+            .ca_cert_path
+            .as_ref()
+            .map(|p| p.display().to_string()),
+        client_cert_path: workspace
+            .client_cert_path
+            .as_ref()
+            .map(|p| p.display().to_string()),
+        accept_invalid_certs: workspace.accept_invalid_certs,
+        proxy: workspace.proxy.clone(),
         release_commits: workspace.release_commits.clone(),
+        release_ignore_paths: workspace.release_ignore_paths.clone(),
+        packages_include: workspace.packages_include.clone(),
+        packages_exclude: workspace.packages_exclude.clone(),
+        workspace_roots: workspace.workspace_roots.clone(),
+        workspace_discovery_paths: workspace.workspace_discovery_paths.clone(),
         release_always: workspace.release_always,
+        release_wait_for_checks: workspace.release_wait_for_checks,
+        release_alias_tags: workspace.release_alias_tags,
+        git_tag_sign: workspace.git_tag_sign,
+        signing_key: workspace.signing_key.clone(),
+        release_close_milestone: workspace.release_close_milestone,
+        milestone_title: workspace.milestone_title.clone(),
+        milestone_next: workspace.milestone_next.map(|n| format!("{:?}", n)),
+        release_comment_issues: workspace.release_comment_issues,
+        release_close_issues: workspace.release_close_issues,
+        required_checks: workspace.required_checks.clone(),
+        ci_job_name: workspace.ci_job_name.clone(),
+        checks_timeout: workspace.checks_timeout.clone(),
         max_analyze_commits: workspace.max_analyze_commits,
+        version_mode: workspace.version_mode.as_ref().map(|m| format!("{:?}", m)),
+        prerelease_label: workspace.prerelease_label.clone(),
+        pre_update_hook: workspace.pre_update_hook.clone(),
+        post_update_hook: workspace.post_update_hook.clone(),
+        pre_publish_hook: workspace.pre_publish_hook.clone(),
+        post_publish_hook: workspace.post_publish_hook.clone(),
+        pre_release_hook: workspace.pre_release_hook.clone(),
+        post_release_hook: workspace.post_release_hook.clone(),
+        extra_version_files: workspace
+            .extra_version_files
+            .iter()
+            .map(|file| file.path.display().to_string())
+            .collect(),
     }
 }
 
@@ -341,7 +672,11 @@ fn build_package_config_display(
 
     // Determine which fields are explicitly overridden
     let explicit_overrides = if let Some(pkg_cfg) = package_config {
-        extract_explicit_overrides(pkg_cfg.common())
+        let mut overrides = extract_explicit_overrides(pkg_cfg.common());
+        if !pkg_cfg.is_release_enabled() {
+            overrides.insert("release".to_string(), "false".to_string());
+        }
+        overrides
     } else {
         HashMap::new()
     };
@@ -357,6 +692,13 @@ fn build_package_config_display(
     })
 }
 
+fn registry_names(config: &PackageConfig) -> Option<Vec<String>> {
+    config
+        .publish_registries
+        .as_ref()
+        .map(|registries| registries.iter().map(|r| r.name.clone()).collect())
+}
+
 pub(crate) fn extract_explicit_overrides(config: &PackageConfig) -> HashMap<String, String> {
     let mut overrides = HashMap::new();
 
@@ -378,6 +720,12 @@ pub(crate) fn extract_explicit_overrides(config: &PackageConfig) -> HashMap<Stri
     if let Some(ref val) = config.git_release_body {
         overrides.insert("git_release_body".to_string(), val.clone());
     }
+    if let Some(ref val) = config.git_release_body_source {
+        overrides.insert("git_release_body_source".to_string(), format!("{:?}", val));
+    }
+    if let Some(ref val) = config.git_release_assets {
+        overrides.insert("git_release_assets".to_string(), format!("{:?}", val));
+    }
     if let Some(ref val) = config.git_release_type {
         overrides.insert("git_release_type".to_string(), format!("{:?}", val));
     }
@@ -396,6 +744,15 @@ pub(crate) fn extract_explicit_overrides(config: &PackageConfig) -> HashMap<Stri
     if let Some(ref val) = config.git_tag_name {
         overrides.insert("git_tag_name".to_string(), val.clone());
     }
+    if let Some(val) = config.git_tag_annotated {
+        overrides.insert("git_tag_annotated".to_string(), val.to_string());
+    }
+    if let Some(ref val) = config.git_tag_message {
+        overrides.insert("git_tag_message".to_string(), val.clone());
+    }
+    if let Some(ref val) = config.git_tag_extra_names {
+        overrides.insert("git_tag_extra_names".to_string(), format!("{:?}", val));
+    }
     if let Some(val) = config.publish_allow_dirty {
         overrides.insert("publish_allow_dirty".to_string(), val.to_string());
     }
@@ -408,6 +765,18 @@ pub(crate) fn extract_explicit_overrides(config: &PackageConfig) -> HashMap<Stri
     if let Some(val) = config.publish_all_features {
         overrides.insert("publish_all_features".to_string(), val.to_string());
     }
+    if let Some(val) = registry_names(config) {
+        overrides.insert("publish_registries".to_string(), format!("{:?}", val));
+    }
+    if let Some(ref val) = config.publish_deny_files {
+        overrides.insert("publish_deny_files".to_string(), format!("{:?}", val));
+    }
+    if let Some(ref val) = config.publish_max_size {
+        overrides.insert("publish_max_size".to_string(), val.clone());
+    }
+    if let Some(ref val) = config.crates_io_owners {
+        overrides.insert("crates_io_owners".to_string(), format!("{:?}", val));
+    }
     if let Some(val) = config.semver_check {
         overrides.insert("semver_check".to_string(), val.to_string());
     }
@@ -1,4 +1,7 @@
-use crate::config::{PackageConfig, Workspace};
+use crate::config::{
+    ExtraVersionFile, FetchDepthStrategy, MergeMethod, MilestoneNext, PackageConfig, PrProject,
+    PrSplit, PrUpdateStrategy, VersionMode, Workspace,
+};
 use crate::config_show::{
     extract_explicit_overrides, extract_workspace_defaults, extract_workspace_overrides,
 };
@@ -12,6 +15,7 @@ fn extract_workspace_defaults_with_all_fields() {
     defaults.features_always_increment_minor = Some(true);
     defaults.git_release_enable = Some(true);
     defaults.git_release_body = Some("{{ changelog }}".to_string());
+    defaults.git_release_assets = Some(vec!["dist/*.tar.gz".to_string()]);
     defaults.git_release_draft = Some(true);
     defaults.git_release_latest = Some(false);
     defaults.git_release_name = Some("Release {{ version }}".to_string());
@@ -33,6 +37,10 @@ fn extract_workspace_defaults_with_all_fields() {
         display.git_release_body,
         Some("{{ changelog }}".to_string())
     );
+    assert_eq!(
+        display.git_release_assets,
+        Some(vec!["dist/*.tar.gz".to_string()])
+    );
     assert_eq!(display.git_release_draft, Some(true));
     assert_eq!(display.git_release_latest, Some(false));
     assert_eq!(
@@ -59,6 +67,7 @@ fn extract_workspace_defaults_with_none_fields() {
     assert_eq!(display.features_always_increment_minor, None);
     assert_eq!(display.git_release_enable, None);
     assert_eq!(display.git_release_body, None);
+    assert_eq!(display.git_release_assets, None);
     assert_eq!(display.git_release_draft, None);
     assert_eq!(display.git_release_latest, None);
     assert_eq!(display.git_release_name, None);
@@ -76,17 +85,75 @@ fn extract_workspace_overrides_with_all_fields() {
     let workspace = Workspace {
         allow_dirty: Some(true),
         changelog_config: Some("cliff.toml".into()),
+        changelog_group_by_package: true,
         dependencies_update: Some(true),
         pr_name: Some("Release PR".to_string()),
         pr_body: Some("Release body".to_string()),
         pr_draft: true,
         pr_labels: vec!["release".to_string()],
+        pr_reviewers: vec!["my-org/maintainers".to_string()],
+        pr_reviewers_from_codeowners: true,
+        pr_assignees: vec!["alice".to_string()],
+        pr_required_approvals: Some(2),
+        pr_auto_merge: Some(MergeMethod::Squash),
         pr_branch_prefix: Some("release-".to_string()),
+        pr_branch_name: Some("v{{ version }}".to_string()),
+        pr_milestone: Some("v{{ version }}".to_string()),
+        pr_project: Some(PrProject {
+            owner: None,
+            number: 3,
+            status: None,
+        }),
+        pr_split: Some(PrSplit::PerPackage),
+        pr_update_strategy: Some(PrUpdateStrategy::Rebase),
+        pr_signed_commits: true,
         publish_timeout: Some("30m".to_string()),
+        publish_wait_poll_interval: None,
+        package_output_dir: None,
+        publish_smoke_test: false,
+        publish_require_checks: false,
+        docs_rs_timeout: None,
         repo_url: Some("https://github.com/user/repo".parse().unwrap()),
+        git_remote: Some("upstream".to_string()),
+        fetch_depth_strategy: Some(FetchDepthStrategy::Fail),
+        ca_cert_path: Some("ca.pem".into()),
+        client_cert_path: None,
+        accept_invalid_certs: true,
+        proxy: Some("http://proxy:3128".to_string()),
         release_commits: Some("^feat:".to_string()),
+        release_ignore_paths: vec!["docs/**".to_string()],
+        packages_include: vec!["crates/public-*".to_string()],
+        packages_exclude: vec![],
+        workspace_roots: vec!["rust/agent".to_string()],
+        workspace_discovery_paths: vec![],
         release_always: Some(true),
+        release_wait_for_checks: false,
+        release_alias_tags: false,
+        git_tag_sign: Some(true),
+        signing_key: None,
+        release_close_milestone: true,
+        milestone_title: None,
+        milestone_next: Some(MilestoneNext::Minor),
+        release_comment_issues: true,
+        release_close_issues: false,
+        required_checks: vec![],
+        ci_job_name: None,
+        checks_timeout: None,
         max_analyze_commits: Some(2000),
+        version_mode: Some(VersionMode::Independent),
+        prerelease_label: Some("beta".to_string()),
+        pre_update_hook: Some("make codegen".to_string()),
+        post_update_hook: None,
+        pre_publish_hook: None,
+        post_publish_hook: None,
+        pre_release_hook: None,
+        post_release_hook: Some("./notify.sh".to_string()),
+        extra_version_files: vec![ExtraVersionFile {
+            path: "helm/Chart.yaml".into(),
+            search: None,
+            replace: None,
+            package: None,
+        }],
         packages_defaults: PackageConfig::default(),
     };
 
@@ -94,20 +161,60 @@ fn extract_workspace_overrides_with_all_fields() {
 
     assert_eq!(display.allow_dirty, Some(true));
     assert_eq!(display.changelog_config, Some("cliff.toml".to_string()));
+    assert!(display.changelog_group_by_package);
     assert_eq!(display.dependencies_update, Some(true));
     assert_eq!(display.pr_name, Some("Release PR".to_string()));
     assert_eq!(display.pr_body, Some("Release body".to_string()));
     assert!(display.pr_draft);
     assert_eq!(display.pr_labels, vec!["release".to_string()]);
+    assert_eq!(display.pr_reviewers, vec!["my-org/maintainers".to_string()]);
+    assert!(display.pr_reviewers_from_codeowners);
+    assert_eq!(display.pr_assignees, vec!["alice".to_string()]);
+    assert_eq!(display.pr_required_approvals, Some(2));
+    assert_eq!(display.pr_auto_merge, Some("Squash".to_string()));
     assert_eq!(display.pr_branch_prefix, Some("release-".to_string()));
+    assert_eq!(display.pr_branch_name, Some("v{{ version }}".to_string()));
+    assert_eq!(display.pr_milestone, Some("v{{ version }}".to_string()));
+    assert_eq!(
+        display.pr_project,
+        Some("PrProject { owner: None, number: 3, status: None }".to_string())
+    );
+    assert_eq!(display.pr_split, Some("PerPackage".to_string()));
+    assert_eq!(display.pr_update_strategy, Some("Rebase".to_string()));
+    assert!(display.pr_signed_commits);
     assert_eq!(display.publish_timeout, Some("30m".to_string()));
     assert_eq!(
         display.repo_url,
         Some("https://github.com/user/repo".to_string())
     );
+    assert_eq!(display.git_remote, Some("upstream".to_string()));
+    assert_eq!(display.fetch_depth_strategy, Some("Fail".to_string()));
+    assert_eq!(display.ca_cert_path, Some("ca.pem".to_string()));
+    assert!(display.accept_invalid_certs);
+    assert_eq!(display.proxy, Some("http://proxy:3128".to_string()));
     assert_eq!(display.release_commits, Some("^feat:".to_string()));
+    assert_eq!(display.release_ignore_paths, vec!["docs/**".to_string()]);
+    assert_eq!(
+        display.packages_include,
+        vec!["crates/public-*".to_string()]
+    );
+    assert_eq!(display.workspace_roots, vec!["rust/agent".to_string()]);
     assert_eq!(display.release_always, Some(true));
+    assert_eq!(display.git_tag_sign, Some(true));
+    assert!(display.release_close_milestone);
+    assert_eq!(display.milestone_next, Some("Minor".to_string()));
+    assert!(display.release_comment_issues);
+    assert!(!display.release_close_issues);
     assert_eq!(display.max_analyze_commits, Some(2000));
+    assert_eq!(display.version_mode, Some("Independent".to_string()));
+    assert_eq!(display.prerelease_label, Some("beta".to_string()));
+    assert_eq!(display.pre_update_hook, Some("make codegen".to_string()));
+    assert_eq!(display.post_update_hook, None);
+    assert_eq!(display.post_release_hook, Some("./notify.sh".to_string()));
+    assert_eq!(
+        display.extra_version_files,
+        vec!["helm/Chart.yaml".to_string()]
+    );
 }
 
 #[test]
@@ -119,6 +226,7 @@ fn extract_explicit_overrides_with_all_fields() {
     config.features_always_increment_minor = Some(true);
     config.git_release_enable = Some(true);
     config.git_release_body = Some("{{ changelog }}".to_string());
+    config.git_release_assets = Some(vec!["dist/*.tar.gz".to_string()]);
     config.git_release_draft = Some(true);
     config.git_release_latest = Some(false);
     config.git_release_name = Some("Release {{ version }}".to_string());
@@ -149,6 +257,10 @@ fn extract_explicit_overrides_with_all_fields() {
         overrides.get("git_release_body"),
         Some(&"{{ changelog }}".to_string())
     );
+    assert_eq!(
+        overrides.get("git_release_assets"),
+        Some(&"[\"dist/*.tar.gz\"]".to_string())
+    );
     assert_eq!(
         overrides.get("git_release_draft"),
         Some(&"true".to_string())
@@ -0,0 +1,241 @@
+//! Diagnostics of the environment where k-releaser runs.
+
+use std::process::Command;
+
+use anyhow::Context as _;
+use git_cmd::Repo;
+use k_releaser_core::{GitClient, PublishRequest, Publishable as _};
+
+use crate::{
+    args::{
+        doctor::Doctor, manifest_command::ManifestCommand as _, repo_command::RepoCommand as _,
+        update::UpdateOptions,
+    },
+    config::Config,
+};
+
+/// Result of a single check.
+struct Check {
+    name: &'static str,
+    /// Details of the passed check, or reason of the failure.
+    result: anyhow::Result<String>,
+}
+
+impl Check {
+    fn new(name: &'static str, result: anyhow::Result<String>) -> Self {
+        Self { name, result }
+    }
+}
+
+/// Run all the checks and print the report.
+/// Fails if any check fails.
+pub async fn doctor(args: &Doctor) -> anyhow::Result<()> {
+    let checks = run_checks(args).await;
+    let (report, failed) = report(&checks);
+    print!("{report}");
+    anyhow::ensure!(failed == 0, "{failed} of {} checks failed", checks.len());
+    Ok(())
+}
+
+async fn run_checks(args: &Doctor) -> Vec<Check> {
+    let mut checks = vec![Check::new("git", git_version())];
+
+    let config = args.project.load_config();
+    let config_check = config
+        .as_ref()
+        .map(|_| "loaded".to_string())
+        .map_err(clone_err);
+    checks.push(Check::new("config", config_check));
+    // Run the other checks with the default config if the config is invalid.
+    let config = config.unwrap_or_default();
+
+    let repo = repo(args);
+    checks.push(Check::new("repository", repository_state(&repo)));
+    checks.push(Check::new("remote tags", remote_tags(&repo)));
+
+    let http = args.project.http.configure(&config);
+    checks.push(Check::new(
+        "forge token",
+        match http {
+            Ok(()) => forge_token(args, &config).await,
+            Err(e) => Err(e),
+        },
+    ));
+
+    let metadata = args.project.cargo_metadata();
+    checks.push(Check::new(
+        "registry token",
+        registry_token(args, &config, metadata.as_ref().ok()),
+    ));
+    checks.push(Check::new(
+        "cargo metadata",
+        metadata.as_ref().map_err(clone_err).map(|metadata| {
+            let packages = metadata.workspace_members.len();
+            format!("{packages} workspace packages")
+        }),
+    ));
+    if let Ok(metadata) = metadata {
+        let changelogs = changelogs(args, &config, metadata);
+        checks.push(Check::new("changelogs", changelogs));
+    }
+    checks
+}
+
+/// Format the report of the checks.
+/// Returns the report and the number of failed checks.
+fn report(checks: &[Check]) -> (String, usize) {
+    let mut report = String::new();
+    let mut failed = 0;
+    for check in checks {
+        let line = match &check.result {
+            Ok(details) => format!("PASS {}: {details}\n", check.name),
+            Err(e) => {
+                failed += 1;
+                format!("FAIL {}: {e:#}\n", check.name)
+            }
+        };
+        report.push_str(&line);
+    }
+    (report, failed)
+}
+
+fn clone_err(e: &anyhow::Error) -> anyhow::Error {
+    anyhow::anyhow!("{e:#}")
+}
+
+fn git_version() -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .arg("--version")
+        .output()
+        .context("git is not installed or not in PATH")?;
+    anyhow::ensure!(output.status.success(), "`git --version` failed");
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn repo(args: &Doctor) -> anyhow::Result<Repo> {
+    let manifest_path = args.project.manifest_path();
+    let project_dir = k_releaser_core::manifest_dir(&manifest_path)?;
+    Repo::new(project_dir)
+}
+
+fn repository_state(repo: &anyhow::Result<Repo>) -> anyhow::Result<String> {
+    let repo = repo.as_ref().map_err(clone_err)?;
+    anyhow::ensure!(
+        !repo.is_shallow()?,
+        "the repository is a shallow clone: fetch the full history, e.g. with `fetch-depth: 0` in actions/checkout"
+    );
+    repo.is_clean()
+        .context("the repository has uncommitted changes")?;
+    Ok(format!("clean, on branch {}", repo.original_branch()))
+}
+
+fn remote_tags(repo: &anyhow::Result<Repo>) -> anyhow::Result<String> {
+    let repo = repo.as_ref().map_err(clone_err)?;
+    let tags = repo
+        .git(&["ls-remote", "--tags", repo.original_remote()])
+        .with_context(|| {
+            format!(
+                "can't list the tags of the remote {}",
+                repo.original_remote()
+            )
+        })?;
+    let tags = tags.lines().filter(|line| !line.ends_with("^{}")).count();
+    Ok(format!("{tags} tags in {}", repo.original_remote()))
+}
+
+async fn forge_token(args: &Doctor, config: &Config) -> anyhow::Result<String> {
+    let repo_url = args.project.get_repo_url(config)?;
+    let forge = args.project.forge.git_forge(repo_url.clone())?.context(
+        "no git token found: pass it with --git-token or set the token environment variable of the forge",
+    )?;
+    let git_client = GitClient::new(forge)?;
+    k_releaser_core::check_token_scopes(&git_client).await?;
+    Ok(format!("can write to {}/{}", repo_url.owner, repo_url.name))
+}
+
+/// The token is only needed if some package is published.
+/// If the cargo metadata can't be read, the packages are assumed to be published.
+fn registry_token(
+    args: &Doctor,
+    config: &Config,
+    metadata: Option<&cargo_metadata::Metadata>,
+) -> anyhow::Result<String> {
+    if let Some(metadata) = metadata
+        && !has_packages_to_publish(config, metadata)?
+    {
+        return Ok("not needed: no package is published".to_string());
+    }
+    anyhow::ensure!(
+        args.registry_token_available(),
+        "no registry token found: `publish` needs the `CARGO_REGISTRY_TOKEN` environment variable, unless you use trusted publishing"
+    );
+    Ok("found".to_string())
+}
+
+/// Whether `cargo publish` runs for some package, according to the `publish` field of its
+/// `Cargo.toml` and to the `publish` and `release` fields of the configuration.
+fn has_packages_to_publish(
+    config: &Config,
+    metadata: &cargo_metadata::Metadata,
+) -> anyhow::Result<bool> {
+    let request =
+        config.fill_publish_config(false, false, PublishRequest::new(metadata.clone()))?;
+    let packages = k_releaser_core::workspace_packages(metadata)?;
+    Ok(packages
+        .iter()
+        .any(|p| p.is_publishable() && request.is_publish_enabled(&p.name)))
+}
+
+fn changelogs(
+    args: &Doctor,
+    config: &Config,
+    metadata: cargo_metadata::Metadata,
+) -> anyhow::Result<String> {
+    let packages = k_releaser_core::workspace_packages(&metadata)?;
+    let request = UpdateOptions::default().update_request(&args.project, config, metadata)?;
+    let mut parsed = 0;
+    for package in &packages {
+        let changelog = request.changelog_path(package);
+        if !changelog.exists() {
+            continue;
+        }
+        k_releaser_core::last_changes(&changelog)
+            .with_context(|| format!("can't parse changelog {changelog}"))?;
+        parsed += 1;
+    }
+    Ok(format!("{parsed} changelogs parsed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_checks_are_counted() {
+        let checks = [
+            Check::new("git", Ok("git version 2.43.0".to_string())),
+            Check::new("registry token", Err(anyhow::anyhow!("no registry token"))),
+        ];
+        let (report, failed) = report(&checks);
+        assert_eq!(failed, 1);
+        expect_test::expect![[r#"
+            PASS git: git version 2.43.0
+            FAIL registry token: no registry token
+        "#]]
+        .assert_eq(&report);
+    }
+
+    #[test]
+    fn registry_token_is_not_needed_if_no_package_is_released() {
+        let metadata = fake_package::metadata::fake_metadata();
+        assert!(has_packages_to_publish(&Config::default(), &metadata).unwrap());
+
+        let config: String = k_releaser_core::workspace_packages(&metadata)
+            .unwrap()
+            .iter()
+            .map(|p| format!("[[package]]\nname = \"{}\"\nrelease = false\n", p.name))
+            .collect();
+        let config: Config = toml::from_str(&config).unwrap();
+        assert!(!has_packages_to_publish(&config, &metadata).unwrap());
+    }
+}
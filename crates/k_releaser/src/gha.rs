@@ -0,0 +1,402 @@
+//! Outputs and job summary of the GitHub Actions step.
+//!
+//! Workflows can read the outputs with `steps.<step_id>.outputs.<name>`,
+//! without parsing the json printed to stdout.
+//! See the [docs](https://docs.github.com/en/actions/writing-workflows/choosing-what-your-workflow-does/passing-information-between-jobs).
+//!
+//! The job summary is markdown shown in the page of the workflow run.
+//! See the [docs](https://docs.github.com/en/actions/writing-workflows/choosing-what-your-workflow-does/workflow-commands-for-github-actions#adding-a-job-summary).
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use k_releaser_core::{PackagesUpdate, RepoUrl};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use tracing::{debug, warn};
+
+/// File where the outputs of the step are written.
+/// If `path` is [`Option::None`], the `GITHUB_OUTPUT` file is used when running in GitHub Actions.
+pub fn output_file(path: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = path {
+        return Some(path.to_path_buf());
+    }
+    if std::env::var("GITHUB_ACTIONS").as_deref() != Ok("true") {
+        return None;
+    }
+    std::env::var_os("GITHUB_OUTPUT").map(PathBuf::from)
+}
+
+/// Outputs of the `release-pr` command.
+pub fn release_pr_outputs(prs: &impl Serialize) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let prs: Vec<Value> = parse(prs)?;
+    let pr = prs
+        .first()
+        .cloned()
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    Ok(vec![
+        ("prs_created", (!prs.is_empty()).to_string()),
+        ("pr", pr.to_string()),
+        ("prs", serde_json::to_string(&prs)?),
+    ])
+}
+
+/// Outputs of the `release` command.
+/// `version` and `tag` are the ones of the first released package.
+pub fn release_outputs(release: &impl Serialize) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let releases = parse::<ReleaseOutput<Value>>(release)?.releases;
+    let first = parse::<ReleaseOutput>(release)?.releases.into_iter().next();
+    let (version, tag) = first
+        .map(|release| (release.version, release.tag))
+        .unwrap_or_default();
+    Ok(vec![
+        ("releases_created", (!releases.is_empty()).to_string()),
+        ("version", version),
+        ("tag", tag),
+        ("releases", serde_json::to_string(&releases)?),
+    ])
+}
+
+/// Outputs of the `publish` command.
+pub fn publish_outputs(output: &impl Serialize) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let published = parse::<PublishOutput<Value>>(output)?.published;
+    let packages = parse::<PublishOutput>(output)?.published;
+    let published_crates: Vec<&str> = packages
+        .iter()
+        .filter(|package| package.is_published())
+        .map(|package| package.package_name.as_str())
+        .collect();
+    Ok(vec![
+        ("published", (!published_crates.is_empty()).to_string()),
+        (
+            "published_crates",
+            serde_json::to_string(&published_crates)?,
+        ),
+        ("packages", serde_json::to_string(&published)?),
+    ])
+}
+
+/// Append the outputs to the given file, in the `name=value` format of GitHub Actions.
+pub fn write_outputs(path: &Path, outputs: &[(&str, String)]) -> anyhow::Result<()> {
+    let mut content = String::new();
+    for (name, value) in outputs {
+        // Outputs are json, so they don't contain new lines.
+        content.push_str(&format!("{name}={value}\n"));
+    }
+    let mut file = fs_err::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context("can't open GitHub Actions output file")?;
+    file.write_all(content.as_bytes())?;
+    debug!("wrote GitHub Actions outputs to {}", path.display());
+    Ok(())
+}
+
+/// `GITHUB_STEP_SUMMARY` file, if running in GitHub Actions.
+pub fn summary_file() -> Option<PathBuf> {
+    let file = std::env::var_os("GITHUB_STEP_SUMMARY").map(PathBuf::from);
+    if file.is_none() {
+        warn!("GITHUB_STEP_SUMMARY is not set: the job summary is not written");
+    }
+    file
+}
+
+/// Job summary of the `update` command.
+pub fn update_summary(packages_update: &PackagesUpdate) -> anyhow::Result<String> {
+    let releases: Vec<UpdateRelease> = parse(&packages_update.releases())?;
+    let mut summary = "## k-releaser update\n\n".to_string();
+    if releases.is_empty() {
+        summary.push_str("No packages to update.\n");
+        return Ok(summary);
+    }
+    summary.push_str("| Package | Previous version | Next version |\n|---|---|---|\n");
+    for release in &releases {
+        summary.push_str(&format!(
+            "| `{}` | {} | {} |\n",
+            release.package, release.previous_version, release.next_version,
+        ));
+    }
+    for release in &releases {
+        let changelog = release.changelog.as_deref().unwrap_or_default();
+        if !changelog.trim().is_empty() {
+            summary.push_str(&format!(
+                "\n<details><summary><code>{}</code> changelog</summary>\n\n{}\n</details>\n",
+                release.package,
+                changelog.trim(),
+            ));
+        }
+    }
+    Ok(summary)
+}
+
+/// Job summary of the `release-pr` command.
+pub fn release_pr_summary(prs: &impl Serialize) -> anyhow::Result<String> {
+    let prs: Vec<ReleasePr> = parse(prs)?;
+    let mut summary = "## k-releaser release PR\n\n".to_string();
+    if prs.is_empty() {
+        summary.push_str("No release PR opened or updated.\n");
+        return Ok(summary);
+    }
+    for pr in prs {
+        summary.push_str(&format!("- [#{}]({})\n", pr.number, pr.html_url));
+        for release in pr.releases {
+            summary.push_str(&format!(
+                "  - `{}` {}\n",
+                release.package_name, release.version
+            ));
+        }
+    }
+    Ok(summary)
+}
+
+/// Job summary of the `publish` command.
+pub fn publish_summary(output: &impl Serialize) -> anyhow::Result<String> {
+    let PublishOutput { published } = parse::<PublishOutput>(output)?;
+    let mut summary = "## k-releaser publish\n\n".to_string();
+    if published.is_empty() {
+        summary.push_str("No packages published.\n");
+        return Ok(summary);
+    }
+    summary.push_str("| Package | Version | Tag |\n|---|---|---|\n");
+    for package in published {
+        summary.push_str(&format!(
+            "| `{}` | {} | `{}` |\n",
+            package.package_name, package.version, package.tag,
+        ));
+    }
+    Ok(summary)
+}
+
+/// Job summary of the `release` command.
+/// The tags link to the release created on the forge or, if `repo_url` is provided,
+/// to their release page.
+pub fn release_summary(
+    release: &impl Serialize,
+    repo_url: Option<&RepoUrl>,
+) -> anyhow::Result<String> {
+    let ReleaseOutput { releases } = parse::<ReleaseOutput>(release)?;
+    let mut summary = "## k-releaser release\n\n".to_string();
+    if releases.is_empty() {
+        summary.push_str("No packages released.\n");
+        return Ok(summary);
+    }
+    summary.push_str("| Package | Version | Release |\n|---|---|---|\n");
+    for release in releases {
+        let tag = &release.tag;
+        let link = match (release.git_release, repo_url) {
+            (Some(git_release), _) => format!("[{tag}]({})", git_release.html_url),
+            (None, Some(repo_url)) => format!("[{tag}]({})", repo_url.git_release_link(tag, tag)),
+            (None, None) => format!("`{tag}`"),
+        };
+        summary.push_str(&format!(
+            "| `{}` | {} | {link} |\n",
+            release.package_name, release.version,
+        ));
+    }
+    Ok(summary)
+}
+
+/// Json output of the `release` command.
+/// `T` is [`Value`] to keep the releases as they are printed.
+#[derive(Deserialize)]
+struct ReleaseOutput<T = PackageRelease> {
+    releases: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct PackageRelease {
+    package_name: String,
+    version: String,
+    tag: String,
+    git_release: Option<GitRelease>,
+}
+
+#[derive(Deserialize)]
+struct GitRelease {
+    html_url: String,
+}
+
+/// Json output of the `publish` command.
+/// `T` is [`Value`] to keep the packages as they are printed.
+#[derive(Deserialize)]
+struct PublishOutput<T = PackagePublish> {
+    published: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct PackagePublish {
+    package_name: String,
+    version: String,
+    tag: String,
+    registries: Option<Vec<RegistryPublish>>,
+}
+
+impl PackagePublish {
+    /// Packages that failed to publish in every registry aren't published.
+    fn is_published(&self) -> bool {
+        self.registries.as_ref().is_none_or(|registries| {
+            registries
+                .iter()
+                .any(|registry| registry.status == "published")
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RegistryPublish {
+    status: String,
+}
+
+/// Json output of the `release-pr` command.
+#[derive(Deserialize)]
+struct ReleasePr {
+    number: u64,
+    html_url: String,
+    releases: Vec<PrPackageRelease>,
+}
+
+#[derive(Deserialize)]
+struct PrPackageRelease {
+    package_name: String,
+    version: String,
+}
+
+/// Release of the `update` command.
+#[derive(Deserialize)]
+struct UpdateRelease {
+    package: String,
+    previous_version: String,
+    next_version: String,
+    changelog: Option<String>,
+}
+
+/// Read the json output of a command into `T`.
+/// The output is serialized first, so that a renamed field fails instead of being empty.
+fn parse<T: DeserializeOwned>(output: &impl Serialize) -> anyhow::Result<T> {
+    let value = serde_json::to_value(output)?;
+    T::deserialize(value).context("can't parse the command output")
+}
+
+/// Append the markdown summary to the given file.
+pub fn write_summary(path: &Path, summary: &str) -> anyhow::Result<()> {
+    let mut file = fs_err::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context("can't open GitHub Actions job summary file")?;
+    writeln!(file, "{summary}")?;
+    debug!("wrote GitHub Actions job summary to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn release_outputs_contain_first_release() {
+        let release = json!({"releases": [
+            {"package_name": "a", "prs": [], "tag": "v1.2.0", "version": "1.2.0"},
+            {"package_name": "b", "prs": [], "tag": "v1.2.0", "version": "1.2.0"},
+        ]});
+        let outputs = release_outputs(&release).unwrap();
+        assert_eq!(outputs[0], ("releases_created", "true".to_string()));
+        assert_eq!(outputs[1], ("version", "1.2.0".to_string()));
+        assert_eq!(outputs[2], ("tag", "v1.2.0".to_string()));
+    }
+
+    #[test]
+    fn no_releases_are_reported() {
+        let outputs = release_outputs(&json!({"releases": []})).unwrap();
+        assert_eq!(outputs[0], ("releases_created", "false".to_string()));
+        assert_eq!(outputs[1], ("version", String::new()));
+        let outputs = release_pr_outputs(&json!([])).unwrap();
+        assert_eq!(outputs[0], ("prs_created", "false".to_string()));
+        assert_eq!(outputs[1], ("pr", "{}".to_string()));
+    }
+
+    #[test]
+    fn unexpected_output_is_an_error() {
+        let release = json!({"releases": [{"package": "a", "tag": "v1.2.0"}]});
+        assert!(release_outputs(&release).is_err());
+        assert!(release_summary(&release, None).is_err());
+    }
+
+    #[test]
+    fn outputs_are_appended_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output");
+        fs_err::write(&path, "existing=1\n").unwrap();
+        let outputs = publish_outputs(&json!({"published": [
+            {"package_name": "a", "version": "1.0.0", "tag": "v1.0.0"},
+        ]}))
+        .unwrap();
+        write_outputs(&path, &outputs).unwrap();
+        let content = fs_err::read_to_string(&path).unwrap();
+        expect_test::expect![[r#"
+            existing=1
+            published=true
+            published_crates=["a"]
+            packages=[{"package_name":"a","tag":"v1.0.0","version":"1.0.0"}]
+        "#]]
+        .assert_eq(&content);
+    }
+
+    #[test]
+    fn packages_failed_in_every_registry_are_not_published() {
+        let outputs = publish_outputs(&json!({"published": [
+            {"package_name": "a", "version": "1.0.0", "tag": "v1.0.0", "registries": [
+                {"registry": "crates-io", "status": "published"},
+                {"registry": "private", "status": "failed", "error": "timeout"},
+            ]},
+            {"package_name": "b", "version": "1.0.0", "tag": "v1.0.0", "registries": [
+                {"registry": "crates-io", "status": "failed", "error": "timeout"},
+            ]},
+        ]}))
+        .unwrap();
+        assert_eq!(outputs[1], ("published_crates", r#"["a"]"#.to_string()));
+    }
+
+    #[test]
+    fn release_summary_links_to_releases() {
+        let release = json!({"releases": [
+            {"package_name": "a", "prs": [], "tag": "v1.2.0", "version": "1.2.0"},
+        ]});
+        let repo_url = RepoUrl::new("https://github.com/owner/repo").unwrap();
+        let summary = release_summary(&release, Some(&repo_url)).unwrap();
+        expect_test::expect![[r#"
+            ## k-releaser release
+
+            | Package | Version | Release |
+            |---|---|---|
+            | `a` | 1.2.0 | [v1.2.0](https://github.com/owner/repo/releases/tag/v1.2.0) |
+        "#]]
+        .assert_eq(&summary);
+    }
+
+    #[test]
+    fn release_summary_links_to_created_releases() {
+        let release = json!({"releases": [
+            {
+                "git_release": {"html_url": "https://gitlab.com/owner/repo/-/releases/v1.2.0"},
+                "package_name": "a",
+                "prs": [],
+                "tag": "v1.2.0",
+                "version": "1.2.0",
+            },
+        ]});
+        let summary = release_summary(&release, None).unwrap();
+        expect_test::expect![[r#"
+            ## k-releaser release
+
+            | Package | Version | Release |
+            |---|---|---|
+            | `a` | 1.2.0 | [v1.2.0](https://gitlab.com/owner/repo/-/releases/v1.2.0) |
+        "#]]
+        .assert_eq(&summary);
+    }
+}
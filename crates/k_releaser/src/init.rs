@@ -0,0 +1,355 @@
+use anyhow::Context as _;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use git_cmd::Repo;
+use k_releaser_core::RepoUrl;
+use tracing::{info, warn};
+
+use crate::{
+    args::{GitForgeKind, init::Init, manifest_command::ManifestCommand as _},
+    config::Config,
+};
+
+const STARTER_CONFIG: &str = r#"# Create and update the CHANGELOG.md file.
+changelog_update = true
+# Labels added to the release PR.
+pr_labels = ["release"]
+"#;
+
+const GITHUB_WORKFLOW: &str = r#"name: k-releaser
+
+on:
+  push:
+    branches:
+      - main
+
+jobs:
+  # Create git tags and releases of the merged release PR.
+  k-releaser-release:
+    name: k-releaser release
+    runs-on: ubuntu-latest
+    permissions:
+      contents: write
+    steps:
+      - &checkout
+        name: Checkout repository
+        uses: actions/checkout@v4
+        with:
+          fetch-depth: 0
+          persist-credentials: false
+      - &install-rust
+        name: Install Rust toolchain
+        uses: dtolnay/rust-toolchain@stable
+      - name: Run k-releaser
+        uses: secana/k-releaser@v1
+        with:
+          command: release
+        env:
+          GITHUB_TOKEN: ${{ secrets.K_RELEASER_TOKEN }}
+
+  # Create a PR with the new versions and changelog, preparing the next release.
+  k-releaser-pr:
+    name: k-releaser PR
+    runs-on: ubuntu-latest
+    permissions:
+      contents: write
+      pull-requests: write
+    concurrency:
+      group: k-releaser-${{ github.ref }}
+      cancel-in-progress: false
+    steps:
+      - *checkout
+      - *install-rust
+      - name: Run k-releaser
+        uses: secana/k-releaser@v1
+        with:
+          command: release-pr
+        env:
+          GITHUB_TOKEN: ${{ secrets.K_RELEASER_TOKEN }}
+"#;
+
+const GITEA_WORKFLOW: &str = r#"name: k-releaser
+
+on:
+  push:
+    branches:
+      - main
+
+jobs:
+  # Create git tags and releases of the merged release PR.
+  k-releaser-release:
+    name: k-releaser release
+    runs-on: ubuntu-latest
+    steps:
+      - name: Checkout repository
+        uses: actions/checkout@v4
+        with:
+          fetch-depth: 0
+      - name: Install Rust toolchain
+        uses: https://github.com/dtolnay/rust-toolchain@stable
+      - name: Run k-releaser
+        uses: https://github.com/secana/k-releaser@v1
+        with:
+          command: release
+          backend: gitea
+          git-token: ${{ secrets.K_RELEASER_TOKEN }}
+
+  # Create a PR with the new versions and changelog, preparing the next release.
+  k-releaser-pr:
+    name: k-releaser PR
+    runs-on: ubuntu-latest
+    steps:
+      - name: Checkout repository
+        uses: actions/checkout@v4
+        with:
+          fetch-depth: 0
+      - name: Install Rust toolchain
+        uses: https://github.com/dtolnay/rust-toolchain@stable
+      - name: Run k-releaser
+        uses: https://github.com/secana/k-releaser@v1
+        with:
+          command: release-pr
+          backend: gitea
+          git-token: ${{ secrets.K_RELEASER_TOKEN }}
+"#;
+
+/// Forgejo runners, like the ones of Codeberg, use the `docker` label by default
+/// and resolve short action names from a mirror: use the full urls.
+const FORGEJO_WORKFLOW: &str = r#"name: k-releaser
+
+on:
+  push:
+    branches:
+      - main
+
+jobs:
+  # Create git tags and releases of the merged release PR.
+  k-releaser-release:
+    name: k-releaser release
+    runs-on: docker
+    steps:
+      - name: Checkout repository
+        uses: https://code.forgejo.org/actions/checkout@v4
+        with:
+          fetch-depth: 0
+      - name: Install Rust toolchain
+        uses: https://github.com/dtolnay/rust-toolchain@stable
+      - name: Run k-releaser
+        uses: https://github.com/secana/k-releaser@v1
+        with:
+          command: release
+          backend: forgejo
+          git-token: ${{ secrets.K_RELEASER_TOKEN }}
+
+  # Create a PR with the new versions and changelog, preparing the next release.
+  k-releaser-pr:
+    name: k-releaser PR
+    runs-on: docker
+    steps:
+      - name: Checkout repository
+        uses: https://code.forgejo.org/actions/checkout@v4
+        with:
+          fetch-depth: 0
+      - name: Install Rust toolchain
+        uses: https://github.com/dtolnay/rust-toolchain@stable
+      - name: Run k-releaser
+        uses: https://github.com/secana/k-releaser@v1
+        with:
+          command: release-pr
+          backend: forgejo
+          git-token: ${{ secrets.K_RELEASER_TOKEN }}
+"#;
+
+const GITLAB_WORKFLOW: &str = r#"# Add `K_RELEASER_TOKEN` to the CI/CD variables of the project.
+# The token needs the `api` and `write_repository` scopes.
+.k-releaser:
+  image: rust:latest
+  rules:
+    - if: $CI_COMMIT_BRANCH == $CI_DEFAULT_BRANCH
+  before_script:
+    - VERSION=$(curl -s https://api.github.com/repos/secana/k-releaser/releases/latest | grep '"tag_name":' | sed -E 's/.*"v([^"]+)".*/\1/')
+    - curl -fsSL "https://github.com/secana/k-releaser/releases/download/v${VERSION}/k-releaser-${VERSION}-x86_64-linux-musl.tar.gz" | tar -xz -C /usr/local/bin
+    - git fetch --tags --unshallow || git fetch --tags
+
+# Create git tags and releases of the merged release PR.
+k-releaser-release:
+  extends: .k-releaser
+  script:
+    - k-releaser release --forge gitlab --git-token "$K_RELEASER_TOKEN"
+
+# Create a merge request with the new versions and changelog, preparing the next release.
+k-releaser-pr:
+  extends: .k-releaser
+  script:
+    - k-releaser release-pr --forge gitlab --git-token "$K_RELEASER_TOKEN"
+"#;
+
+/// Write a starter k-releaser configuration and CI workflow.
+pub fn init(args: &Init) -> anyhow::Result<()> {
+    let manifest_path = args.manifest_path();
+    let project_dir = k_releaser_core::manifest_dir(&manifest_path)?;
+
+    write_config(&manifest_path)?;
+
+    if !args.no_ci {
+        let forge = match args.forge {
+            Some(forge) => forge,
+            None => detect_forge(project_dir),
+        };
+        write_ci_workflow(&repo_root(project_dir), forge)?;
+    }
+    Ok(())
+}
+
+/// Detect the forge from the url of the git remote. Defaults to GitHub.
+fn detect_forge(project_dir: &Utf8Path) -> GitForgeKind {
+    let repo_url = Repo::new(project_dir).and_then(|repo| RepoUrl::from_repo(&repo));
+    match repo_url {
+        Ok(repo_url) => GitForgeKind::detect(&repo_url),
+        Err(e) => {
+            warn!("can't determine the git remote, defaulting to GitHub: {e:?}");
+            GitForgeKind::Github
+        }
+    }
+}
+
+/// The forge reads the CI workflow from the root of the repository,
+/// which is a parent of the project directory if the workspace is in a subdirectory.
+fn repo_root(project_dir: &Utf8Path) -> Utf8PathBuf {
+    match k_releaser_core::root_repo_path_from_manifest_dir(project_dir) {
+        Ok(repo_root) => repo_root,
+        Err(e) => {
+            warn!("can't determine the root of the git repository, using {project_dir}: {e:?}");
+            project_dir.to_path_buf()
+        }
+    }
+}
+
+/// Append the starter configuration to the manifest.
+/// Workspaces are configured in `[workspace.metadata.k-releaser]`,
+/// single packages in `[package.metadata.k-releaser]`.
+fn write_config(manifest_path: &Utf8Path) -> anyhow::Result<()> {
+    let manifest = fs_err::read_to_string(manifest_path)?;
+    let new_manifest = manifest_with_config(&manifest)
+        .with_context(|| format!("can't add k-releaser configuration to {manifest_path}"))?;
+    fs_err::write(manifest_path, new_manifest)?;
+    info!("added k-releaser configuration to {manifest_path}");
+    Ok(())
+}
+
+fn manifest_with_config(manifest: &str) -> anyhow::Result<String> {
+    let cargo_toml: toml::Value = toml::from_str(manifest).context("invalid Cargo.toml")?;
+    let section = if cargo_toml.get("workspace").is_some() {
+        "workspace"
+    } else {
+        "package"
+    };
+    let is_configured = cargo_toml
+        .get(section)
+        .and_then(|s| s.get("metadata"))
+        .and_then(|m| m.get("k-releaser"))
+        .is_some();
+    anyhow::ensure!(
+        !is_configured,
+        "k-releaser is already configured in [{section}.metadata.k-releaser]"
+    );
+
+    let separator = if manifest.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    let new_manifest =
+        format!("{manifest}{separator}[{section}.metadata.k-releaser.workspace]\n{STARTER_CONFIG}");
+    // Make sure the configuration we wrote can be loaded.
+    let cargo_toml: toml::Value = toml::from_str(&new_manifest)?;
+    let config = cargo_toml[section]["metadata"]["k-releaser"].clone();
+    let _config: Config = config.try_into()?;
+    Ok(new_manifest)
+}
+
+fn ci_workflow(forge: GitForgeKind) -> (&'static str, &'static str) {
+    match forge {
+        GitForgeKind::Github => (".github/workflows/k-releaser.yml", GITHUB_WORKFLOW),
+        GitForgeKind::Gitea => (".gitea/workflows/k-releaser.yml", GITEA_WORKFLOW),
+        GitForgeKind::Forgejo => (".forgejo/workflows/k-releaser.yml", FORGEJO_WORKFLOW),
+        GitForgeKind::Gitlab => (".gitlab-ci.yml", GITLAB_WORKFLOW),
+    }
+}
+
+fn write_ci_workflow(repo_root: &Utf8Path, forge: GitForgeKind) -> anyhow::Result<()> {
+    let (path, workflow) = ci_workflow(forge);
+    let workflow_path: Utf8PathBuf = repo_root.join(path);
+    if workflow_path.exists() {
+        warn!(
+            "{workflow_path} already exists, skipping CI workflow. Add the k-releaser jobs manually:\n{workflow}"
+        );
+        return Ok(());
+    }
+    if let Some(parent) = workflow_path.parent() {
+        fs_err::create_dir_all(parent)?;
+    }
+    fs_err::write(&workflow_path, workflow)?;
+    info!("added CI workflow {workflow_path}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_is_added_to_workspace() {
+        let manifest = "[workspace]\nmembers = [\"crates/*\"]\n";
+        let new_manifest = manifest_with_config(manifest).unwrap();
+        expect_test::expect![[r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.metadata.k-releaser.workspace]
+            # Create and update the CHANGELOG.md file.
+            changelog_update = true
+            # Labels added to the release PR.
+            pr_labels = ["release"]
+        "#]]
+        .assert_eq(&new_manifest);
+    }
+
+    #[test]
+    fn config_is_added_to_package() {
+        let manifest = "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"";
+        let new_manifest = manifest_with_config(manifest).unwrap();
+        assert!(new_manifest.contains("\n\n[package.metadata.k-releaser.workspace]\n"));
+    }
+
+    #[test]
+    fn existing_config_is_not_overwritten() {
+        let manifest =
+            "[workspace]\n\n[workspace.metadata.k-releaser.workspace]\npr_draft = true\n";
+        let error = manifest_with_config(manifest).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "k-releaser is already configured in [workspace.metadata.k-releaser]"
+        );
+    }
+
+    #[test]
+    fn ci_workflow_is_written() {
+        let temp = tempfile::tempdir().unwrap();
+        let project_dir = Utf8Path::from_path(temp.path()).unwrap();
+        write_ci_workflow(project_dir, GitForgeKind::Gitea).unwrap();
+        let workflow =
+            fs_err::read_to_string(project_dir.join(".gitea/workflows/k-releaser.yml")).unwrap();
+        assert_eq!(workflow, GITEA_WORKFLOW);
+    }
+
+    #[test]
+    fn ci_workflow_is_written_in_repo_root() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(temp.path()).unwrap();
+        git_cmd::git_in_dir(root, &["init"]).unwrap();
+        let project_dir = root.join("rust");
+        fs_err::create_dir_all(&project_dir).unwrap();
+        let workflow_root = fs_err::canonicalize(repo_root(&project_dir)).unwrap();
+        assert_eq!(workflow_root, fs_err::canonicalize(root).unwrap());
+    }
+}
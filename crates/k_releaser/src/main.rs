@@ -1,16 +1,28 @@
 mod args;
+#[cfg(feature = "keyring")]
+mod auth;
 mod changelog_config;
+mod changelog_show;
+mod check_commit;
 mod config;
 mod config_show;
+mod doctor;
+mod gha;
+mod init;
 mod log;
+mod next_version;
+mod preview;
 
 use args::OutputType;
 use clap::Parser;
-use k_releaser_core::ReleaseRequest;
+use k_releaser_core::{ReleasePrDryRun, ReleaseRequest};
 use serde::Serialize;
-use tracing::error;
+use tracing::{error, info, warn};
 
-use crate::args::{CliArgs, Command, manifest_command::ManifestCommand as _};
+use crate::args::{
+    CliArgs, Command, manifest_command::ManifestCommand as _, publish::GraphFormat,
+    repo_command::RepoCommand as _,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -26,44 +38,80 @@ async fn main() -> anyhow::Result<()> {
 
 async fn run(args: CliArgs) -> anyhow::Result<()> {
     match args.command {
-        Command::Update(cmd_args) => {
-            let cargo_metadata = cmd_args.cargo_metadata()?;
-            let config = cmd_args.load_config()?;
-            let update_request = cmd_args.update_request(&config, cargo_metadata)?;
-            let (packages_update, _temp_repo) = k_releaser_core::update(&update_request).await?;
-            println!("{}", packages_update.summary());
+        Command::Update(mut cmd_args) => {
+            cmd_args.update.project.prepare_workspaces()?;
+            for workspace_root in cmd_args.update.project.workspace_roots()? {
+                cmd_args.update.project.set_workspace_root(workspace_root);
+                let cargo_metadata = cmd_args.update.project.cargo_metadata()?;
+                let config = cmd_args.update.project.load_config()?;
+                let update_request = cmd_args.update.update_request(&config, cargo_metadata)?;
+                let packages_update = if cmd_args.dry_run {
+                    let (packages_update, diff) =
+                        k_releaser_core::update_dry_run(&update_request).await?;
+                    println!("{}", packages_update.summary());
+                    if cmd_args.diff {
+                        println!("{diff}");
+                    }
+                    packages_update
+                } else {
+                    let (packages_update, _temp_repo) =
+                        k_releaser_core::update(&update_request).await?;
+                    println!("{}", packages_update.summary());
+                    packages_update
+                };
+                if let Some(gha_summary) = cmd_args.gha_summary.summary_file() {
+                    gha::write_summary(&gha_summary, &gha::update_summary(&packages_update)?)?;
+                }
+                if cmd_args.check_run.is_enabled() {
+                    k_releaser_core::publish_release_plan(&update_request, &packages_update)
+                        .await?;
+                }
+            }
         }
-        Command::ReleasePr(cmd_args) => {
-            let cargo_metadata = cmd_args.update.cargo_metadata()?;
-            let config = cmd_args.update.load_config()?;
-            let request = cmd_args.release_pr_req(&config, cargo_metadata)?;
+        Command::ReleasePr(mut cmd_args) => {
+            anyhow::ensure!(
+                cmd_args.dry_run || cmd_args.update.project.forge.has_git_credentials(),
+                "please provide the git token with the --git-token cli argument, or the GitHub App credentials with --github-app-id and --github-app-private-key."
+            );
+            cmd_args.update.project.prepare_workspaces()?;
+            let mut prs = vec![];
+            let mut dry_run_results = vec![];
+            let workspace_roots = cmd_args.update.project.workspace_roots()?;
+            let has_workspace_roots = workspace_roots.iter().any(Option::is_some);
+            for workspace_root in workspace_roots {
+                cmd_args.update.project.set_workspace_root(workspace_root);
+                let cargo_metadata = cmd_args.update.project.cargo_metadata()?;
+                let config = cmd_args.update.project.load_config()?;
+                let request = cmd_args.release_pr_req(&config, cargo_metadata)?;
 
-            if cmd_args.dry_run {
-                // Dry-run mode: calculate what the PR would contain but don't create it
-                let dry_run_result = k_releaser_core::release_pr_dry_run(&request).await?;
-                println!("=== Dry Run Results ===\n");
-                println!("Title: {}\n", dry_run_result.title);
-                if let Some(version) = &dry_run_result.version {
-                    println!("Version: {}\n", version);
+                if cmd_args.dry_run {
+                    // Dry-run mode: calculate what the PR would contain but don't create it
+                    let dry_run_result = k_releaser_core::release_pr_dry_run(&request).await?;
+                    if cmd_args.output.is_none() {
+                        print_release_pr_dry_run(&dry_run_result);
+                    }
+                    dry_run_results.push(dry_run_result);
+                } else {
+                    prs.extend(k_releaser_core::release_prs(&request).await?);
                 }
-                println!("Body:\n{}\n", dry_run_result.body);
-                if !dry_run_result.commits.is_empty() {
-                    println!("Commits detected:");
-                    for commit in &dry_run_result.commits {
-                        println!("  {}", commit);
+            }
+            if cmd_args.dry_run {
+                if let Some(output_type) = cmd_args.output {
+                    // Without `workspace_roots`, keep printing the dry run of the only workspace.
+                    if has_workspace_roots {
+                        print_output(output_type, dry_run_results);
+                    } else if let Some(dry_run_result) = dry_run_results.pop() {
+                        print_output(output_type, dry_run_result);
                     }
                 }
             } else {
-                anyhow::ensure!(
-                    cmd_args.update.git_token.is_some(),
-                    "please provide the git token with the --git-token cli argument."
-                );
-                let release_pr = k_releaser_core::release_pr(&request).await?;
+                if let Some(gha_output) = cmd_args.gha_output.output_file() {
+                    gha::write_outputs(&gha_output, &gha::release_pr_outputs(&prs)?)?;
+                }
+                if let Some(gha_summary) = cmd_args.gha_summary.summary_file() {
+                    gha::write_summary(&gha_summary, &gha::release_pr_summary(&prs)?)?;
+                }
                 if let Some(output_type) = cmd_args.output {
-                    let prs = match release_pr {
-                        Some(pr) => vec![pr],
-                        None => vec![],
-                    };
                     let prs_json = serde_json::json!({
                         "prs": prs
                     });
@@ -72,10 +120,14 @@ async fn run(args: CliArgs) -> anyhow::Result<()> {
             }
         }
         Command::Publish(cmd_args) => {
-            let cargo_metadata = cmd_args.cargo_metadata()?;
-            let config = cmd_args.load_config()?;
+            let cargo_metadata = cmd_args.project.cargo_metadata()?;
+            let config = cmd_args.project.load_config()?;
+            cmd_args.project.http.configure(&config)?;
             let print_order = cmd_args.print_order;
+            let graph_format = cmd_args.format;
             let cmd_args_output = cmd_args.output;
+            let gha_output = cmd_args.gha_output.output_file();
+            let gha_summary = cmd_args.gha_summary.summary_file();
             let request = cmd_args.publish_request(&config, cargo_metadata)?;
 
             if print_order {
@@ -83,29 +135,149 @@ async fn run(args: CliArgs) -> anyhow::Result<()> {
                 if let Some(output_type) = cmd_args_output {
                     print_output(output_type, order_output);
                 } else {
-                    println!("{}", order_output.display());
+                    match graph_format {
+                        Some(GraphFormat::Dot) => println!("{}", order_output.dot()),
+                        Some(GraphFormat::Mermaid) => print!("{}", order_output.mermaid()),
+                        None => println!("{}", order_output.display()),
+                    }
                 }
             } else {
                 let output = k_releaser_core::publish(&request)
                     .await?
                     .unwrap_or_default();
+                if let Some(gha_output) = gha_output {
+                    gha::write_outputs(&gha_output, &gha::publish_outputs(&output)?)?;
+                }
+                if let Some(gha_summary) = gha_summary {
+                    gha::write_summary(&gha_summary, &gha::publish_summary(&output)?)?;
+                }
+                let has_failures = output.has_failures();
                 if let Some(output_type) = cmd_args_output {
                     print_output(output_type, output);
                 }
+                anyhow::ensure!(!has_failures, "failed to publish to some registries");
             }
         }
-        Command::Release(cmd_args) => {
-            let cargo_metadata = cmd_args.cargo_metadata()?;
-            let config = cmd_args.load_config()?;
+        Command::Release(mut cmd_args) => {
             let cmd_args_output = cmd_args.output;
-            let request: ReleaseRequest = cmd_args.release_request(&config, cargo_metadata)?;
-            let output = k_releaser_core::release(&request)
-                .await?
-                .unwrap_or_default();
+            let gha_output = cmd_args.gha_output.output_file();
+            let gha_summary = cmd_args.gha_summary.summary_file();
+            let mut repo_url = None;
+            let mut output = k_releaser_core::Release::default();
+            cmd_args.project.prepare_workspaces()?;
+            for workspace_root in cmd_args.project.workspace_roots()? {
+                cmd_args.project.set_workspace_root(workspace_root);
+                let cargo_metadata = cmd_args.project.cargo_metadata()?;
+                let config = cmd_args.project.load_config()?;
+                repo_url = repo_url.or_else(|| cmd_args.project.get_repo_url(&config).ok());
+                let request: ReleaseRequest = cmd_args.release_request(&config, cargo_metadata)?;
+                if let Some(release) = k_releaser_core::release(&request).await? {
+                    output.extend(release);
+                }
+            }
+            if let Some(gha_output) = gha_output {
+                gha::write_outputs(&gha_output, &gha::release_outputs(&output)?)?;
+            }
+            if let Some(gha_summary) = gha_summary {
+                let summary = gha::release_summary(&output, repo_url.as_ref())?;
+                gha::write_summary(&gha_summary, &summary)?;
+            }
             if let Some(output_type) = cmd_args_output {
                 print_output(output_type, output);
             }
         }
+        Command::PublishDrafts(cmd_args) => {
+            let cargo_metadata = cmd_args.project.cargo_metadata()?;
+            let config = cmd_args.project.load_config()?;
+            cmd_args.project.http.configure(&config)?;
+            let cmd_args_output = cmd_args.output;
+            let request = cmd_args.publish_drafts_request(&config, cargo_metadata)?;
+            let output = k_releaser_core::publish_drafts(&request).await?;
+            if let Some(output_type) = cmd_args_output {
+                print_output(output_type, output);
+            }
+        }
+        Command::Rollback(cmd_args) => {
+            let cargo_metadata = cmd_args.project.cargo_metadata()?;
+            let config = cmd_args.project.load_config()?;
+            cmd_args.project.http.configure(&config)?;
+            let cmd_args_output = cmd_args.output;
+            let request = cmd_args.rollback_request(&config, cargo_metadata)?;
+            let output = k_releaser_core::rollback(&request).await?;
+            if let Some(output_type) = cmd_args_output {
+                print_output(output_type, output);
+            }
+        }
+        Command::VerifyTags(cmd_args) => {
+            let cargo_metadata = cmd_args.project.cargo_metadata()?;
+            let config = cmd_args.project.load_config()?;
+            cmd_args.project.http.configure(&config)?;
+            let cmd_args_output = cmd_args.output;
+            let request = cmd_args.verify_tags_request(&config, cargo_metadata)?;
+            let output = k_releaser_core::verify_tags(&request).await?;
+            for issue in &output.issues {
+                warn!("{issue}");
+            }
+            let issues = output.issues.len();
+            if let Some(output_type) = cmd_args_output {
+                print_output(output_type, output);
+            }
+            anyhow::ensure!(issues == 0, "found {issues} inconsistencies in the tags");
+            info!("tags, forge releases and published versions are consistent");
+        }
+        Command::SetVersion(cmd_args) => {
+            let cargo_metadata = cmd_args.project.cargo_metadata()?;
+            let config = cmd_args.project.load_config()?;
+            let request = cmd_args.set_version_request(&config, cargo_metadata)?;
+            k_releaser_core::set_versions(&request)?;
+        }
+        Command::NextVersion(cmd_args) => {
+            let next_versions =
+                next_version::next_versions(&cmd_args.project, cmd_args.package.as_deref()).await?;
+            match cmd_args.output {
+                Some(output_type) => print_output(output_type, next_versions),
+                None => println!("{}", next_versions.display()),
+            }
+        }
+        Command::Preview(cmd_args) => preview::preview(&cmd_args).await?,
+        Command::GenerateChangelog(cmd_args) => {
+            let cargo_metadata = cmd_args.project.cargo_metadata()?;
+            let config = cmd_args.project.load_config()?;
+            let request = cmd_args.generate_changelog_request(&config, cargo_metadata)?;
+            let generated = k_releaser_core::generate_changelog(&request)?;
+            if cmd_args.write {
+                fs_err::write(&generated.path, generated.changelog)?;
+                info!("changelog written to {}", generated.path);
+            } else if cmd_args.full {
+                print!("{}", generated.changelog);
+            } else {
+                println!("{}", generated.entry);
+            }
+        }
+        Command::Backfill(cmd_args) => {
+            let cargo_metadata = cmd_args.project.cargo_metadata()?;
+            let config = cmd_args.project.load_config()?;
+            cmd_args.project.http.configure(&config)?;
+            let request = cmd_args.backfill_request(&config, cargo_metadata)?;
+            let output = k_releaser_core::backfill(&request).await?;
+            match cmd_args.output {
+                Some(output_type) => print_output(output_type, output),
+                None if cmd_args.dry_run => print!("{}", output.changelog),
+                None => {}
+            }
+        }
+        Command::CheckCommit(cmd_args) => check_commit::check_commits(&cmd_args)?,
+        Command::Init(cmd_args) => init::init(&cmd_args)?,
+        Command::Doctor(cmd_args) => doctor::doctor(&cmd_args).await?,
+        #[cfg(feature = "keyring")]
+        Command::Auth(cmd) => auth::auth(&cmd.subcommand)?,
+        Command::Completions(cmd_args) => cmd_args.print(),
+        Command::Manpage(cmd_args) => cmd_args.generate()?,
+        Command::Changelog(cmd) => match cmd.subcommand {
+            crate::args::changelog::ChangelogSubcommand::Show(show_args) => {
+                changelog_show::show_changelog(&show_args)?;
+            }
+        },
         Command::Config(cmd) => match cmd.subcommand {
             crate::args::config::ConfigSubcommand::Show(show_args) => {
                 config_show::show_config(show_args)?;
@@ -115,6 +287,31 @@ async fn run(args: CliArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn print_release_pr_dry_run(dry_run_result: &ReleasePrDryRun) {
+    println!("=== Dry Run Results ===\n");
+    println!("Title: {}\n", dry_run_result.title);
+    if let Some(version) = &dry_run_result.version {
+        println!("Version: {}\n", version);
+    }
+    println!("Body:\n{}\n", dry_run_result.body);
+    if !dry_run_result.packages.is_empty() {
+        println!("Packages:");
+        for package in &dry_run_result.packages {
+            println!(
+                "  {}: {} -> {}",
+                package.package_name, package.previous_version, package.next_version
+            );
+        }
+        println!();
+    }
+    if !dry_run_result.commits.is_empty() {
+        println!("Commits detected:");
+        for commit in &dry_run_result.commits {
+            println!("  {}", commit);
+        }
+    }
+}
+
 fn print_output(output_type: OutputType, output: impl Serialize) {
     match output_type {
         OutputType::Json => match serde_json::to_string(&output) {
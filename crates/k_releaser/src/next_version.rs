@@ -0,0 +1,141 @@
+//! Next versions of the packages, computed without changing the project.
+
+use cargo_metadata::semver::Version;
+use serde::Serialize;
+
+use crate::args::{
+    manifest_command::ManifestCommand as _, project::ProjectArgs, update::UpdateOptions,
+};
+
+/// Part of the version incremented by the next release.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BumpKind {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+    /// The package doesn't need a release.
+    None,
+}
+
+impl BumpKind {
+    fn between(current: &Version, next: &Version) -> Self {
+        if next.major != current.major {
+            Self::Major
+        } else if next.minor != current.minor {
+            Self::Minor
+        } else if next.patch != current.patch {
+            Self::Patch
+        } else if next.pre != current.pre {
+            Self::Prerelease
+        } else {
+            Self::None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Major => "major",
+            Self::Minor => "minor",
+            Self::Patch => "patch",
+            Self::Prerelease => "prerelease",
+            Self::None => "none",
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct PackageNextVersion {
+    pub package_name: String,
+    pub current_version: Version,
+    pub next_version: Version,
+    pub bump: BumpKind,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NextVersions {
+    pub packages: Vec<PackageNextVersion>,
+}
+
+impl NextVersions {
+    /// One line per package. If there's only one package, its name is omitted.
+    pub fn display(&self) -> String {
+        let single_package = self.packages.len() == 1;
+        self.packages
+            .iter()
+            .map(|p| {
+                let line = format!("{} {}", p.next_version, p.bump.as_str());
+                if single_package {
+                    line
+                } else {
+                    format!("{} {line}", p.package_name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Compute the next version of the workspace packages, or of the package
+/// selected with `--package`, from the commits since the last tag.
+pub async fn next_versions(
+    project: &ProjectArgs,
+    selected: Option<&str>,
+) -> anyhow::Result<NextVersions> {
+    let cargo_metadata = project.cargo_metadata()?;
+    let config = project.load_config()?;
+    project.http.configure(&config)?;
+    let packages = k_releaser_core::workspace_packages(&cargo_metadata)?;
+    let update_request = UpdateOptions {
+        package: selected.map(str::to_string),
+        ..Default::default()
+    }
+    .update_request(project, &config, cargo_metadata)?;
+    let (packages_update, _temp_repo) = k_releaser_core::next_versions(&update_request).await?;
+
+    let packages = packages
+        .into_iter()
+        .filter(|p| selected.is_none_or(|name| p.name.as_str() == name))
+        .map(|package| {
+            let next_version = packages_update
+                .updates()
+                .iter()
+                .find(|(p, _)| p.name == package.name)
+                .map_or_else(
+                    || package.version.clone(),
+                    |(_, update)| update.version.clone(),
+                );
+            PackageNextVersion {
+                bump: BumpKind::between(&package.version, &next_version),
+                package_name: package.name.to_string(),
+                current_version: package.version,
+                next_version,
+            }
+        })
+        .collect::<Vec<_>>();
+    if let Some(name) = selected {
+        anyhow::ensure!(
+            !packages.is_empty(),
+            "package `{name}` not found in the workspace"
+        );
+    }
+    Ok(NextVersions { packages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump_kind_is_computed_from_versions() {
+        let bump = |current: &str, next: &str| {
+            BumpKind::between(&current.parse().unwrap(), &next.parse().unwrap())
+        };
+        assert_eq!(bump("1.2.3", "2.0.0"), BumpKind::Major);
+        assert_eq!(bump("0.2.3", "0.3.0"), BumpKind::Minor);
+        assert_eq!(bump("1.2.3", "1.2.4"), BumpKind::Patch);
+        assert_eq!(bump("1.3.0-rc.1", "1.3.0-rc.2"), BumpKind::Prerelease);
+        assert_eq!(bump("1.2.3", "1.2.3"), BumpKind::None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Comment on a PR with the next release after merging it.
+//! The release is cumulative: it contains the unreleased commits of the base branch too.
+
+use anyhow::Context as _;
+use k_releaser_core::GitClient;
+use tracing::info;
+
+use crate::{
+    args::{preview::Preview, repo_command::RepoCommand as _},
+    next_version::{BumpKind, NextVersions, next_versions},
+};
+
+/// Hidden marker of the preview comment, used to update it instead of posting a new one.
+const PREVIEW_MARKER: &str = "<!-- k-releaser-preview -->";
+
+/// Post or update the preview comment on the PR.
+pub async fn preview(args: &Preview) -> anyhow::Result<()> {
+    let next_versions = next_versions(&args.project, args.package.as_deref()).await?;
+    let comment = preview_comment(&next_versions);
+    if args.dry_run {
+        println!("{comment}");
+        return Ok(());
+    }
+    let pr_number = args
+        .pr_number()
+        .context("can't determine the PR number: pass it with `--pr`")?;
+    let config = args.project.load_config()?;
+    let repo_url = args.project.get_repo_url(&config)?;
+    let forge = args
+        .project
+        .forge
+        .git_forge(repo_url)?
+        .context("git token not provided: can't comment on the PR")?;
+    GitClient::new(forge)?
+        .upsert_pr_comment(pr_number, PREVIEW_MARKER, &comment)
+        .await?;
+    info!("updated the release preview of PR #{pr_number}");
+    Ok(())
+}
+
+fn preview_comment(next_versions: &NextVersions) -> String {
+    let mut comment = format!("{PREVIEW_MARKER}\n## Release preview\n\n");
+    let releases: Vec<_> = next_versions
+        .packages
+        .iter()
+        .filter(|p| p.bump != BumpKind::None)
+        .collect();
+    if releases.is_empty() {
+        comment.push_str("After merging this PR, there is nothing to release.\n");
+        return comment;
+    }
+    comment.push_str("After merging this PR, the next release contains:\n\n");
+    comment.push_str("| Package | Current version | Next version | Bump |\n");
+    comment.push_str("| --- | --- | --- | --- |\n");
+    for p in &releases {
+        comment.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            p.package_name,
+            p.current_version,
+            p.next_version,
+            p.bump.as_str()
+        ));
+    }
+    if releases.iter().any(|p| p.bump == BumpKind::Major) {
+        comment.push_str(
+            "\n> [!WARNING]\n> The next release is a major release: check that the breaking changes are intended.\n",
+        );
+    }
+    comment.push_str(
+        "\nComputed from all the commits since the last release, not only the ones of this PR.\n",
+    );
+    comment
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::next_version::PackageNextVersion;
+
+    use super::*;
+
+    fn package(name: &str, current: &str, next: &str, bump: BumpKind) -> PackageNextVersion {
+        PackageNextVersion {
+            package_name: name.to_string(),
+            current_version: current.parse().unwrap(),
+            next_version: next.parse().unwrap(),
+            bump,
+        }
+    }
+
+    #[test]
+    fn preview_comment_lists_the_releases() {
+        let next_versions = NextVersions {
+            packages: vec![
+                package("aaa", "1.2.3", "2.0.0", BumpKind::Major),
+                package("bbb", "0.1.0", "0.1.0", BumpKind::None),
+            ],
+        };
+        expect_test::expect![[r#"
+            <!-- k-releaser-preview -->
+            ## Release preview
+
+            After merging this PR, the next release contains:
+
+            | Package | Current version | Next version | Bump |
+            | --- | --- | --- | --- |
+            | `aaa` | 1.2.3 | 2.0.0 | major |
+
+            > [!WARNING]
+            > The next release is a major release: check that the breaking changes are intended.
+
+            Computed from all the commits since the last release, not only the ones of this PR.
+        "#]]
+        .assert_eq(&preview_comment(&next_versions));
+    }
+
+    #[test]
+    fn preview_comment_without_releases() {
+        let next_versions = NextVersions {
+            packages: vec![package("aaa", "1.2.3", "1.2.3", BumpKind::None)],
+        };
+        assert!(preview_comment(&next_versions).contains("nothing to release"));
+    }
+}
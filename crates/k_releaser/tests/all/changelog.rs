@@ -145,7 +145,7 @@ async fn k_releaser_adds_custom_changelog() {
     assert_eq!(opened_prs.len(), 1);
     let open_pr = &opened_prs[0];
     let expected_pr_body = format!(
-        r"
+        r#"
 ## New release v0.1.1
 
 This release updates all workspace packages to version **0.1.1**.
@@ -179,7 +179,9 @@ owner: {username}, repo: {package}, link: https://localhost/{username}/{package}
 
 
 ---
-Generated by [k-releaser](https://github.com/secana/k-releaser/)",
+Generated by [k-releaser](https://github.com/secana/k-releaser/)
+
+<!-- k-releaser-release-pr branch-prefix="k-releaser-" -->"#,
     );
     assert_eq!(
         open_pr.body.as_ref().unwrap().trim(),
@@ -144,6 +144,8 @@ impl GiteaContext {
 
 #[derive(Debug, serde::Deserialize)]
 pub struct GiteaRelease {
+    pub id: u64,
+    pub html_url: String,
     pub name: String,
     pub body: String,
 }
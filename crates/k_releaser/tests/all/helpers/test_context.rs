@@ -91,6 +91,12 @@ impl TestContext {
             body: "This is my pull request".to_string(),
             draft: false,
             labels: vec![],
+            reviewers: vec![],
+            assignees: vec![],
+            milestone: None,
+            project: None,
+            required_approvals: None,
+            auto_merge: None,
         };
         self.git_client.open_pr(&pr).await.unwrap();
         // go back to main
@@ -27,9 +27,14 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
     let crate_name = &context.gitea.repo;
 
     let outcome = context.run_release().success();
+    let gitea_release = context.gitea.get_gitea_release("v0.1.0").await;
     let expected_stdout = serde_json::json!({
         "releases": [
             {
+                "git_release": {
+                    "id": gitea_release.id,
+                    "html_url": gitea_release.html_url,
+                },
                 "package_name": crate_name,
                 "tag": "v0.1.0",
                 "version": "0.1.0",
@@ -74,9 +79,14 @@ async fn k_releaser_releases_a_new_project_with_custom_tag_name() {
     assert!(!is_tag_created());
 
     let outcome = context.run_release().success();
+    let gitea_release = context.gitea.get_gitea_release(expected_tag).await;
     let expected_stdout = serde_json::json!({
         "releases": [
             {
+                "git_release": {
+                    "id": gitea_release.id,
+                    "html_url": gitea_release.html_url,
+                },
                 "package_name": crate_name,
                 "prs": [],
                 "tag": expected_tag,
@@ -194,9 +204,14 @@ async fn k_releaser_does_not_releases_twice() {
 
     // Running `release` the first time, releases the project
     let outcome = context.run_release().success();
+    let gitea_release = context.gitea.get_gitea_release("v0.1.0").await;
     let expected_stdout = serde_json::json!({
         "releases": [
             {
+                "git_release": {
+                    "id": gitea_release.id,
+                    "html_url": gitea_release.html_url,
+                },
                 "package_name": crate_name,
                 "prs": [],
                 "tag": "v0.1.0",
@@ -221,9 +236,14 @@ async fn k_releaser_can_do_backports() {
 
     // Running `release` the first time, releases the project
     let outcome = context.run_release().success();
+    let gitea_release = context.gitea.get_gitea_release("v0.1.0").await;
     let expected_stdout = serde_json::json!({
         "releases": [
             {
+                "git_release": {
+                    "id": gitea_release.id,
+                    "html_url": gitea_release.html_url,
+                },
                 "package_name": crate_name,
                 "prs": [],
                 "tag": "v0.1.0",
@@ -240,9 +260,14 @@ async fn k_releaser_can_do_backports() {
     context.run_cargo_check();
     context.push_all_changes("breaking release");
     let outcome = context.run_release().success();
+    let gitea_release = context.gitea.get_gitea_release("v0.2.0").await;
     let expected_stdout = serde_json::json!({
         "releases": [
             {
+                "git_release": {
+                    "id": gitea_release.id,
+                    "html_url": gitea_release.html_url,
+                },
                 "package_name": crate_name,
                 "prs": [],
                 "tag": "v0.2.0",
@@ -260,9 +285,14 @@ async fn k_releaser_can_do_backports() {
     context.run_cargo_check();
     context.push_all_changes("backport release");
     let outcome = context.run_release().success();
+    let gitea_release = context.gitea.get_gitea_release("v0.1.1").await;
     let expected_stdout = serde_json::json!({
         "releases": [
             {
+                "git_release": {
+                    "id": gitea_release.id,
+                    "html_url": gitea_release.html_url,
+                },
                 "package_name": crate_name,
                 "prs": [],
                 "tag": "v0.1.1",
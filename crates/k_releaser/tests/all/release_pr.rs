@@ -23,7 +23,7 @@ async fn k_releaser_opens_pr_with_default_config() {
     assert_eq!(
         opened_prs[0].body.as_ref().unwrap().trim(),
         format!(
-            r"
+            r#"
 ## New release v0.1.1
 
 This release updates all workspace packages to version **0.1.1**.
@@ -50,7 +50,9 @@ This release updates all workspace packages to version **0.1.1**.
 
 
 ---
-Generated by [k-releaser](https://github.com/secana/k-releaser/)",
+Generated by [k-releaser](https://github.com/secana/k-releaser/)
+
+<!-- k-releaser-release-pr branch-prefix="k-releaser-" -->"#,
         )
         .trim()
     );
@@ -98,7 +100,7 @@ async fn k_releaser_opens_pr_without_breaking_changes() {
     pretty_assertions::assert_eq!(
         pr_body,
         format!(
-            r"
+            r#"
 ## New release v0.1.2
 
 This release updates all workspace packages to version **0.1.2**.
@@ -120,7 +122,9 @@ This release updates all workspace packages to version **0.1.2**.
 
 
 ---
-Generated by [k-releaser](https://github.com/secana/k-releaser/)",
+Generated by [k-releaser](https://github.com/secana/k-releaser/)
+
+<!-- k-releaser-release-pr branch-prefix="k-releaser-" -->"#,
         )
         .trim()
     );
@@ -244,7 +248,7 @@ async fn k_releaser_honors_features_always_increment_minor_flag() {
 #[tokio::test]
 #[cfg(unix)]
 #[cfg_attr(not(feature = "docker-tests"), ignore)]
-async fn k_releaser_adds_labels_to_release_pr() {
+async fn k_releaser_syncs_labels_of_release_pr() {
     let test_context = TestContext::new().await;
 
     // Initial PR setup with two labels
@@ -269,13 +273,13 @@ async fn k_releaser_adds_labels_to_release_pr() {
         "Labels don't match expected values"
     );
 
-    // Update PR with additional label
+    // Update PR with different labels: the stale ones are removed
     let updated_config = r#"
     [workspace]
     pr_name = "add labels to release label update"
-    pr_labels = ["needs-testing"]
+    pr_labels = ["enhancement", "needs-testing"]
     "#;
-    let expected_labels = ["bug", "enhancement", "needs-testing"];
+    let expected_labels = ["enhancement", "needs-testing"];
 
     test_context.write_release_plz_toml(updated_config);
     test_context.run_release_pr().success();
@@ -285,7 +289,7 @@ async fn k_releaser_adds_labels_to_release_pr() {
 
     let updated_pr = &updated_prs[0];
     assert_eq!(updated_pr.title, "add labels to release label update");
-    assert_eq!(updated_pr.labels.len(), 3, "Expected 3 labels after update");
+    assert_eq!(updated_pr.labels.len(), 2, "Expected 2 labels after update");
 
     assert_eq!(
         updated_pr.label_names(),
@@ -509,6 +513,60 @@ async fn k_releaser_works_with_valid_readme_path() {
     );
 }
 
+#[tokio::test]
+#[cfg_attr(not(feature = "docker-tests"), ignore)]
+async fn rebased_release_pr_merges_the_changelog_edited_by_a_contributor() {
+    let context = TestContext::new().await;
+    let config = r#"
+    [workspace]
+    changelog_update = true
+    pr_update_strategy = "rebase"
+    "#;
+    context.write_release_plz_toml(config);
+    context.run_release_pr().success();
+    let opened_prs = context.opened_release_prs().await;
+    assert_eq!(opened_prs.len(), 1);
+    let branch = opened_prs[0].branch().to_string();
+
+    // A contributor edits the changelog of the release PR.
+    context.repo.git(&["fetch", "origin", &branch]).unwrap();
+    context.repo.git(&["checkout", &branch]).unwrap();
+    let changelog = format!("{}\nReleased with care.\n", context.read_changelog());
+    fs_err::write(context.repo_dir().join("CHANGELOG.md"), &changelog).unwrap();
+    context
+        .repo
+        .add_all_and_commit("docs: edit changelog")
+        .unwrap();
+    context.repo.git(&["push", "origin", &branch]).unwrap();
+    context.repo.git(&["checkout", "-"]).unwrap();
+
+    fs_err::write(context.repo_dir().join("new.rs"), "// hi").unwrap();
+    context.push_all_changes("feat: add new file");
+    context.run_release_pr().success();
+
+    let opened_prs = context.opened_release_prs().await;
+    assert_eq!(opened_prs.len(), 1);
+    assert_eq!(opened_prs[0].branch(), branch);
+    let pr_changelog = context
+        .gitea
+        .get_file_content(&branch, "CHANGELOG.md")
+        .await;
+    // The edit of the contributor is applied to the regenerated changelog.
+    assert!(pr_changelog.contains("add new file"), "{pr_changelog}");
+    assert!(
+        pr_changelog.ends_with("\nReleased with care.\n"),
+        "{pr_changelog}"
+    );
+    // The stale commit of k-releaser is dropped, the one of the contributor is rebased.
+    context.repo.git(&["fetch", "origin", &branch]).unwrap();
+    let range = format!("HEAD..origin/{branch}");
+    let commits = context.repo.git(&["log", "--format=%s", &range]).unwrap();
+    assert_eq!(
+        commits.lines().collect::<Vec<_>>(),
+        ["docs: edit changelog", opened_prs[0].title.as_str()]
+    );
+}
+
 #[tokio::test]
 #[cfg_attr(not(feature = "docker-tests"), ignore)]
 async fn changelog_is_updated_correctly_if_no_new_line_after_h1() {
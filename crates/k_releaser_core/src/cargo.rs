@@ -1,7 +1,8 @@
 use anyhow::Context;
 use cargo_metadata::{Package, camino::Utf8Path};
-use crates_index::{Crate, GitIndex, SparseIndex};
-use tracing::{debug, info};
+use crates_index::{Crate, GitIndex, IndexConfig, SparseIndex};
+use tracing::{debug, info, warn};
+use url::Url;
 
 use http::{Version, header};
 use secrecy::{ExposeSecret, SecretString};
@@ -21,6 +22,24 @@ pub struct CargoRegistry {
     pub fallback_index: Option<CargoIndex>,
 }
 
+/// Kind of registry, which determines how its web API is queried.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RegistryKind {
+    /// Registry implementing the web API of crates.io.
+    #[default]
+    Cargo,
+    /// [Kellnr](https://kellnr.io) registry.
+    /// Its web API is used to check if a package is published, instead of the index.
+    Kellnr,
+}
+
+/// Web API of a registry.
+#[derive(Debug, Clone, Copy)]
+pub struct RegistryApi<'a> {
+    pub url: &'a Url,
+    pub kind: RegistryKind,
+}
+
 #[allow(clippy::large_enum_variant)]
 pub enum CargoIndex {
     Git(GitIndex),
@@ -83,6 +102,122 @@ pub async fn is_published(
     .with_context(|| format!("timeout while publishing {}", package.name))
 }
 
+/// Check if the package is published using the web API of the registry.
+///
+/// Useful when the index can't be read, e.g. because it requires authentication.
+/// Kellnr registries are queried at the download url of the `config.json` of the `index`.
+pub async fn is_published_api(
+    api: RegistryApi<'_>,
+    index: &CargoIndex,
+    package: &Package,
+    timeout: Duration,
+    token: &Option<SecretString>,
+) -> anyhow::Result<bool> {
+    match api.kind {
+        RegistryKind::Cargo => is_published_cargo_api(api.url, package, timeout, token).await,
+        RegistryKind::Kellnr => crate::kellnr::is_published(index, package, timeout, token).await,
+    }
+}
+
+/// Read the `config.json` of the index, containing the url where the packages are downloaded.
+pub(crate) async fn index_config(
+    index: &CargoIndex,
+    timeout: Duration,
+    token: &Option<SecretString>,
+) -> anyhow::Result<IndexConfig> {
+    match index {
+        CargoIndex::Git(index) => index
+            .index_config()
+            .context("can't read config.json of the git index"),
+        CargoIndex::Sparse(index) => {
+            let (parts, _) = index.make_config_request()?.body(())?.into_parts();
+            let mut req: reqwest::Request = http::Request::from_parts(parts, vec![]).try_into()?;
+            if let Some(token) = token {
+                let authorization = token
+                    .expose_secret()
+                    .parse()
+                    .context("parse token as header value")?;
+                req.headers_mut()
+                    .insert(header::AUTHORIZATION, authorization);
+            }
+            let client = crate::http_client::http_client_builder()
+                .timeout(timeout)
+                .build()?;
+            let res = client
+                .execute(req)
+                .await
+                .context("can't fetch config.json of the sparse index")?;
+            let builder = http::Response::builder()
+                .status(res.status())
+                .version(res.version());
+            let body = res.bytes().await?;
+            let res = builder.body(body.to_vec())?;
+            index
+                .parse_config_response(res, false)
+                .context("can't parse config.json of the sparse index")
+        }
+    }
+}
+
+/// Check if the package is published with `{api_url}/api/v1/crates/{name}/{version}`.
+async fn is_published_cargo_api(
+    api_url: &Url,
+    package: &Package,
+    timeout: Duration,
+    token: &Option<SecretString>,
+) -> anyhow::Result<bool> {
+    let url = format!(
+        "{}/api/v1/crates/{}/{}",
+        api_url.as_str().trim_end_matches('/'),
+        package.name,
+        package.version
+    );
+    let client = crate::http_client::http_client_builder()
+        .timeout(timeout)
+        .build()?;
+    let mut req = client.get(&url);
+    if let Some(token) = token {
+        req = req.header(header::AUTHORIZATION, token.expose_secret());
+    }
+    let res = req
+        .send()
+        .await
+        .with_context(|| format!("failed to call registry api {url}"))?;
+    match res.status() {
+        reqwest::StatusCode::OK => Ok(true),
+        reqwest::StatusCode::NOT_FOUND => Ok(false),
+        status => anyhow::bail!("unexpected status {status} from registry api {url}"),
+    }
+}
+
+/// Check if the package is published in the index.
+/// If the index can't be read and `api` is specified, use the web API of the registry.
+/// Kellnr registries are always queried with their web API.
+pub async fn is_published_with_api_fallback(
+    index: &mut CargoIndex,
+    package: &Package,
+    api: Option<RegistryApi<'_>>,
+    timeout: Duration,
+    token: &Option<SecretString>,
+) -> anyhow::Result<bool> {
+    if let Some(api) = api
+        && api.kind == RegistryKind::Kellnr
+    {
+        return is_published_api(api, index, package, timeout, token).await;
+    }
+    let is_published_in_index = is_published(index, package, timeout, token).await;
+    match (is_published_in_index, api) {
+        (Err(e), Some(api)) => {
+            warn!(
+                "Error checking index for package {}: {e:?}. Trying registry api.",
+                package.name
+            );
+            is_published_api(api, index, package, timeout, token).await
+        }
+        (is_published_in_index, _) => is_published_in_index,
+    }
+}
+
 pub fn is_published_git(index: &mut GitIndex, package: &Package) -> anyhow::Result<bool> {
     // See if we already have the package in cache.
     if is_in_cache_git(index, package) {
@@ -114,6 +249,32 @@ async fn is_in_cache_sparse(
     Ok(is_in_cache(crate_data.as_ref(), version))
 }
 
+/// Versions of the crate published in the index, yanked ones included.
+pub async fn published_versions(
+    index: &mut CargoIndex,
+    crate_name: &str,
+    token: &Option<SecretString>,
+) -> anyhow::Result<Vec<String>> {
+    let crate_data = match index {
+        CargoIndex::Git(index) => {
+            index.update().context("failed to update git index")?;
+            index.crate_(crate_name)
+        }
+        CargoIndex::Sparse(index) => fetch_sparse_metadata(index, crate_name, token)
+            .await
+            .context("failed fetching sparse metadata")?,
+    };
+    let versions = crate_data
+        .map(|c| {
+            c.versions()
+                .iter()
+                .map(|v| v.version().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(versions)
+}
+
 fn is_in_cache(crate_data: Option<&Crate>, version: &str) -> bool {
     if let Some(crate_data) = crate_data
         && is_version_present(version, crate_data)
@@ -209,7 +370,7 @@ async fn request_for_sparse_metadata(
             .insert(header::AUTHORIZATION, authorization);
     }
 
-    let mut client_builder = reqwest::ClientBuilder::new().gzip(true);
+    let mut client_builder = crate::http_client::http_client_builder().gzip(true);
     if http_version == Version::HTTP_2 {
         client_builder = client_builder.http2_prior_knowledge();
     }
@@ -223,15 +384,17 @@ async fn request_for_sparse_metadata(
 pub async fn wait_until_published(
     index: &mut CargoIndex,
     package: &Package,
+    api: Option<RegistryApi<'_>>,
     timeout: Duration,
+    poll_interval: Duration,
     token: &Option<SecretString>,
 ) -> anyhow::Result<()> {
     let now: Instant = Instant::now();
-    let sleep_time = Duration::from_secs(2);
     let mut logged = false;
 
     loop {
-        let is_published = is_published(index, package, timeout, token).await?;
+        let is_published =
+            is_published_with_api_fallback(index, package, api, timeout, token).await?;
         if is_published {
             break;
         } else if timeout < now.elapsed() {
@@ -250,7 +413,7 @@ pub async fn wait_until_published(
             logged = true;
         }
 
-        tokio::time::sleep(sleep_time).await;
+        tokio::time::sleep(poll_interval).await;
     }
 
     Ok(())
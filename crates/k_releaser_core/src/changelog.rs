@@ -35,6 +35,7 @@ pub struct Changelog<'a> {
     package: String,
     remote: Option<Remote>,
     pr_link: Option<String>,
+    group_by_package: bool,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -103,8 +104,12 @@ impl Changelog<'_> {
 
     fn changelog_config(&self, header: Option<String>) -> Config {
         let user_config = self.config.clone().unwrap_or(default_git_cliff_config());
+        let mut changelog = apply_defaults_to_changelog_config(user_config.changelog, header);
+        if self.group_by_package && changelog.body == default_changelog_body_config() {
+            changelog.body = package_groups_changelog_body_config().to_string();
+        }
         Config {
-            changelog: apply_defaults_to_changelog_config(user_config.changelog, header),
+            changelog,
             git: apply_defaults_to_git_config(user_config.git, self.pr_link.as_deref()),
             remote: user_config.remote,
             bump: Bump::default(),
@@ -284,6 +289,7 @@ pub struct ChangelogBuilder<'a> {
     release_link: Option<String>,
     package: String,
     pr_link: Option<String>,
+    group_by_package: bool,
 }
 
 impl<'a> ChangelogBuilder<'a> {
@@ -302,6 +308,7 @@ impl<'a> ChangelogBuilder<'a> {
             release_link: None,
             package: package.into(),
             pr_link: None,
+            group_by_package: false,
         }
     }
 
@@ -347,6 +354,15 @@ impl<'a> ChangelogBuilder<'a> {
         }
     }
 
+    /// Group the commits by the package in their `extra.package` field, unless the
+    /// changelog body is customized.
+    pub fn with_group_by_package(self, group_by_package: bool) -> Self {
+        Self {
+            group_by_package,
+            ..self
+        }
+    }
+
     pub fn config(&self) -> Option<&Config> {
         self.config.as_ref()
     }
@@ -405,6 +421,7 @@ impl<'a> ChangelogBuilder<'a> {
             config: self.config.clone(),
             package: self.package.clone(),
             pr_link: self.pr_link.clone(),
+            group_by_package: self.group_by_package,
         }
     }
 
@@ -504,6 +521,26 @@ fn default_changelog_body_config() -> &'static str {
 {% endfor %}"#
 }
 
+/// Like [`default_changelog_body_config`], with the commits grouped by package first.
+fn package_groups_changelog_body_config() -> &'static str {
+    r#"
+## [{{ version }}]{%- if release_link -%}({{ release_link }}){% endif %} - {{ timestamp | date(format="%Y-%m-%d") }}
+{% for package, package_commits in commits | group_by(attribute="extra.package") %}
+### {{ package }}
+{% for group, commits in package_commits | group_by(attribute="group") %}
+#### {{ group | upper_first }}
+
+{% for commit in commits %}
+{%- if commit.scope -%}
+- *({{commit.scope}})* {% if commit.breaking %}[**breaking**] {% endif %}{{ commit.message }}{%- if commit.links %} ({% for link in commit.links %}[{{link.text}}]({{link.href}}) {% endfor -%}){% endif %}
+{% else -%}
+- {% if commit.breaking %}[**breaking**] {% endif %}{{ commit.message }}
+{% endif -%}
+{% endfor -%}
+{% endfor -%}
+{% endfor %}"#
+}
+
 #[cfg(test)]
 mod tests {
     use crate::NO_COMMIT_ID;
@@ -543,6 +580,25 @@ mod tests {
         .assert_eq(&changelog.generate().unwrap());
     }
 
+    #[test]
+    fn changelog_entries_are_grouped_by_package() {
+        let feat = crate::diff::Commit::new(NO_COMMIT_ID.to_string(), "feat: add foo".to_string());
+        let fix = crate::diff::Commit::new(NO_COMMIT_ID.to_string(), "fix: fix ci".to_string());
+        let commits = vec![
+            feat.to_package_cliff_commit("core"),
+            fix.to_package_cliff_commit("workspace-wide"),
+        ];
+        let changelog = ChangelogBuilder::new(commits, "1.1.1", "workspace")
+            .with_release_date(NaiveDate::from_ymd_opt(2015, 5, 15).unwrap())
+            .with_group_by_package(true)
+            .build()
+            .generate()
+            .unwrap();
+
+        assert!(changelog.contains("### core\n\n#### Added\n\n- add foo\n"));
+        assert!(changelog.contains("### workspace-wide\n\n#### Fixed\n\n- fix ci\n"));
+    }
+
     #[test]
     fn changelog_entry_with_link_is_generated() {
         let commits = vec![Commit::new(
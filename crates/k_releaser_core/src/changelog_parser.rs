@@ -87,6 +87,34 @@ pub fn last_release_from_str(changelog: &str) -> anyhow::Result<Option<Changelog
     Ok(last_release)
 }
 
+/// Versions of the releases contained in the changelog, newest first.
+/// The unreleased section is excluded.
+pub fn versions_from_str(changelog: &str) -> anyhow::Result<Vec<String>> {
+    let parser = ChangelogParser::new(changelog)?;
+    let versions = parser
+        .changelog
+        .keys()
+        .filter(|version| !version.to_lowercase().contains("unreleased"))
+        .map(|version| version.to_string())
+        .collect();
+    Ok(versions)
+}
+
+/// Release of the given version, or the last release if `version` is `latest`.
+/// A leading `v` in `version` is ignored.
+pub fn release_from_str(
+    changelog: &str,
+    version: &str,
+) -> anyhow::Result<Option<ChangelogRelease>> {
+    let parser = ChangelogParser::new(changelog)?;
+    let release = if version == "latest" {
+        parser.last_release()
+    } else {
+        parser.release(version)
+    };
+    Ok(release.map(ChangelogRelease::from_release))
+}
+
 #[derive(Debug)]
 pub struct ChangelogRelease {
     title: String,
@@ -129,6 +157,12 @@ impl<'a> ChangelogParser<'a> {
         };
         Some(last_release)
     }
+
+    fn release(&self, version: &str) -> Option<&parse_changelog::Release<'_>> {
+        self.changelog
+            .get(version)
+            .or_else(|| self.changelog.get(version.strip_prefix('v')?))
+    }
 }
 
 fn release_at<'a>(
@@ -315,4 +349,31 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
 - Add function to retrieve default branch (#372)";
         assert_eq!(changes, expected_changes);
     }
+
+    #[test]
+    fn release_of_version_is_parsed() {
+        let changelog = "\
+# Changelog
+
+## [Unreleased]
+
+## [0.2.5] - 2022-12-16
+
+### Added
+
+- Add function to retrieve default branch (#372)
+
+## [0.2.4] - 2022-12-12
+
+### Changed
+
+- improved error message
+";
+        let release = release_from_str(changelog, "v0.2.4").unwrap().unwrap();
+        assert_eq!(release.title(), "[0.2.4] - 2022-12-12");
+        assert_eq!(release.notes(), "### Changed\n\n- improved error message");
+        let latest = release_from_str(changelog, "latest").unwrap().unwrap();
+        assert_eq!(latest.title(), "[0.2.5] - 2022-12-16");
+        assert!(release_from_str(changelog, "0.1.0").unwrap().is_none());
+    }
 }
@@ -0,0 +1,159 @@
+//! Owners of the files of the repository, read from the `CODEOWNERS` file.
+//! Docs: https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/customizing-your-repository/about-code-owners
+
+use anyhow::Context as _;
+use cargo_metadata::camino::Utf8Path;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use tracing::debug;
+
+/// Locations of the `CODEOWNERS` file, relative to the repository root,
+/// where GitHub, Gitea and GitLab look for it.
+const CODEOWNERS_PATHS: &[&str] = &[
+    ".github/CODEOWNERS",
+    ".gitea/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+];
+
+/// Rule of the `CODEOWNERS` file: the files matching the pattern are owned by `owners`.
+struct Rule {
+    pattern: Gitignore,
+    /// Users (`user`) and teams (`org/team`), without the leading `@`.
+    owners: Vec<String>,
+}
+
+pub(crate) struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Read the `CODEOWNERS` file of the repository.
+    /// Returns [`Option::None`] if the repository doesn't have one.
+    pub(crate) fn read(repo_root: &Utf8Path) -> anyhow::Result<Option<Self>> {
+        let Some(path) = CODEOWNERS_PATHS
+            .iter()
+            .map(|p| repo_root.join(p))
+            .find(|p| p.is_file())
+        else {
+            return Ok(None);
+        };
+        let content = fs_err::read_to_string(&path)?;
+        let codeowners =
+            Self::parse(&content).with_context(|| format!("invalid CODEOWNERS file {path}"))?;
+        Ok(Some(codeowners))
+    }
+
+    fn parse(content: &str) -> anyhow::Result<Self> {
+        let mut rules = vec![];
+        for line in content.lines().map(str::trim) {
+            // GitLab sections, e.g. `[Documentation]`, group rules without changing them.
+            if line.is_empty() || line.starts_with('#') || line.starts_with(['[', '^']) {
+                continue;
+            }
+            let mut tokens = line.split_whitespace();
+            let Some(pattern) = tokens.next() else {
+                continue;
+            };
+            let owners = tokens
+                .take_while(|t| !t.starts_with('#'))
+                // Emails can't be requested to review
+                .filter_map(|t| t.strip_prefix('@'))
+                .map(String::from)
+                .collect();
+            let mut builder = GitignoreBuilder::new("");
+            builder
+                .add_line(None, pattern)
+                .with_context(|| format!("invalid pattern {pattern}"))?;
+            rules.push(Rule {
+                pattern: builder.build()?,
+                owners,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Owners of `file`, relative to the repository root.
+    /// The last matching rule wins, like in GitHub.
+    fn owners_of(&self, file: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                rule.pattern
+                    .matched_path_or_any_parents(file, false)
+                    .is_ignore()
+            })
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Owners of any of the `files`, without duplicates.
+    pub(crate) fn owners_of_files(&self, files: &[String]) -> Vec<String> {
+        let mut owners: Vec<String> = vec![];
+        for file in files {
+            for owner in self.owners_of(file) {
+                if !owners.contains(owner) {
+                    owners.push(owner.clone());
+                }
+            }
+        }
+        debug!("owners of the changed files: {owners:?}");
+        owners
+    }
+}
+
+/// Reviewers of a release PR changing `files`: the owners of the files in the
+/// `CODEOWNERS` file of the repository.
+pub(crate) fn codeowners_reviewers(
+    repo_root: &Utf8Path,
+    files: &[String],
+) -> anyhow::Result<Vec<String>> {
+    let owners = match CodeOwners::read(repo_root)? {
+        Some(codeowners) => codeowners.owners_of_files(files),
+        None => {
+            debug!("no CODEOWNERS file found");
+            vec![]
+        }
+    };
+    Ok(owners)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = r"
+# Default owners
+*       @my-org/maintainers
+*.md    @alice docs@example.com
+/crates/core/   @bob @my-org/core # core team
+CHANGELOG.md    @carol
+";
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let codeowners = CodeOwners::parse(CODEOWNERS).unwrap();
+        assert_eq!(codeowners.owners_of("Cargo.lock"), ["my-org/maintainers"]);
+        assert_eq!(codeowners.owners_of("README.md"), ["alice"]);
+        assert_eq!(
+            codeowners.owners_of("crates/core/Cargo.toml"),
+            ["bob", "my-org/core"]
+        );
+        assert_eq!(codeowners.owners_of("crates/core/CHANGELOG.md"), ["carol"]);
+    }
+
+    #[test]
+    fn owners_of_files_are_deduplicated() {
+        let codeowners = CodeOwners::parse(CODEOWNERS).unwrap();
+        let files = [
+            "crates/core/Cargo.toml".to_string(),
+            "crates/core/src/lib.rs".to_string(),
+            "Cargo.lock".to_string(),
+        ];
+        assert_eq!(
+            codeowners.owners_of_files(&files),
+            ["bob", "my-org/core", "my-org/maintainers"]
+        );
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use cargo_metadata::{camino::Utf8PathBuf, semver::Version};
+use chrono::NaiveDate;
+use git_cmd::Repo;
+use serde::Serialize;
+use tracing::{debug, info, instrument};
+
+use crate::{
+    CHANGELOG_FILENAME, CHANGELOG_HEADER, ChangelogBuilder, GitClient, Project, Remote,
+    changelog_parser,
+    project::version_from_tag,
+    update_request::UpdateRequest,
+    updater::{WORKSPACE_PACKAGE_NAME, commits_in_range, get_contributors, new_changelog_entry},
+};
+
+use super::release::GitReleaseInfo;
+
+/// Generate the changelog sections of the releases made before adopting k-releaser.
+#[derive(Debug, Clone)]
+pub struct BackfillRequest {
+    update_req: UpdateRequest,
+    /// Create and push the git tags missing for the found releases.
+    create_tags: bool,
+    /// Create the forge releases missing for the tags of the found releases.
+    create_releases: bool,
+    /// Don't write the changelog and don't create tags and releases.
+    dry_run: bool,
+}
+
+impl BackfillRequest {
+    pub fn new(update_req: UpdateRequest) -> Self {
+        Self {
+            update_req,
+            create_tags: false,
+            create_releases: false,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_create_tags(self, create_tags: bool) -> Self {
+        Self {
+            create_tags,
+            ..self
+        }
+    }
+
+    pub fn with_create_releases(self, create_releases: bool) -> Self {
+        Self {
+            create_releases,
+            ..self
+        }
+    }
+
+    pub fn with_dry_run(self, dry_run: bool) -> Self {
+        Self { dry_run, ..self }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct BackfillOutput {
+    pub releases: Vec<BackfilledRelease>,
+    /// Path of the workspace changelog.
+    #[serde(skip)]
+    pub changelog_path: Utf8PathBuf,
+    /// The entire changelog, with the backfilled sections.
+    #[serde(skip)]
+    pub changelog: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BackfilledRelease {
+    version: Version,
+    tag: String,
+    commit: String,
+    /// Whether the git tag was created by the backfill.
+    tag_created: bool,
+    /// Whether the forge release was created by the backfill.
+    release_created: bool,
+}
+
+/// A past release found in the git history.
+#[derive(Debug)]
+struct HistoricalRelease {
+    version: Version,
+    commit: String,
+    tag: Option<String>,
+}
+
+/// Find the releases made before the oldest version of the changelog, looking
+/// at the version tags and at the commits that changed the version in the Cargo.toml,
+/// and add their sections at the bottom of the changelog, before its footer.
+#[instrument(skip_all)]
+pub async fn backfill(input: &BackfillRequest) -> anyhow::Result<BackfillOutput> {
+    let req = &input.update_req;
+    let manifest_dir = req.local_manifest_dir()?;
+    let mut repo = Repo::new(manifest_dir)?;
+    if let Some(remote) = req.git_remote() {
+        repo = repo.with_remote(remote);
+    }
+    let overrides = req.packages_config().overridden_packages();
+    let project = Project::new(
+        req.local_manifest(),
+        None,
+        &overrides,
+        req.cargo_metadata(),
+        req,
+    )?
+    .with_version_mode(req.version_mode())
+    .with_branch(req.branch().map(str::to_string))
+    .with_workspace_tag_name(req.workspace_tag_name().map(str::to_string));
+    let tags = ReleaseTags::new(&project)?;
+    let git_client = if input.create_releases {
+        Some(
+            req.git_client()?
+                .context("can't create the forge releases without a git token")?,
+        )
+    } else {
+        None
+    };
+
+    let changelog_path = manifest_dir.join(CHANGELOG_FILENAME);
+    let old_changelog = if changelog_path.exists() {
+        Some(fs_err::read_to_string(&changelog_path)?)
+    } else {
+        None
+    };
+    let oldest_changelog_version = match &old_changelog {
+        Some(changelog) => changelog_parser::versions_from_str(changelog)?
+            .iter()
+            .filter_map(|v| Version::parse(v).ok())
+            .min(),
+        None => None,
+    };
+
+    let releases: Vec<HistoricalRelease> = historical_releases(&repo, &tags)?
+        .into_values()
+        .filter(|r| {
+            oldest_changelog_version
+                .as_ref()
+                .is_none_or(|oldest| r.version < *oldest)
+        })
+        .collect();
+    if releases.is_empty() {
+        info!("no past release to backfill");
+    }
+
+    let mut entries = vec![];
+    let mut previous: Option<&HistoricalRelease> = None;
+    for release in &releases {
+        let range = match previous {
+            Some(previous) => format!("{}..{}", previous.commit, release.commit),
+            None => release.commit.clone(),
+        };
+        let entry = changelog_entry(input, &repo, &tags, release, previous, &range)?;
+        entries.push(entry);
+        previous = Some(release);
+    }
+
+    // The newest release goes first.
+    let newest_first: Vec<&str> = entries.iter().rev().map(String::as_str).collect();
+    let changelog = match &old_changelog {
+        Some(old_changelog) if entries.is_empty() => old_changelog.clone(),
+        Some(old_changelog) => {
+            let footer = req
+                .changelog_req()
+                .changelog_config
+                .as_ref()
+                .and_then(|config| config.changelog.footer.as_deref());
+            let (body, footer) = split_footer(old_changelog, footer);
+            let entries = newest_first.join("\n\n");
+            if footer.is_empty() {
+                format!("{body}\n\n{entries}\n")
+            } else {
+                format!("{body}\n\n{entries}\n\n{footer}\n")
+            }
+        }
+        None => format!("{CHANGELOG_HEADER}\n{}\n", newest_first.join("\n\n")),
+    };
+
+    let released_tags = match &git_client {
+        Some(git_client) => git_client.release_tags().await?,
+        None => vec![],
+    };
+    let mut backfilled = vec![];
+    for (release, entry) in releases.into_iter().zip(&entries) {
+        let tag = match &release.tag {
+            Some(tag) => tag.clone(),
+            None => tags.tag(&release.version)?,
+        };
+        let tag_created = release.tag.is_none() && input.create_tags;
+        if tag_created {
+            create_tag(input.dry_run, &repo, &tag, &release.commit)?;
+        }
+        let tag_exists = release.tag.is_some() || tag_created;
+        let release_created = match &git_client {
+            Some(git_client) if tag_exists && !released_tags.contains(&tag) => {
+                let release_info = GitReleaseInfo {
+                    git_tag: tag.clone(),
+                    release_name: tags.release_name(&release.version)?,
+                    release_body: entry.clone(),
+                    // The backfilled releases are older than the latest one.
+                    latest: Some(false),
+                    draft: false,
+                    pre_release: !release.version.pre.is_empty(),
+                    generate_release_notes: false,
+                    assets: vec![],
+                    tag_ref: None,
+                    tag_message: None,
+                };
+                create_release(input.dry_run, git_client, &release_info).await?;
+                true
+            }
+            _ => false,
+        };
+        backfilled.push(BackfilledRelease {
+            version: release.version,
+            tag,
+            commit: release.commit,
+            tag_created,
+            release_created,
+        });
+    }
+
+    if input.dry_run {
+        info!("dry run: changelog not written");
+    } else if !backfilled.is_empty() {
+        fs_err::write(&changelog_path, &changelog)?;
+        info!("added {} releases to {changelog_path}", backfilled.len());
+    }
+    Ok(BackfillOutput {
+        releases: backfilled,
+        changelog_path,
+        changelog,
+    })
+}
+
+/// Split the changelog into its body and its footer, made of the `footer` of the changelog
+/// config and of the link reference definitions, e.g. `[0.1.0]: https://...`.
+/// Both are trimmed.
+fn split_footer<'a>(changelog: &'a str, config_footer: Option<&str>) -> (&'a str, &'a str) {
+    let config_footer = config_footer.map(str::trim).filter(|f| !f.is_empty());
+    let mut body = changelog.trim_end();
+    loop {
+        if let Some(rest) = config_footer.and_then(|footer| body.strip_suffix(footer)) {
+            body = rest.trim_end();
+            continue;
+        }
+        match body.rsplit_once('\n') {
+            Some((rest, last_line)) if is_link_reference_definition(last_line) => {
+                body = rest.trim_end();
+            }
+            _ => break,
+        }
+    }
+    (body, changelog[body.len()..].trim())
+}
+
+fn is_link_reference_definition(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('[') && line.contains("]: ")
+}
+
+/// Names of the tags and of the forge releases of the project.
+struct ReleaseTags<'a> {
+    project: &'a Project,
+    /// Package whose tag template is used.
+    package_name: String,
+}
+
+impl<'a> ReleaseTags<'a> {
+    fn new(project: &'a Project) -> anyhow::Result<Self> {
+        let package_name = if project.version_mode().is_independent() {
+            // The backfilled versions are the ones of the Cargo.toml of the project.
+            match project.publishable_packages().as_slice() {
+                [package] => package.name.to_string(),
+                _ => anyhow::bail!(
+                    "with independent versioning, backfill supports only projects with a single package"
+                ),
+            }
+        } else {
+            WORKSPACE_PACKAGE_NAME.to_string()
+        };
+        Ok(Self {
+            project,
+            package_name,
+        })
+    }
+
+    fn tag(&self, version: &Version) -> anyhow::Result<String> {
+        self.project
+            .git_tag(&self.package_name, &version.to_string())
+    }
+
+    fn release_name(&self, version: &Version) -> anyhow::Result<String> {
+        if self.project.version_mode().is_independent() {
+            self.project
+                .release_name(&self.package_name, &version.to_string())
+        } else {
+            // Same name as the releases of the workspace.
+            Ok(format!("Version {version}"))
+        }
+    }
+}
+
+/// Past releases, sorted by version.
+/// Releases are found from the git tags of the project and from the commits that
+/// changed the version of the Cargo.toml. If a version has a tag, the tagged
+/// commit is used.
+fn historical_releases(
+    repo: &Repo,
+    tags: &ReleaseTags<'_>,
+) -> anyhow::Result<BTreeMap<Version, HistoricalRelease>> {
+    let mut releases = BTreeMap::new();
+    for (version, commit) in version_bumps(repo)? {
+        releases
+            .entry(version.clone())
+            .or_insert(HistoricalRelease {
+                version,
+                commit,
+                tag: None,
+            });
+    }
+    for tag in repo.get_all_tags() {
+        let Some(version) = version_from_tag(&tag) else {
+            continue;
+        };
+        if tags.tag(&version)? != tag {
+            continue;
+        }
+        let commit = repo
+            .get_tag_commit(&tag)
+            .with_context(|| format!("can't find the commit of tag {tag}"))?;
+        releases.insert(
+            version.clone(),
+            HistoricalRelease {
+                version,
+                commit: commit.trim().to_string(),
+                tag: Some(tag),
+            },
+        );
+    }
+    Ok(releases)
+}
+
+/// Commits that changed the version in the Cargo.toml, oldest first.
+fn version_bumps(repo: &Repo) -> anyhow::Result<Vec<(Version, String)>> {
+    let commits = repo.git(&["log", "--reverse", "--format=%H", "--", "Cargo.toml"])?;
+    let mut bumps = vec![];
+    let mut last_version: Option<Version> = None;
+    for commit in commits.lines() {
+        // The Cargo.toml doesn't exist in the commits that delete it.
+        let Ok(manifest) = repo.git(&["show", &format!("{commit}:./Cargo.toml")]) else {
+            continue;
+        };
+        let Some(version) = manifest_version(&manifest) else {
+            continue;
+        };
+        if last_version.as_ref() != Some(&version) {
+            debug!("version {version} set in commit {commit}");
+            bumps.push((version.clone(), commit.to_string()));
+            last_version = Some(version);
+        }
+    }
+    Ok(bumps)
+}
+
+/// Version of the workspace, or of the package if the manifest isn't a workspace.
+fn manifest_version(manifest: &str) -> Option<Version> {
+    let manifest: toml_edit::DocumentMut = manifest.parse().ok()?;
+    let version = manifest
+        .get("workspace")
+        .and_then(|w| w.get("package"))
+        .and_then(|p| p.get("version"))
+        .or_else(|| manifest.get("package").and_then(|p| p.get("version")))?;
+    Version::parse(version.as_str()?).ok()
+}
+
+fn changelog_entry(
+    input: &BackfillRequest,
+    repo: &Repo,
+    tags: &ReleaseTags<'_>,
+    release: &HistoricalRelease,
+    previous: Option<&HistoricalRelease>,
+    range: &str,
+) -> anyhow::Result<String> {
+    let req = &input.update_req;
+    let commits = commits_in_range(repo, range)?;
+    let commits: Vec<git_cliff_core::commit::Commit> =
+        commits.iter().map(|c| c.to_cliff_commit()).collect();
+    let mut changelog_builder = ChangelogBuilder::new(
+        commits.clone(),
+        release.version.to_string(),
+        WORKSPACE_PACKAGE_NAME,
+    )
+    .with_release_date(commit_date(repo, &release.commit)?);
+    if let Some(config) = req.changelog_req().changelog_config.clone() {
+        changelog_builder = changelog_builder.with_config(config);
+    }
+    if let Some(previous) = previous {
+        changelog_builder = changelog_builder.with_previous_version(previous.version.to_string());
+    }
+    if let Some(repo_url) = req.repo_url() {
+        if let Some(previous) = previous {
+            let link = repo_url
+                .git_release_link(&tags.tag(&previous.version)?, &tags.tag(&release.version)?);
+            changelog_builder = changelog_builder.with_release_link(link);
+        }
+        let remote = Remote {
+            owner: repo_url.owner.clone(),
+            repo: repo_url.name.clone(),
+            link: repo_url.full_host(),
+            contributors: get_contributors(&commits),
+        };
+        changelog_builder = changelog_builder
+            .with_remote(remote)
+            .with_pr_link(repo_url.git_pr_link());
+    }
+    let entry = new_changelog_entry(changelog_builder)
+        .with_context(|| format!("can't generate changelog of version {}", release.version))?;
+    Ok(entry.unwrap_or_default())
+}
+
+fn commit_date(repo: &Repo, commit: &str) -> anyhow::Result<NaiveDate> {
+    let date = repo.git(&["log", "-1", "--format=%cs", commit])?;
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .with_context(|| format!("can't parse date of commit {commit}"))
+}
+
+fn create_tag(dry_run: bool, repo: &Repo, tag: &str, commit: &str) -> anyhow::Result<()> {
+    if dry_run {
+        info!("{tag}: dry run: would create the git tag at commit {commit}");
+        return Ok(());
+    }
+    repo.git(&["tag", "-m", tag, tag, commit])
+        .with_context(|| format!("can't create tag {tag}"))?;
+    repo.push(tag)
+        .with_context(|| format!("can't push tag {tag}"))?;
+    info!("{tag}: created git tag at commit {commit}");
+    Ok(())
+}
+
+async fn create_release(
+    dry_run: bool,
+    git_client: &GitClient,
+    release_info: &GitReleaseInfo,
+) -> anyhow::Result<()> {
+    let tag = &release_info.git_tag;
+    if dry_run {
+        info!("{tag}: dry run: would create the forge release");
+        return Ok(());
+    }
+    git_client
+        .create_release(release_info)
+        .await
+        .with_context(|| format!("can't create the release of tag {tag}"))?;
+    info!("{tag}: created forge release");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_version_is_read_from_manifest() {
+        let manifest = r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "0.3.1"
+"#;
+        assert_eq!(manifest_version(manifest), Some(Version::new(0, 3, 1)));
+    }
+
+    #[test]
+    fn package_version_is_read_from_manifest() {
+        let manifest = r#"
+[package]
+name = "my-crate"
+version = "1.2.0"
+"#;
+        assert_eq!(manifest_version(manifest), Some(Version::new(1, 2, 0)));
+        assert_eq!(manifest_version("[workspace]\nmembers = []\n"), None);
+    }
+
+    #[test]
+    fn footer_is_split_from_changelog() {
+        let changelog = "# Changelog\n\n## [0.2.0]\n\n- feature\n\n[0.2.0]: https://example.com/compare/v0.1.0...v0.2.0\n[0.1.0]: https://example.com/releases/v0.1.0\n\n<!-- generated by git-cliff -->\n";
+        let (body, footer) = split_footer(changelog, Some("<!-- generated by git-cliff -->\n"));
+        assert_eq!(body, "# Changelog\n\n## [0.2.0]\n\n- feature");
+        assert_eq!(
+            footer,
+            "[0.2.0]: https://example.com/compare/v0.1.0...v0.2.0\n[0.1.0]: https://example.com/releases/v0.1.0\n\n<!-- generated by git-cliff -->"
+        );
+
+        let (body, footer) = split_footer("# Changelog\n\n## [0.2.0]\n\n- [feature](link)\n", None);
+        assert_eq!(body, "# Changelog\n\n## [0.2.0]\n\n- [feature](link)");
+        assert_eq!(footer, "");
+    }
+}
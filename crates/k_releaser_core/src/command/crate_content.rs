@@ -0,0 +1,72 @@
+use anyhow::Context as _;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+
+/// Files of a package that violate its content policy.
+///
+/// - `files` are the paths listed by `cargo package --list`, relative to `package_dir`.
+/// - `deny_files` are glob patterns of the files that must not be published.
+/// - `max_size` is the maximum size in bytes of the packaged files, before compression.
+pub(crate) fn content_violations(
+    package_dir: &Utf8Path,
+    files: &[Utf8PathBuf],
+    deny_files: &[String],
+    max_size: Option<u64>,
+) -> anyhow::Result<Vec<String>> {
+    let patterns = deny_files
+        .iter()
+        .map(|p| glob::Pattern::new(p).with_context(|| format!("invalid deny glob `{p}`")))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut violations = vec![];
+    for file in files {
+        if let Some(pattern) = patterns.iter().find(|p| p.matches(file.as_str())) {
+            violations.push(format!("{file} matches denied glob `{pattern}`"));
+        }
+    }
+
+    if let Some(max_size) = max_size {
+        let mut size = 0;
+        for file in files {
+            // Files generated by `cargo package`, like `Cargo.toml.orig`, aren't on disk.
+            let path = package_dir.join(file);
+            if path.is_file() {
+                size += fs_err::metadata(&path)?.len();
+            }
+        }
+        if size > max_size {
+            violations.push(format!(
+                "package size of {size} bytes exceeds the limit of {max_size} bytes"
+            ));
+        }
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs_utils::Utf8TempDir;
+
+    #[test]
+    fn denied_files_and_size_are_reported() {
+        let dir = Utf8TempDir::new().unwrap();
+        fs_err::create_dir_all(dir.path().join("certs")).unwrap();
+        fs_err::write(dir.path().join("certs/key.pem"), "secret").unwrap();
+        fs_err::write(dir.path().join("lib.rs"), "fn main() {}").unwrap();
+        let files = ["Cargo.toml.orig", "certs/key.pem", "lib.rs"].map(Utf8PathBuf::from);
+
+        let violations =
+            content_violations(dir.path(), &files, &["*.pem".to_string()], Some(10)).unwrap();
+        assert_eq!(
+            violations,
+            [
+                "certs/key.pem matches denied glob `*.pem`",
+                "package size of 18 bytes exceeds the limit of 10 bytes",
+            ]
+        );
+
+        let violations =
+            content_violations(dir.path(), &files, &["tests/*".to_string()], Some(18)).unwrap();
+        assert!(violations.is_empty());
+    }
+}
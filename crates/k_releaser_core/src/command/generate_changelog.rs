@@ -0,0 +1,219 @@
+use anyhow::Context as _;
+use cargo_metadata::{camino::Utf8PathBuf, semver::Version};
+use cargo_utils::LocalManifest;
+use git_cmd::Repo;
+use tracing::{debug, instrument};
+
+use crate::{
+    CHANGELOG_FILENAME, ChangelogBuilder, Remote, changelog_parser,
+    project::version_from_tag,
+    update_request::UpdateRequest,
+    updater::{
+        commits_in_range, forced_bump, get_contributors, new_changelog_entry, patch_only_version,
+    },
+};
+
+/// Package name used in the changelog entry, since the changelog covers the whole workspace.
+const WORKSPACE_PACKAGE_NAME: &str = "workspace";
+
+/// Heading of the changelog entry of the commits not released yet.
+const UNRELEASED: &str = "Unreleased";
+
+/// Generate the changelog from the git history, without changing
+/// the versions of the packages.
+#[derive(Debug, Clone)]
+pub struct GenerateChangelogRequest {
+    update_req: UpdateRequest,
+    /// Tag or commit after which commits are included.
+    /// If unspecified, the latest tag reachable from `to` is used.
+    since: Option<String>,
+    /// Tag or commit up to which commits are included. If unspecified, `HEAD` is used.
+    to: Option<String>,
+    /// Title the entry as unreleased instead of using a version.
+    unreleased: bool,
+}
+
+impl GenerateChangelogRequest {
+    pub fn new(update_req: UpdateRequest) -> Self {
+        Self {
+            update_req,
+            since: None,
+            to: None,
+            unreleased: false,
+        }
+    }
+
+    pub fn with_since(self, since: String) -> Self {
+        Self {
+            since: Some(since),
+            ..self
+        }
+    }
+
+    pub fn with_to(self, to: String) -> Self {
+        Self {
+            to: Some(to),
+            ..self
+        }
+    }
+
+    pub fn with_unreleased(self, unreleased: bool) -> Self {
+        Self { unreleased, ..self }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratedChangelog {
+    /// Path of the workspace changelog.
+    pub path: Utf8PathBuf,
+    /// The new changelog entry alone, without header and footer.
+    pub entry: String,
+    /// The entire changelog, with the new entry.
+    pub changelog: String,
+}
+
+/// Generate the changelog entry of the commits in the requested range.
+/// Nothing is written to disk.
+#[instrument(skip_all)]
+pub fn generate_changelog(input: &GenerateChangelogRequest) -> anyhow::Result<GeneratedChangelog> {
+    let req = &input.update_req;
+    let manifest_dir = req.local_manifest_dir()?;
+    let repo = Repo::new(manifest_dir)?;
+
+    let to = input.to.as_deref().unwrap_or("HEAD");
+    let since = match &input.since {
+        Some(since) => Some(since.clone()),
+        None => previous_tag(&repo, to),
+    };
+    let range = match &since {
+        Some(since) => format!("{since}..{to}"),
+        None => to.to_string(),
+    };
+    debug!("generating changelog of commits in {range}");
+    let commits = commits_in_range(&repo, &range)?;
+    let commits: Vec<git_cliff_core::commit::Commit> =
+        commits.iter().map(|c| c.to_cliff_commit()).collect();
+
+    let path = manifest_dir.join(CHANGELOG_FILENAME);
+    let old_changelog = if path.exists() {
+        Some(fs_err::read_to_string(&path)?)
+    } else {
+        None
+    };
+
+    let version = if input.unreleased {
+        UNRELEASED.to_string()
+    } else {
+        match version_from_tag(to) {
+            Some(version) => version.to_string(),
+            None => unreleased_version(req, since.as_deref(), &commits)?.to_string(),
+        }
+    };
+    let mut changelog_builder =
+        ChangelogBuilder::new(commits.clone(), version, WORKSPACE_PACKAGE_NAME);
+    let changelog_req = req.changelog_req();
+    if let Some(release_date) = changelog_req.release_date {
+        changelog_builder = changelog_builder.with_release_date(release_date);
+    }
+    if let Some(config) = changelog_req.changelog_config.clone() {
+        changelog_builder = changelog_builder.with_config(config);
+    }
+    if let Some(repo_url) = req.repo_url() {
+        if let Some(since) = &since
+            && !input.unreleased
+            && version_from_tag(to).is_some()
+        {
+            changelog_builder =
+                changelog_builder.with_release_link(repo_url.git_release_link(since, to));
+        }
+        let remote = Remote {
+            owner: repo_url.owner.clone(),
+            repo: repo_url.name.clone(),
+            link: repo_url.full_host(),
+            contributors: get_contributors(&commits),
+        };
+        changelog_builder = changelog_builder
+            .with_remote(remote)
+            .with_pr_link(repo_url.git_pr_link());
+    }
+    let previous_version = since
+        .as_deref()
+        .and_then(version_from_tag)
+        .map(|v| v.to_string())
+        .or_else(|| {
+            old_changelog
+                .as_deref()
+                .and_then(|c| changelog_parser::last_version_from_str(c).ok().flatten())
+        });
+    if let Some(previous_version) = previous_version {
+        changelog_builder = changelog_builder.with_previous_version(previous_version);
+    }
+
+    let new_changelog = changelog_builder.build();
+    let changelog = match old_changelog {
+        Some(old_changelog) => new_changelog.prepend(old_changelog)?,
+        None => new_changelog.generate()?,
+    };
+    let entry = new_changelog_entry(changelog_builder).context("can't generate changelog entry")?;
+    Ok(GeneratedChangelog {
+        path,
+        entry: entry.unwrap_or_default(),
+        changelog,
+    })
+}
+
+/// Latest tag before `to`.
+/// If `to` is itself tagged, the tag before it is returned.
+fn previous_tag(repo: &Repo, to: &str) -> Option<String> {
+    let describe = |rev: &str| {
+        repo.git(&["describe", "--tags", "--abbrev=0", rev])
+            .ok()
+            .map(|tag| tag.trim().to_string())
+    };
+    let tag = describe(to)?;
+    let tag_commit = repo
+        .git(&["rev-parse", &format!("{tag}^{{commit}}")])
+        .ok()?;
+    let to_commit = repo.git(&["rev-parse", &format!("{to}^{{commit}}")]).ok()?;
+    if tag_commit.trim() == to_commit.trim() {
+        describe(&format!("{to}^"))
+    } else {
+        Some(tag)
+    }
+}
+
+/// Version of the commits after the tag `since`, not released yet.
+/// If the version in Cargo.toml is already tagged, the next version is computed from the commits.
+fn unreleased_version(
+    req: &UpdateRequest,
+    since: Option<&str>,
+    commits: &[git_cliff_core::commit::Commit],
+) -> anyhow::Result<Version> {
+    let current_version = manifest_version(req)?;
+    let released_version = since.and_then(version_from_tag);
+    if released_version.is_none_or(|released| released < current_version) {
+        // The version in Cargo.toml was bumped but not released yet.
+        return Ok(current_version);
+    }
+    let version_updater = req
+        .get_package_config(WORKSPACE_PACKAGE_NAME)
+        .generic
+        .version_updater();
+    let next_version = match req.bump() {
+        Some(bump) => forced_bump(bump, &current_version)?,
+        None => version_updater.increment(&current_version, commits.iter().map(|c| &c.message)),
+    };
+    Ok(if req.patch_only() {
+        patch_only_version(&current_version, &next_version)
+    } else {
+        next_version
+    })
+}
+
+fn manifest_version(req: &UpdateRequest) -> anyhow::Result<Version> {
+    let local_manifest = LocalManifest::try_new(req.local_manifest())?;
+    local_manifest
+        .get_workspace_version()
+        .or_else(|| local_manifest.get_package_version())
+        .context("could not find version in Cargo.toml")
+}
@@ -0,0 +1,119 @@
+use cargo_metadata::Package;
+
+/// Maximum number of keywords and of categories accepted by crates.io.
+const MAX_KEYWORDS: usize = 5;
+const MAX_CATEGORIES: usize = 5;
+/// Maximum length of a keyword accepted by crates.io.
+const MAX_KEYWORD_LENGTH: usize = 20;
+
+/// Problems of the manifest of `package` that make crates.io reject it.
+/// <https://doc.rust-lang.org/cargo/reference/publishing.html#before-publishing-a-new-crate>
+pub(crate) fn metadata_problems(package: &Package) -> Vec<String> {
+    let mut problems = vec![];
+    if package
+        .description
+        .as_deref()
+        .is_none_or(|d| d.trim().is_empty())
+    {
+        problems.push("missing `description`".to_string());
+    }
+    if package.license.is_none() && package.license_file.is_none() {
+        problems.push("missing `license` or `license-file`".to_string());
+    }
+    if package.repository.is_none() {
+        problems.push("missing `repository`".to_string());
+    }
+
+    if package.keywords.len() > MAX_KEYWORDS {
+        problems.push(format!(
+            "{} keywords, but at most {MAX_KEYWORDS} are allowed",
+            package.keywords.len()
+        ));
+    }
+    for keyword in package.keywords.iter().filter(|k| !is_valid_keyword(k)) {
+        problems.push(format!(
+            "invalid keyword `{keyword}`: keywords must start with a letter, contain only letters, numbers, `_`, `-` or `+`, and have at most {MAX_KEYWORD_LENGTH} characters"
+        ));
+    }
+
+    if package.categories.len() > MAX_CATEGORIES {
+        problems.push(format!(
+            "{} categories, but at most {MAX_CATEGORIES} are allowed",
+            package.categories.len()
+        ));
+    }
+    for category in package.categories.iter().filter(|c| !is_valid_category(c)) {
+        problems.push(format!(
+            "invalid category `{category}`: use a category slug from https://crates.io/category_slugs"
+        ));
+    }
+    problems
+}
+
+fn is_valid_keyword(keyword: &str) -> bool {
+    keyword.len() <= MAX_KEYWORD_LENGTH
+        && keyword.starts_with(|c: char| c.is_ascii_alphabetic())
+        && keyword
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+}
+
+/// Whether `category` looks like a crates.io category slug, e.g. `development-tools::cargo-plugins`.
+fn is_valid_category(category: &str) -> bool {
+    category.split("::").all(|part| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package() -> Package {
+        let mut package: Package = fake_package::FakePackage::new("my_crate").into();
+        package.description = Some("My crate".to_string());
+        package.license = Some("MIT".to_string());
+        package.repository = Some("https://github.com/me/my_crate".to_string());
+        package.keywords = vec!["release".to_string(), "c++".to_string()];
+        package.categories = vec!["development-tools::cargo-plugins".to_string()];
+        package
+    }
+
+    #[test]
+    fn complete_manifest_has_no_problems() {
+        assert!(metadata_problems(&package()).is_empty());
+    }
+
+    #[test]
+    fn missing_and_invalid_fields_are_reported() {
+        let mut package = package();
+        package.description = Some(" ".to_string());
+        package.license = None;
+        package.repository = None;
+        package.keywords = vec!["1password".to_string(), "release".to_string()];
+        package.categories = vec!["Command Line".to_string()];
+        assert_eq!(
+            metadata_problems(&package),
+            [
+                "missing `description`",
+                "missing `license` or `license-file`",
+                "missing `repository`",
+                "invalid keyword `1password`: keywords must start with a letter, contain only letters, numbers, `_`, `-` or `+`, and have at most 20 characters",
+                "invalid category `Command Line`: use a category slug from https://crates.io/category_slugs",
+            ]
+        );
+    }
+
+    #[test]
+    fn too_many_keywords_are_reported() {
+        let mut package = package();
+        package.keywords = ["a", "b", "c", "d", "e", "f"].map(String::from).to_vec();
+        assert_eq!(
+            metadata_problems(&package),
+            ["6 keywords, but at most 5 are allowed"]
+        );
+    }
+}
@@ -1,10 +1,28 @@
+mod backfill;
+mod crate_content;
+mod generate_changelog;
+mod manifest_metadata;
 mod publish;
+mod publish_drafts;
+mod publish_state;
 mod release;
+mod release_plan;
 mod release_pr;
+mod rollback;
+mod set_version;
+mod smoke_test;
 mod trusted_publishing;
 mod update;
+mod verify_tags;
 
+pub use backfill::*;
+pub use generate_changelog::*;
 pub use publish::*;
+pub use publish_drafts::*;
 pub use release::*;
+pub use release_plan::*;
 pub use release_pr::*;
+pub use rollback::*;
+pub use set_version::*;
 pub use update::*;
+pub use verify_tags::*;
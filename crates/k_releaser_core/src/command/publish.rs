@@ -1,22 +1,45 @@
 use std::{collections::BTreeMap, time::Duration};
 
 use anyhow::Context;
-use cargo_metadata::{Metadata, Package, camino::Utf8Path};
+use cargo_metadata::{
+    Metadata, Package,
+    camino::{Utf8Path, Utf8PathBuf},
+};
 use crates_index::{GitIndex, SparseIndex};
+use git_cmd::Repo;
 use secrecy::{ExposeSecret, SecretString};
 use serde::Serialize;
-use tracing::{info, instrument, trace, warn};
+use sha2::{Digest as _, Sha256};
+use tracing::{error, info, instrument, trace, warn};
 use url::Url;
 
 use crate::{
-    Project, Publishable as _,
-    cargo::{CargoIndex, CargoRegistry, CmdOutput, is_published, run_cargo, wait_until_published},
+    GitClient, GitForge, Hooks, Project, Publishable as _, VersionMode,
+    cargo::{
+        CargoIndex, CargoRegistry, CmdOutput, RegistryApi, RegistryKind, is_published,
+        is_published_api, run_cargo, wait_until_published,
+    },
     cargo_hash_kind::{get_hash_kind, try_get_fallback_hash_kind},
-    command::trusted_publishing,
+    command::{
+        crate_content::content_violations,
+        manifest_metadata::metadata_problems,
+        publish_state::PublishState,
+        smoke_test::{SmokeTestStatus, smoke_test},
+        trusted_publishing,
+    },
+    crates_io::{CRATES_IO_API_URL, OwnersStatus, add_owners, versions_count},
+    docs_rs::{DocsRsStatus, docs_url, wait_for_builds},
+    git::commit_checks::{ChecksOutcome, commit_checks_outcome},
+    hooks::{HookEnv, run_hook},
+    kellnr,
+    release_order::should_dep_be_released_before,
 };
 
 use super::release::PublishConfig;
 
+/// Name of crates.io in the Cargo config.
+pub const CRATES_IO_REGISTRY: &str = "crates-io";
+
 #[derive(Debug)]
 pub struct PublishRequest {
     /// Cargo metadata.
@@ -34,6 +57,47 @@ pub struct PublishRequest {
     packages_config: PackagesConfig,
     /// publish timeout
     publish_timeout: Duration,
+    /// Interval between the registry checks while waiting for a package to be published.
+    publish_wait_poll_interval: Duration,
+    /// Don't wait for the published packages to be available in the registry.
+    no_wait: bool,
+    /// Whether packages share the workspace version or are versioned independently.
+    version_mode: VersionMode,
+    /// Branch the packages are released from, available as `{{ branch }}` in the tag templates.
+    branch: Option<String>,
+    /// Template of the workspace git tag with unified versioning. Defaults to `v{{ version }}`.
+    workspace_tag_name: Option<String>,
+    /// Shell commands to run before and after publishing each package.
+    hooks: Hooks,
+    /// Publish only these packages. If empty, all the packages are published.
+    packages: Vec<String>,
+    /// Don't publish these packages.
+    excluded_packages: Vec<String>,
+    /// Url of the web API of the registries, by registry name.
+    /// Used to check if a package is published when the registry index can't be read.
+    registry_api_urls: BTreeMap<String, Url>,
+    /// Kind of the registries, by registry name. Registries not listed are [`RegistryKind::Cargo`].
+    registry_kinds: BTreeMap<String, RegistryKind>,
+    /// Skip the packages published by the interrupted run.
+    resume: bool,
+    /// Directory where the `.crate` files of the packages are copied.
+    package_output_dir: Option<Utf8PathBuf>,
+    /// After publishing, check that each published package can be used as a dependency.
+    smoke_test: bool,
+    /// After publishing to crates.io, wait up to this duration for the docs.rs builds.
+    /// If [`Option::None`], docs.rs isn't checked.
+    docs_rs_timeout: Option<Duration>,
+    /// Forge used to verify the checks of the published commit.
+    /// If [`Option::None`], the checks aren't verified.
+    checks_forge: Option<GitForge>,
+    /// Names of the commit statuses or check runs that must pass on the published commit.
+    /// If empty, all of them must pass.
+    required_checks: Vec<String>,
+    /// Name of the check of the CI job running k-releaser.
+    /// If [`Option::None`], it's read from the environment.
+    ci_job_name: Option<String>,
+    /// Publish even if the checks of the published commit didn't pass.
+    force: bool,
 }
 
 impl PublishRequest {
@@ -46,6 +110,24 @@ impl PublishRequest {
             dry_run: false,
             packages_config: PackagesConfig::default(),
             publish_timeout: minutes_30,
+            publish_wait_poll_interval: Duration::from_secs(2),
+            no_wait: false,
+            version_mode: VersionMode::default(),
+            branch: None,
+            workspace_tag_name: None,
+            hooks: Hooks::default(),
+            packages: vec![],
+            excluded_packages: vec![],
+            registry_api_urls: BTreeMap::new(),
+            registry_kinds: BTreeMap::new(),
+            resume: false,
+            package_output_dir: None,
+            smoke_test: false,
+            docs_rs_timeout: None,
+            checks_forge: None,
+            required_checks: vec![],
+            ci_job_name: None,
+            force: false,
         }
     }
 
@@ -74,6 +156,165 @@ impl PublishRequest {
         self
     }
 
+    pub fn with_publish_wait_poll_interval(mut self, interval: Duration) -> Self {
+        self.publish_wait_poll_interval = interval;
+        self
+    }
+
+    pub fn with_no_wait(mut self, no_wait: bool) -> Self {
+        self.no_wait = no_wait;
+        self
+    }
+
+    pub fn with_version_mode(mut self, version_mode: VersionMode) -> Self {
+        self.version_mode = version_mode;
+        self
+    }
+
+    pub fn with_branch(mut self, branch: String) -> Self {
+        self.branch = Some(branch);
+        self
+    }
+
+    pub fn with_workspace_tag_name(mut self, workspace_tag_name: String) -> Self {
+        self.workspace_tag_name = Some(workspace_tag_name);
+        self
+    }
+
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    pub fn with_packages(mut self, packages: Vec<String>) -> Self {
+        self.packages = packages;
+        self
+    }
+
+    pub fn with_excluded_packages(mut self, excluded_packages: Vec<String>) -> Self {
+        self.excluded_packages = excluded_packages;
+        self
+    }
+
+    /// Set the url of the web API of the `registry`, e.g. `https://crates.io`.
+    /// Use [`CRATES_IO_REGISTRY`] for crates.io.
+    pub fn with_registry_api_url(mut self, registry: impl Into<String>, api_url: Url) -> Self {
+        self.registry_api_urls.insert(registry.into(), api_url);
+        self
+    }
+
+    /// Set the kind of the `registry`.
+    /// [`RegistryKind::Kellnr`] requires the url set with [`Self::with_registry_api_url`].
+    pub fn with_registry_kind(mut self, registry: impl Into<String>, kind: RegistryKind) -> Self {
+        self.registry_kinds.insert(registry.into(), kind);
+        self
+    }
+
+    /// Web API of the `registry` ([`Option::None`] means crates.io).
+    fn registry_api(&self, registry: Option<&str>) -> anyhow::Result<Option<RegistryApi<'_>>> {
+        let registry = registry.unwrap_or(CRATES_IO_REGISTRY);
+        let kind = self
+            .registry_kinds
+            .get(registry)
+            .copied()
+            .unwrap_or_default();
+        match self.registry_api_urls.get(registry) {
+            Some(url) => Ok(Some(RegistryApi { url, kind })),
+            None => {
+                anyhow::ensure!(
+                    kind != RegistryKind::Kellnr,
+                    "the api url of the Kellnr registry `{registry}` is not set"
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Page of `package` in the `registry`, if its web UI is known.
+    fn version_url(&self, registry: &str, package: &Package) -> Option<String> {
+        let (name, version) = (&package.name, &package.version);
+        let api = self.registry_api(Some(registry)).ok().flatten();
+        match api {
+            Some(RegistryApi {
+                url,
+                kind: RegistryKind::Kellnr,
+            }) => Some(format!(
+                "{}/crate?name={name}&version={version}",
+                url.as_str().trim_end_matches('/')
+            )),
+            _ if registry == CRATES_IO_REGISTRY => {
+                let url = api.map_or(CRATES_IO_API_URL, |api| api.url.as_str());
+                Some(format!(
+                    "{}/crates/{name}/{version}",
+                    url.trim_end_matches('/')
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    pub fn with_package_output_dir(mut self, dir: impl Into<Utf8PathBuf>) -> Self {
+        self.package_output_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_smoke_test(mut self, smoke_test: bool) -> Self {
+        self.smoke_test = smoke_test;
+        self
+    }
+
+    pub fn with_docs_rs_timeout(mut self, timeout: Duration) -> Self {
+        self.docs_rs_timeout = Some(timeout);
+        self
+    }
+
+    /// Refuse to publish if the `required_checks` of the current commit didn't pass on `forge`.
+    pub fn with_commit_checks(mut self, forge: GitForge, required_checks: Vec<String>) -> Self {
+        self.checks_forge = Some(forge);
+        self.required_checks = required_checks;
+        self
+    }
+
+    pub fn with_ci_job_name(mut self, ci_job_name: String) -> Self {
+        self.ci_job_name = Some(ci_job_name);
+        self
+    }
+
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Packages selected with [`Self::with_packages`] and [`Self::with_excluded_packages`],
+    /// in the same order as `packages`.
+    fn selected_packages<'a>(&self, packages: &[&'a Package]) -> anyhow::Result<Vec<&'a Package>> {
+        for name in self.packages.iter().chain(&self.excluded_packages) {
+            anyhow::ensure!(
+                packages.iter().any(|p| p.name.as_str() == name),
+                "package `{name}` not found among the publishable packages of the workspace"
+            );
+        }
+        let is_selected = |p: &Package| {
+            (self.packages.is_empty() || self.packages.iter().any(|n| n == p.name.as_str()))
+                && !self.excluded_packages.iter().any(|n| n == p.name.as_str())
+        };
+        let selected = packages
+            .iter()
+            .copied()
+            .filter(|p| is_selected(p))
+            .collect();
+        Ok(selected)
+    }
+
+    fn is_filtered(&self) -> bool {
+        !self.packages.is_empty() || !self.excluded_packages.is_empty()
+    }
+
     /// Set publish config for a specific package.
     pub fn with_package_config(
         mut self,
@@ -84,7 +325,12 @@ impl PublishRequest {
         self
     }
 
-    fn is_publish_enabled(&self, package: &str) -> bool {
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Whether the configuration allows publishing `package`.
+    pub fn is_publish_enabled(&self, package: &str) -> bool {
         let config = self.get_package_config(package);
         config.publish.is_enabled()
     }
@@ -115,14 +361,74 @@ impl PublishRequest {
 
     /// Find the token to use for the given `registry` ([`Option::None`] means crates.io).
     fn find_registry_token(&self, registry: Option<&str>) -> anyhow::Result<Option<SecretString>> {
-        let is_registry_same_as_request = self.registry.as_deref() == registry;
+        let is_registry_same_as_request = cargo_registry(self.registry.as_deref()) == registry;
         let token = is_registry_same_as_request
             .then(|| self.token.clone())
             .flatten()
             // if the registry is not the same as the request or if there's no token in the request,
             // try to find the token in the Cargo credentials file or in the environment variables.
-            .or(cargo_utils::registry_token(self.registry.as_deref())?);
-        Ok(token)
+            .or(cargo_utils::registry_token(registry)?);
+        if token.is_some() {
+            return Ok(token);
+        }
+        // Finally, ask the credential providers configured in the cargo config.
+        let manifest_path = cargo_utils::workspace_manifest(&self.metadata);
+        cargo_utils::registry_token_from_credential_provider(manifest_path.as_std_path(), registry)
+            .context("failed to get registry token from cargo credential provider")
+    }
+
+    /// Registries where `package` is published, as configured with
+    /// [`PublishPackageConfig::with_registries`].
+    fn package_registries(&self, package: &str) -> Vec<String> {
+        let config = self.get_package_config(package);
+        config.registries.into_iter().map(|r| r.name).collect()
+    }
+
+    /// Whether `package` is published to crates.io.
+    fn is_published_to_crates_io(&self, package: &Package) -> bool {
+        let registries = match &self.registry {
+            Some(registry) => vec![registry.clone()],
+            None => {
+                let registries = self.package_registries(&package.name);
+                if registries.is_empty() {
+                    package.publish.clone().unwrap_or_default()
+                } else {
+                    registries
+                }
+            }
+        };
+        registries.is_empty() || registries.iter().any(|r| r == CRATES_IO_REGISTRY)
+    }
+
+    /// Settings used to publish `package` to `registry` ([`Option::None`] means crates.io).
+    fn publish_target<'a>(
+        &'a self,
+        package: &str,
+        registry: Option<&'a str>,
+    ) -> anyhow::Result<PublishTarget<'a>> {
+        let registry = cargo_registry(registry);
+        let registry_name = registry.unwrap_or(CRATES_IO_REGISTRY);
+        let config = self.get_package_config(package);
+        let registry_config = config.registries.iter().find(|r| r.name == registry_name);
+        let env_token = registry_config
+            .and_then(|r| r.token_env.as_deref())
+            .and_then(|env| std::env::var(env).ok())
+            .map(SecretString::from);
+        let token = match env_token {
+            Some(token) => Some(token),
+            None => self.find_registry_token(registry)?,
+        };
+        Ok(PublishTarget {
+            name: registry,
+            api: self.registry_api(registry)?,
+            token,
+            timeout: registry_config
+                .and_then(|r| r.publish_timeout)
+                .unwrap_or(self.publish_timeout),
+            no_verify: registry_config
+                .and_then(|r| r.no_verify)
+                .unwrap_or(config.no_verify),
+        })
     }
 
     /// Checks for inconsistency in the `publish` fields in the workspace metadata and k-releaser config.
@@ -205,6 +511,15 @@ pub struct PublishPackageConfig {
     /// Enable all features when packaging the crate.
     /// If true, pass the `--all-features` flag to `cargo publish`.
     all_features: bool,
+    /// Registries where the package is published, with their own settings.
+    /// If empty, the `publish` field of the package manifest is used.
+    registries: Vec<PublishRegistryConfig>,
+    /// Glob patterns of the files that must not be published.
+    deny_files: Vec<String>,
+    /// Maximum size in bytes of the packaged files, before compression.
+    max_size: Option<u64>,
+    /// Owners added to the crate after its first publication to crates.io.
+    crates_io_owners: Vec<String>,
 }
 
 impl PublishPackageConfig {
@@ -232,19 +547,160 @@ impl PublishPackageConfig {
         self.all_features = all_features;
         self
     }
+
+    pub fn with_registries(mut self, registries: Vec<PublishRegistryConfig>) -> Self {
+        self.registries = registries;
+        self
+    }
+
+    pub fn with_deny_files(mut self, deny_files: Vec<String>) -> Self {
+        self.deny_files = deny_files;
+        self
+    }
+
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn with_crates_io_owners(mut self, owners: Vec<String>) -> Self {
+        self.crates_io_owners = owners;
+        self
+    }
+}
+
+/// Settings of a package for one of the registries where it's published.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishRegistryConfig {
+    /// Name of the registry in the Cargo config.
+    name: String,
+    /// Environment variable containing the token used to publish to the registry.
+    /// If unset or empty, the token is searched as for the other registries.
+    token_env: Option<String>,
+    /// Publish timeout. If unspecified, the timeout of the request is used.
+    publish_timeout: Option<Duration>,
+    /// Whether to pass `--no-verify` to `cargo publish`.
+    /// If unspecified, the `no_verify` setting of the package is used.
+    no_verify: Option<bool>,
+}
+
+impl PublishRegistryConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            token_env: None,
+            publish_timeout: None,
+            no_verify: None,
+        }
+    }
+
+    pub fn with_token_env(mut self, token_env: impl Into<String>) -> Self {
+        self.token_env = Some(token_env.into());
+        self
+    }
+
+    pub fn with_publish_timeout(mut self, timeout: Duration) -> Self {
+        self.publish_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_no_verify(mut self, no_verify: bool) -> Self {
+        self.no_verify = Some(no_verify);
+        self
+    }
+}
+
+/// Registry where a package is published, with the settings of the package for it.
+struct PublishTarget<'a> {
+    /// Name of the registry. [`Option::None`] means crates.io.
+    name: Option<&'a str>,
+    api: Option<RegistryApi<'a>>,
+    token: Option<SecretString>,
+    timeout: Duration,
+    no_verify: bool,
 }
 
 #[derive(Serialize, Default, Debug)]
 pub struct PublishOutput {
+    /// Packages published to at least one registry, or that failed to publish.
     published: Vec<PackagePublish>,
 }
 
+impl PublishOutput {
+    /// Whether publishing failed in any registry.
+    pub fn has_failures(&self) -> bool {
+        self.published.iter().any(|p| p.has_failures())
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct PackagePublish {
     package_name: String,
     version: String,
     /// Git tag name (format: package-vX.Y.Z)
     tag: String,
+    /// Outcome of the publish in each registry of the package.
+    registries: Vec<RegistryPublish>,
+    /// SHA-256 checksum of the uploaded `.crate` file, in hex.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+    /// Page where docs.rs publishes the documentation, if the package is on crates.io.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docs_rs_url: Option<String>,
+    /// Outcome of the smoke test, if it ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    smoke_test: Option<SmokeTestStatus>,
+    /// Outcome of the docs.rs build, if it was checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docs_rs: Option<DocsRsStatus>,
+    /// Outcome of adding the crates.io owners, if the package was published for the first time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crates_io_owners: Option<OwnersStatus>,
+}
+
+impl PackagePublish {
+    fn has_failures(&self) -> bool {
+        self.registries
+            .iter()
+            .any(|r| matches!(r.status, RegistryPublishStatus::Failed { .. }))
+            || matches!(self.smoke_test, Some(SmokeTestStatus::Failed { .. }))
+            || self.docs_rs == Some(DocsRsStatus::Failed)
+            || matches!(self.crates_io_owners, Some(OwnersStatus::Failed { .. }))
+    }
+
+    /// Whether the package was published to crates.io by this run.
+    fn is_published_to_crates_io(&self) -> bool {
+        self.registries.iter().any(|r| {
+            r.registry == CRATES_IO_REGISTRY && matches!(r.status, RegistryPublishStatus::Published)
+        })
+    }
+
+    /// First registry where the package was published by this run.
+    fn published_registry(&self) -> Option<&str> {
+        self.registries
+            .iter()
+            .find(|r| matches!(r.status, RegistryPublishStatus::Published))
+            .map(|r| r.registry.as_str())
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct RegistryPublish {
+    /// Name of the registry in the Cargo config.
+    registry: String,
+    /// Page of the published version in the registry, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(flatten)]
+    status: RegistryPublishStatus,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RegistryPublishStatus {
+    Published,
+    AlreadyPublished,
+    Failed { error: String },
 }
 
 #[derive(Serialize, Debug)]
@@ -256,6 +712,9 @@ pub struct PublishOrderOutput {
 pub struct PackageOrderInfo {
     name: String,
     path: String,
+    /// Packages of the publish order that must be published before this one,
+    /// because this package depends on them.
+    dependencies: Vec<String>,
 }
 
 impl PublishOrderOutput {
@@ -267,6 +726,50 @@ impl PublishOrderOutput {
         output.push_str(&format!("\nTotal: {} packages", self.publish_order.len()));
         output
     }
+
+    /// Dependency graph in the DOT format of Graphviz.
+    /// Edges go from a dependency to the packages published after it.
+    pub fn dot(&self) -> String {
+        let mut output = String::from("digraph publish_order {\n    rankdir=LR;\n");
+        for pkg in &self.publish_order {
+            output.push_str(&format!("    \"{}\";\n", pkg.name));
+        }
+        for pkg in &self.publish_order {
+            for dependency in &pkg.dependencies {
+                output.push_str(&format!("    \"{dependency}\" -> \"{}\";\n", pkg.name));
+            }
+        }
+        output.push('}');
+        output
+    }
+
+    /// Dependency graph as a Mermaid flowchart.
+    /// Edges go from a dependency to the packages published after it.
+    pub fn mermaid(&self) -> String {
+        // Package names can contain `-`, which Mermaid doesn't accept in node ids.
+        let node_id = |name: &str| {
+            let idx = self
+                .publish_order
+                .iter()
+                .position(|pkg| pkg.name == name)
+                .unwrap_or_default();
+            format!("p{idx}")
+        };
+        let mut output = String::from("flowchart LR\n");
+        for pkg in &self.publish_order {
+            output.push_str(&format!("    {}[\"{}\"]\n", node_id(&pkg.name), pkg.name));
+        }
+        for pkg in &self.publish_order {
+            for dependency in &pkg.dependencies {
+                output.push_str(&format!(
+                    "    {} --> {}\n",
+                    node_id(dependency),
+                    node_id(&pkg.name)
+                ));
+            }
+        }
+        output
+    }
 }
 
 /// Print the order packages would be published in.
@@ -280,7 +783,7 @@ pub fn print_publish_order(input: &PublishRequest) -> anyhow::Result<PublishOrde
         &input.metadata,
     )?;
 
-    let packages = project.publishable_packages();
+    let packages = input.selected_packages(&project.publishable_packages())?;
 
     if packages.is_empty() {
         anyhow::bail!("No publishable packages found in workspace");
@@ -289,7 +792,7 @@ pub fn print_publish_order(input: &PublishRequest) -> anyhow::Result<PublishOrde
     let workspace_root = &input.metadata.workspace_root;
     let mut order_info = Vec::new();
 
-    for package in packages {
+    for package in &packages {
         let relative_path = package
             .manifest_path
             .parent()
@@ -297,9 +800,20 @@ pub fn print_publish_order(input: &PublishRequest) -> anyhow::Result<PublishOrde
             .map(|p| p.to_string())
             .unwrap_or_else(|| ".".to_string());
 
+        let dependencies = packages
+            .iter()
+            .filter(|p| {
+                package
+                    .dependencies
+                    .iter()
+                    .any(|d| d.name == *p.name && should_dep_be_released_before(d, package))
+            })
+            .map(|p| p.name.to_string())
+            .collect();
         order_info.push(PackageOrderInfo {
             name: package.name.to_string(),
             path: relative_path,
+            dependencies,
         });
     }
 
@@ -308,6 +822,45 @@ pub fn print_publish_order(input: &PublishRequest) -> anyhow::Result<PublishOrde
     })
 }
 
+/// Make sure the required checks of the commit being published passed,
+/// so that crates are never uploaded from a red commit.
+async fn check_commit_status(input: &PublishRequest) -> anyhow::Result<()> {
+    let Some(forge) = &input.checks_forge else {
+        return Ok(());
+    };
+    let repo = Repo::new(&input.metadata.workspace_root)?;
+    let sha = repo.current_commit_hash()?;
+    let git_client = GitClient::new(forge.clone())?;
+    let outcome = commit_checks_outcome(
+        &git_client,
+        &sha,
+        &input.required_checks,
+        input.ci_job_name.as_deref(),
+    )
+    .await?;
+    let problem = match outcome {
+        ChecksOutcome::Passed => {
+            info!("checks of commit {sha} passed");
+            return Ok(());
+        }
+        ChecksOutcome::Failed(failed) => {
+            format!("checks of commit {sha} failed: {}", failed.join(", "))
+        }
+        ChecksOutcome::Pending(pending) => {
+            format!(
+                "checks of commit {sha} didn't finish: {}",
+                pending.join(", ")
+            )
+        }
+    };
+    if input.force {
+        warn!("{problem}. Publishing anyway because of --force");
+        Ok(())
+    } else {
+        anyhow::bail!("{problem}. Refusing to publish. Use --force to publish anyway")
+    }
+}
+
 /// Publish packages to cargo registry in dependency order.
 #[instrument(skip(input))]
 pub async fn publish(input: &PublishRequest) -> anyhow::Result<Option<PublishOutput>> {
@@ -318,10 +871,14 @@ pub async fn publish(input: &PublishRequest) -> anyhow::Result<Option<PublishOut
         None,
         &overrides,
         &input.metadata,
-    )?;
+    )?
+    .with_version_mode(input.version_mode)
+    .with_branch(input.branch.clone())
+    .with_workspace_tag_name(input.workspace_tag_name.clone());
 
     // Packages are already ordered by release order (dependencies first).
-    let packages = project.publishable_packages();
+    let all_packages = project.publishable_packages();
+    let packages = input.selected_packages(&all_packages)?;
     if packages.is_empty() {
         info!("nothing to publish");
         return Ok(None);
@@ -329,21 +886,80 @@ pub async fn publish(input: &PublishRequest) -> anyhow::Result<Option<PublishOut
 
     let mut package_publishes: Vec<PackagePublish> = vec![];
     let hash_kind = get_hash_kind()?;
+    if input.is_filtered() {
+        check_excluded_dependencies(input, &all_packages, &packages, &hash_kind).await?;
+    }
+    let state_path = PublishState::path(&input.metadata.target_directory);
+    let mut state = if input.resume {
+        PublishState::load(&state_path)?
+    } else {
+        PublishState::default()
+    };
+    if !input.dry_run {
+        check_commit_status(input).await?;
+    }
+    if !input.resume {
+        // Make sure every package can be published before uploading any of them,
+        // so that the workspace is never left half-published.
+        let unpublished = unpublished_packages(input, &packages, &hash_kind).await?;
+        check_manifest_metadata(input, &unpublished)?;
+        check_crate_content(input, &unpublished)?;
+        if !input.dry_run {
+            preflight(input, &unpublished)?;
+        }
+    }
     // The same trusted publishing token can be used for all packages.
     let mut trusted_publishing_client: Option<trusted_publishing::TrustedPublisher> = None;
 
     for package in packages {
-        if let Some(pkg_publish) = publish_package_if_needed(
+        let version = package.version.to_string();
+        if state.is_published(package.name.as_str(), &version) {
+            info!(
+                "{} {version}: published by the interrupted run",
+                package.name
+            );
+            continue;
+        }
+        let pkg_publish = publish_package_if_needed(
             input,
             &project,
             package,
             &hash_kind,
             &mut trusted_publishing_client,
         )
-        .await?
-        {
+        .await?;
+        if let Some(pkg_publish) = pkg_publish {
+            let has_failures = pkg_publish.has_failures();
             package_publishes.push(pkg_publish);
+            if has_failures {
+                // The next packages might depend on this one.
+                warn!(
+                    "{} {version}: publishing failed in some registries, the next packages are not published",
+                    package.name
+                );
+                break;
+            }
         }
+        if !input.dry_run {
+            state
+                .add(package.name.as_str(), &version, &state_path)
+                .context("can't save publish state")?;
+        }
+    }
+    let has_failures = package_publishes.iter().any(|p| p.has_failures());
+    if !input.dry_run && !has_failures {
+        PublishState::remove(&state_path)?;
+    }
+    if !input.dry_run {
+        add_crates_io_owners(input, &mut package_publishes).await;
+    }
+    if input.smoke_test && !input.dry_run {
+        run_smoke_tests(input, &mut package_publishes);
+    }
+    if let Some(timeout) = input.docs_rs_timeout
+        && !input.dry_run
+    {
+        check_docs_rs_builds(timeout, &mut package_publishes).await;
     }
 
     if let Some(tp) = trusted_publishing_client.as_ref()
@@ -365,12 +981,12 @@ async fn publish_package_if_needed(
     hash_kind: &crates_index::HashKind,
     trusted_publishing_client: &mut Option<trusted_publishing::TrustedPublisher>,
 ) -> anyhow::Result<Option<PackagePublish>> {
-    let git_tag = project.git_tag(&package.version.to_string())?;
+    let git_tag = project.git_tag(&package.name, &package.version.to_string())?;
 
-    let registry_indexes = registry_indexes(package, input.registry.clone(), hash_kind)
+    let registry_indexes = package_registry_indexes(input, package, hash_kind)
         .context("can't determine registry indexes")?;
 
-    let mut package_was_published = false;
+    let mut registries = vec![];
 
     for CargoRegistry {
         name,
@@ -378,9 +994,12 @@ async fn publish_package_if_needed(
         fallback_index,
     } in registry_indexes
     {
-        let token = input.find_registry_token(name.as_deref())?;
+        let target = input.publish_target(&package.name, name.as_deref())?;
+        let registry = name
+            .clone()
+            .unwrap_or_else(|| CRATES_IO_REGISTRY.to_string());
         let (pkg_is_published, mut index) =
-            is_package_published(input, package, primary_index, fallback_index, &token)
+            is_package_published(package, primary_index, fallback_index, &target)
                 .await
                 .with_context(|| {
                     format!("can't determine if package {} is published", package.name)
@@ -388,47 +1007,343 @@ async fn publish_package_if_needed(
 
         if pkg_is_published {
             info!("{} {}: already published", package.name, package.version);
+            registries.push(RegistryPublish {
+                url: input.version_url(&registry, package),
+                registry,
+                status: RegistryPublishStatus::AlreadyPublished,
+            });
             continue;
         }
 
-        let is_crates_io = name.is_none();
         let package_was_published_at_index = publish_package_to_registry(
             &mut index,
+            &target,
             input,
             package,
-            &token,
-            is_crates_io,
             trusted_publishing_client,
         )
         .await
-        .context("failed to publish package")?;
-
-        if package_was_published_at_index {
-            package_was_published = true;
-        }
+        .context("failed to publish package");
+
+        // Keep publishing to the other registries, so that the output reports
+        // the outcome of each of them.
+        let (status, url) = match package_was_published_at_index {
+            Ok(true) => (
+                RegistryPublishStatus::Published,
+                input.version_url(&registry, package),
+            ),
+            Ok(false) => continue,
+            Err(e) => {
+                error!("{} {} in {registry}: {e:?}", package.name, package.version);
+                let status = RegistryPublishStatus::Failed {
+                    error: format!("{e:#}"),
+                };
+                (status, None)
+            }
+        };
+        registries.push(RegistryPublish {
+            registry,
+            url,
+            status,
+        });
     }
 
-    let package_publish = package_was_published.then_some(PackagePublish {
+    let package_was_published_or_failed = registries
+        .iter()
+        .any(|r| !matches!(r.status, RegistryPublishStatus::AlreadyPublished));
+    let is_published = registries
+        .iter()
+        .any(|r| matches!(r.status, RegistryPublishStatus::Published));
+    let checksum = if is_published {
+        crate_checksum(input, package)
+            .inspect_err(|e| warn!("{}: can't compute the checksum: {e:?}", package.name))
+            .ok()
+    } else {
+        None
+    };
+    let is_on_crates_io = registries.iter().any(|r| {
+        r.registry == CRATES_IO_REGISTRY
+            && !matches!(r.status, RegistryPublishStatus::Failed { .. })
+    });
+    let docs_rs_url =
+        is_on_crates_io.then(|| docs_url(&package.name, &package.version.to_string()));
+    let package_publish = package_was_published_or_failed.then_some(PackagePublish {
         package_name: package.name.to_string(),
         version: package.version.to_string(),
         tag: git_tag,
+        registries,
+        checksum,
+        docs_rs_url,
+        smoke_test: None,
+        docs_rs: None,
+        crates_io_owners: None,
     });
     Ok(package_publish)
 }
 
+/// Fail if a selected package depends on a package that isn't selected
+/// and isn't published yet, because the selected package couldn't be published.
+async fn check_excluded_dependencies(
+    input: &PublishRequest,
+    all_packages: &[&Package],
+    selected: &[&Package],
+    hash_kind: &crates_index::HashKind,
+) -> anyhow::Result<()> {
+    let excluded_dependencies: Vec<&Package> = all_packages
+        .iter()
+        .copied()
+        .filter(|p| !selected.iter().any(|s| s.name == p.name))
+        .filter(|p| {
+            selected.iter().any(|s| {
+                s.dependencies
+                    .iter()
+                    .any(|d| d.name == *p.name && should_dep_be_released_before(d, s))
+            })
+        })
+        .collect();
+    let unpublished = unpublished_packages(input, &excluded_dependencies, hash_kind).await?;
+    if let Some(dependency) = unpublished.first() {
+        anyhow::bail!(
+            "{} {} is excluded from publishing, but it isn't published yet and the selected packages depend on it",
+            dependency.name,
+            dependency.version
+        );
+    }
+    Ok(())
+}
+
+/// Packages with publishing enabled that aren't published in at least one of their registries.
+async fn unpublished_packages<'a>(
+    input: &PublishRequest,
+    packages: &[&'a Package],
+    hash_kind: &crates_index::HashKind,
+) -> anyhow::Result<Vec<&'a Package>> {
+    let mut unpublished = vec![];
+    for &package in packages {
+        if !input.is_publish_enabled(&package.name) {
+            continue;
+        }
+        let registry_indexes = package_registry_indexes(input, package, hash_kind)
+            .context("can't determine registry indexes")?;
+        for CargoRegistry {
+            name,
+            index,
+            fallback_index,
+        } in registry_indexes
+        {
+            let target = input.publish_target(&package.name, name.as_deref())?;
+            let (pkg_is_published, _) =
+                is_package_published(package, index, fallback_index, &target)
+                    .await
+                    .with_context(|| {
+                        format!("can't determine if package {} is published", package.name)
+                    })?;
+            if !pkg_is_published {
+                unpublished.push(package);
+                break;
+            }
+        }
+    }
+    Ok(unpublished)
+}
+
+/// Run `cargo publish --dry-run` for all the packages at once, so that they are packaged
+/// and verified against each other before anything is uploaded.
+/// Fails if any package can't be published.
+fn preflight(input: &PublishRequest, packages: &[&Package]) -> anyhow::Result<()> {
+    if packages.is_empty() {
+        return Ok(());
+    }
+    let workspace_root = &input.metadata.workspace_root;
+    let output = run_cargo_publish_dry_run(input, packages, workspace_root)
+        .context("failed to run cargo publish --dry-run")?;
+    if output.status.success() && !output.stderr.contains("error:") {
+        info!("preflight passed for {} packages", packages.len());
+        return Ok(());
+    }
+    if output.stderr.contains("cannot be used multiple times") {
+        warn!(
+            "skipping publish preflight: checking multiple packages at once requires cargo 1.90 or newer"
+        );
+        return Ok(());
+    }
+    anyhow::bail!(
+        "publish preflight failed, no package was published: {}",
+        output.stderr
+    )
+}
+
+/// Add the configured owners to the packages published to crates.io for the first time,
+/// recording the outcome in `package_publishes`.
+async fn add_crates_io_owners(input: &PublishRequest, package_publishes: &mut [PackagePublish]) {
+    for package_publish in package_publishes {
+        let owners = input
+            .get_package_config(&package_publish.package_name)
+            .crates_io_owners;
+        if owners.is_empty() || !package_publish.is_published_to_crates_io() {
+            continue;
+        }
+        match add_owners_after_first_publish(input, &package_publish.package_name, &owners).await {
+            Ok(false) => {}
+            Ok(true) => {
+                info!(
+                    "{}: added crates.io owners {owners:?}",
+                    package_publish.package_name
+                );
+                package_publish.crates_io_owners = Some(OwnersStatus::Added { owners });
+            }
+            Err(e) => {
+                error!(
+                    "{}: can't add crates.io owners: {e:?}",
+                    package_publish.package_name
+                );
+                package_publish.crates_io_owners = Some(OwnersStatus::Failed {
+                    error: format!("{e:#}"),
+                });
+            }
+        }
+    }
+}
+
+/// Add `owners` to the crate `name` if this is its first version.
+/// Returns whether the owners were added.
+async fn add_owners_after_first_publish(
+    input: &PublishRequest,
+    name: &str,
+    owners: &[String],
+) -> anyhow::Result<bool> {
+    let api_url = input
+        .registry_api_urls
+        .get(CRATES_IO_REGISTRY)
+        .map_or(CRATES_IO_API_URL, |url| url.as_str());
+    if versions_count(api_url, name, input.publish_timeout).await? != 1 {
+        return Ok(false);
+    }
+    let token = input.publish_target(name, None)?.token.context(
+        "no crates.io token found. Trusted publishing tokens can't manage the crate owners",
+    )?;
+    add_owners(api_url, name, owners, input.publish_timeout, &token).await?;
+    Ok(true)
+}
+
+/// Check that the published packages can be used as dependencies,
+/// recording the outcome in `package_publishes`.
+fn run_smoke_tests(input: &PublishRequest, package_publishes: &mut [PackagePublish]) {
+    if input.no_wait {
+        warn!("skipping smoke tests: packages might not be available in the registry yet");
+        return;
+    }
+    for package_publish in package_publishes {
+        let Some(registry) = package_publish.published_registry() else {
+            continue;
+        };
+        let status = smoke_test(
+            &input.metadata.workspace_root,
+            &package_publish.package_name,
+            &package_publish.version,
+            registry,
+        );
+        if let SmokeTestStatus::Failed { error } = &status {
+            error!(
+                "{} {}: smoke test failed: {error}",
+                package_publish.package_name, package_publish.version
+            );
+        }
+        package_publish.smoke_test = Some(status);
+    }
+}
+
+/// Wait for the docs.rs builds of the packages published to crates.io,
+/// recording the outcome in `package_publishes`.
+async fn check_docs_rs_builds(timeout: Duration, package_publishes: &mut [PackagePublish]) {
+    let mut published: Vec<&mut PackagePublish> = package_publishes
+        .iter_mut()
+        .filter(|p| p.is_published_to_crates_io())
+        .collect();
+    if published.is_empty() {
+        return;
+    }
+    let packages: Vec<(&str, &str)> = published
+        .iter()
+        .map(|p| (p.package_name.as_str(), p.version.as_str()))
+        .collect();
+    let statuses = wait_for_builds(&packages, timeout).await;
+    for (package_publish, status) in published.iter_mut().zip(statuses) {
+        package_publish.docs_rs = Some(status);
+    }
+}
+
+/// Fail if the manifest of a package published to crates.io misses required metadata
+/// or has keywords or categories that crates.io rejects, reporting the problems of all the packages.
+fn check_manifest_metadata(input: &PublishRequest, packages: &[&Package]) -> anyhow::Result<()> {
+    let report: Vec<String> = packages
+        .iter()
+        .filter(|p| input.is_published_to_crates_io(p))
+        .flat_map(|p| {
+            metadata_problems(p)
+                .into_iter()
+                .map(move |problem| format!("- {}: {problem}", p.name))
+        })
+        .collect();
+    anyhow::ensure!(
+        report.is_empty(),
+        "invalid package metadata, no package was published:\n{}",
+        report.join("\n")
+    );
+    Ok(())
+}
+
+/// Fail if the files of a package match its denied globs or exceed its maximum size,
+/// reporting the violations of all the packages.
+fn check_crate_content(input: &PublishRequest, packages: &[&Package]) -> anyhow::Result<()> {
+    let mut report = vec![];
+    for package in packages {
+        let config = input.get_package_config(&package.name);
+        if config.deny_files.is_empty() && config.max_size.is_none() {
+            continue;
+        }
+        let package_dir = package
+            .manifest_path
+            .parent()
+            .context("cannot find package directory")?;
+        let files = crate::get_cargo_package_files(package_dir)
+            .with_context(|| format!("cannot list the files of package {}", package.name))?;
+        let violations =
+            content_violations(package_dir, &files, &config.deny_files, config.max_size)?;
+        report.extend(
+            violations
+                .iter()
+                .map(|v| format!("- {}: {v}", package.name)),
+        );
+    }
+    anyhow::ensure!(
+        report.is_empty(),
+        "crate content policy violated, no package was published:\n{}",
+        report.join("\n")
+    );
+    Ok(())
+}
+
 /// Check if `package` is published in the primary index.
 /// If the check fails, check the fallback index if it exists.
 ///
 /// Returns whether the package is published and the index used for the check.
 async fn is_package_published(
-    input: &PublishRequest,
     package: &Package,
     mut primary_index: CargoIndex,
     fallback_index: Option<CargoIndex>,
-    token: &Option<SecretString>,
+    target: &PublishTarget<'_>,
 ) -> anyhow::Result<(bool, CargoIndex)> {
-    let is_published_in_primary =
-        is_published(&mut primary_index, package, input.publish_timeout, token).await;
+    let (api, timeout, token) = (target.api, target.timeout, &target.token);
+    // Kellnr registries are checked with their API only.
+    if let Some(api) = api
+        && api.kind == RegistryKind::Kellnr
+    {
+        let is_published_in_api =
+            is_published_api(api, &primary_index, package, timeout, token).await?;
+        return Ok((is_published_in_api, primary_index));
+    }
+    let is_published_in_primary = is_published(&mut primary_index, package, timeout, token).await;
 
     // If a fallback index is defined.
     if let Some(mut fallback_index) = fallback_index {
@@ -440,22 +1355,31 @@ async fn is_package_published(
                 package.name
             );
             let is_published_in_fallback =
-                is_published(&mut fallback_index, package, input.publish_timeout, token).await;
+                is_published(&mut fallback_index, package, timeout, token).await;
             if let Ok(fallback_is_published) = is_published_in_fallback {
                 return Ok((fallback_is_published, fallback_index));
             }
         };
     };
+    // If no index can be read, attempt to use the registry API.
+    if let (Err(e), Some(api)) = (&is_published_in_primary, api) {
+        warn!(
+            "Error checking index for package {}: {e:?}. Trying registry api.",
+            package.name
+        );
+        let is_published_in_api =
+            is_published_api(api, &primary_index, package, timeout, token).await?;
+        return Ok((is_published_in_api, primary_index));
+    }
     Ok((is_published_in_primary?, primary_index))
 }
 
 /// Return `true` if package was published, `false` otherwise.
 async fn publish_package_to_registry(
     index: &mut CargoIndex,
+    target: &PublishTarget<'_>,
     input: &PublishRequest,
     package: &Package,
-    token: &Option<SecretString>,
-    is_crates_io: bool,
     trusted_publishing_client: &mut Option<trusted_publishing::TrustedPublisher>,
 ) -> anyhow::Result<bool> {
     let workspace_root = &input.metadata.workspace_root;
@@ -466,9 +1390,10 @@ async fn publish_package_to_registry(
         return Ok(false);
     }
 
-    let mut publish_token: Option<SecretString> = token.clone();
+    let mut publish_token: Option<SecretString> = target.token.clone();
     let should_use_trusted_publishing = {
         let is_github_actions = std::env::var("GITHUB_ACTIONS").is_ok();
+        let is_crates_io = target.name.is_none();
         publish_token.is_none()
             && input.token.is_none()
             && is_crates_io
@@ -492,8 +1417,29 @@ async fn publish_package_to_registry(
         }
     }
 
+    if let Some(api) = target.api
+        && api.kind == RegistryKind::Kellnr
+        && !input.dry_run
+    {
+        kellnr::validate_token(api.url, package, target.timeout, &publish_token).await?;
+    }
+
+    let hook_env = HookEnv {
+        package: &package.name,
+        next_version: package.version.to_string(),
+        tag: None,
+    };
+    if !input.dry_run {
+        run_hook(
+            "pre_publish",
+            input.hooks.pre_publish.as_deref(),
+            workspace_root,
+            &hook_env,
+        )?;
+    }
+
     // Run `cargo publish`. Note that `--dry-run` is added if `input.dry_run` is true.
-    let output = run_cargo_publish(package, input, workspace_root, &publish_token)
+    let output = run_cargo_publish(package, input, target, workspace_root, &publish_token)
         .context("failed to run cargo publish")?;
 
     if !output.status.success()
@@ -515,6 +1461,17 @@ async fn publish_package_to_registry(
         }
     }
 
+    // `cargo publish` already uploaded the package, so failing to copy it
+    // mustn't report the publish as failed.
+    if let Some(dir) = &input.package_output_dir
+        && let Err(e) = export_crate_file(input, package, dir)
+    {
+        warn!(
+            "{} {}: can't export the package file: {e:#}",
+            package.name, package.version
+        );
+    }
+
     if input.dry_run {
         info!(
             "{} {}: dry run - skipping cargo registry upload",
@@ -522,16 +1479,53 @@ async fn publish_package_to_registry(
         );
         Ok(false)
     } else {
-        wait_until_published(index, package, input.publish_timeout, token).await?;
-        info!("published {} {}", package.name, package.version);
+        if input.no_wait {
+            info!(
+                "uploaded {} {}: not waiting for the registry to index it",
+                package.name, package.version
+            );
+        } else {
+            wait_until_published(
+                index,
+                package,
+                target.api,
+                target.timeout,
+                input.publish_wait_poll_interval,
+                &target.token,
+            )
+            .await?;
+            info!("published {} {}", package.name, package.version);
+        }
+        run_hook(
+            "post_publish",
+            input.hooks.post_publish.as_deref(),
+            workspace_root,
+            &hook_env,
+        )?;
         Ok(true)
     }
 }
 
+/// Get the indexes where the package should be published.
+/// The registry of the request takes precedence over the registries of the package config,
+/// which take precedence over the `publish` field of the package manifest.
+fn package_registry_indexes(
+    input: &PublishRequest,
+    package: &Package,
+    hash_kind: &crates_index::HashKind,
+) -> anyhow::Result<Vec<CargoRegistry>> {
+    let registries = input.package_registries(&package.name);
+    if input.registry.is_some() || registries.is_empty() {
+        registry_indexes(package, input.registry.clone(), hash_kind)
+    } else {
+        registries_indexes(package, registries, hash_kind)
+    }
+}
+
 /// Get the indexes where the package should be published.
 /// If `registry` is specified, it takes precedence over the `publish` field
 /// of the package manifest.
-fn registry_indexes(
+pub(crate) fn registry_indexes(
     package: &Package,
     registry: Option<String>,
     hash_kind: &crates_index::HashKind,
@@ -539,29 +1533,47 @@ fn registry_indexes(
     let registries = registry
         .map(|r| vec![r])
         .unwrap_or_else(|| package.publish.clone().unwrap_or_default());
-    let registry_urls = registries
-        .into_iter()
-        .map(|r| {
-            cargo_utils::registry_url(package.manifest_path.as_ref(), Some(&r))
-                .context("failed to retrieve registry url")
-                .map(|url| (r, url))
-        })
-        .collect::<anyhow::Result<Vec<(String, Url)>>>()?;
+    registries_indexes(package, registries, hash_kind)
+}
+
+/// Name of `registry` in the cargo config.
+/// crates.io is the default registry of cargo, which isn't in the cargo config,
+/// so [`CRATES_IO_REGISTRY`] is mapped to [`Option::None`].
+fn cargo_registry(registry: Option<&str>) -> Option<&str> {
+    registry.filter(|r| *r != CRATES_IO_REGISTRY)
+}
 
-    let mut registry_indexes = registry_urls
-        .into_iter()
-        .map(|(registry, u)| get_cargo_registry(hash_kind, registry, &u))
-        .collect::<anyhow::Result<Vec<CargoRegistry>>>()?;
+/// Get the indexes of the given registries, or of crates.io if `registries` is empty.
+fn registries_indexes(
+    package: &Package,
+    registries: Vec<String>,
+    hash_kind: &crates_index::HashKind,
+) -> anyhow::Result<Vec<CargoRegistry>> {
+    let mut registry_indexes = vec![];
+    for registry in registries {
+        let index = if cargo_registry(Some(&registry)).is_none() {
+            crates_io_registry()?
+        } else {
+            let url = cargo_utils::registry_url(package.manifest_path.as_ref(), Some(&registry))
+                .context("failed to retrieve registry url")?;
+            get_cargo_registry(hash_kind, registry, &url)?
+        };
+        registry_indexes.push(index);
+    }
     if registry_indexes.is_empty() {
-        registry_indexes.push(CargoRegistry {
-            name: None,
-            index: CargoIndex::Git(GitIndex::new_cargo_default()?),
-            fallback_index: None,
-        });
+        registry_indexes.push(crates_io_registry()?);
     }
     Ok(registry_indexes)
 }
 
+fn crates_io_registry() -> anyhow::Result<CargoRegistry> {
+    Ok(CargoRegistry {
+        name: None,
+        index: CargoIndex::Git(GitIndex::new_cargo_default()?),
+        fallback_index: None,
+    })
+}
+
 fn get_cargo_registry(
     hash_kind: &crates_index::HashKind,
     registry: String,
@@ -625,9 +1637,94 @@ fn verify_ci_cargo_registry_token() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Run `cargo publish --dry-run` for the given packages in a single cargo invocation,
+/// so that packages depending on each other are verified with the local versions.
+fn run_cargo_publish_dry_run(
+    input: &PublishRequest,
+    packages: &[&Package],
+    workspace_root: &Utf8Path,
+) -> anyhow::Result<CmdOutput> {
+    let manifest_path = cargo_utils::workspace_manifest(&input.metadata);
+    let mut args = vec!["publish", "--dry-run", "--color", "always"];
+    args.push("--manifest-path");
+    args.push(manifest_path.as_str());
+    for package in packages {
+        args.push("--package");
+        args.push(&package.name);
+    }
+    if let Some(registry) = &input.registry {
+        args.push("--registry");
+        args.push(registry);
+    }
+    if packages.iter().any(|p| input.allow_dirty(&p.name)) {
+        args.push("--allow-dirty");
+    }
+    if packages.iter().all(|p| input.no_verify(&p.name)) {
+        args.push("--no-verify");
+    }
+    // Features are qualified with the package name because multiple packages are selected.
+    let features = packages
+        .iter()
+        .flat_map(|p| {
+            input
+                .features(&p.name)
+                .into_iter()
+                .map(move |feature| format!("{}/{feature}", p.name))
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    if !features.is_empty() {
+        args.push("--features");
+        args.push(&features);
+    }
+    if packages.iter().all(|p| input.all_features(&p.name)) {
+        args.push("--all-features");
+    }
+    run_cargo(workspace_root, &args)
+}
+
+/// `.crate` file packaged by `cargo publish`.
+fn crate_file(input: &PublishRequest, package: &Package) -> Utf8PathBuf {
+    input
+        .metadata
+        .target_directory
+        .join("package")
+        .join(crate_file_name(package))
+}
+
+fn crate_file_name(package: &Package) -> String {
+    format!("{}-{}.crate", package.name, package.version)
+}
+
+/// SHA-256 checksum of the `.crate` file packaged by `cargo publish`, in hex.
+/// It's the checksum that the registry stores in its index.
+fn crate_checksum(input: &PublishRequest, package: &Package) -> anyhow::Result<String> {
+    let content = fs_err::read(crate_file(input, package))?;
+    Ok(format!("{:x}", Sha256::digest(&content)))
+}
+
+/// Copy the `.crate` file packaged by `cargo publish` to `dir`.
+fn export_crate_file(
+    input: &PublishRequest,
+    package: &Package,
+    dir: &Utf8Path,
+) -> anyhow::Result<()> {
+    let crate_file = crate_file(input, package);
+    fs_err::create_dir_all(dir).context("can't create package output directory")?;
+    let destination = dir.join(crate_file_name(package));
+    fs_err::copy(&crate_file, &destination)
+        .with_context(|| format!("can't copy {crate_file} to the package output directory"))?;
+    info!(
+        "{} {}: copied package to {destination}",
+        package.name, package.version
+    );
+    Ok(())
+}
+
 fn run_cargo_publish(
     package: &Package,
     input: &PublishRequest,
+    target: &PublishTarget<'_>,
     workspace_root: &Utf8Path,
     token: &Option<SecretString>,
 ) -> anyhow::Result<CmdOutput> {
@@ -639,7 +1736,7 @@ fn run_cargo_publish(
     // We specify the package name to allow publishing root packages.
     args.push("--package");
     args.push(&package.name);
-    if let Some(registry) = &input.registry {
+    if let Some(registry) = target.name {
         args.push("--registry");
         args.push(registry);
     }
@@ -655,7 +1752,7 @@ fn run_cargo_publish(
     if input.allow_dirty(&package.name) {
         args.push("--allow-dirty");
     }
-    if input.no_verify(&package.name) {
+    if target.no_verify {
         args.push("--no-verify");
     }
     let features = input.features(&package.name).join(",");
@@ -674,3 +1771,72 @@ impl PackagesConfig {
         self.overrides.keys().map(|s| s.as_str()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fake_package::metadata::fake_metadata;
+
+    use super::*;
+
+    #[test]
+    fn crates_io_registry_is_the_default_registry() {
+        let timeout = Duration::from_secs(60);
+        let config = PublishPackageConfig::default().with_registries(vec![
+            PublishRegistryConfig::new(CRATES_IO_REGISTRY).with_publish_timeout(timeout),
+        ]);
+        let request = PublishRequest::new(fake_metadata())
+            .with_registry(CRATES_IO_REGISTRY)
+            .with_token(SecretString::from("my-token".to_string()))
+            .with_package_config("aaa", config);
+        let target = request
+            .publish_target("aaa", request.registry.as_deref())
+            .unwrap();
+        assert_eq!(target.name, None);
+        assert_eq!(target.timeout, timeout);
+        assert_eq!(target.token.unwrap().expose_secret(), "my-token");
+    }
+
+    fn publish_order() -> PublishOrderOutput {
+        let package = |name: &str, dependencies: &[&str]| PackageOrderInfo {
+            name: name.to_string(),
+            path: format!("crates/{name}"),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        };
+        PublishOrderOutput {
+            publish_order: vec![
+                package("utils", &[]),
+                package("my-core", &["utils"]),
+                package("my-cli", &["utils", "my-core"]),
+            ],
+        }
+    }
+
+    #[test]
+    fn publish_order_is_printed_as_dot() {
+        expect_test::expect![[r#"
+            digraph publish_order {
+                rankdir=LR;
+                "utils";
+                "my-core";
+                "my-cli";
+                "utils" -> "my-core";
+                "utils" -> "my-cli";
+                "my-core" -> "my-cli";
+            }"#]]
+        .assert_eq(&publish_order().dot());
+    }
+
+    #[test]
+    fn publish_order_is_printed_as_mermaid() {
+        expect_test::expect![[r#"
+            flowchart LR
+                p0["utils"]
+                p1["my-core"]
+                p2["my-cli"]
+                p0 --> p1
+                p0 --> p2
+                p1 --> p2
+        "#]]
+        .assert_eq(&publish_order().mermaid());
+    }
+}
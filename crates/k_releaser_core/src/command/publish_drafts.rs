@@ -0,0 +1,118 @@
+use std::collections::BTreeSet;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tracing::{info, instrument};
+
+use crate::ForgeRelease;
+
+use super::{release::ReleaseRequest, verify_tags::package_tags};
+
+/// Publish the draft forge releases of the packages,
+/// created with `git_release_draft = true`.
+#[derive(Debug)]
+pub struct PublishDraftsRequest {
+    release_req: ReleaseRequest,
+}
+
+impl PublishDraftsRequest {
+    pub fn new(release_req: ReleaseRequest) -> Self {
+        Self { release_req }
+    }
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct PublishDraftsOutput {
+    pub published: Vec<PublishedDraft>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PublishedDraft {
+    pub tag: String,
+    /// [`Option::None`] in dry run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_release: Option<ForgeRelease>,
+}
+
+/// Publish the draft releases whose tag is the tag of a release of the packages.
+/// Drafts of other tags are left untouched.
+#[instrument(skip_all)]
+pub async fn publish_drafts(input: &PublishDraftsRequest) -> anyhow::Result<PublishDraftsOutput> {
+    let release_req = &input.release_req;
+    let project = release_req.project()?;
+    let git_client = release_req
+        .git_client()?
+        .context("git token not provided: can't read the draft releases")?;
+    let drafts = git_client
+        .draft_releases()
+        .await
+        .context("can't read the draft releases")?;
+    let draft_tags: BTreeSet<String> = drafts.iter().map(|d| d.tag_name.clone()).collect();
+    let package_tags: BTreeSet<String> = package_tags(&project, &draft_tags)?
+        .into_iter()
+        .map(|(tag, _)| tag)
+        .collect();
+
+    let mut published = vec![];
+    for draft in drafts.iter().filter(|d| package_tags.contains(&d.tag_name)) {
+        let git_release = if release_req.is_dry_run() {
+            info!(
+                "{}: dry run: would publish the draft release",
+                draft.tag_name
+            );
+            None
+        } else {
+            let release = git_client.publish_draft_release(draft).await?;
+            info!("{}: published release {}", draft.tag_name, release.html_url);
+            Some(release)
+        };
+        published.push(PublishedDraft {
+            tag: draft.tag_name.clone(),
+            git_release,
+        });
+    }
+    if published.is_empty() {
+        info!("no draft releases to publish");
+    }
+    Ok(PublishDraftsOutput { published })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn published_drafts_are_serialized() {
+        let output = PublishDraftsOutput {
+            published: vec![
+                PublishedDraft {
+                    tag: "v1.0.0".to_string(),
+                    git_release: Some(ForgeRelease {
+                        html_url: "https://github.com/owner/repo/releases/tag/v1.0.0".to_string(),
+                        id: Some(1),
+                    }),
+                },
+                PublishedDraft {
+                    tag: "v0.9.0".to_string(),
+                    git_release: None,
+                },
+            ],
+        };
+        expect_test::expect![[r#"
+            {
+              "published": [
+                {
+                  "tag": "v1.0.0",
+                  "git_release": {
+                    "html_url": "https://github.com/owner/repo/releases/tag/v1.0.0",
+                    "id": 1
+                  }
+                },
+                {
+                  "tag": "v0.9.0"
+                }
+              ]
+            }"#]]
+        .assert_eq(&serde_json::to_string_pretty(&output).unwrap());
+    }
+}
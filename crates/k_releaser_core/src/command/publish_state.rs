@@ -0,0 +1,86 @@
+//! Packages published by the current `publish` run, used to resume it if it's interrupted.
+
+use anyhow::Context as _;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// Name of the state file, stored in the cargo target directory.
+const PUBLISH_STATE_FILE: &str = "k-releaser-publish-state.json";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub(crate) struct PublishState {
+    published: Vec<PublishedPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct PublishedPackage {
+    name: String,
+    version: String,
+}
+
+impl PublishState {
+    pub(crate) fn path(target_directory: &Utf8Path) -> Utf8PathBuf {
+        target_directory.join(PUBLISH_STATE_FILE)
+    }
+
+    /// Load the state of the interrupted run.
+    /// Returns an empty state if no run was interrupted.
+    pub(crate) fn load(path: &Utf8Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let state = fs_err::read_to_string(path)?;
+        serde_json::from_str(&state).with_context(|| format!("can't parse publish state {path}"))
+    }
+
+    pub(crate) fn is_published(&self, name: &str, version: &str) -> bool {
+        self.published
+            .iter()
+            .any(|p| p.name == name && p.version == version)
+    }
+
+    /// Record that the package was published and save the state.
+    pub(crate) fn add(&mut self, name: &str, version: &str, path: &Utf8Path) -> anyhow::Result<()> {
+        self.published.push(PublishedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+        });
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        let state = serde_json::to_string_pretty(self).context("can't serialize publish state")?;
+        fs_err::write(path, state)?;
+        Ok(())
+    }
+
+    /// Delete the state once the run completed.
+    pub(crate) fn remove(path: &Utf8Path) -> anyhow::Result<()> {
+        if path.exists() {
+            fs_err::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fs_utils::Utf8TempDir;
+
+    use super::*;
+
+    #[test]
+    fn published_packages_are_persisted() {
+        let dir = Utf8TempDir::new().unwrap();
+        let path = PublishState::path(dir.path());
+        let mut state = PublishState::load(&path).unwrap();
+        assert!(!state.is_published("my-crate", "1.0.0"));
+
+        state.add("my-crate", "1.0.0", &path).unwrap();
+        let state = PublishState::load(&path).unwrap();
+        assert!(state.is_published("my-crate", "1.0.0"));
+        assert!(!state.is_published("my-crate", "1.1.0"));
+
+        PublishState::remove(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -1,17 +1,33 @@
-use std::collections::{BTreeMap, HashSet};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    time::Duration,
+};
 
 use anyhow::Context;
 use cargo::util::VersionExt;
-use cargo_metadata::{Metadata, Package, camino::Utf8PathBuf, semver::Version};
+use cargo_metadata::{
+    Metadata, Package,
+    camino::{Utf8Path, Utf8PathBuf},
+    semver::Version,
+};
 use git_cmd::Repo;
+use next_version::VersionIncrement;
+use regex::Regex;
 use serde::Serialize;
 use tracing::{debug, info, instrument, trace, warn};
 
 use crate::{
-    CHANGELOG_FILENAME, DEFAULT_BRANCH_PREFIX, GitForge, PackagePath, Project, ReleaseMetadata,
-    ReleaseMetadataBuilder, Remote, changelog_parser,
+    CHANGELOG_FILENAME, DEFAULT_BRANCH_PREFIX, ForgeRelease, GitForge, Hooks, PackagePath, Project,
+    ReleaseMetadata, ReleaseMetadataBuilder, Remote, VersionMode, changelog_parser,
+    git::commit_checks::wait_for_checks,
     git::forge::GitClient,
+    git::lock::{LockMode, with_lock},
+    git::shallow::{FetchDepthStrategy, ensure_full_history},
+    git::token_scopes::check_token_scopes,
+    hooks::{HookEnv, run_hook},
+    pr::strip_release_pr_marker,
     pr_parser::{Pr, prs_from_text},
+    tera::{CHANGELOG_VAR, render_template, tera_context},
 };
 
 #[derive(Debug)]
@@ -33,6 +49,44 @@ pub struct ReleaseRequest {
     packages_config: PackagesConfig,
     /// PR Branch Prefix
     branch_prefix: String,
+    /// Whether packages share the workspace version or are versioned independently.
+    version_mode: VersionMode,
+    /// Branch the packages are released from, available as `{{ branch }}` in the tag templates.
+    branch: Option<String>,
+    /// Template of the workspace git tag with unified versioning. Defaults to `v{{ version }}`.
+    workspace_tag_name: Option<String>,
+    /// Shell commands to run before and after releasing each package.
+    hooks: Hooks,
+    /// Whether to lock the repository while releasing.
+    lock_mode: LockMode,
+    /// Names of the commit statuses or check runs that must pass on the released commit.
+    /// If empty, all of them must pass.
+    required_checks: Vec<String>,
+    /// Name of the check of the CI job running k-releaser.
+    /// If [`Option::None`], it's read from the environment.
+    ci_job_name: Option<String>,
+    /// Wait up to this duration for the checks of the released commit to pass
+    /// before creating tags and releases. If [`Option::None`], the checks are ignored.
+    checks_timeout: Option<Duration>,
+    /// Move floating tags, like `v1` and `v1.4`, to the latest matching release.
+    alias_tags: bool,
+    /// Whether to create signed tags locally.
+    /// If [`Option::None`], tags are signed if `tag.gpgSign` is set in the git config.
+    sign_tags: Option<bool>,
+    /// GPG key id or SSH key used to sign the tags.
+    /// If [`Option::None`], git uses `user.signingkey`.
+    signing_key: Option<String>,
+    /// Close the forge milestone of the released version.
+    milestone: Option<MilestoneConfig>,
+    /// Comment on the issues referenced by the released commits, e.g. with `fixes #123`.
+    comment_issues: bool,
+    /// Close the issues referenced by the released commits.
+    close_issues: bool,
+    /// Remote where tags are fetched from and pushed to.
+    /// If [`Option::None`], the remote of the current branch is used.
+    git_remote: Option<String>,
+    /// What to do if the repository is a shallow clone.
+    fetch_depth_strategy: FetchDepthStrategy,
 }
 
 impl ReleaseRequest {
@@ -45,6 +99,22 @@ impl ReleaseRequest {
             packages_config: PackagesConfig::default(),
             release_always: true,
             branch_prefix: DEFAULT_BRANCH_PREFIX.to_string(),
+            version_mode: VersionMode::default(),
+            branch: None,
+            workspace_tag_name: None,
+            hooks: Hooks::default(),
+            lock_mode: LockMode::default(),
+            required_checks: vec![],
+            ci_job_name: None,
+            checks_timeout: None,
+            alias_tags: false,
+            sign_tags: None,
+            signing_key: None,
+            milestone: None,
+            comment_issues: false,
+            close_issues: false,
+            git_remote: None,
+            fetch_depth_strategy: FetchDepthStrategy::default(),
         }
     }
 
@@ -85,7 +155,94 @@ impl ReleaseRequest {
         self
     }
 
+    pub fn with_version_mode(mut self, version_mode: VersionMode) -> Self {
+        self.version_mode = version_mode;
+        self
+    }
+
+    pub fn with_branch(mut self, branch: String) -> Self {
+        self.branch = Some(branch);
+        self
+    }
+
+    pub fn with_workspace_tag_name(mut self, workspace_tag_name: String) -> Self {
+        self.workspace_tag_name = Some(workspace_tag_name);
+        self
+    }
+
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    pub fn with_lock_mode(mut self, lock_mode: LockMode) -> Self {
+        self.lock_mode = lock_mode;
+        self
+    }
+
+    pub fn with_required_checks(mut self, required_checks: Vec<String>) -> Self {
+        self.required_checks = required_checks;
+        self
+    }
+
+    pub fn with_ci_job_name(mut self, ci_job_name: String) -> Self {
+        self.ci_job_name = Some(ci_job_name);
+        self
+    }
+
+    /// Wait for the checks of the released commit to pass, up to `timeout`.
+    pub fn with_wait_for_checks(mut self, timeout: Duration) -> Self {
+        self.checks_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_alias_tags(mut self, alias_tags: bool) -> Self {
+        self.alias_tags = alias_tags;
+        self
+    }
+
+    /// Always sign the tags if `true`, never sign them if `false`,
+    /// regardless of the `tag.gpgSign` git config.
+    pub fn with_sign_tags(mut self, sign_tags: bool) -> Self {
+        self.sign_tags = Some(sign_tags);
+        self
+    }
+
+    /// GPG key id, or SSH public key or path, used to sign the tags.
+    pub fn with_signing_key(mut self, signing_key: impl Into<String>) -> Self {
+        self.signing_key = Some(signing_key.into());
+        self
+    }
+
+    /// Close the forge milestone of the released version.
+    pub fn with_milestone(mut self, milestone: MilestoneConfig) -> Self {
+        self.milestone = Some(milestone);
+        self
+    }
+
+    /// Comment "Released in ..." on the issues referenced by the released commits.
+    pub fn with_comment_issues(mut self, comment_issues: bool) -> Self {
+        self.comment_issues = comment_issues;
+        self
+    }
+
+    /// Close the issues referenced by the released commits.
+    pub fn with_close_issues(mut self, close_issues: bool) -> Self {
+        self.close_issues = close_issues;
+        self
+    }
+
     /// Set release config for a specific package.
+    pub fn with_git_remote(mut self, git_remote: impl Into<String>) -> Self {
+        self.git_remote = Some(git_remote.into());
+        self
+    }
+
+    pub fn with_fetch_depth_strategy(mut self, fetch_depth_strategy: FetchDepthStrategy) -> Self {
+        self.fetch_depth_strategy = fetch_depth_strategy;
+        self
+    }
+
     pub fn with_package_config(
         mut self,
         package: impl Into<String>,
@@ -121,14 +278,55 @@ impl ReleaseRequest {
     pub fn get_package_config(&self, package: &str) -> ReleaseConfig {
         self.packages_config.get(package)
     }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub(crate) fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Repository of the project, using the configured remote.
+    pub(crate) fn repo(&self) -> anyhow::Result<Repo> {
+        let repo = Repo::new(&self.metadata.workspace_root)?;
+        Ok(match &self.git_remote {
+            Some(remote) => repo.with_remote(remote),
+            None => repo,
+        })
+    }
+
+    pub(crate) fn git_client(&self) -> anyhow::Result<Option<GitClient>> {
+        self.git_release
+            .as_ref()
+            .map(|git_release| GitClient::new(git_release.forge.clone()))
+            .transpose()
+    }
+
+    /// Project containing the packages to release.
+    pub(crate) fn project(&self) -> anyhow::Result<Project> {
+        let overrides = self.packages_config.overridden_packages();
+        let project = Project::new(
+            &self.local_manifest(),
+            None,
+            &overrides,
+            &self.metadata,
+            self,
+        )?
+        .with_version_mode(self.version_mode)
+        .with_branch(self.branch.clone())
+        .with_workspace_tag_name(self.workspace_tag_name.clone());
+        Ok(project)
+    }
 }
 
 impl ReleaseMetadataBuilder for ReleaseRequest {
     fn get_release_metadata(&self, package_name: &str) -> Option<ReleaseMetadata> {
         let config = self.get_package_config(package_name);
-        Some(ReleaseMetadata {
+        config.release.then(|| ReleaseMetadata {
             tag_name_template: config.git_tag.name_template.clone(),
             release_name_template: config.git_release.name_template.clone(),
+            extra_tag_name_templates: config.git_tag.extra_name_templates.clone(),
         })
     }
 }
@@ -184,6 +382,9 @@ pub struct ReleaseConfig {
     /// Whether this package has a changelog that k-releaser updates or not.
     /// Default: `true`.
     changelog_update: bool,
+    /// If `false`, the package isn't released, as if it wasn't part of the workspace.
+    /// Default: `true`.
+    release: bool,
 }
 
 impl ReleaseConfig {
@@ -232,6 +433,11 @@ impl ReleaseConfig {
         self
     }
 
+    pub fn with_release(mut self, release: bool) -> Self {
+        self.release = release;
+        self
+    }
+
     pub fn publish(&self) -> &PublishConfig {
         &self.publish
     }
@@ -239,6 +445,10 @@ impl ReleaseConfig {
     pub fn git_release(&self) -> &GitReleaseConfig {
         &self.git_release
     }
+
+    pub fn git_tag(&self) -> &GitTagConfig {
+        &self.git_tag
+    }
 }
 
 impl Default for ReleaseConfig {
@@ -253,6 +463,7 @@ impl Default for ReleaseConfig {
             all_features: false,
             changelog_path: None,
             changelog_update: true,
+            release: true,
         }
     }
 }
@@ -286,6 +497,17 @@ pub enum ReleaseType {
     Auto,
 }
 
+/// Source of the body of the git release.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseBodySource {
+    /// Render the body template with the changelog of the release.
+    #[default]
+    Changelog,
+    /// Let the forge generate the release notes from the merged PRs.
+    /// Only GitHub supports it: other forges fall back to the changelog.
+    Forge,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GitReleaseConfig {
     enabled: bool,
@@ -294,6 +516,9 @@ pub struct GitReleaseConfig {
     release_type: ReleaseType,
     name_template: Option<String>,
     body_template: Option<String>,
+    body_source: ReleaseBodySource,
+    /// Glob patterns of the files to upload to the release.
+    assets: Vec<String>,
 }
 
 impl Default for GitReleaseConfig {
@@ -311,6 +536,8 @@ impl GitReleaseConfig {
             release_type: ReleaseType::default(),
             name_template: None,
             body_template: None,
+            body_source: ReleaseBodySource::default(),
+            assets: vec![],
         }
     }
 
@@ -343,6 +570,18 @@ impl GitReleaseConfig {
         self
     }
 
+    pub fn set_body_source(mut self, body_source: ReleaseBodySource) -> Self {
+        self.body_source = body_source;
+        self
+    }
+
+    /// Set the glob patterns of the files to upload to the release.
+    /// Patterns are Tera templates relative to the workspace root.
+    pub fn set_assets(mut self, assets: Vec<String>) -> Self {
+        self.assets = assets;
+        self
+    }
+
     pub fn is_pre_release(&self, version: &Version) -> bool {
         match self.release_type {
             ReleaseType::Pre => true,
@@ -352,10 +591,44 @@ impl GitReleaseConfig {
     }
 }
 
+/// Milestone closed when the matching version is released.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MilestoneConfig {
+    /// Tera template of the milestone title. Defaults to `{{ version }}`.
+    title_template: Option<String>,
+    /// Increment giving the version of the next milestone, which receives
+    /// the open issues of the closed one. If [`Option::None`], no milestone is created.
+    next: Option<VersionIncrement>,
+}
+
+impl MilestoneConfig {
+    pub fn set_title_template(mut self, title_template: Option<String>) -> Self {
+        self.title_template = title_template;
+        self
+    }
+
+    pub fn set_next(mut self, next: VersionIncrement) -> Self {
+        self.next = Some(next);
+        self
+    }
+
+    fn title(&self, package: &str, version: &Version) -> anyhow::Result<String> {
+        let template = self.title_template.as_deref().unwrap_or("{{ version }}");
+        let context = tera_context(package, &version.to_string());
+        render_template(template, &context, "milestone_title")
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GitTagConfig {
     enabled: bool,
     name_template: Option<String>,
+    /// If `false`, create lightweight tags instead of annotated ones.
+    annotated: bool,
+    /// Tera template of the message of annotated tags.
+    message_template: Option<String>,
+    /// Tera templates of additional tags created on the released commit.
+    extra_name_templates: Vec<String>,
 }
 
 impl Default for GitTagConfig {
@@ -369,6 +642,9 @@ impl GitTagConfig {
         Self {
             enabled,
             name_template: None,
+            annotated: true,
+            message_template: None,
+            extra_name_templates: vec![],
         }
     }
 
@@ -377,6 +653,21 @@ impl GitTagConfig {
         self
     }
 
+    pub fn set_annotated(mut self, annotated: bool) -> Self {
+        self.annotated = annotated;
+        self
+    }
+
+    pub fn set_message_template(mut self, message_template: Option<String>) -> Self {
+        self.message_template = message_template;
+        self
+    }
+
+    pub fn set_extra_name_templates(mut self, extra_name_templates: Vec<String>) -> Self {
+        self.extra_name_templates = extra_name_templates;
+        self
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
@@ -393,8 +684,25 @@ pub struct Release {
     releases: Vec<PackageRelease>,
 }
 
+impl Release {
+    /// Add the releases of another run, e.g. of another workspace of the repository.
+    pub fn extend(&mut self, other: Self) {
+        self.releases.extend(other.releases);
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct PackageRelease {
+    /// Floating tags, like `v1` and `v1.4`, moved to this release.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    alias_tags: Vec<String>,
+    /// Additional tags created for this release, besides [`PackageRelease::tag`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    extra_tags: Vec<String>,
+    /// Release created on the forge.
+    /// [`Option::None`] if git releases are disabled for the package.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_release: Option<ForgeRelease>,
     package_name: String,
     prs: Vec<Pr>,
     /// Git tag name. It's not guaranteed that k-releaser created the git tag.
@@ -408,24 +716,41 @@ pub struct PackageRelease {
 /// Release the project as it is.
 #[instrument(skip(input))]
 pub async fn release(input: &ReleaseRequest) -> anyhow::Result<Option<Release>> {
-    let overrides = input.packages_config.overridden_packages();
-    let project = Project::new(
-        &input.local_manifest(),
-        None,
-        &overrides,
-        &input.metadata,
-        input,
-    )?;
-    let repo = Repo::new(&input.metadata.workspace_root)?;
+    let project = input.project()?;
+    let repo = input.repo()?;
+    ensure_full_history(&repo, input.fetch_depth_strategy)?;
 
     // Fetch tags from remote to ensure we have the latest tag information
     // This prevents attempting to create duplicate tags
-    if let Err(e) = repo.git(&["fetch", "--tags"]) {
+    if let Err(e) = project.fetch_release_tags(&repo) {
         debug!("Failed to fetch tags (this is ok if there's no remote): {e}");
     }
 
     let git_client = get_git_client(input)?;
-    let should_release = should_release(input, &repo, &git_client).await?;
+    let head = repo.current_commit_hash()?;
+    // A dry run doesn't change the repository, so it can't race with other runs.
+    let lock_mode = if input.dry_run {
+        LockMode::Disabled
+    } else {
+        check_token_scopes(&git_client).await?;
+        input.lock_mode
+    };
+    with_lock(
+        lock_mode,
+        &git_client,
+        &head,
+        release_with_client(input, &project, &repo, &git_client),
+    )
+    .await
+}
+
+async fn release_with_client(
+    input: &ReleaseRequest,
+    project: &Project,
+    repo: &Repo,
+    git_client: &GitClient,
+) -> anyhow::Result<Option<Release>> {
+    let should_release = should_release(input, repo, git_client).await?;
     debug!("should release: {should_release:?}");
 
     if should_release == ShouldRelease::No {
@@ -433,6 +758,23 @@ pub async fn release(input: &ReleaseRequest) -> anyhow::Result<Option<Release>>
         return Ok(None);
     }
 
+    if let Some(timeout) = input.checks_timeout
+        && !input.dry_run
+    {
+        let commit = match &should_release {
+            ShouldRelease::YesWithCommit(commit) => commit.clone(),
+            _ => repo.current_commit_hash()?,
+        };
+        wait_for_checks(
+            git_client,
+            &commit,
+            &input.required_checks,
+            input.ci_job_name.as_deref(),
+            timeout,
+        )
+        .await?;
+    }
+
     let mut checkout_done = false;
     if let ShouldRelease::YesWithCommit(commit) = &should_release {
         match repo.checkout(commit) {
@@ -446,7 +788,7 @@ pub async fn release(input: &ReleaseRequest) -> anyhow::Result<Option<Release>>
     }
 
     // Don't return the error immediately because we want to go back to the previous commit if needed
-    let release = release_packages(input, &project, &repo, &git_client).await;
+    let release = release_packages(input, project, repo, git_client).await;
 
     if let ShouldRelease::YesWithCommit(_) = should_release {
         // Go back to the previous commit so that the user finds
@@ -475,18 +817,32 @@ async fn release_packages(
 
     // Check if all packages have the same version (unified workspace versioning)
     let first_version = &packages[0].version;
-    let is_unified_workspace = packages.iter().all(|p| &p.version == first_version);
+    let is_unified_workspace = !input.version_mode.is_independent()
+        && packages.iter().all(|p| &p.version == first_version);
 
     if is_unified_workspace && packages.len() > 1 {
         // Unified workspace versioning: create ONE release for the workspace
         info!("Detected unified workspace versioning - creating single workspace release");
         release_unified_workspace(input, project, &packages, repo, git_client).await
     } else {
-        // Multi-package versioning: release each package individually
+        // Extra tags rendered from templates without the package name, like `latest`,
+        // are shared by the packages: only the first one released creates them.
+        let mut created_tags = HashSet::new();
+        // Multi-package versioning: release each package individually.
+        // The release PR body is used as a fallback when a package has no changelog file.
+        let release_pr_body = release_pr_body(input, repo, git_client).await?;
         let mut package_releases: Vec<PackageRelease> = vec![];
         for package in packages {
-            if let Some(pkg_release) =
-                release_package_if_needed(input, project, package, repo, git_client).await?
+            if let Some(pkg_release) = release_package_if_needed(
+                input,
+                project,
+                package,
+                repo,
+                git_client,
+                release_pr_body.as_deref(),
+                &mut created_tags,
+            )
+            .await?
             {
                 package_releases.push(pkg_release);
             }
@@ -505,25 +861,50 @@ async fn get_workspace_changelog_entry(
     repo: &Repo,
     git_client: &GitClient,
 ) -> anyhow::Result<String> {
-    // Get the release PR associated with the current commit
+    if let Some(body) = release_pr_body(input, repo, git_client).await? {
+        // Extract changelog from PR body
+        // The PR body contains the changelog generated from git history
+        return Ok(extract_changelog_from_pr_body(&body));
+    }
+
+    warn!("No release PR found or PR has no body. Release will have empty body.");
+    Ok(String::new())
+}
+
+/// Body of the release PR associated with the current commit, if any.
+async fn release_pr_body(
+    input: &ReleaseRequest,
+    repo: &Repo,
+    git_client: &GitClient,
+) -> anyhow::Result<Option<String>> {
     let last_commit = repo.current_commit_hash()?;
     let prs = git_client.associated_prs(&last_commit).await?;
     let release_pr = prs
         .iter()
-        .find(|pr| pr.branch().starts_with(&input.branch_prefix));
+        .find(|pr| pr.looks_like_release_pr(&input.branch_prefix));
 
-    if let Some(pr) = release_pr
-        && let Some(body) = &pr.body
-    {
-        // Extract changelog from PR body
-        // The PR body contains the changelog generated from git history
-        let changelog = extract_changelog_from_pr_body(body);
+    Ok(release_pr.and_then(|pr| {
         debug!("Using changelog from release PR #{}", pr.number);
-        return Ok(changelog);
-    }
+        pr.body.as_deref().map(strip_release_pr_marker)
+    }))
+}
 
-    warn!("No release PR found or PR has no body. Release will have empty body.");
-    Ok(String::new())
+/// Extract the changelog of a single package from the body of a release PR
+/// created with independent versioning.
+/// Each package changelog is in a `<details>` block whose summary contains the package name
+/// surrounded by backticks.
+fn extract_package_changelog_from_pr_body(pr_body: &str, package_name: &str) -> Option<String> {
+    let package_summary = format!("`{package_name}`");
+    pr_body
+        .split("<details>")
+        .skip(1)
+        .filter_map(|block| block.split_once("</details>").map(|(content, _)| content))
+        .find_map(|content| {
+            let (summary, changelog) = content.split_once("</summary>")?;
+            summary
+                .contains(&package_summary)
+                .then(|| changelog.trim().to_string())
+        })
 }
 
 /// Extract changelog content from release PR body.
@@ -555,7 +936,16 @@ async fn release_unified_workspace(
     git_client: &GitClient,
 ) -> anyhow::Result<Option<Release>> {
     let version = &packages[0].version;
-    let git_tag = project.git_tag(&version.to_string())?;
+    let git_tag = project.git_tag(&packages[0].name, &version.to_string())?;
+    // The packages can configure different extra tags: create all of them.
+    let mut extra_git_tags: Vec<String> = vec![];
+    for package in packages {
+        for tag in project.extra_git_tags(&package.name, &version.to_string())? {
+            if tag != git_tag && !extra_git_tags.contains(&tag) {
+                extra_git_tags.push(tag);
+            }
+        }
+    }
 
     // Check if tag already exists
     if repo.tag_exists(&git_tag)? {
@@ -583,14 +973,16 @@ async fn release_unified_workspace(
     let release_info = ReleaseInfo {
         package: packages[0], // Use first package for metadata
         git_tag: &git_tag,
+        extra_git_tags: &extra_git_tags,
         release_name: &release_name,
         changelog: &changelog_entry,
         prs: &prs,
     };
 
-    let was_released = release_package(input, repo, git_client, &release_info).await?;
+    let released =
+        release_package(input, repo, git_client, &release_info, &mut HashSet::new()).await?;
 
-    if was_released {
+    if let Some(released) = released {
         let package_names: Vec<String> = packages.iter().map(|p| p.name.to_string()).collect();
         info!(
             "Released workspace version {} for packages: {}",
@@ -601,6 +993,9 @@ async fn release_unified_workspace(
         // Return a single PackageRelease representing the unified workspace
         Ok(Some(Release {
             releases: vec![PackageRelease {
+                alias_tags: released.alias_tags,
+                extra_tags: released.extra_tags,
+                git_release: released.git_release,
                 package_name: "workspace".to_string(),
                 prs,
                 tag: git_tag,
@@ -618,8 +1013,11 @@ async fn release_package_if_needed(
     package: &Package,
     repo: &Repo,
     git_client: &GitClient,
+    release_pr_body: Option<&str>,
+    created_tags: &mut HashSet<String>,
 ) -> anyhow::Result<Option<PackageRelease>> {
-    let git_tag = project.git_tag(&package.version.to_string())?;
+    let git_tag = project.git_tag(&package.name, &package.version.to_string())?;
+    let extra_git_tags = project.extra_git_tags(&package.name, &package.version.to_string())?;
     let release_name = project.release_name(&package.name, &package.version.to_string())?;
     if repo.tag_exists(&git_tag)? {
         info!(
@@ -629,21 +1027,31 @@ async fn release_package_if_needed(
         return Ok(None);
     }
 
-    let changelog = last_changelog_entry(input, package);
+    let mut changelog = last_changelog_entry(input, package);
+    if changelog.is_empty()
+        && let Some(pr_changelog) = release_pr_body
+            .and_then(|body| extract_package_changelog_from_pr_body(body, &package.name))
+    {
+        changelog = pr_changelog;
+    }
     let prs = prs_from_text(&changelog);
     let release_info = ReleaseInfo {
         package,
         git_tag: &git_tag,
+        extra_git_tags: &extra_git_tags,
         release_name: &release_name,
         changelog: &changelog,
         prs: &prs,
     };
 
-    let package_was_released = release_package(input, repo, git_client, &release_info)
+    let released = release_package(input, repo, git_client, &release_info, created_tags)
         .await
         .context("failed to release package")?;
 
-    let package_release = package_was_released.then_some(PackageRelease {
+    let package_release = released.map(|released| PackageRelease {
+        alias_tags: released.alias_tags,
+        extra_tags: released.extra_tags,
+        git_release: released.git_release,
         package_name: package.name.to_string(),
         version: package.version.clone(),
         tag: git_tag,
@@ -668,7 +1076,7 @@ async fn should_release(
     let prs = git_client.associated_prs(&last_commit).await?;
     let associated_release_pr = prs
         .iter()
-        .find(|pr| pr.branch().starts_with(&input.branch_prefix));
+        .find(|pr| pr.looks_like_release_pr(&input.branch_prefix));
 
     match associated_release_pr {
         Some(pr) => {
@@ -694,6 +1102,11 @@ async fn should_release(
             if input.release_always {
                 Ok(ShouldRelease::Yes)
             } else {
+                // Job tokens can't read the merge requests of the commit.
+                anyhow::ensure!(
+                    !git_client.job_token,
+                    "GitLab CI/CD job tokens (CI_JOB_TOKEN) can't check if the commit is from a release MR: set `release_always = true` or set GITLAB_TOKEN to a project access token with the `api` scope"
+                );
                 info!("skipping release: current commit is not from a release PR");
                 Ok(ShouldRelease::No)
             }
@@ -713,47 +1126,90 @@ fn is_pr_commit_in_original_branch(repo: &Repo, commit: &crate::git::forge::PrCo
 struct ReleaseInfo<'a> {
     package: &'a Package,
     git_tag: &'a str,
+    /// Tags to create in addition to `git_tag`.
+    extra_git_tags: &'a [String],
     release_name: &'a str,
     changelog: &'a str,
     prs: &'a [Pr],
 }
 
-/// Return `true` if package was released, `false` otherwise.
+/// Package released by [`release_package`].
+struct ReleasedPackage {
+    alias_tags: Vec<String>,
+    extra_tags: Vec<String>,
+    git_release: Option<ForgeRelease>,
+}
+
+/// Return [`Option::None`] if the package wasn't released, e.g. because of dry-run.
+/// `created_tags` are the tags created by this run so far. They aren't fetched,
+/// so [`Repo::tag_exists`] doesn't see them.
 async fn release_package(
     input: &ReleaseRequest,
     repo: &Repo,
     git_client: &GitClient,
     release_info: &ReleaseInfo<'_>,
-) -> anyhow::Result<bool> {
+    created_tags: &mut HashSet<String>,
+) -> anyhow::Result<Option<ReleasedPackage>> {
     let should_create_git_tag = input.is_git_tag_enabled(&release_info.package.name);
     let should_create_git_release = input.is_git_release_enabled(&release_info.package.name);
 
     if input.dry_run {
         log_dry_run_info(
+            input,
             release_info,
             should_create_git_tag,
             should_create_git_release,
         );
-        Ok(false)
+        Ok(None)
     } else {
+        let package_name = if input.version_mode.is_independent() {
+            release_info.package.name.as_str()
+        } else {
+            "workspace"
+        };
+        let hook_env = HookEnv {
+            package: package_name,
+            next_version: release_info.package.version.to_string(),
+            tag: Some(release_info.git_tag),
+        };
+        let workspace_root = &input.metadata.workspace_root;
+        run_hook(
+            "pre_release",
+            input.hooks.pre_release.as_deref(),
+            workspace_root,
+            &hook_env,
+        )?;
+        let mut extra_tags = vec![];
+        let message = if should_create_git_tag {
+            tag_message(input, release_info)?
+        } else {
+            None
+        };
+        // GitLab job tokens can't call the tags API: the release creates its tag instead.
+        let release_creates_tag = should_create_git_tag
+            && should_create_git_release
+            && git_client.job_token
+            && !should_sign_tags(input, repo)?;
         if should_create_git_tag {
-            // Use same tag message of cargo-release
-            let message = format!(
-                "chore: Release package {} version {}",
-                release_info.package.name, release_info.package.version
-            );
-            let should_sign_tags = repo
-                .git(&["config", "--default", "false", "--get", "tag.gpgSign"])
-                .map(|s| s.trim() == "true")?;
-            // If tag signing is enabled, create the tag locally instead of using the API
-            if should_sign_tags {
-                repo.tag(release_info.git_tag, &message)?;
-                repo.push(release_info.git_tag)?;
-            } else {
-                let sha = repo.current_commit_hash()?;
-                git_client
-                    .create_tag(release_info.git_tag, &message, &sha)
-                    .await?;
+            if !release_creates_tag {
+                create_git_tag(
+                    input,
+                    repo,
+                    git_client,
+                    release_info.git_tag,
+                    message.as_deref(),
+                )
+                .await?;
+            }
+            created_tags.insert(release_info.git_tag.to_string());
+            for tag in release_info.extra_git_tags {
+                if created_tags.contains(tag) || repo.tag_exists(tag)? {
+                    warn!("tag {tag} already exists - skipping it");
+                    continue;
+                }
+                create_git_tag(input, repo, git_client, tag, message.as_deref()).await?;
+                created_tags.insert(tag.clone());
+                extra_tags.push(tag.clone());
             }
         }
 
@@ -766,6 +1222,7 @@ async fn release_package(
             link: String::new(),
             contributors,
         };
+        let mut git_release = None;
         if should_create_git_release {
             let release_body =
                 release_body(input, release_info.package, release_info.changelog, &remote);
@@ -773,6 +1230,11 @@ async fn release_package(
                 .get_package_config(&release_info.package.name)
                 .git_release;
             let is_pre_release = release_config.is_pre_release(&release_info.package.version);
+            let assets = release_assets(
+                &release_config.assets,
+                release_info.package,
+                &input.metadata.workspace_root,
+            )?;
             let git_release_info = GitReleaseInfo {
                 git_tag: release_info.git_tag.to_string(),
                 release_name: release_info.release_name.to_string(),
@@ -780,20 +1242,358 @@ async fn release_package(
                 draft: release_config.draft,
                 latest: release_config.latest,
                 pre_release: is_pre_release,
+                generate_release_notes: release_config.body_source == ReleaseBodySource::Forge,
+                assets,
+                tag_ref: release_creates_tag
+                    .then(|| repo.current_commit_hash())
+                    .transpose()?,
+                tag_message: message.filter(|_| release_creates_tag),
             };
-            git_client.create_release(&git_release_info).await?;
+            let forge_release = git_client.create_release(&git_release_info).await?;
+            info!("created release {}", forge_release.html_url);
+            git_release = Some(forge_release);
+        }
+        let alias_tags = if should_create_git_tag && input.alias_tags {
+            update_alias_tags(repo, git_client, release_info).await
+        } else {
+            vec![]
+        };
+        if let Some(milestone) = &input.milestone
+            && let Err(e) = close_milestone(milestone, git_client, release_info.package).await
+        {
+            warn!(
+                "can't update the milestone of {}: {e:?}",
+                release_info.git_tag
+            );
         }
+        if input.comment_issues || input.close_issues {
+            let release_url = git_release.as_ref().map(|r| r.html_url.as_str());
+            update_referenced_issues(input, repo, git_client, release_info, release_url).await;
+        }
+        run_hook(
+            "post_release",
+            input.hooks.post_release.as_deref(),
+            workspace_root,
+            &hook_env,
+        )?;
 
         info!(
             "released {} {}",
             release_info.package.name, release_info.package.version
         );
-        Ok(true)
+        Ok(Some(ReleasedPackage {
+            alias_tags,
+            extra_tags,
+            git_release,
+        }))
+    }
+}
+
+/// Create the tag `tag` on the current commit, annotated with `message` if present.
+async fn create_git_tag(
+    input: &ReleaseRequest,
+    repo: &Repo,
+    git_client: &GitClient,
+    tag: &str,
+    message: Option<&str>,
+) -> anyhow::Result<()> {
+    // If tag signing is enabled, create the tag locally instead of using the API.
+    // Lightweight tags can't be signed.
+    match message {
+        Some(message) if should_sign_tags(input, repo)? => {
+            let mut args = signing_key_config(input.signing_key.as_deref());
+            args.extend(["tag", "--sign", "-m", message, tag].map(String::from));
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            repo.git(&args)
+                .with_context(|| format!("failed to create signed tag {tag}"))?;
+            repo.push(tag)?;
+        }
+        None if input.sign_tags == Some(true) => {
+            anyhow::bail!(
+                "can't sign the lightweight tag {tag}: set `git_tag_annotated = true` to sign tags"
+            );
+        }
+        // GitLab job tokens can't call the tags API, so the tag is pushed with git.
+        _ if git_client.job_token => {
+            let mut args = vec!["tag"];
+            if let Some(message) = message {
+                args.extend(["--annotate", "--no-sign", "-m", message]);
+            }
+            args.push(tag);
+            repo.git(&args)
+                .with_context(|| format!("failed to create tag {tag}"))?;
+            repo.push(tag).with_context(|| {
+                format!("failed to push tag {tag}: allow the CI/CD job token to push to the repository, or use a project access token")
+            })?;
+        }
+        _ => {
+            let sha = repo.current_commit_hash()?;
+            git_client.create_tag(tag, message, &sha).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether to sign the tags, as configured or as set in the git config.
+fn should_sign_tags(input: &ReleaseRequest, repo: &Repo) -> anyhow::Result<bool> {
+    match input.sign_tags {
+        Some(sign_tags) => Ok(sign_tags),
+        None => repo
+            .git(&["config", "--default", "false", "--get", "tag.gpgSign"])
+            .map(|s| s.trim() == "true"),
+    }
+}
+
+/// Git config arguments to sign with `signing_key`.
+/// SSH keys, given as a public key or as the path of a key file, need `gpg.format=ssh`.
+fn signing_key_config(signing_key: Option<&str>) -> Vec<String> {
+    let Some(key) = signing_key else {
+        return vec![];
+    };
+    let is_literal_ssh_key = key.starts_with("ssh-") || key.starts_with("ecdsa-");
+    let is_ssh_key = is_literal_ssh_key
+        || key.starts_with("key::")
+        || key.contains('/')
+        || key.ends_with(".pub");
+    let key = if is_literal_ssh_key {
+        format!("key::{key}")
+    } else {
+        key.to_string()
+    };
+    let mut config = vec![];
+    if is_ssh_key {
+        config.extend(["-c".to_string(), "gpg.format=ssh".to_string()]);
+    }
+    config.extend(["-c".to_string(), format!("user.signingkey={key}")]);
+    config
+}
+
+/// Message of the annotated tag of the release, or [`Option::None`] for a lightweight tag.
+fn tag_message(
+    input: &ReleaseRequest,
+    release_info: &ReleaseInfo<'_>,
+) -> anyhow::Result<Option<String>> {
+    let git_tag = input.get_package_config(&release_info.package.name).git_tag;
+    if !git_tag.annotated {
+        return Ok(None);
     }
+    let package = &release_info.package.name;
+    let version = release_info.package.version.to_string();
+    let message = match &git_tag.message_template {
+        Some(template) => {
+            let mut context = tera_context(package, &version);
+            context.insert(CHANGELOG_VAR, release_info.changelog);
+            render_template(template, &context, "tag_message")?
+        }
+        // Use same tag message of cargo-release
+        None => format!("chore: Release package {package} version {version}"),
+    };
+    Ok(Some(message))
+}
+
+/// Close the milestone of the released version of `package`, if it's open.
+/// If a next milestone is configured, create it if needed and move the open issues to it.
+async fn close_milestone(
+    config: &MilestoneConfig,
+    git_client: &GitClient,
+    package: &Package,
+) -> anyhow::Result<()> {
+    let title = config.title(&package.name, &package.version)?;
+    let milestones = git_client.open_milestones().await?;
+    let Some(milestone) = milestones.iter().find(|m| m.title == title) else {
+        debug!("no open milestone {title}");
+        return Ok(());
+    };
+    if let Some(next) = &config.next {
+        let next_title = config.title(&package.name, &next.bump(&package.version))?;
+        let next_milestone = match milestones.iter().find(|m| m.title == next_title) {
+            Some(next_milestone) => next_milestone.clone(),
+            None => {
+                let next_milestone = git_client.create_milestone(&next_title).await?;
+                info!("created milestone {next_title}");
+                next_milestone
+            }
+        };
+        let moved = git_client
+            .move_open_issues(milestone, &next_milestone)
+            .await?;
+        info!("moved {moved} open issues from milestone {title} to {next_title}");
+    }
+    git_client.close_milestone(milestone).await?;
+    info!("closed milestone {title}");
+    Ok(())
+}
+
+/// Comment on the issues referenced by the released commits and, if configured, close them.
+/// Failures are only logged, because the release is already done.
+async fn update_referenced_issues(
+    input: &ReleaseRequest,
+    repo: &Repo,
+    git_client: &GitClient,
+    release_info: &ReleaseInfo<'_>,
+    release_url: Option<&str>,
+) {
+    let git_tag = release_info.git_tag;
+    // In independent mode, only the commits of the released package reference its issues.
+    let package_dir = if input.version_mode.is_independent() {
+        match release_info.package.package_path() {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                warn!(
+                    "can't find the directory of {}: {e:?}",
+                    release_info.package.name
+                );
+                return;
+            }
+        }
+    } else {
+        None
+    };
+    let messages =
+        match released_commit_messages(repo, git_tag, &release_info.package.version, package_dir) {
+            Ok(Some(messages)) => messages,
+            Ok(None) => {
+                info!("{git_tag}: no previous release found, skipping the referenced issues");
+                return;
+            }
+            Err(e) => {
+                warn!("can't read the commits released in {git_tag}: {e:?}");
+                return;
+            }
+        };
+    let release = match release_url {
+        Some(url) => format!("[{git_tag}]({url})"),
+        None => format!("`{git_tag}`"),
+    };
+    let comment = format!("Released in {release} 🎉");
+    for issue in referenced_issues(&messages) {
+        if input.comment_issues {
+            match git_client.comment_issue(issue, &comment).await {
+                Ok(()) => info!("commented on issue #{issue}"),
+                Err(e) => warn!("{e:?}"),
+            }
+        }
+        if input.close_issues {
+            match git_client.close_issue(issue).await {
+                Ok(()) => info!("closed issue #{issue}"),
+                Err(e) => warn!("{e:?}"),
+            }
+        }
+    }
+}
+
+/// Messages of the commits since the previous release with the same tag format.
+/// If `package_dir` is present, only the commits touching it are considered.
+/// Returns [`Option::None`] for the first release, to avoid notifying every issue of the history.
+fn released_commit_messages(
+    repo: &Repo,
+    git_tag: &str,
+    version: &Version,
+    package_dir: Option<&Utf8Path>,
+) -> anyhow::Result<Option<String>> {
+    let tag_pattern = git_tag.replacen(&version.to_string(), "*", 1);
+    let Ok(previous_tag) = repo.git(&[
+        "describe",
+        "--tags",
+        "--abbrev=0",
+        "--match",
+        &tag_pattern,
+        "HEAD^",
+    ]) else {
+        return Ok(None);
+    };
+    let range = format!("{}..HEAD", previous_tag.trim());
+    let mut args = vec!["log", "--format=%B", &range];
+    if let Some(package_dir) = package_dir {
+        args.extend(["--", package_dir.as_str()]);
+    }
+    let messages = repo.git(&args)?;
+    Ok(Some(messages))
+}
+
+/// Issues closed by `text` with keywords like `fixes #123` or `closes #123`.
+fn referenced_issues(text: &str) -> BTreeSet<u64> {
+    let re = Regex::new(r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?):?\s+#(\d+)\b").unwrap();
+    re.captures_iter(text)
+        .filter_map(|capture| capture.get(1)?.as_str().parse().ok())
+        .collect()
+}
+
+/// Move the alias tags of the release to the released commit.
+/// Returns the moved tags. Failures are only logged, because the release is already done.
+async fn update_alias_tags(
+    repo: &Repo,
+    git_client: &GitClient,
+    release_info: &ReleaseInfo<'_>,
+) -> Vec<String> {
+    let aliases = alias_tags(
+        release_info.git_tag,
+        &release_info.package.version,
+        &repo.get_all_tags(),
+    );
+    let sha = match repo.current_commit_hash() {
+        Ok(sha) => sha,
+        Err(e) => {
+            warn!(
+                "can't update the alias tags of {}: {e:?}",
+                release_info.git_tag
+            );
+            return vec![];
+        }
+    };
+    let mut moved = vec![];
+    for alias in aliases {
+        match git_client.force_update_tag(&alias, &sha).await {
+            Ok(()) => {
+                info!("moved tag {alias} to {}", release_info.git_tag);
+                moved.push(alias);
+            }
+            Err(e) => warn!("can't move tag {alias} to {}: {e:?}", release_info.git_tag),
+        }
+    }
+    moved
+}
+
+/// Floating tags, like `v1` and `v1.4`, that point to the latest release of their series.
+/// They are derived from `git_tag` by replacing `version` with its major and minor parts.
+/// An alias is skipped if `existing_tags` contain a greater version of its series,
+/// e.g. `v1` isn't moved to `v1.4.3` if `v1.5.0` exists. Pre-releases have no aliases.
+fn alias_tags(git_tag: &str, version: &Version, existing_tags: &[String]) -> Vec<String> {
+    if !version.pre.is_empty() {
+        return vec![];
+    }
+    let Some((prefix, suffix)) = git_tag.split_once(&version.to_string()) else {
+        return vec![];
+    };
+    let released: Vec<Version> = existing_tags
+        .iter()
+        .filter_map(|tag| tag.strip_prefix(prefix)?.strip_suffix(suffix))
+        .filter_map(|v| Version::parse(v).ok())
+        .filter(|v| v.pre.is_empty())
+        .collect();
+    // Whether `version` is the latest release of the major series, or of the minor one.
+    let is_latest = |minor: Option<u64>| {
+        released
+            .iter()
+            .filter(|v| v.major == version.major && minor.is_none_or(|m| v.minor == m))
+            .all(|v| v <= version)
+    };
+    let mut aliases = vec![];
+    if is_latest(None) {
+        aliases.push(format!("{prefix}{}{suffix}", version.major));
+    }
+    if is_latest(Some(version.minor)) {
+        aliases.push(format!(
+            "{prefix}{}.{}{suffix}",
+            version.major, version.minor
+        ));
+    }
+    aliases
 }
 
 /// Traces the steps that would have been taken had release been run without dry-run.
 fn log_dry_run_info(
+    input: &ReleaseRequest,
     release_info: &ReleaseInfo,
     should_create_git_tag: bool,
     should_create_git_release: bool,
@@ -807,10 +1607,44 @@ fn log_dry_run_info(
 
     if should_create_git_tag {
         items_to_skip.push(format!("creation of tag '{}'", release_info.git_tag));
+        for tag in release_info.extra_git_tags {
+            items_to_skip.push(format!("creation of tag '{tag}'"));
+        }
+        if input.alias_tags {
+            items_to_skip.push("update of the alias tags".to_string());
+        }
     }
 
     if should_create_git_release {
         items_to_skip.push("creation of git release".to_string());
+        let asset_patterns = input
+            .get_package_config(&release_info.package.name)
+            .git_release
+            .assets;
+        match release_assets(
+            &asset_patterns,
+            release_info.package,
+            &input.metadata.workspace_root,
+        ) {
+            Ok(assets) if !assets.is_empty() => {
+                items_to_skip.push(format!("upload of release assets {assets:?}"));
+            }
+            Ok(_) => {}
+            Err(e) => warn!("{e:?}"),
+        }
+    }
+
+    if let Some(milestone) = &input.milestone {
+        match milestone.title(&release_info.package.name, &release_info.package.version) {
+            Ok(title) => items_to_skip.push(format!("closing of milestone '{title}'")),
+            Err(e) => warn!("{e:?}"),
+        }
+    }
+    if input.comment_issues {
+        items_to_skip.push("comments on the referenced issues".to_string());
+    }
+    if input.close_issues {
+        items_to_skip.push("closing of the referenced issues".to_string());
     }
 
     if items_to_skip.is_empty() {
@@ -867,6 +1701,49 @@ pub struct GitReleaseInfo {
     pub latest: Option<bool>,
     pub draft: bool,
     pub pre_release: bool,
+    /// Let the forge generate the release notes instead of using `release_body`.
+    pub generate_release_notes: bool,
+    /// Files to upload to the release.
+    pub assets: Vec<Utf8PathBuf>,
+    /// Commit to create `git_tag` from, if the release creates the tag.
+    /// Only used by GitLab, where job tokens can't create tags with the tags API.
+    pub tag_ref: Option<String>,
+    /// Message of the annotated tag created by the release. See [`Self::tag_ref`].
+    pub tag_message: Option<String>,
+}
+
+/// Find the files matching the release asset patterns.
+/// Patterns can use the `package` and `version` Tera variables
+/// and are relative to the workspace root.
+fn release_assets(
+    patterns: &[String],
+    package: &Package,
+    workspace_root: &Utf8Path,
+) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    let context = crate::tera::tera_context(&package.name, &package.version.to_string());
+    let mut assets = vec![];
+    for pattern in patterns {
+        let pattern = crate::tera::render_template(pattern, &context, "git_release_assets")?;
+        let full_pattern = workspace_root.join(&pattern);
+        let mut matched = false;
+        for path in glob::glob(full_pattern.as_str())
+            .with_context(|| format!("invalid release asset pattern `{pattern}`"))?
+        {
+            let path = path.context("can't read release asset")?;
+            let path = Utf8PathBuf::from_path_buf(path)
+                .map_err(|p| anyhow::anyhow!("release asset {p:?} isn't valid UTF-8"))?;
+            if path.is_file() {
+                matched = true;
+                assets.push(path);
+            }
+        }
+        if !matched {
+            warn!("release asset pattern `{pattern}` didn't match any file");
+        }
+    }
+    assets.sort();
+    assets.dedup();
+    Ok(assets)
 }
 
 /// Return an empty string if the changelog cannot be parsed.
@@ -926,6 +1803,121 @@ fn last_changelog_entry(req: &ReleaseRequest, package: &Package) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn release_assets_are_matched_with_templated_glob() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(temp.path()).unwrap();
+        fs_err::create_dir_all(root.join("dist")).unwrap();
+        fs_err::write(root.join("dist/my_crate-v0.1.0.tar.gz"), "a").unwrap();
+        fs_err::write(root.join("dist/my_crate-v0.0.1.tar.gz"), "b").unwrap();
+        fs_err::write(root.join("dist/notes.txt"), "c").unwrap();
+
+        let package = fake_package::FakePackage::new("my_crate").into();
+        let assets = release_assets(
+            &[
+                "dist/{{ package }}-v{{ version }}.*".to_string(),
+                "missing/*".to_string(),
+            ],
+            &package,
+            root,
+        )
+        .unwrap();
+        assert_eq!(assets, vec![root.join("dist/my_crate-v0.1.0.tar.gz")]);
+    }
+
+    #[test]
+    fn alias_tags_point_to_the_latest_release_of_their_series() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let tags = ["v1.4.2", "v1.5.0", "v2.0.0", "v1.4.4-rc.1"].map(String::from);
+        assert_eq!(alias_tags("v1.5.1", &v("1.5.1"), &tags), ["v1", "v1.5"]);
+        // `v1.5.0` is more recent than the backported fix.
+        assert_eq!(alias_tags("v1.4.3", &v("1.4.3"), &tags), ["v1.4"]);
+        assert_eq!(
+            alias_tags("my-crate-v2.1.0-lts", &v("2.1.0"), &[]),
+            ["my-crate-v2-lts", "my-crate-v2.1-lts"]
+        );
+        assert!(alias_tags("v2.1.0-rc.1", &v("2.1.0-rc.1"), &tags).is_empty());
+    }
+
+    #[test]
+    fn milestone_title_is_rendered() {
+        let version = Version::parse("1.4.2").unwrap();
+        let config = MilestoneConfig::default();
+        assert_eq!(config.title("my-crate", &version).unwrap(), "1.4.2");
+        let config = MilestoneConfig::default()
+            .set_title_template(Some("{{ package }} v{{ version }}".to_string()))
+            .set_next(VersionIncrement::Minor);
+        let next_version = config.next.unwrap().bump(&version);
+        assert_eq!(
+            config.title("my-crate", &next_version).unwrap(),
+            "my-crate v1.5.0"
+        );
+    }
+
+    #[test]
+    fn closing_references_are_parsed() {
+        let messages = "fix: handle empty input\n\nFixes #12, closes #7\n\n\
+            feat: new flag (#30)\n\nResolved: #12\nRefs #99\nprefix#5 fixes #abc";
+        assert_eq!(
+            referenced_issues(messages).into_iter().collect::<Vec<_>>(),
+            [7, 12]
+        );
+    }
+
+    #[test]
+    fn released_commits_are_filtered_by_package() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(temp.path()).unwrap();
+        let repo = Repo::init(root);
+        for package in ["a", "b"] {
+            fs_err::create_dir(root.join(package)).unwrap();
+            fs_err::write(root.join(package).join("lib.rs"), "").unwrap();
+        }
+        repo.add_all_and_commit("add packages").unwrap();
+        repo.tag("a-v0.1.0", "a-v0.1.0").unwrap();
+        fs_err::write(root.join("b/lib.rs"), "// b").unwrap();
+        repo.add_all_and_commit("fix: b, fixes #2").unwrap();
+        fs_err::write(root.join("a/lib.rs"), "// a").unwrap();
+        repo.add_all_and_commit("fix: a, fixes #1").unwrap();
+
+        let version = Version::new(0, 2, 0);
+        let messages = released_commit_messages(&repo, "a-v0.2.0", &version, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(referenced_issues(&messages), BTreeSet::from([1, 2]));
+        let messages = released_commit_messages(&repo, "a-v0.2.0", &version, Some(&root.join("a")))
+            .unwrap()
+            .unwrap();
+        assert_eq!(referenced_issues(&messages), BTreeSet::from([1]));
+    }
+
+    #[test]
+    fn signing_key_format_is_detected() {
+        assert!(signing_key_config(None).is_empty());
+        assert_eq!(
+            signing_key_config(Some("3AA5C34371567BD2")),
+            ["-c", "user.signingkey=3AA5C34371567BD2"]
+        );
+        assert_eq!(
+            signing_key_config(Some("~/.ssh/id_ed25519.pub")),
+            [
+                "-c",
+                "gpg.format=ssh",
+                "-c",
+                "user.signingkey=~/.ssh/id_ed25519.pub"
+            ]
+        );
+        assert_eq!(
+            signing_key_config(Some("ssh-ed25519 AAAAC3Nza")),
+            [
+                "-c",
+                "gpg.format=ssh",
+                "-c",
+                "user.signingkey=key::ssh-ed25519 AAAAC3Nza"
+            ]
+        );
+    }
+
     #[test]
     fn test_extract_changelog_from_pr_body() {
         let pr_body = r#"
@@ -967,6 +1959,36 @@ Generated by k-releaser"#;
         assert!(!changelog.contains("Generated by k-releaser"));
     }
 
+    #[test]
+    fn test_extract_package_changelog_from_pr_body() {
+        let pr_body = r#"
+## 🤖 New release
+
+* `pkg_a`: 0.1.0 -> 0.2.0
+* `pkg_b`: 1.0.0 -> 1.0.1
+
+<details><summary><i><b>`pkg_a` changelog</b></i></summary>
+
+### Added
+
+- new feature
+
+</details>
+
+<details><summary><i><b>`pkg_b` changelog</b></i></summary>
+
+### Fixed
+
+- bug
+
+</details>
+"#;
+
+        let changelog = extract_package_changelog_from_pr_body(pr_body, "pkg_b").unwrap();
+        assert_eq!(changelog, "### Fixed\n\n- bug");
+        assert!(extract_package_changelog_from_pr_body(pr_body, "pkg_c").is_none());
+    }
+
     #[test]
     fn test_extract_changelog_from_pr_body_without_details_tag() {
         let pr_body = "Some custom PR body without details tag";
@@ -0,0 +1,164 @@
+//! Release plan reported to the forge as a check run or a commit status,
+//! so that the next versions are visible directly on the commit and its PRs.
+
+use anyhow::Context as _;
+use git_cmd::Repo;
+use tracing::{info, warn};
+
+use crate::{PackagesUpdate, root_repo_path_from_manifest_dir, semver_check::SemverCheck};
+
+use super::update_request::UpdateRequest;
+
+const CHECK_NAME: &str = "k-releaser release plan";
+/// Maximum number of characters of the summary of a GitHub check run.
+const MAX_SUMMARY_LEN: usize = 65535;
+const TRUNCATED_NOTE: &str = "\n\n_The release plan is too long and has been truncated._\n";
+
+/// Report the release plan of `packages_update` on the current commit of the project.
+/// The summary of the GitHub check run contains the next versions, the semver check results
+/// and the changelog. Gitea and GitLab only show the title in the commit status.
+pub async fn publish_release_plan(
+    update_request: &UpdateRequest,
+    packages_update: &PackagesUpdate,
+) -> anyhow::Result<()> {
+    let git_client = update_request
+        .git_client()?
+        .context("git token not provided: can't publish the release plan")?;
+    let repo_root = root_repo_path_from_manifest_dir(update_request.local_manifest_dir()?)?;
+    let sha = Repo::new(repo_root)?.current_commit_hash()?;
+    let name = match update_request.single_package() {
+        Some(package) => format!("{CHECK_NAME} ({package})"),
+        None => CHECK_NAME.to_string(),
+    };
+    git_client
+        .report_check(
+            &sha,
+            &name,
+            &release_plan_title(packages_update),
+            &trim_summary(release_plan(packages_update)),
+        )
+        .await
+        .context("failed to publish the release plan")?;
+    info!("published the release plan on commit {sha}");
+    Ok(())
+}
+
+fn release_plan_title(packages_update: &PackagesUpdate) -> String {
+    let releases = packages_update
+        .updates()
+        .iter()
+        .filter(|(package, update)| package.version != update.version)
+        .count();
+    match releases {
+        0 => "No packages to release".to_string(),
+        1 => "1 package to release".to_string(),
+        n => format!("{n} packages to release"),
+    }
+}
+
+fn trim_summary(summary: String) -> String {
+    if summary.chars().count() <= MAX_SUMMARY_LEN {
+        return summary;
+    }
+    warn!("release plan is longer than {MAX_SUMMARY_LEN} characters. Truncating it.");
+    let max_len = MAX_SUMMARY_LEN - TRUNCATED_NOTE.chars().count();
+    let mut summary: String = summary.chars().take(max_len).collect();
+    summary.push_str(TRUNCATED_NOTE);
+    summary
+}
+
+/// Markdown with the next versions, the semver check results and the changelog
+/// of the packages to release.
+pub fn release_plan(packages_update: &PackagesUpdate) -> String {
+    let updates: Vec<_> = packages_update
+        .updates()
+        .iter()
+        .filter(|(package, update)| package.version != update.version)
+        .collect();
+    if updates.is_empty() {
+        return "All the packages are up-to-date.\n".to_string();
+    }
+    let mut plan = "| Package | Current version | Next version | Semver check |\n".to_string();
+    plan.push_str("|---|---|---|---|\n");
+    for (package, update) in &updates {
+        let semver_check = match update.semver_check {
+            SemverCheck::Compatible => "✅ compatible",
+            SemverCheck::Incompatible(_) => "⚠️ breaking changes",
+            SemverCheck::Skipped => "skipped",
+        };
+        plan.push_str(&format!(
+            "| `{}` | {} | {} | {semver_check} |\n",
+            package.name, package.version, update.version
+        ));
+    }
+    for (package, update) in &updates {
+        if let SemverCheck::Incompatible(incompatibilities) = &update.semver_check {
+            plan.push_str(&format!(
+                "\n### `{}` breaking changes\n\n```\n{}\n```\n",
+                package.name,
+                incompatibilities.trim()
+            ));
+        }
+        if let Some(changelog) = update
+            .new_changelog_entry
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+        {
+            plan.push_str(&format!(
+                "\n<details><summary><code>{}</code> changelog</summary>\n\n{}\n</details>\n",
+                package.name,
+                changelog.trim()
+            ));
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UpdateResult;
+
+    use super::*;
+
+    #[test]
+    fn release_plan_contains_versions_and_changelog() {
+        let update = UpdateResult {
+            version: "0.2.0".parse().unwrap(),
+            changelog: None,
+            semver_check: SemverCheck::Incompatible("removed function `foo`".to_string()),
+            new_changelog_entry: Some("## [0.2.0]\n\n- remove foo\n".to_string()),
+        };
+        let packages_update =
+            PackagesUpdate::new(vec![(fake_package::FakePackage::new("aaa").into(), update)]);
+        assert_eq!(release_plan_title(&packages_update), "1 package to release");
+        expect_test::expect![[r#"
+            | Package | Current version | Next version | Semver check |
+            |---|---|---|---|
+            | `aaa` | 0.1.0 | 0.2.0 | ⚠️ breaking changes |
+
+            ### `aaa` breaking changes
+
+            ```
+            removed function `foo`
+            ```
+
+            <details><summary><code>aaa</code> changelog</summary>
+
+            ## [0.2.0]
+
+            - remove foo
+            </details>
+        "#]]
+        .assert_eq(&release_plan(&packages_update));
+    }
+
+    #[test]
+    fn long_release_plan_is_truncated_to_the_check_run_limit() {
+        let short = "a".repeat(MAX_SUMMARY_LEN);
+        assert_eq!(trim_summary(short.clone()), short);
+
+        let summary = trim_summary("é".repeat(MAX_SUMMARY_LEN + 1));
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_LEN);
+        assert!(summary.ends_with(TRUNCATED_NOTE));
+    }
+}
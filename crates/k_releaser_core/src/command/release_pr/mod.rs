@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use cargo_metadata::camino::Utf8Path;
 use cargo_metadata::semver::Version;
 use cargo_utils::CARGO_TOML;
@@ -5,22 +7,52 @@ use git_cmd::Repo;
 
 use anyhow::Context;
 use serde::Serialize;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 use url::Url;
 
+use crate::codeowners::codeowners_reviewers;
 use crate::git::forge::{
     ForgeType, GitClient, GitPr, PrEdit, contributors_from_commits, validate_labels,
 };
-use crate::git::github_graphql;
-use crate::pr::{DEFAULT_BRANCH_PREFIX, OLD_BRANCH_PREFIX, Pr};
+use crate::git::lock::{LockMode, with_lock};
+use crate::git::token_scopes::check_token_scopes;
+use crate::git::{forge_commit, github_graphql};
+use crate::pr::{
+    DEFAULT_BRANCH_PREFIX, MergeMethod, OLD_BRANCH_PREFIX, Pr, PrProject, milestone_title,
+};
 use crate::{
-    PackagesUpdate, copy_to_temp_dir, new_manifest_dir_path, new_project_root,
-    publishable_packages_from_manifest, root_repo_path_from_manifest_dir, update,
+    PackagesUpdate, copy_to_temp_dir, new_manifest_dir_path, new_project_root, next_versions,
+    publish_release_plan, publishable_packages_from_manifest, root_repo_path_from_manifest_dir,
+    update,
 };
 
 use super::update_request::UpdateRequest;
 
-#[derive(Debug)]
+/// How the updated packages are distributed across release PRs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrSplit {
+    /// A single release PR updates all the packages.
+    #[default]
+    Single,
+    /// Every updated package gets its own release PR.
+    PerPackage,
+}
+
+/// How the opened release PR is updated when it contains manual commits,
+/// or when its branch conflicts with the original branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrUpdateStrategy {
+    /// Close the release PR and open a new one.
+    #[default]
+    Recreate,
+    /// Rebase the release PR branch, including its manual commits, onto the original branch.
+    /// Fails on conflicts.
+    Rebase,
+    /// Fail, leaving the release PR untouched.
+    AbortWithError,
+}
+
+#[derive(Debug, Clone)]
 pub struct ReleasePrRequest {
     /// Tera template for the release pull request name.
     pr_name_template: Option<String>,
@@ -30,8 +62,40 @@ pub struct ReleasePrRequest {
     draft: bool,
     /// Labels to add to the release PR.
     labels: Vec<String>,
+    /// Users and teams (`org/team`) requested to review the release PR.
+    reviewers: Vec<String>,
+    /// Users assigned to the release PR.
+    assignees: Vec<String>,
     /// PR Branch Prefix
     branch_prefix: String,
+    /// Tera template for the name of the release branch, after the prefix.
+    branch_name_template: Option<String>,
+    /// Whether to open one release PR or one per package.
+    pr_split: PrSplit,
+    /// If `true`, the release PR commits are created via the forge API,
+    /// so that they are signed by the forge.
+    signed_commits: bool,
+    /// Whether to lock the repository while opening the release PRs.
+    lock_mode: LockMode,
+    /// If `true`, the release plan is reported as a check of the current commit.
+    check_run: bool,
+    /// If `true`, the opened release PRs are closed and their branches deleted,
+    /// instead of being updated.
+    force_new: bool,
+    /// If `true`, the opened release PR is updated, but a new one is never opened.
+    update_only: bool,
+    /// How to update the opened release PR.
+    update_strategy: PrUpdateStrategy,
+    /// Tera template for the title of the milestone of the release PR.
+    milestone_template: Option<String>,
+    /// Request reviews from the owners of the changed files in the `CODEOWNERS` file.
+    codeowners_reviewers: bool,
+    /// GitHub Project where the release PR is added.
+    project: Option<PrProject>,
+    /// Approvals required to merge the release PR. Only supported by GitLab.
+    required_approvals: Option<u32>,
+    /// If set, the release PR is merged with this method once its checks pass.
+    auto_merge: Option<MergeMethod>,
     pub update_request: UpdateRequest,
 }
 
@@ -42,7 +106,22 @@ impl ReleasePrRequest {
             pr_body_template: None,
             draft: false,
             labels: vec![],
+            reviewers: vec![],
+            assignees: vec![],
             branch_prefix: DEFAULT_BRANCH_PREFIX.to_string(),
+            branch_name_template: None,
+            pr_split: PrSplit::default(),
+            signed_commits: false,
+            lock_mode: LockMode::default(),
+            check_run: false,
+            force_new: false,
+            update_only: false,
+            update_strategy: PrUpdateStrategy::default(),
+            milestone_template: None,
+            codeowners_reviewers: false,
+            project: None,
+            required_approvals: None,
+            auto_merge: None,
             update_request,
         }
     }
@@ -62,6 +141,16 @@ impl ReleasePrRequest {
         self
     }
 
+    pub fn with_reviewers(mut self, reviewers: Vec<String>) -> Self {
+        self.reviewers = reviewers;
+        self
+    }
+
+    pub fn with_assignees(mut self, assignees: Vec<String>) -> Self {
+        self.assignees = assignees;
+        self
+    }
+
     pub fn mark_as_draft(mut self, draft: bool) -> Self {
         self.draft = draft;
         self
@@ -73,6 +162,71 @@ impl ReleasePrRequest {
         }
         self
     }
+
+    pub fn with_branch_name_template(mut self, branch_name_template: Option<String>) -> Self {
+        self.branch_name_template = branch_name_template;
+        self
+    }
+
+    pub fn with_pr_split(mut self, pr_split: PrSplit) -> Self {
+        self.pr_split = pr_split;
+        self
+    }
+
+    pub fn with_signed_commits(mut self, signed_commits: bool) -> Self {
+        self.signed_commits = signed_commits;
+        self
+    }
+
+    pub fn with_lock_mode(mut self, lock_mode: LockMode) -> Self {
+        self.lock_mode = lock_mode;
+        self
+    }
+
+    pub fn with_check_run(mut self, check_run: bool) -> Self {
+        self.check_run = check_run;
+        self
+    }
+
+    pub fn with_force_new(mut self, force_new: bool) -> Self {
+        self.force_new = force_new;
+        self
+    }
+
+    pub fn with_update_only(mut self, update_only: bool) -> Self {
+        self.update_only = update_only;
+        self
+    }
+
+    pub fn with_update_strategy(mut self, update_strategy: PrUpdateStrategy) -> Self {
+        self.update_strategy = update_strategy;
+        self
+    }
+
+    pub fn with_milestone_template(mut self, milestone_template: Option<String>) -> Self {
+        self.milestone_template = milestone_template;
+        self
+    }
+
+    pub fn with_codeowners_reviewers(mut self, codeowners_reviewers: bool) -> Self {
+        self.codeowners_reviewers = codeowners_reviewers;
+        self
+    }
+
+    pub fn with_project(mut self, project: Option<PrProject>) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_required_approvals(mut self, required_approvals: Option<u32>) -> Self {
+        self.required_approvals = required_approvals;
+        self
+    }
+
+    pub fn with_auto_merge(mut self, auto_merge: Option<MergeMethod>) -> Self {
+        self.auto_merge = auto_merge;
+        self
+    }
 }
 
 /// Release pull request that k-releaser opened/updated.
@@ -114,7 +268,7 @@ pub struct PrPackageRelease {
 
 /// Result of a dry-run release PR calculation.
 /// Contains the PR title and body that would be created, without actually creating the PR.
-#[derive(Debug)]
+#[derive(Serialize, Debug)]
 pub struct ReleasePrDryRun {
     /// The title that would be used for the PR.
     pub title: String,
@@ -122,10 +276,23 @@ pub struct ReleasePrDryRun {
     pub body: String,
     /// The version that would be set.
     pub version: Option<Version>,
+    /// Version bumps of the packages that would be updated.
+    pub packages: Vec<PrPackageBump>,
     /// Commits that were found since the last tag.
     pub commits: Vec<String>,
 }
 
+/// Version bump of a package in a dry-run release PR.
+#[derive(Serialize, Debug)]
+pub struct PrPackageBump {
+    /// The name of the package.
+    pub package_name: String,
+    /// The current version of the package.
+    pub previous_version: Version,
+    /// The version the PR would set.
+    pub next_version: Version,
+}
+
 /// Perform a dry-run of the release PR process.
 /// Calculates what the PR would contain but doesn't create it.
 /// Returns information about what the PR would look like.
@@ -159,11 +326,15 @@ pub async fn release_pr_dry_run(input: &ReleasePrRequest) -> anyhow::Result<Rele
             title: "No updates needed".to_string(),
             body: "All packages are up-to-date. No PR would be created.".to_string(),
             version: None,
+            packages: vec![],
             commits: vec![],
         });
     }
 
-    let repo = Repo::new(tmp_project_root)?;
+    let mut repo = Repo::new(tmp_project_root)?;
+    if let Some(remote) = input.update_request.git_remote() {
+        repo = repo.with_remote(remote);
+    }
     let project_contains_multiple_pub_packages =
         publishable_packages_from_manifest(&local_manifest)?.len() > 1;
 
@@ -172,10 +343,21 @@ pub async fn release_pr_dry_run(input: &ReleasePrRequest) -> anyhow::Result<Rele
         &packages_to_update,
         project_contains_multiple_pub_packages,
         &input.branch_prefix,
+        input.branch_name_template.as_deref(),
         input.pr_name_template.clone(),
         input.pr_body_template.as_deref(),
     )?;
 
+    let packages = packages_to_update
+        .updates()
+        .iter()
+        .map(|(package, update)| PrPackageBump {
+            package_name: package.name.to_string(),
+            previous_version: package.version.clone(),
+            next_version: update.version.clone(),
+        })
+        .collect();
+
     // Collect commit messages from the updates
     let commits: Vec<String> = packages_to_update
         .updates()
@@ -196,15 +378,96 @@ pub async fn release_pr_dry_run(input: &ReleasePrRequest) -> anyhow::Result<Rele
         title: pr.title.clone(),
         body: pr.body.clone(),
         version: packages_to_update.workspace_version().cloned(),
+        packages,
         commits,
     })
 }
 
+/// Open the release pull requests of a local rust project, according to the [`PrSplit`]
+/// of the request.
+/// Returns the PRs that k-releaser opened or updated.
+/// If the [`LockMode`] of the request enables it, the repository is locked meanwhile.
+#[instrument(skip_all)]
+pub async fn release_prs(input: &ReleasePrRequest) -> anyhow::Result<Vec<ReleasePr>> {
+    let git_client = input
+        .update_request
+        .git_client()?
+        .context("can't find git client")?;
+    git_client.ensure_not_job_token("open merge requests")?;
+    check_token_scopes(&git_client).await?;
+    if input.lock_mode == LockMode::Disabled {
+        return unlocked_release_prs(input).await;
+    }
+    let manifest_dir = input.update_request.local_manifest_dir()?;
+    let head = Repo::new(root_repo_path_from_manifest_dir(manifest_dir)?)?.current_commit_hash()?;
+    with_lock(
+        input.lock_mode,
+        &git_client,
+        &head,
+        unlocked_release_prs(input),
+    )
+    .await
+}
+
+async fn unlocked_release_prs(input: &ReleasePrRequest) -> anyhow::Result<Vec<ReleasePr>> {
+    match input.pr_split {
+        PrSplit::Single => Ok(release_pr(input).await?.into_iter().collect()),
+        PrSplit::PerPackage => release_pr_per_package(input).await,
+    }
+}
+
+/// Open a release pull request for every package (or version group) that needs to be updated.
+/// Every PR uses its own branch prefix, so it's updated independently of the other PRs.
+async fn release_pr_per_package(input: &ReleasePrRequest) -> anyhow::Result<Vec<ReleasePr>> {
+    anyhow::ensure!(
+        input.update_request.version_mode().is_independent(),
+        "one release PR per package requires `version_mode = \"independent\"`"
+    );
+    let (packages_to_update, _repository) = next_versions(&input.update_request)
+        .await
+        .context("failed to determine next versions")?;
+    let mut release_prs = vec![];
+    let mut released_groups = HashSet::new();
+    for (package, _) in packages_to_update.updates() {
+        // Packages of a version group are released in the same PR.
+        let version_group = input
+            .update_request
+            .get_package_config(&package.name)
+            .version_group;
+        let pr_key = match version_group {
+            Some(version_group) if !released_groups.insert(version_group.clone()) => continue,
+            Some(version_group) => version_group,
+            None => package.name.to_string(),
+        };
+        let package_request = ReleasePrRequest {
+            branch_prefix: package_branch_prefix(&input.branch_prefix, &pr_key),
+            update_request: input
+                .update_request
+                .clone()
+                .with_single_package(package.name.to_string()),
+            ..input.clone()
+        };
+        if let Some(pr) = release_pr(&package_request)
+            .await
+            .with_context(|| format!("failed to open release PR of {}", package.name))?
+        {
+            release_prs.push(pr);
+        }
+    }
+    Ok(release_prs)
+}
+
+/// The package (or version group) name is followed by `/` so that the branches of a package
+/// don't match the prefix of packages with a longer name (e.g. `foo` and `foo-bar`).
+fn package_branch_prefix(branch_prefix: &str, package: &str) -> String {
+    format!("{branch_prefix}{package}/")
+}
+
 /// Open a pull request with the next packages versions of a local rust project
 /// Returns:
 /// - [`ReleasePr`] if k-releaser opened or updated a PR.
 /// - [`None`] if k-releaser didn't open any pr. This happens when all packages
-///   are up-to-date.
+///   are up-to-date, or when there's no release PR to update in update-only mode.
 #[instrument(skip_all)]
 pub async fn release_pr(input: &ReleasePrRequest) -> anyhow::Result<Option<ReleasePr>> {
     let manifest_dir = input.update_request.local_manifest_dir()?;
@@ -229,12 +492,21 @@ pub async fn release_pr(input: &ReleasePrRequest) -> anyhow::Result<Option<Relea
     let (packages_to_update, _temp_repository) = update(&new_update_request)
         .await
         .context("failed to update packages")?;
+    if input.check_run {
+        // The release plan is informative: don't stop the release PR if it can't be published.
+        if let Err(e) = publish_release_plan(&input.update_request, &packages_to_update).await {
+            warn!("can't publish the release plan: {e:?}");
+        }
+    }
     let git_client = input
         .update_request
         .git_client()?
         .context("can't find git client")?;
     if !packages_to_update.updates().is_empty() {
-        let repo = Repo::new(tmp_project_root)?;
+        let mut repo = Repo::new(tmp_project_root)?;
+        if let Some(remote) = input.update_request.git_remote() {
+            repo = repo.with_remote(remote);
+        }
         let there_are_commits_to_push = repo.is_clean().is_err();
         if there_are_commits_to_push {
             let pr = open_or_update_release_pr(
@@ -247,11 +519,23 @@ pub async fn release_pr(input: &ReleasePrRequest) -> anyhow::Result<Option<Relea
                     pr_name: input.pr_name_template.clone(),
                     pr_body: input.pr_body_template.clone(),
                     pr_labels: input.labels.clone(),
+                    pr_reviewers: input.reviewers.clone(),
+                    pr_assignees: input.assignees.clone(),
                     pr_branch_prefix: input.branch_prefix.clone(),
+                    pr_branch_name: input.branch_name_template.clone(),
+                    signed_commits: input.signed_commits,
+                    force_new: input.force_new,
+                    update_only: input.update_only,
+                    update_strategy: input.update_strategy,
+                    pr_milestone: input.milestone_template.clone(),
+                    codeowners_reviewers: input.codeowners_reviewers,
+                    pr_project: input.project.clone(),
+                    pr_required_approvals: input.required_approvals,
+                    pr_auto_merge: input.auto_merge,
                 },
             )
             .await?;
-            return Ok(Some(pr));
+            return Ok(pr);
         }
     }
 
@@ -263,7 +547,19 @@ struct ReleasePrOptions {
     pr_name: Option<String>,
     pr_body: Option<String>,
     pr_labels: Vec<String>,
+    pr_reviewers: Vec<String>,
+    pr_assignees: Vec<String>,
     pr_branch_prefix: String,
+    pr_branch_name: Option<String>,
+    signed_commits: bool,
+    force_new: bool,
+    update_only: bool,
+    update_strategy: PrUpdateStrategy,
+    pr_milestone: Option<String>,
+    codeowners_reviewers: bool,
+    pr_project: Option<PrProject>,
+    pr_required_approvals: Option<u32>,
+    pr_auto_merge: Option<MergeMethod>,
 }
 
 async fn open_or_update_release_pr(
@@ -272,7 +568,7 @@ async fn open_or_update_release_pr(
     git_client: &GitClient,
     repo: &Repo,
     release_pr_options: ReleasePrOptions,
-) -> anyhow::Result<ReleasePr> {
+) -> anyhow::Result<Option<ReleasePr>> {
     let mut opened_release_prs = git_client
         .opened_prs(&release_pr_options.pr_branch_prefix)
         .await
@@ -288,6 +584,25 @@ async fn open_or_update_release_pr(
             .context("cannot get opened k-releaser prs")?;
     }
 
+    if release_pr_options.force_new {
+        // Recreate the release PR from the current HEAD, e.g. because its branch diverged
+        // or was edited into a broken state.
+        for pr in &opened_release_prs {
+            git_client
+                .close_pr(pr.number)
+                .await
+                .context("cannot close old k-releaser prs")?;
+            git_client
+                .delete_branch(pr.branch())
+                .await
+                .with_context(|| {
+                    format!("cannot delete branch {} of pr #{}", pr.branch(), pr.number)
+                })?;
+            info!("deleted branch {} of pr #{}", pr.branch(), pr.number);
+        }
+        opened_release_prs.clear();
+    }
+
     // Close all k-releaser prs, except one.
     let old_release_prs = opened_release_prs.iter().skip(1);
     for pr in old_release_prs {
@@ -300,16 +615,37 @@ async fn open_or_update_release_pr(
     let new_pr = {
         let project_contains_multiple_pub_packages =
             publishable_packages_from_manifest(local_manifest)?.len() > 1;
+        let milestone = release_pr_options
+            .pr_milestone
+            .as_deref()
+            .map(|template| milestone_title(template, packages_to_update))
+            .transpose()?;
+        let mut reviewers = release_pr_options.pr_reviewers;
+        if release_pr_options.codeowners_reviewers {
+            let changed_files = repo.changes_except_typechanges()?;
+            for owner in codeowners_reviewers(repo.directory(), &changed_files)? {
+                if !reviewers.contains(&owner) {
+                    reviewers.push(owner);
+                }
+            }
+        }
         Pr::new(
             repo.original_branch(),
             packages_to_update,
             project_contains_multiple_pub_packages,
             &release_pr_options.pr_branch_prefix,
+            release_pr_options.pr_branch_name.as_deref(),
             release_pr_options.pr_name,
             release_pr_options.pr_body.as_deref(),
         )?
         .mark_as_draft(release_pr_options.draft)
         .with_labels(release_pr_options.pr_labels)
+        .with_reviewers(reviewers)
+        .with_assignees(release_pr_options.pr_assignees)
+        .with_milestone(milestone)
+        .with_project(release_pr_options.pr_project)
+        .with_required_approvals(release_pr_options.pr_required_approvals)
+        .with_auto_merge(release_pr_options.pr_auto_merge)
     };
     let release_pr = match opened_release_prs.first() {
         Some(opened_pr) => {
@@ -319,10 +655,16 @@ async fn open_or_update_release_pr(
                 repo,
                 &new_pr,
                 &release_pr_options.pr_branch_prefix,
+                release_pr_options.signed_commits,
+                release_pr_options.update_strategy,
             )
             .await
         }
-        None => create_pr(git_client, repo, &new_pr).await,
+        None if release_pr_options.update_only => {
+            info!("no opened release PR to update: not opening a new one");
+            return Ok(None);
+        }
+        None => create_pr(git_client, repo, &new_pr, release_pr_options.signed_commits).await,
     }?;
     let release_pr = ReleasePr {
         releases: packages_to_update
@@ -335,7 +677,7 @@ async fn open_or_update_release_pr(
             .collect(),
         ..release_pr
     };
-    Ok(release_pr)
+    Ok(Some(release_pr))
 }
 
 async fn handle_opened_pr(
@@ -344,57 +686,139 @@ async fn handle_opened_pr(
     repo: &Repo,
     new_pr: &Pr,
     branch_prefix: &str,
+    signed_commits: bool,
+    update_strategy: PrUpdateStrategy,
 ) -> Result<ReleasePr, anyhow::Error> {
     let pr_commits = git_client
         .pr_commits(opened_pr.number)
         .await
         .context("cannot get commits of k-releaser pr")?;
     let pr_contributors = contributors_from_commits(&pr_commits, git_client.forge);
-    Ok(if pr_contributors.is_empty() {
-        // There are no contributors, so we can force-push
-        // in this PR, because we don't care about the git history.
-        match update_pr(
-            git_client,
-            opened_pr,
-            pr_commits.len(),
-            repo,
-            new_pr,
-            branch_prefix,
-        )
-        .await
-        {
-            Ok(()) => ReleasePr::new(opened_pr, new_pr.base_branch.clone()),
-            Err(e) => {
-                tracing::error!(
-                    "cannot update release pr {}: {:?}. I'm closing the old release pr and opening a new one",
-                    opened_pr.number,
-                    e
+    // The Gitea API can't force-push, so we replace the PR instead of updating it.
+    let gitea_signed_commits = signed_commits && git_client.forge == ForgeType::Gitea;
+    if !pr_contributors.is_empty() {
+        // There's a contributor, so we don't want to discard their commits.
+        // TODO improvement: check how many lines the commit added, if no lines (for example a merge to update the branch),
+        //      then don't count it as a contributor.
+        match update_strategy {
+            // We close the PR because we want to save the contributor's work.
+            PrUpdateStrategy::Recreate => {
+                info!("closing pr {} to preserve git history", opened_pr.html_url);
+                return recreate_pr(git_client, opened_pr, repo, new_pr, signed_commits).await;
+            }
+            PrUpdateStrategy::Rebase if gitea_signed_commits => {
+                info!(
+                    "closing pr {} to open a new one with signed commits",
+                    opened_pr.html_url
                 );
-                git_client
-                    .close_pr(opened_pr.number)
-                    .await
-                    .context("cannot close old k-releaser prs")?;
-                create_pr(git_client, repo, new_pr).await?
+                return recreate_pr(git_client, opened_pr, repo, new_pr, signed_commits).await;
             }
+            PrUpdateStrategy::Rebase => {
+                update_pr(
+                    git_client,
+                    opened_pr,
+                    None,
+                    repo,
+                    new_pr,
+                    branch_prefix,
+                    signed_commits,
+                )
+                .await
+                .with_context(|| {
+                    format!(
+                        "cannot rebase release pr {}: resolve the conflicts manually or run `release-pr --force-new`",
+                        opened_pr.html_url
+                    )
+                })?;
+                return Ok(ReleasePr::new(opened_pr, new_pr.base_branch.clone()));
+            }
+            PrUpdateStrategy::AbortWithError => anyhow::bail!(
+                "release pr {} contains commits of other contributors: not updating it. Run `release-pr --force-new` to replace it",
+                opened_pr.html_url
+            ),
         }
-    } else {
-        // There's a contributor, so we don't want to force-push in this PR.
-        // We close it because we want to save the contributor's work.
-        // TODO improvement: check how many lines the commit added, if no lines (for example a merge to update the branch),
-        //      then don't count it as a contributor.
-        info!("closing pr {} to preserve git history", opened_pr.html_url);
+    }
+    if gitea_signed_commits {
+        info!(
+            "closing pr {} to open a new one with signed commits",
+            opened_pr.html_url
+        );
+        return recreate_pr(git_client, opened_pr, repo, new_pr, signed_commits).await;
+    }
+    // There are no contributors, so we can force-push
+    // in this PR, because we don't care about the git history.
+    match update_pr(
+        git_client,
+        opened_pr,
+        Some(pr_commits.len()),
+        repo,
+        new_pr,
+        branch_prefix,
+        signed_commits,
+    )
+    .await
+    {
+        Ok(()) => Ok(ReleasePr::new(opened_pr, new_pr.base_branch.clone())),
+        Err(e) if update_strategy == PrUpdateStrategy::Recreate => {
+            tracing::error!(
+                "cannot update release pr {}: {:?}. I'm closing the old release pr and opening a new one",
+                opened_pr.number,
+                e
+            );
+            recreate_pr(git_client, opened_pr, repo, new_pr, signed_commits).await
+        }
+        Err(e) => Err(e.context(format!(
+            "cannot update release pr {}: resolve the conflicts manually or run `release-pr --force-new`",
+            opened_pr.html_url
+        ))),
+    }
+}
+
+/// Close the opened release PR and open a new one.
+async fn recreate_pr(
+    git_client: &GitClient,
+    opened_pr: &GitPr,
+    repo: &Repo,
+    new_pr: &Pr,
+    signed_commits: bool,
+) -> anyhow::Result<ReleasePr> {
+    git_client
+        .close_pr(opened_pr.number)
+        .await
+        .context("cannot close old k-releaser prs")?;
+    // A deterministic `pr_branch_name` names the new branch like the old one:
+    // delete it to open the new PR. The closed PR still shows its commits.
+    if opened_pr.branch() == new_pr.branch {
         git_client
-            .close_pr(opened_pr.number)
+            .delete_branch(opened_pr.branch())
             .await
-            .context("cannot close old k-releaser prs")?;
-        create_pr(git_client, repo, new_pr).await?
-    })
+            .with_context(|| {
+                format!(
+                    "cannot delete branch {} of pr #{}",
+                    opened_pr.branch(),
+                    opened_pr.number
+                )
+            })?;
+        info!(
+            "deleted branch {} of pr #{}",
+            opened_pr.branch(),
+            opened_pr.number
+        );
+    }
+    create_pr(git_client, repo, new_pr, signed_commits).await
 }
 
-async fn create_pr(git_client: &GitClient, repo: &Repo, pr: &Pr) -> anyhow::Result<ReleasePr> {
+async fn create_pr(
+    git_client: &GitClient,
+    repo: &Repo,
+    pr: &Pr,
+    signed_commits: bool,
+) -> anyhow::Result<ReleasePr> {
     repo.checkout_new_branch(&pr.branch)?;
     if git_client.forge == ForgeType::Github {
         github_create_release_branch(git_client, repo, &pr.branch, &pr.title).await?;
+    } else if signed_commits {
+        api_create_release_branch(git_client, repo, &pr.branch, &pr.title).await?;
     } else {
         create_release_branch(repo, &pr.branch, &pr.title)?;
     }
@@ -404,29 +828,55 @@ async fn create_pr(git_client: &GitClient, repo: &Repo, pr: &Pr) -> anyhow::Resu
     Ok(ReleasePr::new(&git_pr, pr.base_branch.clone()))
 }
 
+/// Update the release PR branch with the new changes.
+/// The first `commits_number` commits of the PR are dropped before rebasing the branch onto
+/// the original branch. If [`Option::None`], the commits of k-releaser are dropped, while the
+/// ones of the contributors are rebased on top of the new changes.
 async fn update_pr(
     git_client: &GitClient,
     opened_pr: &GitPr,
-    commits_number: usize,
+    commits_number: Option<usize>,
     repository: &Repo,
     new_pr: &Pr,
     branch_prefix: &str,
+    signed_commits: bool,
 ) -> anyhow::Result<()> {
-    update_pr_branch(commits_number, opened_pr, repository, branch_prefix).with_context(|| {
+    // The commit is named after the new title, so that the next update recognizes it.
+    let commit_message = &new_pr.title;
+    let contributor_commits = update_pr_branch(
+        commits_number,
+        opened_pr,
+        commit_message,
+        repository,
+        branch_prefix,
+    )
+    .with_context(|| {
         format!(
             "failed to update pr branch with changes from `{}` branch",
             repository.original_branch()
         )
     })?;
     if git_client.forge == ForgeType::Github {
-        github_force_push(git_client, opened_pr, repository).await?;
+        github_force_push(git_client, opened_pr, commit_message, repository).await?;
+    } else if signed_commits {
+        api_force_push(git_client, opened_pr, commit_message, repository).await?;
     } else {
-        force_push(opened_pr, repository)?;
+        force_push(opened_pr, commit_message, repository)?;
+    }
+    if !contributor_commits.is_empty() {
+        push_contributor_commits(repository, opened_pr.branch(), &contributor_commits)?;
     }
     let pr_edit = {
         let mut pr_edit = PrEdit::new();
-        if opened_pr.title != new_pr.title {
+        // GitLab marks drafts with a prefix in the title, so the draft state is updated
+        // with the title.
+        let is_gitlab = git_client.forge == ForgeType::Gitlab;
+        let draft_changed = is_gitlab && opened_pr.draft != new_pr.draft;
+        if opened_pr.title != new_pr.title || draft_changed {
             pr_edit = pr_edit.with_title(new_pr.title.clone());
+            if is_gitlab {
+                pr_edit = pr_edit.with_draft(new_pr.draft);
+            }
         }
         if opened_pr.body.as_ref() != Some(&new_pr.body) {
             pr_edit = pr_edit.with_body(new_pr.body.clone());
@@ -436,10 +886,13 @@ async fn update_pr(
     if pr_edit.contains_edit() {
         git_client.edit_pr(opened_pr.number, pr_edit).await?;
     }
-    if opened_pr.label_names() != new_pr.labels {
-        git_client
-            .add_labels(&new_pr.labels, opened_pr.number)
-            .await?;
+    git_client.sync_labels(&new_pr.labels, opened_pr).await?;
+    if let Some(milestone) = &new_pr.milestone
+        && let Err(e) = git_client
+            .set_pr_milestone(opened_pr.number, milestone)
+            .await
+    {
+        warn!("failed to set the milestone of the PR: {e:?}");
     }
     info!("updated pr {}", opened_pr.html_url);
     Ok(())
@@ -447,34 +900,52 @@ async fn update_pr(
 
 /// Update the PR branch with the latest changes from the
 /// original branch where k-releaser was run (by default it's the default branch, e.g. `main`).
+/// The changes of k-releaser are left uncommitted.
+/// Returns the commits of the contributors to apply on top of the changes,
+/// if `commits_number` is [`Option::None`].
 fn update_pr_branch(
-    commits_number: usize,
+    commits_number: Option<usize>,
     opened_pr: &GitPr,
+    commit_message: &str,
     repository: &Repo,
     branch_prefix: &str,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Vec<String>> {
     // save local work
     repository.git(&["stash", "--include-untracked"])?;
 
-    reset_branch(opened_pr, commits_number, repository, branch_prefix).inspect_err(|_e| {
+    let contributor_commits = reset_branch(
+        opened_pr,
+        commits_number,
+        commit_message,
+        repository,
+        branch_prefix,
+    )
+    .inspect_err(|_e| {
         // restore local work
         if let Err(e) = repository.stash_pop() {
             tracing::error!("cannot restore local work: {:?}", e);
         }
     })?;
+    // The changes were computed from the original branch, so they apply cleanly.
     repository.stash_pop()?;
-    Ok(())
+    if !contributor_commits.is_empty() {
+        ensure_contributor_commits_apply(repository, commit_message, &contributor_commits)?;
+    }
+    Ok(contributor_commits)
 }
 
+/// Reset the PR branch to the original branch.
+/// Returns the commits of the contributors to keep if `commits_number` is [`Option::None`].
 fn reset_branch(
     pr: &GitPr,
-    commits_number: usize,
+    commits_number: Option<usize>,
+    new_title: &str,
     repository: &Repo,
     branch_prefix: &str,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Vec<String>> {
     // sanity check to avoid doing bad things on non-k-releaser branches
     anyhow::ensure!(
-        pr.branch().starts_with(branch_prefix)
+        pr.looks_like_release_pr(branch_prefix)
             || pr.branch().starts_with(DEFAULT_BRANCH_PREFIX)
             || pr.branch().starts_with(OLD_BRANCH_PREFIX),
         "wrong branch name"
@@ -485,23 +956,98 @@ fn reset_branch(
         repository.checkout(pr.branch())?;
     };
 
-    let head = format!("HEAD~{commits_number}");
-    repository.git(&["reset", "--hard", &head])?;
-
     repository.fetch(repository.original_branch())?;
 
+    // The changes of k-releaser are computed again, so only the commits of the contributors
+    // are kept.
+    let contributor_commits = match commits_number {
+        Some(_) => vec![],
+        None => contributor_commits(repository, &[&pr.title, new_title])?,
+    };
+
     // Update PR branch with latest changes from the default branch.
-    if let Err(e) = repository.git(&["rebase", repository.original_branch()]) {
-        // Get back to the state before "git rebase" to clean the merge conflict.
-        repository.git(&["rebase ", "--abort"])?;
-        return Err(e.context("cannot rebase from default branch"));
-    }
+    repository.git(&["reset", "--hard", repository.original_branch()])?;
+
+    Ok(contributor_commits)
+}
+
+/// Check that the commits of the contributors apply on top of the uncommitted changes
+/// of k-releaser, before pushing anything. The changes are left uncommitted.
+fn ensure_contributor_commits_apply(
+    repository: &Repo,
+    commit_message: &str,
+    contributor_commits: &[String],
+) -> anyhow::Result<()> {
+    let original_head = repository.git(&["rev-parse", "HEAD"])?;
+    add_changes_and_commit(repository, commit_message)?;
+    let changes = repository.git(&["rev-parse", "HEAD"])?;
+    let result = cherry_pick_contributor_commits(repository, contributor_commits);
+    repository.git(&["reset", "--hard", &changes])?;
+    repository.git(&["reset", &original_head])?;
+    result
+}
+
+/// Rebase the commits of the contributors on top of the changes of k-releaser pushed to
+/// the release PR `branch`, and push them.
+fn push_contributor_commits(
+    repository: &Repo,
+    branch: &str,
+    contributor_commits: &[String],
+) -> anyhow::Result<()> {
+    repository.fetch(branch)?;
+    repository.git(&["checkout", "--force", "-B", branch, "FETCH_HEAD"])?;
+    cherry_pick_contributor_commits(repository, contributor_commits)?;
+    repository.force_push(branch)?;
+    Ok(())
+}
 
+/// Apply the commits of the contributors on the current branch.
+/// Their edits, e.g. to the changelog, are merged with the new changes of k-releaser:
+/// if they conflict, the conflicting files are reported, to be resolved by hand.
+fn cherry_pick_contributor_commits(
+    repository: &Repo,
+    contributor_commits: &[String],
+) -> anyhow::Result<()> {
+    for commit in contributor_commits {
+        if let Err(e) = repository.git(&["cherry-pick", "--keep-redundant-commits", commit]) {
+            let conflicts = repository
+                .git(&["diff", "--name-only", "--diff-filter=U"])
+                .unwrap_or_default();
+            // Get back to the state before "git cherry-pick" to clean the merge conflict.
+            repository.git(&["cherry-pick", "--abort"])?;
+            return Err(e.context(format!(
+                "commit {commit} of a contributor conflicts with the new changes of k-releaser in: {}",
+                conflicts.lines().collect::<Vec<_>>().join(", ")
+            )));
+        }
+    }
     Ok(())
 }
 
-fn force_push(pr: &GitPr, repository: &Repo) -> anyhow::Result<()> {
-    add_changes_and_commit(repository, &pr.title)?;
+/// Commits of the checked out release PR branch not created by k-releaser, oldest first.
+/// k-releaser commits with the title of the release PR, one of the `release_titles`.
+/// Merge commits are skipped, because the branch is rebased.
+fn contributor_commits(repository: &Repo, release_titles: &[&str]) -> anyhow::Result<Vec<String>> {
+    let range = format!("{}..HEAD", repository.original_branch());
+    let log = repository.git(&[
+        "log",
+        "--reverse",
+        "--no-merges",
+        "--format=%H%x00%s",
+        &range,
+    ])?;
+    let commits = log
+        .lines()
+        .filter_map(|line| {
+            let (sha, subject) = line.split_once('\0')?;
+            (!release_titles.contains(&subject)).then(|| sha.to_string())
+        })
+        .collect();
+    Ok(commits)
+}
+
+fn force_push(pr: &GitPr, commit_message: &str, repository: &Repo) -> anyhow::Result<()> {
+    add_changes_and_commit(repository, commit_message)?;
     repository.force_push(pr.branch())?;
     Ok(())
 }
@@ -509,6 +1055,7 @@ fn force_push(pr: &GitPr, repository: &Repo) -> anyhow::Result<()> {
 async fn github_force_push(
     client: &GitClient,
     pr: &GitPr,
+    commit_message: &str,
     repository: &Repo,
 ) -> anyhow::Result<()> {
     let tmp_release_branch = format!("{}-tmp-{}", pr.branch(), rand::random::<u32>());
@@ -522,8 +1069,8 @@ async fn github_force_push(
     // - If we revert the last commit of the release PR branch, GitHub will close the release PR
     //   because the branch is the same as the default branch. So we can't revert the latest k-releaser commit and push the new one.
     // To learn more, see https://github.com/secana/k-releaser/issues
-    let sha =
-        github_create_release_branch(client, repository, &tmp_release_branch, &pr.title).await?;
+    let sha = github_create_release_branch(client, repository, &tmp_release_branch, commit_message)
+        .await?;
 
     let force_push_result =
         execute_github_force_push(client, pr, repository, &tmp_release_branch, &sha).await;
@@ -553,6 +1100,19 @@ async fn execute_github_force_push(
     Ok(())
 }
 
+/// Reset the PR branch to the local `HEAD` and commit the changes via the forge API.
+async fn api_force_push(
+    client: &GitClient,
+    pr: &GitPr,
+    commit_message: &str,
+    repository: &Repo,
+) -> anyhow::Result<()> {
+    forge_commit::commit_changes(client, repository, commit_message, pr.branch(), true)
+        .await
+        .context("failed to force push PR branch")?;
+    Ok(())
+}
+
 fn create_release_branch(
     repository: &Repo,
     release_branch: &str,
@@ -580,9 +1140,63 @@ async fn github_create_release_branch(
     Ok(sha)
 }
 
+/// Create the release branch and commit the changes via the GitLab or Gitea API.
+async fn api_create_release_branch(
+    client: &GitClient,
+    repository: &Repo,
+    release_branch: &str,
+    commit_message: &str,
+) -> anyhow::Result<()> {
+    let sha = repository.current_commit_hash()?;
+    client.create_branch(release_branch, &sha).await?;
+    forge_commit::commit_changes(client, repository, commit_message, release_branch, false)
+        .await
+        .with_context(|| {
+            format!("failed to create commit via the API on branch `{release_branch}`")
+        })?;
+    debug!("committed changes on branch `{release_branch}` via the API");
+    Ok(())
+}
+
 fn add_changes_and_commit(repository: &Repo, commit_message: &str) -> anyhow::Result<()> {
     let changes_expect_typechanges = repository.changes_except_typechanges()?;
     repository.add(&changes_expect_typechanges)?;
     repository.commit_signed(commit_message)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_release_commits_are_not_contributor_commits() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(temp.path()).unwrap();
+        let repo = Repo::init(root);
+        repo.git(&["checkout", "-b", "release"]).unwrap();
+        fs_err::write(root.join("CHANGELOG.md"), "# Changelog").unwrap();
+        repo.add_all_and_commit("chore: release v0.1.0").unwrap();
+        fs_err::write(root.join("Cargo.lock"), "").unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&[
+            "commit",
+            "--author=dependabot[bot] <bot@example.com>",
+            "-m",
+            "chore(deps): bump serde",
+        ])
+        .unwrap();
+        fs_err::write(root.join("CHANGELOG.md"), "# Changelog\n\nedited").unwrap();
+        repo.add_all_and_commit("docs: edit changelog").unwrap();
+
+        let commits = contributor_commits(&repo, &["chore: release v0.1.0"]).unwrap();
+        let subjects: Vec<String> = commits
+            .iter()
+            .map(|commit| repo.git(&["log", "-1", "--format=%s", commit]).unwrap())
+            .collect();
+        assert_eq!(
+            subjects,
+            ["chore(deps): bump serde", "docs: edit changelog"]
+        );
+    }
+}
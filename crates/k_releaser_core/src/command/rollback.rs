@@ -0,0 +1,211 @@
+use std::collections::BTreeSet;
+
+use anyhow::Context as _;
+use cargo_metadata::{Package, camino::Utf8Path, semver::Version};
+use git_cmd::Repo;
+use secrecy::{ExposeSecret as _, SecretString};
+use serde::Serialize;
+use tracing::{debug, info, instrument, warn};
+
+use crate::{Project, cargo::run_cargo, git::forge::GitClient, project::version_from_tag};
+
+use super::release::ReleaseRequest;
+
+/// Undo a release: delete its git tags and forge releases and,
+/// optionally, yank the published packages.
+#[derive(Debug)]
+pub struct RollbackRequest {
+    release_req: ReleaseRequest,
+    /// Version or git tag of the release to undo.
+    target: String,
+    /// Yank the packages from the cargo registry.
+    yank: bool,
+    /// Registry where the packages are published.
+    /// If unspecified, crates.io is used.
+    registry: Option<String>,
+    /// Token used to yank the packages from the cargo registry.
+    token: Option<SecretString>,
+}
+
+impl RollbackRequest {
+    pub fn new(release_req: ReleaseRequest, target: impl Into<String>) -> Self {
+        Self {
+            release_req,
+            target: target.into(),
+            yank: false,
+            registry: None,
+            token: None,
+        }
+    }
+
+    pub fn with_yank(mut self, yank: bool) -> Self {
+        self.yank = yank;
+        self
+    }
+
+    pub fn with_registry(mut self, registry: impl Into<String>) -> Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    pub fn with_token(mut self, token: impl Into<SecretString>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct RollbackOutput {
+    pub rolled_back: Vec<PackageRollback>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PackageRollback {
+    package_name: String,
+    version: Version,
+    tag: String,
+    yanked: bool,
+}
+
+/// Undo the release of the given version or tag.
+#[instrument(skip_all)]
+pub async fn rollback(input: &RollbackRequest) -> anyhow::Result<RollbackOutput> {
+    let release_req = &input.release_req;
+    let project = release_req.project()?;
+    let repo = release_req.repo()?;
+    if let Err(e) = project.fetch_release_tags(&repo) {
+        debug!("Failed to fetch tags (this is ok if there's no remote): {e}");
+    }
+
+    let version = version_from_tag(&input.target)
+        .with_context(|| format!("can't determine the version of `{}`", input.target))?;
+    let releases = releases_to_rollback(&project, &repo, &version, &input.target)?;
+    anyhow::ensure!(
+        !releases.is_empty(),
+        "no release found for `{}`",
+        input.target
+    );
+
+    let git_client = release_req.git_client()?;
+    if git_client.is_none() {
+        warn!("git token not provided: forge releases won't be deleted");
+    }
+    let tags: BTreeSet<&str> = releases.iter().map(|(_, tag)| tag.as_str()).collect();
+    for tag in tags {
+        delete_tag_and_release(release_req.is_dry_run(), &repo, git_client.as_ref(), tag).await?;
+    }
+    // Additional tags don't have a forge release.
+    let mut extra_tags = BTreeSet::new();
+    for (package, _) in &releases {
+        extra_tags.extend(project.extra_git_tags(&package.name, &version.to_string())?);
+    }
+    for tag in extra_tags {
+        if repo.tag_exists(&tag)? {
+            delete_tag_and_release(release_req.is_dry_run(), &repo, None, &tag).await?;
+        }
+    }
+
+    let mut rolled_back = vec![];
+    for (package, tag) in releases {
+        if input.yank {
+            yank_package(
+                input,
+                package,
+                &version,
+                &release_req.metadata().workspace_root,
+            )?;
+        }
+        rolled_back.push(PackageRollback {
+            package_name: package.name.to_string(),
+            version: version.clone(),
+            tag,
+            yanked: input.yank,
+        });
+    }
+    Ok(RollbackOutput { rolled_back })
+}
+
+/// Packages released with the given version, together with their git tag.
+fn releases_to_rollback<'a>(
+    project: &'a Project,
+    repo: &Repo,
+    version: &Version,
+    target: &str,
+) -> anyhow::Result<Vec<(&'a Package, String)>> {
+    let is_tag = Version::parse(target).is_err();
+    let mut releases = vec![];
+    for package in project.publishable_packages() {
+        let tag = project.git_tag(&package.name, &version.to_string())?;
+        let is_released = if is_tag {
+            tag == target
+        } else {
+            repo.tag_exists(&tag)?
+        };
+        if is_released {
+            releases.push((package, tag));
+        }
+    }
+    Ok(releases)
+}
+
+async fn delete_tag_and_release(
+    dry_run: bool,
+    repo: &Repo,
+    git_client: Option<&GitClient>,
+    tag: &str,
+) -> anyhow::Result<()> {
+    if dry_run {
+        info!("{tag}: dry run: would delete the git tag and the forge release");
+        return Ok(());
+    }
+    if let Some(git_client) = git_client {
+        if git_client.delete_release(tag).await? {
+            info!("{tag}: deleted forge release");
+        } else {
+            warn!("{tag}: forge release not found");
+        }
+    }
+    if repo.remote_tag_exists(tag)? {
+        repo.delete_tag_in_remote(tag)?;
+        info!("{tag}: deleted git tag in remote");
+    }
+    if repo.tag_exists(tag)? {
+        repo.delete_tag(tag)?;
+        info!("{tag}: deleted local git tag");
+    }
+    Ok(())
+}
+
+fn yank_package(
+    input: &RollbackRequest,
+    package: &Package,
+    version: &Version,
+    workspace_root: &Utf8Path,
+) -> anyhow::Result<()> {
+    let version = version.to_string();
+    if input.release_req.is_dry_run() {
+        info!("{}: dry run: would yank version {version}", package.name);
+        return Ok(());
+    }
+    let mut args = vec!["yank", "--version", &version];
+    if let Some(registry) = &input.registry {
+        args.push("--registry");
+        args.push(registry);
+    }
+    if let Some(token) = &input.token {
+        args.push("--token");
+        args.push(token.expose_secret());
+    }
+    args.push(&package.name);
+    let output = run_cargo(workspace_root, &args)
+        .with_context(|| format!("error while running cargo to yank {}", package.name))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "cargo yank of {} failed. stdout: {}; stderr: {}",
+        package.name,
+        output.stdout,
+        output.stderr
+    );
+    info!("{}: yanked version {version}", package.name);
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use anyhow::Context as _;
+use cargo_metadata::{Package, camino::Utf8Path, semver::Version};
+use cargo_utils::LocalManifest;
+use git_cliff_core::commit::Commit;
+use tracing::{info, instrument};
+
+use crate::{
+    CHANGELOG_FILENAME, ChangelogBuilder, ChangelogRequest, NO_COMMIT_ID, PackagePath as _,
+    changelog_parser, update_request::UpdateRequest, updater::WORKSPACE_PACKAGE_NAME,
+};
+
+use super::update::{set_version, update_cargo_lock, update_dependencies};
+
+/// Set a version chosen by the user, instead of the one
+/// determined from the conventional commits.
+#[derive(Debug, Clone)]
+pub struct SetVersionRequest {
+    update_req: UpdateRequest,
+    /// Version to set.
+    version: Version,
+    /// If present, add a changelog entry for the new version containing this message.
+    changelog_entry: Option<String>,
+}
+
+impl SetVersionRequest {
+    pub fn new(update_req: UpdateRequest, version: Version) -> Self {
+        Self {
+            update_req,
+            version,
+            changelog_entry: None,
+        }
+    }
+
+    pub fn with_changelog_entry(self, changelog_entry: String) -> Self {
+        Self {
+            changelog_entry: Some(changelog_entry),
+            ..self
+        }
+    }
+}
+
+/// Rewrite the versions of the workspace and of its packages, update the
+/// version requirements of the dependencies between them and regenerate the Cargo.lock.
+#[instrument(skip_all)]
+pub fn set_versions(input: &SetVersionRequest) -> anyhow::Result<()> {
+    let req = &input.update_req;
+    let version = &input.version;
+    anyhow::ensure!(
+        req.bump().is_none(),
+        "a version bump can't be forced when setting the version explicitly"
+    );
+    let local_manifest_path = req.local_manifest();
+    let all_packages: Vec<Package> =
+        cargo_utils::workspace_members(req.cargo_metadata())?.collect();
+    let all_packages_ref: Vec<&Package> = all_packages.iter().collect();
+    let packages: Vec<&Package> = match req.single_package() {
+        Some(name) => {
+            let package = all_packages_ref
+                .iter()
+                .find(|p| *p.name == name)
+                .with_context(|| format!("package `{name}` not found in the workspace"))?;
+            // Packages of a version group always share the same version.
+            match req.get_package_config(&package.name).version_group {
+                Some(version_group) => all_packages_ref
+                    .iter()
+                    .filter(|p| {
+                        req.get_package_config(&p.name).version_group.as_ref()
+                            == Some(&version_group)
+                    })
+                    .copied()
+                    .collect(),
+                None => vec![*package],
+            }
+        }
+        None => all_packages_ref.clone(),
+    };
+
+    let mut inherited_pkgs = vec![];
+    let mut own_version_pkgs = vec![];
+    for package in packages {
+        let manifest = LocalManifest::try_new(&package.manifest_path)?;
+        if manifest.version_is_inherited() {
+            inherited_pkgs.push(package);
+        } else {
+            own_version_pkgs.push(package);
+        }
+    }
+
+    if !inherited_pkgs.is_empty() {
+        if let Some(name) = req.single_package() {
+            anyhow::bail!(
+                "package `{name}` inherits the workspace version. Don't specify the package to set the version of the whole workspace"
+            );
+        }
+        let mut local_manifest = LocalManifest::try_new(local_manifest_path)?;
+        local_manifest.set_workspace_version(version);
+        local_manifest
+            .write()
+            .context("can't update workspace version")?;
+        for package in &inherited_pkgs {
+            update_dependencies(
+                &all_packages_ref,
+                version,
+                package.package_path()?,
+                local_manifest_path,
+            )?;
+        }
+    }
+    for package in &own_version_pkgs {
+        set_version(
+            &all_packages_ref,
+            package.package_path()?,
+            version,
+            local_manifest_path,
+        )?;
+    }
+
+    if let Some(message) = &input.changelog_entry {
+        if req.version_mode().is_independent() || req.single_package().is_some() {
+            for package in inherited_pkgs.iter().chain(&own_version_pkgs) {
+                let changelog_path = req.changelog_path(package);
+                add_changelog_entry(
+                    &changelog_path,
+                    &package.name,
+                    version,
+                    message,
+                    req.changelog_req(),
+                )?;
+            }
+        } else {
+            let changelog_path = req.local_manifest_dir()?.join(CHANGELOG_FILENAME);
+            add_changelog_entry(
+                &changelog_path,
+                WORKSPACE_PACKAGE_NAME,
+                version,
+                message,
+                req.changelog_req(),
+            )?;
+        }
+    }
+
+    update_cargo_lock(req.local_manifest_dir()?, req.should_update_dependencies())?;
+    info!("set version {version}");
+    Ok(())
+}
+
+fn add_changelog_entry(
+    changelog_path: &Utf8Path,
+    package: &str,
+    version: &Version,
+    message: &str,
+    changelog_req: &ChangelogRequest,
+) -> anyhow::Result<()> {
+    let old_changelog = if changelog_path.exists() {
+        Some(fs_err::read_to_string(changelog_path)?)
+    } else {
+        None
+    };
+    let changelog = changelog_with_entry(
+        old_changelog.as_deref(),
+        package,
+        version,
+        message,
+        changelog_req,
+    )?;
+    fs_err::write(changelog_path, changelog).context("cannot write changelog")?;
+    Ok(())
+}
+
+/// Return the changelog with a new entry for `version` containing `message`.
+fn changelog_with_entry(
+    old_changelog: Option<&str>,
+    package: &str,
+    version: &Version,
+    message: &str,
+    changelog_req: &ChangelogRequest,
+) -> anyhow::Result<String> {
+    let commits = vec![Commit::new(NO_COMMIT_ID.to_string(), message.to_string())];
+    let mut changelog_builder = ChangelogBuilder::new(commits, version.to_string(), package);
+    if let Some(release_date) = changelog_req.release_date {
+        changelog_builder = changelog_builder.with_release_date(release_date);
+    }
+    if let Some(config) = changelog_req.changelog_config.clone() {
+        changelog_builder = changelog_builder.with_config(config);
+    }
+    let last_version = old_changelog
+        .and_then(|old_changelog| changelog_parser::last_version_from_str(old_changelog).ok())
+        .flatten();
+    if let Some(last_version) = last_version {
+        changelog_builder = changelog_builder.with_previous_version(last_version);
+    }
+    let new_changelog = changelog_builder.build();
+    match old_changelog {
+        Some(old_changelog) => new_changelog.prepend(old_changelog),
+        None => new_changelog.generate(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::CHANGELOG_HEADER;
+
+    #[test]
+    fn changelog_entry_is_prepended() {
+        let old_body = r"## [1.0.0] - 2024-01-01
+
+### Added
+
+- first release
+";
+        let old_changelog = format!("{CHANGELOG_HEADER}\n{old_body}");
+        let changelog_req = ChangelogRequest {
+            release_date: NaiveDate::from_ymd_opt(2025, 3, 1),
+            changelog_config: None,
+            group_by_package: false,
+        };
+        let changelog = changelog_with_entry(
+            Some(&old_changelog),
+            WORKSPACE_PACKAGE_NAME,
+            &Version::new(2, 0, 0),
+            "move to the new release process",
+            &changelog_req,
+        )
+        .unwrap();
+        expect_test::expect![[r"
+            # Changelog
+
+            All notable changes to this project will be documented in this file.
+
+            The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.0.0/),
+            and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+
+            ## [Unreleased]
+
+            ## [2.0.0] - 2025-03-01
+
+            ### Other
+
+            - move to the new release process
+
+            ## [1.0.0] - 2024-01-01
+
+            ### Added
+
+            - first release
+        "]]
+        .assert_eq(&changelog);
+    }
+}
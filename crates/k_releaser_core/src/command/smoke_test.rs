@@ -0,0 +1,72 @@
+use cargo_metadata::camino::Utf8Path;
+use cargo_utils::CARGO_TOML;
+use serde::Serialize;
+use tracing::info;
+
+use crate::{cargo::run_cargo, fs_utils::Utf8TempDir};
+
+/// Name of the temporary project depending on the published package.
+const SMOKE_TEST_PROJECT: &str = "k-releaser-smoke-test";
+
+/// Outcome of the check that a published package can be used as a dependency.
+#[derive(Serialize, Debug)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SmokeTestStatus {
+    Passed,
+    Failed { error: String },
+}
+
+/// Check that `name` at `version` can be used as a dependency: add it to a new project
+/// from `registry` and run `cargo check`.
+/// The project is created in `workspace_root`, so that cargo reads the configuration
+/// of the workspace, e.g. the registries of its `.cargo/config.toml`.
+pub(crate) fn smoke_test(
+    workspace_root: &Utf8Path,
+    name: &str,
+    version: &str,
+    registry: &str,
+) -> SmokeTestStatus {
+    match run_smoke_test(workspace_root, name, version, registry) {
+        Ok(()) => {
+            info!("{name} {version}: smoke test passed");
+            SmokeTestStatus::Passed
+        }
+        Err(e) => SmokeTestStatus::Failed {
+            error: format!("{e:#}"),
+        },
+    }
+}
+
+fn run_smoke_test(
+    workspace_root: &Utf8Path,
+    name: &str,
+    version: &str,
+    registry: &str,
+) -> anyhow::Result<()> {
+    let project = Utf8TempDir::new_in(workspace_root, &format!(".{SMOKE_TEST_PROJECT}-"))?;
+    let project = project.path();
+    // `cargo new` would add the project to the members of the workspace.
+    // The empty `[workspace]` table keeps the project out of the workspace.
+    fs_err::write(
+        project.join(CARGO_TOML),
+        format!(
+            "[package]\nname = \"{SMOKE_TEST_PROJECT}\"\nversion = \"0.0.0\"\nedition = \"2021\"\npublish = false\n\n[workspace]\n"
+        ),
+    )?;
+    fs_err::create_dir(project.join("src"))?;
+    fs_err::write(project.join("src").join("lib.rs"), "")?;
+    let dependency = format!("{name}@={version}");
+    cargo(project, &["add", &dependency, "--registry", registry])?;
+    cargo(project, &["check", "--quiet"])
+}
+
+fn cargo(dir: &Utf8Path, args: &[&str]) -> anyhow::Result<()> {
+    let output = run_cargo(dir, args)?;
+    anyhow::ensure!(
+        output.status.success(),
+        "`cargo {}` failed: {}",
+        args.join(" "),
+        output.stderr
+    );
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use anyhow::Context as _;
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    semver::Version,
+};
+use regex::Regex;
+use tracing::{info, warn};
+
+use crate::tera::{render_template, tera_context};
+
+use super::PackagesUpdate;
+
+const PREVIOUS_VERSION_VAR: &str = "previous_version";
+
+/// File containing a version that isn't managed by cargo, e.g. `helm/Chart.yaml`.
+/// k-releaser rewrites the version when it updates the packages.
+#[derive(Debug, Clone)]
+pub struct ExtraVersionFile {
+    /// Path of the file, relative to the workspace root.
+    path: Utf8PathBuf,
+    /// Regex matching the text to replace.
+    /// If [`Option::None`], the previous version is replaced, if it appears only once.
+    search: Option<Regex>,
+    /// Tera template of the replacement text. Defaults to `{{ version }}`.
+    /// It can reference the capture groups of `search`, e.g. `${1}`.
+    replace: Option<String>,
+    /// Package whose version is written.
+    /// If [`Option::None`], the workspace version is written.
+    package: Option<String>,
+}
+
+impl ExtraVersionFile {
+    pub fn new(path: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            search: None,
+            replace: None,
+            package: None,
+        }
+    }
+
+    pub fn with_search(mut self, search: &str) -> anyhow::Result<Self> {
+        let regex = Regex::new(search)
+            .with_context(|| format!("invalid search regex of extra version file {}", self.path))?;
+        self.search = Some(regex);
+        Ok(self)
+    }
+
+    pub fn with_replace(mut self, replace: impl Into<String>) -> Self {
+        self.replace = Some(replace.into());
+        self
+    }
+
+    pub fn with_package(mut self, package: impl Into<String>) -> Self {
+        self.package = Some(package.into());
+        self
+    }
+
+    /// Return the content of the file with the version replaced.
+    fn replace_version(
+        &self,
+        content: &str,
+        package: &str,
+        previous_version: &Version,
+        next_version: &Version,
+    ) -> anyhow::Result<String> {
+        let mut context = tera_context(package, &next_version.to_string());
+        context.insert(PREVIOUS_VERSION_VAR, &previous_version.to_string());
+        let replacement = match &self.replace {
+            Some(template) => render_template(template, &context, "extra_version_file")?,
+            None => next_version.to_string(),
+        };
+        let new_content = match &self.search {
+            Some(search) => search
+                .replace_all(content, replacement.as_str())
+                .into_owned(),
+            None => {
+                let previous_version = previous_version.to_string();
+                let occurrences = version_occurrences(content, &previous_version);
+                match occurrences.as_slice() {
+                    [] => content.to_string(),
+                    [start] => format!(
+                        "{}{replacement}{}",
+                        &content[..*start],
+                        &content[start + previous_version.len()..]
+                    ),
+                    _ => anyhow::bail!(
+                        "extra version file {}: version {previous_version} appears {} times: set `search` to match the one to replace",
+                        self.path,
+                        occurrences.len()
+                    ),
+                }
+            }
+        };
+        Ok(new_content)
+    }
+}
+
+/// Byte offsets of the occurrences of `version` that aren't part of a longer version,
+/// e.g. `0.1.0` isn't found in `10.1.0`, `0.1.0.1` or `0.1.0-beta`.
+fn version_occurrences(content: &str, version: &str) -> Vec<usize> {
+    let is_version_char = |c: char| c.is_ascii_digit() || c == '.';
+    content
+        .match_indices(version)
+        .map(|(start, _)| start)
+        .filter(|&start| {
+            let before = content[..start].chars().next_back();
+            let after = content[start + version.len()..].chars().next();
+            !before.is_some_and(is_version_char)
+                && !after.is_some_and(|c| is_version_char(c) || c == '-' || c == '+')
+        })
+        .collect()
+}
+
+/// Write the next versions in the extra version files.
+pub(crate) fn update_extra_version_files(
+    files: &[ExtraVersionFile],
+    local_manifest_dir: &Utf8Path,
+    packages_to_update: &PackagesUpdate,
+    is_independent: bool,
+) -> anyhow::Result<()> {
+    let updates = packages_to_update.updates();
+    for file in files {
+        let versions = match &file.package {
+            Some(name) => updates
+                .iter()
+                .find(|(p, _)| p.name.as_str() == name)
+                .map(|(p, update)| (name.as_str(), &p.version, &update.version)),
+            None if is_independent => {
+                warn!(
+                    "extra version file {}: `package` is required with independent versioning, skipping",
+                    file.path
+                );
+                continue;
+            }
+            None => updates.first().map(|(p, update)| {
+                let next_version = packages_to_update
+                    .workspace_version()
+                    .unwrap_or(&update.version);
+                ("workspace", &p.version, next_version)
+            }),
+        };
+        let Some((package, previous_version, next_version)) = versions else {
+            continue;
+        };
+
+        let path = local_manifest_dir.join(&file.path);
+        let content = fs_err::read_to_string(&path)
+            .with_context(|| format!("can't read extra version file {}", file.path))?;
+        let new_content =
+            file.replace_version(&content, package, previous_version, next_version)?;
+        if new_content == content {
+            warn!(
+                "extra version file {}: no version to replace was found",
+                file.path
+            );
+            continue;
+        }
+        fs_err::write(&path, new_content)?;
+        info!("{}: updated version to {next_version}", file.path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(file: &ExtraVersionFile, content: &str) -> String {
+        let previous = Version::new(0, 1, 0);
+        let next = Version::new(0, 2, 0);
+        file.replace_version(content, "my_crate", &previous, &next)
+            .unwrap()
+    }
+
+    #[test]
+    fn previous_version_is_replaced_by_default() {
+        let file = ExtraVersionFile::new("package.json");
+        let content = r#"{ "name": "my_crate", "version": "0.1.0" }"#;
+        assert_eq!(
+            replace(&file, content),
+            r#"{ "name": "my_crate", "version": "0.2.0" }"#
+        );
+    }
+
+    #[test]
+    fn previous_version_is_replaced_only_if_unique() {
+        let file = ExtraVersionFile::new("package.json");
+        let content = r#"{ "version": "0.1.0", "engine": "10.1.0", "next": "0.1.0-beta" }"#;
+        assert_eq!(
+            replace(&file, content),
+            r#"{ "version": "0.2.0", "engine": "10.1.0", "next": "0.1.0-beta" }"#
+        );
+
+        let content = r#"{ "version": "0.1.0", "dependencies": { "left-pad": "0.1.0" } }"#;
+        let previous = Version::new(0, 1, 0);
+        let next = Version::new(0, 2, 0);
+        let error = file
+            .replace_version(content, "my_crate", &previous, &next)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "extra version file package.json: version 0.1.0 appears 2 times: set `search` to match the one to replace"
+        );
+    }
+
+    #[test]
+    fn search_regex_is_replaced_with_template() {
+        let file = ExtraVersionFile::new("helm/Chart.yaml")
+            .with_search(r"(?m)^(appVersion: ).*$")
+            .unwrap()
+            .with_replace(r#"${1}"{{ version }}""#);
+        let content = "version: 1.0.0\nappVersion: \"0.1.0\"\n";
+        assert_eq!(
+            replace(&file, content),
+            "version: 1.0.0\nappVersion: \"0.2.0\"\n"
+        );
+    }
+}
@@ -1,10 +1,15 @@
+mod extra_version_files;
 mod packages_update;
 mod update_config;
 pub mod update_request;
 pub mod updater;
 
+use crate::hooks::{HookEnv, run_hook};
 use crate::{PackagePath, tmp_repo::TempRepo};
-use crate::{fs_utils, root_repo_path_from_manifest_dir};
+use crate::{
+    copy_to_temp_dir, fs_utils, new_manifest_dir_path, new_project_root,
+    root_repo_path_from_manifest_dir,
+};
 use anyhow::Context;
 use cargo_metadata::camino::Utf8Path;
 use cargo_metadata::{Package, semver::Version};
@@ -18,6 +23,7 @@ use update_request::UpdateRequest;
 
 use tracing::{debug, instrument};
 
+pub use extra_version_files::ExtraVersionFile;
 pub use packages_update::*;
 pub use update_config::*;
 
@@ -46,11 +52,30 @@ pub async fn update(input: &UpdateRequest) -> anyhow::Result<(PackagesUpdate, Te
     // workspace dependencies.
     let all_packages: Vec<Package> = cargo_utils::workspace_members(&local_metadata)?.collect();
     let all_packages_ref: Vec<&Package> = all_packages.iter().collect();
+    let hooks = input.hooks();
+    run_update_hook(
+        input,
+        "pre_update",
+        hooks.pre_update.as_deref(),
+        &packages_to_update,
+    )?;
     update_manifests(&packages_to_update, local_manifest_path, &all_packages_ref)?;
     update_changelogs(input, &packages_to_update)?;
     if !packages_to_update.updates().is_empty() {
         let local_manifest_dir = input.local_manifest_dir()?;
+        extra_version_files::update_extra_version_files(
+            input.extra_version_files(),
+            local_manifest_dir,
+            &packages_to_update,
+            input.version_mode().is_independent(),
+        )?;
         update_cargo_lock(local_manifest_dir, input.should_update_dependencies())?;
+        run_update_hook(
+            input,
+            "post_update",
+            hooks.post_update.as_deref(),
+            &packages_to_update,
+        )?;
 
         let local_repo_root = root_repo_path_from_manifest_dir(local_manifest_dir)?;
         let there_are_commits_to_push = Repo::new(local_repo_root)?.is_clean().is_err();
@@ -62,6 +87,70 @@ pub async fn update(input: &UpdateRequest) -> anyhow::Result<(PackagesUpdate, Te
     Ok((packages_to_update, repository))
 }
 
+/// Compute the changes of [`update`] without touching the local project:
+/// the update runs in a copy of the repository.
+/// Returns the unified diff of the files that the update would change.
+#[instrument(skip_all)]
+pub async fn update_dry_run(input: &UpdateRequest) -> anyhow::Result<(PackagesUpdate, String)> {
+    let manifest_dir = input.local_manifest_dir()?;
+    let original_project_root = root_repo_path_from_manifest_dir(manifest_dir)?;
+    let tmp_project_root_parent = copy_to_temp_dir(&original_project_root)?;
+    let tmp_project_manifest_dir = new_manifest_dir_path(
+        &original_project_root,
+        manifest_dir,
+        tmp_project_root_parent.path(),
+    )?;
+    let tmp_project_root =
+        new_project_root(&original_project_root, tmp_project_root_parent.path())?;
+    let tmp_update_request = input
+        .clone()
+        .set_local_manifest(tmp_project_manifest_dir.join(CARGO_TOML))
+        .context("can't find temporary project")?;
+    let (packages_to_update, _repository) = update(&tmp_update_request).await?;
+
+    let repo = Repo::new(tmp_project_root)?;
+    // Include the new files in the diff, e.g. a new changelog.
+    repo.git(&["add", "--all", "--intent-to-add"])?;
+    let diff = repo.git(&["diff", "--no-color"])?;
+    Ok((packages_to_update, diff))
+}
+
+/// Run an update hook in the local workspace, so that the files it changes are part of the update.
+/// With unified versioning the hook runs once for the whole workspace,
+/// otherwise it runs once per updated package.
+fn run_update_hook(
+    input: &UpdateRequest,
+    name: &str,
+    command: Option<&str>,
+    packages_to_update: &PackagesUpdate,
+) -> anyhow::Result<()> {
+    let updates = packages_to_update.updates();
+    let Some((_, first_update)) = updates.first() else {
+        return Ok(());
+    };
+    let local_manifest_dir = input.local_manifest_dir()?;
+    if !input.version_mode().is_independent() {
+        let next_version = packages_to_update
+            .workspace_version()
+            .unwrap_or(&first_update.version);
+        let env = HookEnv {
+            package: "workspace",
+            next_version: next_version.to_string(),
+            tag: None,
+        };
+        return run_hook(name, command, local_manifest_dir, &env);
+    }
+    for (package, update) in updates {
+        let env = HookEnv {
+            package: &package.name,
+            next_version: update.version.to_string(),
+            tag: None,
+        };
+        run_hook(name, command, local_manifest_dir, &env)?;
+    }
+    Ok(())
+}
+
 fn update_manifests(
     packages_to_update: &PackagesUpdate,
     local_manifest_path: &Utf8Path,
@@ -137,7 +226,10 @@ fn update_changelogs(
 }
 
 #[instrument(skip_all)]
-fn update_cargo_lock(root: &Utf8Path, update_all_dependencies: bool) -> anyhow::Result<()> {
+pub(crate) fn update_cargo_lock(
+    root: &Utf8Path,
+    update_all_dependencies: bool,
+) -> anyhow::Result<()> {
     let mut args = vec!["update"];
     if !update_all_dependencies {
         args.push("--workspace");
@@ -197,7 +289,7 @@ pub fn set_version(
 /// pkg1 = { path = "../pkg1", version = "1.2.4" }
 /// ```
 ///
-fn update_dependencies(
+pub(crate) fn update_dependencies(
     all_packages: &[&Package],
     version: &Version,
     package_path: &Utf8Path,
@@ -18,6 +18,8 @@ pub struct UpdateConfig {
     pub features_always_increment_minor: bool,
     /// Template for the git tag created by k-releaser.
     pub tag_name_template: Option<String>,
+    /// If `false`, the package isn't updated, as if it wasn't part of the workspace.
+    pub release: bool,
 }
 
 /// Package-specific config
@@ -59,6 +61,7 @@ impl Default for UpdateConfig {
             features_always_increment_minor: false,
             tag_name_template: None,
             changelog_path: None,
+            release: true,
         }
     }
 }
@@ -88,6 +91,10 @@ impl UpdateConfig {
         }
     }
 
+    pub fn with_release(self, release: bool) -> Self {
+        Self { release, ..self }
+    }
+
     pub fn version_updater(&self) -> VersionUpdater {
         VersionUpdater::default()
             .with_features_always_increment_minor(self.features_always_increment_minor)
@@ -8,11 +8,18 @@ use cargo_metadata::{
     Metadata, Package,
     camino::{Utf8Path, Utf8PathBuf},
 };
+use next_version::VersionIncrement;
 use regex::Regex;
 
-use crate::{ChangelogRequest, GitClient, GitForge, PackagePath as _, RepoUrl, fs_utils};
+use crate::{
+    ChangelogRequest, FetchDepthStrategy, GitClient, GitForge, Hooks, PackagePath as _, RepoUrl,
+    VersionMode, fs_utils,
+};
 
-use super::update_config::{PackageUpdateConfig, UpdateConfig};
+use super::{
+    ExtraVersionFile,
+    update_config::{PackageUpdateConfig, UpdateConfig},
+};
 
 pub const DEFAULT_MAX_ANALYZE_COMMITS: u32 = 1000;
 
@@ -46,8 +53,38 @@ pub struct UpdateRequest {
     /// Release Commits
     /// Prepare release only if at least one commit respects a regex.
     release_commits: Option<Regex>,
+    /// Commits that only change files matching these globs don't trigger a release.
+    release_ignore_paths: Vec<glob::Pattern>,
     git: Option<GitForge>,
     max_analyze_commits: Option<u32>,
+    /// Whether packages share the workspace version or are versioned independently.
+    version_mode: VersionMode,
+    /// Branch the packages are released from, available as `{{ branch }}` in the tag templates.
+    branch: Option<String>,
+    /// Template of the workspace git tag with unified versioning. Defaults to `v{{ version }}`.
+    workspace_tag_name: Option<String>,
+    /// Version increment requested by the user.
+    /// If present, it's used instead of the one determined from the commits.
+    bump: Option<VersionIncrement>,
+    /// If present, release pre-release versions of this channel, e.g. `beta` -> `1.4.0-beta.1`.
+    prerelease_label: Option<String>,
+    /// If `true`, only the patch version is incremented, e.g. on a maintenance branch.
+    patch_only: bool,
+    /// Shell commands to run before and after the update.
+    hooks: Hooks,
+    /// Files outside of the cargo manifests where the version is updated.
+    extra_version_files: Vec<ExtraVersionFile>,
+    /// Remote where tags are pushed to.
+    /// If [`Option::None`], the remote of the current branch is used.
+    git_remote: Option<String>,
+    /// What to do if the repository is a shallow clone.
+    fetch_depth_strategy: FetchDepthStrategy,
+    /// Collect the commits of each package from `git log` instead of checking out
+    /// each previous commit.
+    no_checkout_diff: bool,
+    /// With unified versioning, only consider the commits changing the workspace directory
+    /// and the tags of its releases, because the repository contains other workspaces.
+    workspace_scoped: bool,
 }
 
 impl UpdateRequest {
@@ -66,8 +103,21 @@ impl UpdateRequest {
             repo_url: None,
             packages_config: PackagesConfig::default(),
             release_commits: None,
+            release_ignore_paths: vec![],
             git: None,
             max_analyze_commits: None,
+            version_mode: VersionMode::default(),
+            branch: None,
+            workspace_tag_name: None,
+            bump: None,
+            prerelease_label: None,
+            patch_only: false,
+            hooks: Hooks::default(),
+            extra_version_files: vec![],
+            git_remote: None,
+            fetch_depth_strategy: FetchDepthStrategy::default(),
+            no_checkout_diff: false,
+            workspace_scoped: false,
         })
     }
 
@@ -122,6 +172,88 @@ impl UpdateRequest {
         }
     }
 
+    pub fn with_version_mode(self, version_mode: VersionMode) -> Self {
+        Self {
+            version_mode,
+            ..self
+        }
+    }
+
+    pub fn version_mode(&self) -> VersionMode {
+        self.version_mode
+    }
+
+    pub fn with_branch(self, branch: String) -> Self {
+        Self {
+            branch: Some(branch),
+            ..self
+        }
+    }
+
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+
+    pub fn with_workspace_tag_name(self, workspace_tag_name: String) -> Self {
+        Self {
+            workspace_tag_name: Some(workspace_tag_name),
+            ..self
+        }
+    }
+
+    pub fn workspace_tag_name(&self) -> Option<&str> {
+        self.workspace_tag_name.as_deref()
+    }
+
+    pub fn with_bump(self, bump: VersionIncrement) -> Self {
+        Self {
+            bump: Some(bump),
+            ..self
+        }
+    }
+
+    pub fn bump(&self) -> Option<VersionIncrement> {
+        self.bump
+    }
+
+    pub fn with_prerelease_label(self, prerelease_label: String) -> Self {
+        Self {
+            prerelease_label: Some(prerelease_label),
+            ..self
+        }
+    }
+
+    pub fn prerelease_label(&self) -> Option<&str> {
+        self.prerelease_label.as_deref()
+    }
+
+    pub fn with_patch_only(self, patch_only: bool) -> Self {
+        Self { patch_only, ..self }
+    }
+
+    pub fn patch_only(&self) -> bool {
+        self.patch_only
+    }
+
+    pub fn with_hooks(self, hooks: Hooks) -> Self {
+        Self { hooks, ..self }
+    }
+
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
+    pub fn with_extra_version_files(self, extra_version_files: Vec<ExtraVersionFile>) -> Self {
+        Self {
+            extra_version_files,
+            ..self
+        }
+    }
+
+    pub fn extra_version_files(&self) -> &[ExtraVersionFile] {
+        &self.extra_version_files
+    }
+
     pub fn with_registry_manifest_path(self, registry_manifest: &Utf8Path) -> anyhow::Result<Self> {
         let registry_manifest = fs_utils::canonicalize_utf8(registry_manifest)?;
         Ok(Self {
@@ -191,6 +323,23 @@ impl UpdateRequest {
         })
     }
 
+    pub fn with_release_ignore_paths(
+        self,
+        release_ignore_paths: &[String],
+    ) -> anyhow::Result<Self> {
+        let release_ignore_paths = release_ignore_paths
+            .iter()
+            .map(|p| {
+                glob::Pattern::new(p)
+                    .with_context(|| format!("invalid release_ignore_paths glob `{p}`"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            release_ignore_paths,
+            ..self
+        })
+    }
+
     pub fn local_manifest_dir(&self) -> anyhow::Result<&Utf8Path> {
         self.local_manifest
             .parent()
@@ -231,6 +380,58 @@ impl UpdateRequest {
         self.repo_url.as_ref()
     }
 
+    pub fn with_git_remote(self, git_remote: String) -> Self {
+        Self {
+            git_remote: Some(git_remote),
+            ..self
+        }
+    }
+
+    pub fn git_remote(&self) -> Option<&str> {
+        self.git_remote.as_deref()
+    }
+
+    pub fn with_fetch_depth_strategy(self, fetch_depth_strategy: FetchDepthStrategy) -> Self {
+        Self {
+            fetch_depth_strategy,
+            ..self
+        }
+    }
+
+    pub fn fetch_depth_strategy(&self) -> FetchDepthStrategy {
+        self.fetch_depth_strategy
+    }
+
+    /// Don't check out previous commits to determine which commits changed a package.
+    /// This avoids downloading the blobs of every commit in partial clones,
+    /// but the files of a package are approximated with its directory, instead of
+    /// the files listed by `cargo package`.
+    pub fn with_no_checkout_diff(self, no_checkout_diff: bool) -> Self {
+        Self {
+            no_checkout_diff,
+            ..self
+        }
+    }
+
+    pub fn no_checkout_diff(&self) -> bool {
+        self.no_checkout_diff
+    }
+
+    /// Release the workspace independently of the other workspaces of the repository.
+    /// With unified versioning, the commits outside of the workspace directory are ignored
+    /// and the version is compared with the latest tag of the workspace, instead of
+    /// the latest tag of the repository.
+    pub fn with_workspace_scoped(self, workspace_scoped: bool) -> Self {
+        Self {
+            workspace_scoped,
+            ..self
+        }
+    }
+
+    pub fn workspace_scoped(&self) -> bool {
+        self.workspace_scoped
+    }
+
     pub fn packages_config(&self) -> &PackagesConfig {
         &self.packages_config
     }
@@ -246,6 +447,10 @@ impl UpdateRequest {
     pub fn release_commits(&self) -> Option<&Regex> {
         self.release_commits.as_ref()
     }
+
+    pub fn release_ignore_paths(&self) -> &[glob::Pattern] {
+        &self.release_ignore_paths
+    }
 }
 
 #[derive(Debug, Clone, Default)]
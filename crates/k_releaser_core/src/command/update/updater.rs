@@ -7,28 +7,37 @@ use anyhow::Context as _;
 use cargo_metadata::{
     Package,
     camino::{Utf8Path, Utf8PathBuf},
-    semver::Version,
+    semver::{Prerelease, Version},
 };
-use cargo_utils::LocalManifest;
+use cargo_utils::{CARGO_TOML, LocalManifest};
 use git_cliff_core::{
     config::{ChangelogConfig, Config},
     contributor::RemoteContributor,
 };
 use git_cmd::Repo;
-use next_version::VersionUpdater;
+use next_version::{NextVersion as _, VersionIncrement, VersionUpdater};
 use rayon::iter::{IntoParallelRefMutIterator as _, ParallelIterator as _};
 use tracing::{debug, info, instrument, warn};
 
 use crate::{
-    ChangelogBuilder, ChangelogRequest, PackagePath as _, Project, Remote, RepoUrl, UpdateResult,
+    ChangelogBuilder, ChangelogRequest, NO_COMMIT_ID, PackagePath as _, Project, Remote, RepoUrl,
+    UpdateResult,
     changelog_filler::{fill_commit, get_required_info},
     changelog_parser,
     diff::{Commit, Diff},
     fs_utils,
+    semver_check::{is_cargo_semver_checks_installed, run_semver_check},
 };
 
 use super::{PackagesUpdate, update_request::UpdateRequest};
 
+/// Package name used in templates when the whole workspace is released at once.
+pub(crate) const WORKSPACE_PACKAGE_NAME: &str = "workspace";
+
+/// Package of the commits that don't change any package, when the changelog is grouped
+/// by package.
+const WORKSPACE_WIDE_PACKAGE: &str = "workspace-wide";
+
 #[derive(Debug)]
 pub struct Updater<'a> {
     pub project: &'a Project,
@@ -42,14 +51,18 @@ impl Updater<'_> {
         repository: &Repo,
         local_manifest_path: &Utf8Path,
     ) -> anyhow::Result<PackagesUpdate> {
-        debug!("calculating unified workspace version");
-
         // Fetch tags from remote to ensure we have the latest tag information
         // This is critical for determining commits since last release
-        if let Err(e) = repository.git(&["fetch", "--tags"]) {
+        if let Err(e) = self.project.fetch_release_tags(repository) {
             debug!("Failed to fetch tags (this is ok if there's no remote): {e}");
         }
 
+        if self.project.version_mode().is_independent() {
+            return self.independent_packages_to_update(repository).await;
+        }
+
+        debug!("calculating unified workspace version");
+
         // For unified workspace versioning: get ALL commits from the entire repository
         // Not filtered by package paths - we treat the whole workspace as one unit
         let local_manifest = LocalManifest::try_new(local_manifest_path)?;
@@ -62,12 +75,18 @@ impl Updater<'_> {
         };
 
         let mut all_commits = self.get_all_commits_since_latest_tag(repository)?;
-        let git_tag = self.project.git_tag(&current_version.to_string())?;
+        let git_tag = self
+            .project
+            .git_tag(WORKSPACE_PACKAGE_NAME, &current_version.to_string())?;
         let tag_exists = repository.get_tag_commit(&git_tag).is_some();
 
         // Get package diffs for semver checking purposes only
         let packages_diffs = self.get_packages_diffs(repository).await?;
 
+        if !self.req.release_ignore_paths().is_empty() {
+            all_commits.retain(|commit| !self.changes_only_ignored_paths(repository, commit));
+        }
+
         // Filter commits based on release_commits regex if configured
         if let Some(release_commits_regex) = self.req.release_commits() {
             let original_count = all_commits.len();
@@ -114,6 +133,7 @@ impl Updater<'_> {
                 &filled_commits,
                 &workspace_version,
                 local_manifest_path,
+                repository,
             )?;
 
             // Apply the SAME version and SAME changelog to ALL packages
@@ -146,6 +166,160 @@ impl Updater<'_> {
         Ok(packages_to_update)
     }
 
+    /// Calculate next version and changelog of each package independently.
+    /// Only the commits that changed the package since its own git tag are considered.
+    /// Packages of the same version group share the next version and the commits of the group.
+    async fn independent_packages_to_update(
+        &self,
+        repository: &Repo,
+    ) -> anyhow::Result<PackagesUpdate> {
+        debug!("calculating independent package versions");
+        let mut packages_diffs = self.get_packages_diffs(repository).await?;
+        for (_, diff) in &mut packages_diffs {
+            // Release PR commits belong to the previous release of the package.
+            diff.commits
+                .retain(|commit| !is_release_pr_commit(&commit.message));
+            if !self.req.release_ignore_paths().is_empty() {
+                diff.commits
+                    .retain(|commit| !self.changes_only_ignored_paths(repository, commit));
+            }
+            if let Some(release_commits_regex) = self.req.release_commits() {
+                diff.commits
+                    .retain(|commit| release_commits_regex.is_match(&commit.message));
+            }
+        }
+        let version_groups = self.version_groups(&packages_diffs);
+        let mut packages_to_update = PackagesUpdate::default();
+
+        for (p, diff) in &packages_diffs {
+            let package_config = self.req.get_package_config(&p.name);
+            let (commits, current_version) = match package_config
+                .version_group
+                .as_ref()
+                .and_then(|group| version_groups.get(group))
+            {
+                Some((group_diff, group_version)) => (&group_diff.commits, group_version),
+                None => (&diff.commits, &p.version),
+            };
+            if commits.is_empty() {
+                info!("{}: no commits since last tag - no updates needed", p.name);
+                continue;
+            }
+
+            let local_manifest = LocalManifest::try_new(&p.manifest_path)?;
+            anyhow::ensure!(
+                !local_manifest.version_is_inherited(),
+                "package `{}` inherits the workspace version, which isn't supported with `version_mode = \"independent\"`. Set the package version explicitly in its Cargo.toml",
+                p.name
+            );
+
+            let next_version = match self.req.bump() {
+                Some(bump) => forced_bump(bump, current_version)?,
+                None => package_config
+                    .generic
+                    .version_updater()
+                    .increment(current_version, commits.iter().map(|c| &c.message)),
+            };
+            let next_version = if self.req.patch_only() {
+                patch_only_version(current_version, &next_version)
+            } else {
+                next_version
+            };
+            let next_version = match self.req.prerelease_label() {
+                Some(label) => prerelease_version(current_version, &next_version, label)?,
+                None => next_version,
+            };
+            info!("{}: next version {next_version}", p.name);
+
+            let changelog_path = self.req.changelog_path(p);
+            let old_changelog = if changelog_path.exists() {
+                Some(fs_err::read_to_string(&changelog_path)?)
+            } else {
+                None
+            };
+            let repo_url = self.req.repo_url();
+            let release_link = {
+                let prev_tag = self.project.git_tag(&p.name, &p.version.to_string())?;
+                let next_tag = self.project.git_tag(&p.name, &next_version.to_string())?;
+                repo_url.map(|r| r.git_release_link(&prev_tag, &next_tag))
+            };
+            let (changelog, new_changelog_entry) = get_changelog(
+                commits,
+                &next_version,
+                Some(self.req.changelog_req().clone()),
+                old_changelog.as_deref(),
+                repo_url,
+                release_link.as_deref(),
+                p,
+            )
+            .with_context(|| format!("failed to generate changelog of package {}", p.name))?;
+
+            let update_result = UpdateResult {
+                version: next_version,
+                changelog: package_config
+                    .should_update_changelog()
+                    .then_some(changelog),
+                semver_check: diff.semver_check.clone(),
+                new_changelog_entry: Some(new_changelog_entry),
+            };
+            packages_to_update
+                .updates_mut()
+                .push(((*p).clone(), update_result));
+        }
+
+        Ok(packages_to_update)
+    }
+
+    /// Commits and highest current version of each version group.
+    /// Whether all the files changed by `commit` match `release_ignore_paths`,
+    /// e.g. a commit that only updates the documentation.
+    fn changes_only_ignored_paths(&self, repository: &Repo, commit: &Commit) -> bool {
+        let files = match repository.files_of_commit(&commit.id) {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("failed to get changed files of commit {}: {e:?}", commit.id);
+                return false;
+            }
+        };
+        // E.g. merge commits don't list the changed files.
+        if files.is_empty() {
+            return false;
+        }
+        let ignored = files.iter().all(|file| {
+            self.req
+                .release_ignore_paths()
+                .iter()
+                .any(|pattern| pattern.matches(file.as_str()))
+        });
+        if ignored {
+            debug!(
+                "skipping commit {} because it only changes ignored paths",
+                commit.id
+            );
+        }
+        ignored
+    }
+
+    fn version_groups(
+        &self,
+        packages_diffs: &[(&Package, Diff)],
+    ) -> HashMap<String, (Diff, Version)> {
+        let mut version_groups: HashMap<String, (Diff, Version)> = HashMap::new();
+        for (p, diff) in packages_diffs {
+            let Some(group) = self.req.get_package_config(&p.name).version_group else {
+                continue;
+            };
+            let (group_diff, group_version) = version_groups
+                .entry(group)
+                .or_insert_with(|| (Diff::new(), p.version.clone()));
+            group_diff.add_commits(&diff.commits);
+            if p.version > *group_version {
+                *group_version = p.version.clone();
+            }
+        }
+        version_groups
+    }
+
     /// Calculate the unified workspace version based on ALL commits from ALL packages.
     /// This is the core of unified workspace versioning - one version for entire monorepo.
     fn calculate_unified_workspace_version(
@@ -177,7 +351,10 @@ impl Updater<'_> {
         );
 
         // Calculate next version based on ALL commits
-        let next_version = if all_commits.is_empty() {
+        let next_version = if let Some(bump) = self.req.bump() {
+            // The user forced the version increment
+            forced_bump(bump, &current_workspace_version)?
+        } else if all_commits.is_empty() {
             // No commits, keep current version
             current_workspace_version.clone()
         } else {
@@ -187,6 +364,16 @@ impl Updater<'_> {
                 all_commits.iter().map(|c| &c.message),
             )
         };
+        let next_version = if self.req.patch_only() {
+            patch_only_version(&current_workspace_version, &next_version)
+        } else {
+            next_version
+        };
+
+        let next_version = match self.req.prerelease_label() {
+            Some(label) => prerelease_version(&current_workspace_version, &next_version, label)?,
+            None => next_version,
+        };
 
         Ok(next_version)
     }
@@ -198,6 +385,7 @@ impl Updater<'_> {
         all_commits: &[Commit],
         workspace_version: &Version,
         local_manifest_path: &Utf8Path,
+        repository: &Repo,
     ) -> anyhow::Result<(Option<String>, Option<String>)> {
         // Get workspace-level changelog path (defaults to ./CHANGELOG.md at workspace root)
         let workspace_changelog_path = local_manifest_path.parent().unwrap().join("CHANGELOG.md");
@@ -222,16 +410,25 @@ impl Updater<'_> {
         // Generate changelog using workspace context
         let repo_url = self.req.repo_url();
         let release_link = {
-            let prev_tag = self.project.git_tag(&current_version.to_string())?;
-            let next_tag = self.project.git_tag(&workspace_version.to_string())?;
+            let prev_tag = self
+                .project
+                .git_tag(WORKSPACE_PACKAGE_NAME, &current_version.to_string())?;
+            let next_tag = self
+                .project
+                .git_tag(WORKSPACE_PACKAGE_NAME, &workspace_version.to_string())?;
             repo_url.map(|r| r.git_release_link(&prev_tag, &next_tag))
         };
 
         let changelog_req = self.req.changelog_req().clone();
+        let commits = if changelog_req.group_by_package {
+            self.commits_by_package(all_commits, repository)?
+        } else {
+            all_commits.iter().map(Commit::to_cliff_commit).collect()
+        };
 
         // Use "workspace" as the package name for unified changelog
         let (full_changelog, new_entry) = get_workspace_changelog(
-            all_commits,
+            commits,
             workspace_version,
             Some(changelog_req),
             old_changelog.as_deref(),
@@ -243,7 +440,57 @@ impl Updater<'_> {
         Ok((Some(full_changelog), Some(new_entry)))
     }
 
+    /// Attribute each commit to the packages whose files it changed, or to
+    /// [`WORKSPACE_WIDE_PACKAGE`] if it doesn't change any package.
+    /// A commit changing several packages is returned once per package.
+    fn commits_by_package<'c>(
+        &self,
+        commits: &'c [Commit],
+        repository: &Repo,
+    ) -> anyhow::Result<Vec<git_cliff_core::commit::Commit<'c>>> {
+        let mut package_dirs = vec![];
+        for package in self.project.workspace_packages() {
+            let package_path = get_package_path(package, repository, self.project.root())?;
+            let package_dir = fs_utils::strip_prefix(&package_path, repository.directory())?;
+            package_dirs.push((package_dir.to_path_buf(), package.name.as_str()));
+        }
+        let commit_ids: Vec<&str> = commits
+            .iter()
+            .map(|commit| commit.id.as_str())
+            .filter(|id| *id != NO_COMMIT_ID)
+            .collect();
+        let files_of_commits = repository.files_of_commits(&commit_ids)?;
+        let mut cliff_commits = vec![];
+        for commit in commits {
+            let mut packages = vec![];
+            for file in files_of_commits.get(&commit.id).into_iter().flatten() {
+                if let Some(name) = package_of_file(file, &package_dirs)
+                    && !packages.contains(&name)
+                {
+                    packages.push(name);
+                }
+            }
+            if packages.is_empty() {
+                packages.push(WORKSPACE_WIDE_PACKAGE);
+            }
+            packages.sort_unstable();
+            cliff_commits.extend(
+                packages
+                    .into_iter()
+                    .map(|package| commit.to_package_cliff_commit(package)),
+            );
+        }
+        Ok(cliff_commits)
+    }
+
     async fn get_packages_diffs(&self, repository: &Repo) -> anyhow::Result<Vec<(&Package, Diff)>> {
+        if self.req.no_checkout_diff() {
+            debug!("reading the commits of each package from the git log");
+        } else if repository.is_partial_clone() || repository.is_sparse_checkout() {
+            warn!(
+                "the repository is a partial clone or a sparse checkout: k-releaser checks out the previous commits of each package, which downloads their missing files from the remote. Use `--no-checkout-diff` to only read the git log."
+            );
+        }
         // Store diff for each package. This operation is not thread safe, so we do it in one
         // package at a time.
         let packages_diffs_res: anyhow::Result<Vec<(&Package, Diff)>> = self
@@ -264,6 +511,10 @@ impl Updater<'_> {
             .map(|(p, d)| (p.name.to_string(), d.commits.clone()))
             .collect();
 
+        // The worktrees must live until the semver checks are done.
+        let mut tag_worktrees = TagWorktrees::new(repository)?;
+        let semver_baselines = self.semver_check_baselines(&packages_diffs, &mut tag_worktrees)?;
+
         let semver_check_result: anyhow::Result<()> =
             packages_diffs.par_iter_mut().try_for_each(|(p, diff)| {
                 let package_config = self.req.get_package_config(&p.name);
@@ -272,9 +523,10 @@ impl Updater<'_> {
                         diff.add_commits(commits);
                     }
                 }
-                // TODO: Implement git-tag-based semver checking
-                // For now, semver checking is skipped when using git tags only.
-                // It can be implemented by checking out the tag commit and comparing packages.
+                if let Some(baseline) = semver_baselines.get(p.name.as_str()) {
+                    diff.semver_check = run_semver_check(p.package_path()?, baseline)
+                        .with_context(|| format!("failed to run semver check for {}", p.name))?;
+                }
                 Ok(())
             });
         semver_check_result?;
@@ -282,6 +534,50 @@ impl Updater<'_> {
         Ok(packages_diffs)
     }
 
+    /// Check out the git tag of the previous release of each library in a worktree,
+    /// so that it can be used as baseline by cargo-semver-checks.
+    /// Returns the path of the baseline of each package.
+    fn semver_check_baselines(
+        &self,
+        packages_diffs: &[(&Package, Diff)],
+        tag_worktrees: &mut TagWorktrees,
+    ) -> anyhow::Result<HashMap<String, Utf8PathBuf>> {
+        let packages_to_check: Vec<&Package> = packages_diffs
+            .iter()
+            .map(|(p, _)| *p)
+            .filter(|p| self.req.get_package_config(&p.name).semver_check() && is_library(p))
+            .collect();
+        if packages_to_check.is_empty() {
+            return Ok(HashMap::new());
+        }
+        if !is_cargo_semver_checks_installed() {
+            warn!("cargo-semver-checks not installed, skipping semver check");
+            return Ok(HashMap::new());
+        }
+
+        let mut baselines = HashMap::new();
+        for p in packages_to_check {
+            let git_tag = self.project.git_tag(&p.name, &p.version.to_string())?;
+            let Some(tag_commit) = tag_worktrees.repository.get_tag_commit(&git_tag) else {
+                debug!("{}: tag {git_tag} not found, skipping semver check", p.name);
+                continue;
+            };
+            let worktree = tag_worktrees.get_or_add(&tag_commit)?;
+            let relative_path = fs_utils::strip_prefix(p.package_path()?, self.project.root())?;
+            let baseline = worktree.join(relative_path);
+            // The package might not exist in the previous release.
+            if baseline.join(CARGO_TOML).exists() {
+                baselines.insert(p.name.to_string(), baseline);
+            } else {
+                debug!(
+                    "{}: package not found at tag {git_tag}, skipping semver check",
+                    p.name
+                );
+            }
+        }
+        Ok(baselines)
+    }
+
     /// Fill workspace commits with metadata (e.g., remote contributor info) if needed by changelog template
     async fn fill_workspace_commits(
         &self,
@@ -353,6 +649,9 @@ impl Updater<'_> {
             "determining next version for {} {}",
             package.name, package.version
         );
+        if self.req.no_checkout_diff() {
+            return self.get_diff_from_log(package, repository);
+        }
         let package_path = get_package_path(package, repository, self.project.root())
             .context("failed to determine package path")?;
 
@@ -360,7 +659,9 @@ impl Updater<'_> {
             .checkout_head()
             .context("can't checkout head to calculate diff")?;
 
-        let git_tag = self.project.git_tag(&package.version.to_string())?;
+        let git_tag = self
+            .project
+            .git_tag(&package.name, &package.version.to_string())?;
         let tag_commit = repository.get_tag_commit(&git_tag);
 
         let mut diff = Diff::new();
@@ -392,6 +693,44 @@ impl Updater<'_> {
         Ok(diff)
     }
 
+    /// Collect the commits that changed the package with `git log`, without checking out
+    /// previous commits, so that no blob is downloaded in partial clones.
+    /// The files of the package are approximated with its directory, excluding nested packages.
+    fn get_diff_from_log(&self, package: &Package, repository: &Repo) -> anyhow::Result<Diff> {
+        let package_path = get_package_path(package, repository, self.project.root())
+            .context("failed to determine package path")?;
+        let git_tag = self
+            .project
+            .git_tag(&package.name, &package.version.to_string())?;
+        let tag_commit = repository.get_tag_commit(&git_tag);
+        let range = match &tag_commit {
+            Some(tag_commit) => format!("{tag_commit}..HEAD"),
+            None => "HEAD".to_string(),
+        };
+
+        let mut pathspecs = vec![];
+        for path in pathbufs_to_check(&package_path, package)? {
+            let relative_path = fs_utils::strip_prefix(&path, repository.directory())?;
+            pathspecs.push(format!(":(top){relative_path}"));
+        }
+        for other_package in self.project.workspace_packages() {
+            let other_path = get_package_path(other_package, repository, self.project.root())?;
+            if other_path != package_path && other_path.starts_with(&package_path) {
+                let relative_path = fs_utils::strip_prefix(&other_path, repository.directory())?;
+                pathspecs.push(format!(":(top,exclude){relative_path}"));
+            }
+        }
+
+        let mut diff = Diff::new();
+        diff.commits = commits_in_range_at_paths(repository, &range, &pathspecs)?;
+        // If no tag exists (first release), limit the commits we analyze
+        if tag_commit.is_none() && self.req.max_analyze_commits() != 0 {
+            diff.commits
+                .truncate(self.req.max_analyze_commits() as usize);
+        }
+        Ok(diff)
+    }
+
     fn get_package_diff(
         &self,
         package_path: &Utf8Path,
@@ -531,12 +870,23 @@ impl Updater<'_> {
     /// Get ALL commits from the entire repository since the latest tag.
     /// This is used for unified workspace versioning where we don't filter by package paths.
     /// Uses `git describe --tags --abbrev=0` to find the most recent tag.
+    /// If the request is [workspace scoped](UpdateRequest::workspace_scoped), only the
+    /// commits of the workspace directory and the tags of the workspace are considered.
     fn get_all_commits_since_latest_tag(
         &self,
         repository: &Repo,
     ) -> anyhow::Result<Vec<Commit>> {
+        let tag_patterns = if self.req.workspace_scoped() {
+            self.project.git_tag_patterns()?.unwrap_or_default()
+        } else {
+            vec![]
+        };
+        let mut describe_args = vec!["describe", "--tags", "--abbrev=0"];
+        for pattern in &tag_patterns {
+            describe_args.extend(["--match", pattern.as_str()]);
+        }
         // Use git describe to find the most recent tag reachable from HEAD
-        let commit_range = match repository.git(&["describe", "--tags", "--abbrev=0"]) {
+        let commit_range = match repository.git(&describe_args) {
             Ok(tag) => {
                 let tag = tag.trim();
                 debug!("found most recent tag: {}", tag);
@@ -553,46 +903,13 @@ impl Updater<'_> {
             }
         };
 
-        // Use git log to get all commits (without --first-parent to include commits
-        // from all branches that were merged, e.g., via `git pull` merge commits)
-        // Use %B to get the full commit message (subject + body) which preserves
-        // the blank line between subject and body that conventional commit parsers require.
-        let output = repository.git(&[
-            "log",
-            &commit_range,
-            "--format=%H%n%B%n--END-COMMIT--",
-        ])?;
-
-        let mut commits = Vec::new();
-        let mut seen_hashes = std::collections::HashSet::new();
-        let commit_strings: Vec<&str> = output.split("--END-COMMIT--").collect();
-
-        for commit_str in commit_strings {
-            let commit_str = commit_str.trim();
-            if commit_str.is_empty() {
-                continue;
-            }
-
-            let mut lines = commit_str.lines();
-            if let Some(hash) = lines.next() {
-                // Skip duplicate commits (can occur when traversing merge commits)
-                if !seen_hashes.insert(hash.to_string()) {
-                    continue;
-                }
-
-                // Collect the full commit message (already includes blank line between subject and body)
-                let message: String = lines.collect::<Vec<_>>().join("\n");
-
-                // Skip release PR commits (version bumps created by k-releaser or similar tools)
-                // These commits are already part of a previous release and shouldn't be counted again
-                if is_release_pr_commit(&message) {
-                    debug!("skipping release PR commit: {}", hash);
-                    continue;
-                }
-
-                commits.push(Commit::new(hash.to_string(), message));
-            }
+        let mut pathspecs = vec![];
+        if self.req.workspace_scoped() {
+            let workspace_dir =
+                fs_utils::strip_prefix(self.req.local_manifest_dir()?, self.project.root())?;
+            pathspecs.push(format!(":(top){workspace_dir}"));
         }
+        let commits = commits_in_range_at_paths(repository, &commit_range, &pathspecs)?;
 
         debug!(
             "collected {} commits from entire repository since latest tag",
@@ -602,6 +919,63 @@ impl Updater<'_> {
     }
 }
 
+/// Get the commits of the given `git log` range, newest first.
+/// Release PR commits are skipped.
+pub(crate) fn commits_in_range(repository: &Repo, range: &str) -> anyhow::Result<Vec<Commit>> {
+    commits_in_range_at_paths(repository, range, &[])
+}
+
+/// Like [`commits_in_range`], but only the commits changing the files
+/// matched by `pathspecs` are returned.
+fn commits_in_range_at_paths(
+    repository: &Repo,
+    range: &str,
+    pathspecs: &[String],
+) -> anyhow::Result<Vec<Commit>> {
+    // Use git log to get all commits (without --first-parent to include commits
+    // from all branches that were merged, e.g., via `git pull` merge commits)
+    // Use %B to get the full commit message (subject + body) which preserves
+    // the blank line between subject and body that conventional commit parsers require.
+    let mut args = vec!["log", range, "--format=%H%n%B%n--END-COMMIT--"];
+    if !pathspecs.is_empty() {
+        args.push("--");
+        args.extend(pathspecs.iter().map(String::as_str));
+    }
+    let output = repository.git(&args)?;
+
+    let mut commits = Vec::new();
+    let mut seen_hashes = std::collections::HashSet::new();
+    let commit_strings: Vec<&str> = output.split("--END-COMMIT--").collect();
+
+    for commit_str in commit_strings {
+        let commit_str = commit_str.trim();
+        if commit_str.is_empty() {
+            continue;
+        }
+
+        let mut lines = commit_str.lines();
+        if let Some(hash) = lines.next() {
+            // Skip duplicate commits (can occur when traversing merge commits)
+            if !seen_hashes.insert(hash.to_string()) {
+                continue;
+            }
+
+            // Collect the full commit message (already includes blank line between subject and body)
+            let message: String = lines.collect::<Vec<_>>().join("\n");
+
+            // Skip release PR commits (version bumps created by k-releaser or similar tools)
+            // These commits are already part of a previous release and shouldn't be counted again
+            if is_release_pr_commit(&message) {
+                debug!("skipping release PR commit: {}", hash);
+                continue;
+            }
+
+            commits.push(Commit::new(hash.to_string(), message));
+        }
+    }
+    Ok(commits)
+}
+
 /// Check if a commit message indicates it's a release PR commit.
 /// These are commits created by k-releaser (or similar tools like release-plz)
 /// that bump versions or update changelogs. They should be skipped when
@@ -674,7 +1048,7 @@ fn pathbufs_to_check(
 /// Generate a workspace-level changelog (for unified monorepo versioning).
 /// Returns (full_changelog, new_entry_only)
 fn get_workspace_changelog(
-    commits: &[Commit],
+    commits: Vec<git_cliff_core::commit::Commit<'_>>,
     next_version: &Version,
     changelog_req: Option<ChangelogRequest>,
     old_changelog: Option<&str>,
@@ -682,9 +1056,6 @@ fn get_workspace_changelog(
     release_link: Option<&str>,
     current_version: &Version,
 ) -> anyhow::Result<(String, String)> {
-    let commits: Vec<git_cliff_core::commit::Commit> =
-        commits.iter().map(|c| c.to_cliff_commit()).collect();
-
     // Use "workspace" as the package name for unified changelog
     let mut changelog_builder = ChangelogBuilder::new(
         commits.clone(),
@@ -693,6 +1064,7 @@ fn get_workspace_changelog(
     );
 
     if let Some(changelog_req) = changelog_req {
+        changelog_builder = changelog_builder.with_group_by_package(changelog_req.group_by_package);
         if let Some(release_date) = changelog_req.release_date {
             changelog_builder = changelog_builder.with_release_date(release_date);
         }
@@ -749,7 +1121,6 @@ fn get_workspace_changelog(
 /// - the entire changelog (with the new entries);
 /// - the new changelog entry alone
 ///   (i.e. changelog body update without header and footer).
-#[cfg(test)]
 fn get_changelog(
     commits: &[Commit],
     next_version: &Version,
@@ -820,7 +1191,9 @@ fn get_changelog(
     Ok((changelog, body_only.unwrap_or_default()))
 }
 
-fn new_changelog_entry(changelog_builder: ChangelogBuilder) -> anyhow::Result<Option<String>> {
+pub(crate) fn new_changelog_entry(
+    changelog_builder: ChangelogBuilder,
+) -> anyhow::Result<Option<String>> {
     changelog_builder
         .config()
         .cloned()
@@ -841,7 +1214,9 @@ fn new_changelog_entry(changelog_builder: ChangelogBuilder) -> anyhow::Result<Op
         .transpose()
 }
 
-fn get_contributors(commits: &[git_cliff_core::commit::Commit]) -> Vec<RemoteContributor> {
+pub(crate) fn get_contributors(
+    commits: &[git_cliff_core::commit::Commit],
+) -> Vec<RemoteContributor> {
     let mut unique_contributors = HashSet::new();
     commits
         .iter()
@@ -852,6 +1227,100 @@ fn get_contributors(commits: &[git_cliff_core::commit::Commit]) -> Vec<RemoteCon
         .collect()
 }
 
+/// Apply the version increment requested by the user.
+pub(crate) fn forced_bump(bump: VersionIncrement, version: &Version) -> anyhow::Result<Version> {
+    anyhow::ensure!(
+        bump != VersionIncrement::Prerelease || !version.pre.is_empty(),
+        "can't bump the pre-release of version {version} because it isn't a pre-release"
+    );
+    Ok(bump.bump(version))
+}
+
+/// Limit `next_version` to a patch increment of `version`, so that a maintenance branch
+/// doesn't release a new minor or major version.
+pub(crate) fn patch_only_version(version: &Version, next_version: &Version) -> Version {
+    if next_version.major == version.major && next_version.minor == version.minor {
+        next_version.clone()
+    } else {
+        Version::new(version.major, version.minor, version.patch + 1)
+    }
+}
+
+/// Turn `next_version` into a pre-release of the `label` channel, e.g. `1.4.0-beta.1`.
+/// Subsequent pre-releases of the same channel and base version increment the number,
+/// e.g. `1.4.0-beta.1` -> `1.4.0-beta.2`.
+fn prerelease_version(
+    current_version: &Version,
+    next_version: &Version,
+    label: &str,
+) -> anyhow::Result<Version> {
+    if next_version == current_version {
+        // Nothing to release.
+        return Ok(next_version.clone());
+    }
+    let base = |v: &Version| Version::new(v.major, v.minor, v.patch);
+    let current_pre = current_version.pre.as_str();
+    let is_same_channel = current_pre == label || current_pre.starts_with(&format!("{label}."));
+    if is_same_channel && base(current_version) == base(next_version) {
+        return Ok(current_version.increment_prerelease());
+    }
+    let pre = Prerelease::new(&format!("{label}.1"))
+        .with_context(|| format!("invalid pre-release label `{label}`"))?;
+    Ok(Version {
+        pre,
+        ..base(next_version)
+    })
+}
+
+fn is_library(package: &Package) -> bool {
+    package.targets.iter().any(|t| t.is_lib())
+}
+
+/// Git worktrees checked out at the commits of previous release tags.
+/// The worktrees are removed on drop.
+struct TagWorktrees<'a> {
+    repository: &'a Repo,
+    dir: tempfile::TempDir,
+    /// Worktree path of each commit.
+    worktrees: HashMap<String, Utf8PathBuf>,
+}
+
+impl<'a> TagWorktrees<'a> {
+    fn new(repository: &'a Repo) -> anyhow::Result<Self> {
+        let dir = tempfile::tempdir().context("can't create directory for git worktrees")?;
+        Ok(Self {
+            repository,
+            dir,
+            worktrees: HashMap::new(),
+        })
+    }
+
+    /// Path of the worktree checked out at the given commit.
+    /// The worktree is created if it doesn't exist yet.
+    fn get_or_add(&mut self, commit: &str) -> anyhow::Result<&Utf8Path> {
+        if !self.worktrees.contains_key(commit) {
+            let path = fs_utils::to_utf8_path(self.dir.path())?.join(commit);
+            self.repository.add_worktree(&path, commit)?;
+            self.worktrees.insert(commit.to_string(), path);
+        }
+        Ok(&self.worktrees[commit])
+    }
+}
+
+impl Drop for TagWorktrees<'_> {
+    fn drop(&mut self) {
+        for path in self.worktrees.values() {
+            // Force the removal because cargo-semver-checks might leave build files behind.
+            if let Err(e) = self
+                .repository
+                .git(&["worktree", "remove", "--force", path.as_str()])
+            {
+                warn!("can't remove git worktree {path}: {e:?}");
+            }
+        }
+    }
+}
+
 fn get_package_path(
     package: &Package,
     repository: &Repo,
@@ -873,10 +1342,87 @@ fn get_repo_path(
     Ok(result_path)
 }
 
+/// Package owning `file`, given the directories of the packages relative to the repository:
+/// the innermost package containing it. The root package, whose directory is empty,
+/// only owns the files that no other package contains.
+fn package_of_file<'p>(
+    file: &Utf8Path,
+    package_dirs: &[(Utf8PathBuf, &'p str)],
+) -> Option<&'p str> {
+    package_dirs
+        .iter()
+        .filter(|(dir, _)| !dir.as_str().is_empty() && file.starts_with(dir))
+        .max_by_key(|(dir, _)| dir.as_str().len())
+        .or_else(|| package_dirs.iter().find(|(dir, _)| dir.as_str().is_empty()))
+        .map(|&(_, name)| name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn root_package_owns_only_the_files_of_no_other_package() {
+        let mut package_dirs = vec![
+            (Utf8PathBuf::from(""), "root"),
+            (Utf8PathBuf::from("crates/a"), "a"),
+            (Utf8PathBuf::from("crates/a/nested"), "nested"),
+            (Utf8PathBuf::from("crates/ab"), "ab"),
+        ];
+        let package = |file: &str, dirs: &[(Utf8PathBuf, &'static str)]| {
+            package_of_file(Utf8Path::new(file), dirs)
+        };
+        assert_eq!(package("crates/a/src/lib.rs", &package_dirs), Some("a"));
+        assert_eq!(
+            package("crates/a/nested/lib.rs", &package_dirs),
+            Some("nested")
+        );
+        assert_eq!(package("crates/ab/src/lib.rs", &package_dirs), Some("ab"));
+        assert_eq!(package("src/main.rs", &package_dirs), Some("root"));
+        assert_eq!(package("README.md", &package_dirs), Some("root"));
+        // Without a root package, the files outside the packages are workspace-wide.
+        package_dirs.remove(0);
+        assert_eq!(package("README.md", &package_dirs), None);
+        assert_eq!(package("crates/a/src/lib.rs", &package_dirs), Some("a"));
+    }
+
+    #[test]
+    fn tag_worktrees_are_removed_on_drop() {
+        let temp = tempfile::tempdir().unwrap();
+        let repository = Repo::init(temp.path());
+        let commit = repository.current_commit_hash().unwrap();
+
+        let worktree = {
+            let mut tag_worktrees = TagWorktrees::new(&repository).unwrap();
+            let worktree = tag_worktrees.get_or_add(&commit).unwrap().to_path_buf();
+            assert!(worktree.join("README.md").exists());
+            // The same worktree is reused for the same commit.
+            assert_eq!(tag_worktrees.get_or_add(&commit).unwrap(), worktree);
+            worktree
+        };
+        assert!(!worktree.exists());
+    }
+
+    #[test]
+    fn commits_are_filtered_by_pathspecs() {
+        let temp = tempfile::tempdir().unwrap();
+        let repository = Repo::init(temp.path());
+        let nested = temp.path().join("crates/nested");
+        fs_err::create_dir_all(&nested).unwrap();
+        fs_err::write(temp.path().join("crates/lib.rs"), "").unwrap();
+        repository.add_all_and_commit("feat: parent").unwrap();
+        fs_err::write(nested.join("lib.rs"), "").unwrap();
+        repository.add_all_and_commit("feat: nested").unwrap();
+
+        let pathspecs = [
+            ":(top)crates".to_string(),
+            ":(top,exclude)crates/nested".to_string(),
+        ];
+        let commits = commits_in_range_at_paths(&repository, "HEAD", &pathspecs).unwrap();
+        let messages: Vec<&str> = commits.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, ["feat: parent"]);
+    }
+
     #[test]
     fn same_version_is_not_added_to_changelog() {
         let commits = vec![
@@ -908,6 +1454,53 @@ mod tests {
         assert_eq!(old, new.0);
     }
 
+    #[test]
+    fn forced_bump_is_applied() {
+        let version = Version::new(1, 2, 3);
+        assert_eq!(
+            forced_bump(VersionIncrement::Major, &version).unwrap(),
+            Version::new(2, 0, 0)
+        );
+        assert_eq!(
+            forced_bump(VersionIncrement::Patch, &version).unwrap(),
+            Version::new(1, 2, 4)
+        );
+        assert!(forced_bump(VersionIncrement::Prerelease, &version).is_err());
+        let rc = Version::parse("1.3.0-rc.1").unwrap();
+        assert_eq!(
+            forced_bump(VersionIncrement::Prerelease, &rc).unwrap(),
+            Version::parse("1.3.0-rc.2").unwrap()
+        );
+    }
+
+    #[test]
+    fn patch_only_version_is_capped() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(patch_only_version(&v("1.4.2"), &v("2.0.0")), v("1.4.3"));
+        assert_eq!(patch_only_version(&v("1.4.2"), &v("1.5.0")), v("1.4.3"));
+        assert_eq!(patch_only_version(&v("1.4.2"), &v("1.4.3")), v("1.4.3"));
+        assert_eq!(patch_only_version(&v("1.4.2"), &v("1.4.2")), v("1.4.2"));
+    }
+
+    #[test]
+    fn prerelease_version_is_calculated() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let prerelease = |current: &str, next: &str| {
+            prerelease_version(&v(current), &v(next), "beta")
+                .unwrap()
+                .to_string()
+        };
+        // First pre-release of the next version.
+        assert_eq!(prerelease("1.3.2", "1.4.0"), "1.4.0-beta.1");
+        // Subsequent pre-releases on the same base version.
+        assert_eq!(prerelease("1.4.0-beta.1", "1.4.0-beta.2"), "1.4.0-beta.2");
+        // Switching channel restarts the numbering.
+        assert_eq!(prerelease("1.4.0-alpha.3", "1.4.0-alpha.4"), "1.4.0-beta.1");
+        // No changes, no new version.
+        assert_eq!(prerelease("1.3.2", "1.3.2"), "1.3.2");
+        assert!(prerelease_version(&v("1.3.2"), &v("1.4.0"), "not valid").is_err());
+    }
+
     #[test]
     fn release_pr_commits_are_detected() {
         // Should be detected as release PR commits
@@ -0,0 +1,233 @@
+use std::collections::BTreeSet;
+
+use anyhow::Context as _;
+use cargo_metadata::Package;
+use secrecy::SecretString;
+use serde::Serialize;
+use tracing::{debug, instrument, warn};
+
+use crate::{
+    Project,
+    cargo::{CargoRegistry, published_versions},
+    cargo_hash_kind::get_hash_kind,
+    project::version_from_tag,
+};
+
+use super::{publish::registry_indexes, release::ReleaseRequest};
+
+/// Cross-check the git tags, the forge releases and the versions
+/// published in the cargo registry.
+#[derive(Debug)]
+pub struct VerifyTagsRequest {
+    release_req: ReleaseRequest,
+    /// Registry where the packages are published.
+    /// If unspecified, the registries of the `publish` field of the packages are used.
+    registry: Option<String>,
+    /// Token used to read the cargo registry.
+    token: Option<SecretString>,
+}
+
+impl VerifyTagsRequest {
+    pub fn new(release_req: ReleaseRequest) -> Self {
+        Self {
+            release_req,
+            registry: None,
+            token: None,
+        }
+    }
+
+    pub fn with_registry(mut self, registry: impl Into<String>) -> Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    pub fn with_token(mut self, token: impl Into<SecretString>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct VerifyTagsOutput {
+    pub issues: Vec<TagIssue>,
+}
+
+/// Inconsistency between git tags, forge releases and published versions.
+#[derive(Serialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TagIssue {
+    /// The git tag has no forge release.
+    TagWithoutRelease { tag: String },
+    /// The forge release points to a git tag that doesn't exist.
+    ReleaseWithoutTag { tag: String },
+    /// The version is published in the registry, but its git tag doesn't exist.
+    PublishedWithoutTag {
+        package: String,
+        version: String,
+        tag: String,
+    },
+    /// The git tag points to a commit that isn't contained in any branch.
+    UnreachableTag { tag: String },
+}
+
+impl std::fmt::Display for TagIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TagWithoutRelease { tag } => write!(f, "tag {tag} has no forge release"),
+            Self::ReleaseWithoutTag { tag } => {
+                write!(f, "forge release of {tag} has no git tag")
+            }
+            Self::PublishedWithoutTag {
+                package,
+                version,
+                tag,
+            } => write!(
+                f,
+                "{package} {version} is published but tag {tag} is missing"
+            ),
+            Self::UnreachableTag { tag } => {
+                write!(
+                    f,
+                    "tag {tag} points to a commit not contained in any branch"
+                )
+            }
+        }
+    }
+}
+
+/// Report the inconsistencies between the git tags of the packages,
+/// the forge releases and the versions published in the cargo registry.
+#[instrument(skip_all)]
+pub async fn verify_tags(input: &VerifyTagsRequest) -> anyhow::Result<VerifyTagsOutput> {
+    let release_req = &input.release_req;
+    let project = release_req.project()?;
+    let repo = release_req.repo()?;
+    if let Err(e) = project.fetch_release_tags(&repo) {
+        debug!("Failed to fetch tags (this is ok if there's no remote): {e}");
+    }
+    let tags: BTreeSet<String> = repo.get_all_tags().into_iter().collect();
+    let package_tags = package_tags(&project, &tags)?;
+
+    let mut issues = vec![];
+    for (tag, _) in &package_tags {
+        let branches = repo
+            .git(&["branch", "--all", "--contains", tag])
+            .with_context(|| format!("can't determine the branches containing tag {tag}"))?;
+        if branches.trim().is_empty() {
+            issues.push(TagIssue::UnreachableTag { tag: tag.clone() });
+        }
+    }
+
+    match release_req.git_client()? {
+        Some(git_client) => {
+            let release_tags: BTreeSet<String> =
+                git_client.release_tags().await?.into_iter().collect();
+            for (tag, packages) in &package_tags {
+                let release_enabled = packages.iter().any(|p| {
+                    release_req
+                        .get_package_config(&p.name)
+                        .git_release()
+                        .is_enabled()
+                });
+                if release_enabled && !release_tags.contains(tag) {
+                    issues.push(TagIssue::TagWithoutRelease { tag: tag.clone() });
+                }
+            }
+            for tag in release_tags.difference(&tags) {
+                issues.push(TagIssue::ReleaseWithoutTag { tag: tag.clone() });
+            }
+        }
+        None => warn!("git token not provided: forge releases aren't checked"),
+    }
+
+    let hash_kind = get_hash_kind()?;
+    for package in project.publishable_packages() {
+        let config = release_req.get_package_config(&package.name);
+        if !config.publish().is_enabled() || !config.git_tag().is_enabled() {
+            continue;
+        }
+        let registries = registry_indexes(package, input.registry.clone(), &hash_kind)
+            .context("can't determine registry indexes")?;
+        for CargoRegistry { mut index, .. } in registries {
+            let versions = published_versions(&mut index, &package.name, &input.token)
+                .await
+                .with_context(|| {
+                    format!("can't retrieve published versions of {}", package.name)
+                })?;
+            for version in versions {
+                let tag = project.git_tag(&package.name, &version)?;
+                if !tags.contains(&tag) {
+                    issues.push(TagIssue::PublishedWithoutTag {
+                        package: package.name.to_string(),
+                        version,
+                        tag,
+                    });
+                }
+            }
+        }
+    }
+
+    issues.sort();
+    issues.dedup();
+    Ok(VerifyTagsOutput { issues })
+}
+
+/// Git tags of the releases of the packages, together with the packages they release.
+pub(super) fn package_tags<'a>(
+    project: &'a Project,
+    tags: &BTreeSet<String>,
+) -> anyhow::Result<Vec<(String, Vec<&'a Package>)>> {
+    let mut package_tags = vec![];
+    for tag in tags {
+        let Some(version) = version_from_tag(tag) else {
+            continue;
+        };
+        let mut packages = vec![];
+        for package in project.publishable_packages() {
+            if project.git_tag(&package.name, &version.to_string())? == *tag {
+                packages.push(package);
+            }
+        }
+        if !packages.is_empty() {
+            package_tags.push((tag.clone(), packages));
+        }
+    }
+    Ok(package_tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issues_are_serialized_with_their_kind() {
+        let output = VerifyTagsOutput {
+            issues: vec![
+                TagIssue::TagWithoutRelease {
+                    tag: "v1.0.0".to_string(),
+                },
+                TagIssue::PublishedWithoutTag {
+                    package: "my-crate".to_string(),
+                    version: "0.9.0".to_string(),
+                    tag: "v0.9.0".to_string(),
+                },
+            ],
+        };
+        expect_test::expect![[r#"
+            {
+              "issues": [
+                {
+                  "kind": "tag_without_release",
+                  "tag": "v1.0.0"
+                },
+                {
+                  "kind": "published_without_tag",
+                  "package": "my-crate",
+                  "version": "0.9.0",
+                  "tag": "v0.9.0"
+                }
+              ]
+            }"#]]
+        .assert_eq(&serde_json::to_string_pretty(&output).unwrap());
+    }
+}
@@ -95,6 +95,56 @@ fn copy_directory(from: &Utf8Path, to: &Utf8PathBuf) -> Result<(), anyhow::Error
     Ok(())
 }
 
+/// Files of the git directory that are specific to each worktree.
+const PER_WORKTREE_FILES: &[&str] = &["HEAD", "ORIG_HEAD", "index"];
+
+/// Turn the copy of a linked git worktree into a standalone repository.
+///
+/// The `.git` file of a linked worktree points to a git directory of the original repository,
+/// so running git in the copy (e.g. to checkout a new branch) would change the original worktree.
+/// Instead, the copy gets its own `.git` directory, containing the objects, refs and config
+/// of the repository and the `HEAD` and index of the worktree.
+pub(crate) fn detach_worktree_copy(worktree: &Utf8Path, copy: &Utf8Path) -> anyhow::Result<()> {
+    let git_dir = git_cmd::git_dir(worktree)?;
+    let common_dir = git_cmd::git_common_dir(worktree)?;
+    if git_dir == common_dir {
+        // Not a linked worktree, e.g. a submodule.
+        return Ok(());
+    }
+    let copy_git_dir = copy.join(".git");
+    debug!("replacing the worktree link {copy_git_dir:?} with a copy of {common_dir:?}");
+    fs_err::remove_file(&copy_git_dir)?;
+    fs_err::create_dir(&copy_git_dir)?;
+    for entry in common_dir.read_dir_utf8()? {
+        let entry = entry?;
+        // The copy doesn't have linked worktrees.
+        if entry.file_name() != "worktrees" {
+            copy_git_entry(entry.path(), &copy_git_dir.join(entry.file_name()))?;
+        }
+    }
+    for file in PER_WORKTREE_FILES {
+        let worktree_file = git_dir.join(file);
+        if worktree_file.exists() {
+            fs_err::copy(&worktree_file, copy_git_dir.join(file))?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_git_entry(from: &Utf8Path, to: &Utf8Path) -> anyhow::Result<()> {
+    if from.is_dir() {
+        fs_err::create_dir(to)?;
+        for entry in from.read_dir_utf8()? {
+            let entry = entry?;
+            copy_git_entry(entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        trace!("copying file {:?} to {:?}", from, to);
+        fs_err::copy(from, to)?;
+    }
+    Ok(())
+}
+
 fn destination_path(
     to: &Utf8Path,
     entry: &ignore::DirEntry,
@@ -135,6 +185,30 @@ mod tests {
         assert_eq!(link_target, file1_dest);
     }
 
+    #[test]
+    fn copy_of_linked_worktree_is_detached() {
+        test_logs::init();
+        let temp = Utf8TempDir::new().unwrap();
+        let main_dir = temp.path().join("main");
+        fs_err::create_dir(&main_dir).unwrap();
+        let repo = git_cmd::Repo::init(&main_dir);
+        let worktree_dir = temp.path().join("worktree");
+        repo.git(&["worktree", "add", "-b", "feature", worktree_dir.as_str()])
+            .unwrap();
+
+        let temp2 = Utf8TempDir::new().unwrap();
+        copy_dir(&worktree_dir, temp2.path()).unwrap();
+        let copy_path = temp2.path().join("worktree");
+        detach_worktree_copy(&worktree_dir, &copy_path).unwrap();
+
+        let copy = git_cmd::Repo::new(&copy_path).unwrap();
+        assert!(!copy.is_linked_worktree().unwrap());
+        assert_eq!(copy.original_branch(), "feature");
+        copy.checkout_new_branch("release").unwrap();
+        let worktree = git_cmd::Repo::new(&worktree_dir).unwrap();
+        assert_eq!(worktree.original_branch(), "feature");
+    }
+
     #[test]
     fn is_symlink_created_if_file_exists() {
         let temp = tempfile::tempdir().unwrap();
@@ -0,0 +1,91 @@
+//! Client of the web API of [crates.io](https://crates.io).
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use reqwest::header;
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+
+use crate::response_ext::ResponseExt as _;
+
+pub(crate) const CRATES_IO_API_URL: &str = "https://crates.io";
+
+#[derive(Deserialize)]
+struct CrateResponse {
+    versions: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct OwnersRequest<'a> {
+    users: &'a [String],
+}
+
+/// Outcome of adding the configured owners to a crate published for the first time.
+#[derive(Serialize, Debug)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OwnersStatus {
+    /// Teams were added and users were invited.
+    Added {
+        owners: Vec<String>,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+fn client(timeout: Duration) -> anyhow::Result<reqwest::Client> {
+    let client = crate::http_client::http_client_builder()
+        .timeout(timeout)
+        .build()?;
+    Ok(client)
+}
+
+/// Number of versions of the crate `name` published in the registry.
+pub(crate) async fn versions_count(
+    api_url: &str,
+    name: &str,
+    timeout: Duration,
+) -> anyhow::Result<usize> {
+    let endpoint = format!("{}/api/v1/crates/{name}", api_url.trim_end_matches('/'));
+    let res = client(timeout)?
+        .get(&endpoint)
+        .send()
+        .await
+        .with_context(|| format!("failed to call crates.io api {endpoint}"))?
+        .successful_status()
+        .await
+        .with_context(|| format!("unexpected response from crates.io api {endpoint}"))?;
+    let krate: CrateResponse = res
+        .json()
+        .await
+        .context("invalid response from crates.io api")?;
+    Ok(krate.versions.len())
+}
+
+/// Add `owners` to the crate `name`.
+/// Teams, like `github:my-org:publishers`, are added directly. Users are invited,
+/// and become owners once they accept the invitation.
+pub(crate) async fn add_owners(
+    api_url: &str,
+    name: &str,
+    owners: &[String],
+    timeout: Duration,
+    token: &SecretString,
+) -> anyhow::Result<()> {
+    let endpoint = format!(
+        "{}/api/v1/crates/{name}/owners",
+        api_url.trim_end_matches('/')
+    );
+    client(timeout)?
+        .put(&endpoint)
+        .header(header::AUTHORIZATION, token.expose_secret())
+        .json(&OwnersRequest { users: owners })
+        .send()
+        .await
+        .with_context(|| format!("failed to call crates.io api {endpoint}"))?
+        .successful_status()
+        .await
+        .with_context(|| format!("failed to add owners to {name}"))?;
+    Ok(())
+}
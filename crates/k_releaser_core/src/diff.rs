@@ -40,6 +40,15 @@ impl Commit {
             ..Default::default()
         }
     }
+
+    /// Like [`Commit::to_cliff_commit`], with the package the commit is attributed to
+    /// in the `extra.package` field, to group the commits by package in the changelog.
+    pub fn to_package_cliff_commit(&self, package: &str) -> git_cliff_core::commit::Commit<'_> {
+        git_cliff_core::commit::Commit {
+            extra: Some(serde_json::json!({ "package": package })),
+            ..self.to_cliff_commit()
+        }
+    }
 }
 
 impl Diff {
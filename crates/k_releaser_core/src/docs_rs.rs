@@ -0,0 +1,119 @@
+//! Client of the web API of [docs.rs](https://docs.rs), which builds the documentation
+//! of the packages published to crates.io.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::response_ext::ResponseExt as _;
+
+const DOCS_RS_URL: &str = "https://docs.rs";
+
+/// Interval between the checks of the docs.rs builds. Builds usually take minutes.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Outcome of the docs.rs build of a package version.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DocsRsStatus {
+    /// The documentation was built.
+    Built,
+    /// The build failed.
+    Failed,
+    /// The build didn't finish before the timeout.
+    Pending,
+}
+
+#[derive(Deserialize)]
+struct BuildStatus {
+    doc_status: bool,
+}
+
+/// Page of the documentation of `name` at `version`, once docs.rs builds it.
+pub(crate) fn docs_url(name: &str, version: &str) -> String {
+    format!("{DOCS_RS_URL}/{name}/{version}")
+}
+
+/// Status of the build of `name` at `version`.
+/// docs.rs answers 404 until the build is done.
+async fn build_status(name: &str, version: &str) -> anyhow::Result<DocsRsStatus> {
+    let endpoint = format!("{DOCS_RS_URL}/crate/{name}/{version}/status.json");
+    let client = crate::http_client::http_client_builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let res = client
+        .get(&endpoint)
+        .send()
+        .await
+        .with_context(|| format!("failed to call docs.rs api {endpoint}"))?;
+    if res.status() == StatusCode::NOT_FOUND {
+        return Ok(DocsRsStatus::Pending);
+    }
+    let res = res
+        .successful_status()
+        .await
+        .with_context(|| format!("unexpected response from docs.rs api {endpoint}"))?;
+    let status: BuildStatus = res
+        .json()
+        .await
+        .context("invalid response from docs.rs api")?;
+    Ok(parse_status(&status))
+}
+
+fn parse_status(status: &BuildStatus) -> DocsRsStatus {
+    if status.doc_status {
+        DocsRsStatus::Built
+    } else {
+        DocsRsStatus::Failed
+    }
+}
+
+/// Wait until the docs.rs builds of `packages` (name and version) finish, or until `timeout`.
+/// Returns the status of each package, in the same order.
+pub(crate) async fn wait_for_builds(
+    packages: &[(&str, &str)],
+    timeout: Duration,
+) -> Vec<DocsRsStatus> {
+    let deadline = Instant::now() + timeout;
+    let mut statuses = vec![DocsRsStatus::Pending; packages.len()];
+    loop {
+        for ((name, version), status) in packages.iter().zip(statuses.iter_mut()) {
+            if *status != DocsRsStatus::Pending {
+                continue;
+            }
+            match build_status(name, version).await {
+                Ok(new_status) => *status = new_status,
+                Err(e) => warn!("can't check the docs.rs build of {name} {version}: {e:?}"),
+            }
+            match status {
+                DocsRsStatus::Built => info!("{name} {version}: docs.rs build succeeded"),
+                DocsRsStatus::Failed => warn!("{name} {version}: docs.rs build failed"),
+                DocsRsStatus::Pending => {}
+            }
+        }
+        let is_pending = statuses.contains(&DocsRsStatus::Pending);
+        if !is_pending || Instant::now() + POLL_INTERVAL > deadline {
+            return statuses;
+        }
+        info!("waiting for docs.rs builds...");
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_status_is_parsed() {
+        let status: BuildStatus =
+            serde_json::from_str(r#"{"version":"1.0.0","doc_status":true}"#).unwrap();
+        assert_eq!(parse_status(&status), DocsRsStatus::Built);
+        let status: BuildStatus =
+            serde_json::from_str(r#"{"version":"1.0.0","doc_status":false}"#).unwrap();
+        assert_eq!(parse_status(&status), DocsRsStatus::Failed);
+    }
+}
@@ -40,6 +40,19 @@ impl Utf8TempDir {
         })
     }
 
+    /// Create the temporary directory inside `dir`, with a name starting with `prefix`.
+    pub fn new_in(dir: &Utf8Path, prefix: &str) -> anyhow::Result<Self> {
+        let temp_dir = tempfile::Builder::new()
+            .prefix(prefix)
+            .tempdir_in(dir)
+            .with_context(|| format!("cannot create temporary directory in {dir}"))?;
+        let path = to_utf8_path(temp_dir.as_ref())?.to_path_buf();
+        Ok(Self {
+            _temp_dir: temp_dir,
+            path,
+        })
+    }
+
     pub fn path(&self) -> &Utf8Path {
         &self.path
     }
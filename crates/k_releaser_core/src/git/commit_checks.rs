@@ -0,0 +1,398 @@
+//! Commit statuses and check runs reported by the CI to the forge.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use crate::GitClient;
+use crate::git::forge::ForgeType;
+use crate::response_ext::ResponseExt as _;
+
+/// Interval between the checks of the commit statuses while waiting for them.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Maximum length of the description of a commit status accepted by GitHub.
+const STATUS_DESCRIPTION_MAX_LEN: usize = 140;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Pending,
+    Success,
+    Failure,
+}
+
+/// Commit status or check run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitCheck {
+    pub name: String,
+    pub state: CheckState,
+}
+
+impl CommitCheck {
+    fn new(name: impl Into<String>, state: CheckState) -> Self {
+        Self {
+            name: name.into(),
+            state,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChecksOutcome {
+    Passed,
+    /// Names of the checks that didn't finish yet.
+    Pending(Vec<String>),
+    /// Names of the checks that failed.
+    Failed(Vec<String>),
+}
+
+/// GitHub and Gitea combined status.
+#[derive(Deserialize)]
+struct CombinedStatus {
+    #[serde(default)]
+    statuses: Vec<CommitStatus>,
+}
+
+#[derive(Deserialize)]
+struct CommitStatus {
+    context: String,
+    /// Gitea calls it `status`.
+    #[serde(alias = "status")]
+    state: String,
+}
+
+#[derive(Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitLabStatus {
+    name: String,
+    status: String,
+}
+
+fn status_state(state: &str) -> CheckState {
+    match state {
+        "success" | "warning" => CheckState::Success,
+        "failure" | "error" => CheckState::Failure,
+        _ => CheckState::Pending,
+    }
+}
+
+fn check_run_state(check_run: &CheckRun) -> CheckState {
+    if check_run.status != "completed" {
+        return CheckState::Pending;
+    }
+    match check_run.conclusion.as_deref() {
+        Some("success" | "neutral" | "skipped") => CheckState::Success,
+        _ => CheckState::Failure,
+    }
+}
+
+fn gitlab_state(status: &str) -> CheckState {
+    match status {
+        "success" | "skipped" | "manual" => CheckState::Success,
+        "failed" | "canceled" => CheckState::Failure,
+        _ => CheckState::Pending,
+    }
+}
+
+impl GitClient {
+    /// Statuses and check runs of the commit `sha`.
+    pub async fn commit_checks(&self, sha: &str) -> anyhow::Result<Vec<CommitCheck>> {
+        self.ensure_not_job_token("read commit statuses")?;
+        let mut checks = vec![];
+        match self.forge {
+            ForgeType::Github | ForgeType::Gitea => {
+                let status: CombinedStatus = self
+                    .client
+                    .get(format!("{}/commits/{sha}/status", self.repo_url()))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?
+                    .json()
+                    .await
+                    .context("can't parse commit status")?;
+                checks.extend(
+                    status
+                        .statuses
+                        .into_iter()
+                        .map(|s| CommitCheck::new(s.context, status_state(&s.state))),
+                );
+                // Gitea doesn't have check runs.
+                if self.forge == ForgeType::Github {
+                    let check_runs: CheckRuns = self
+                        .client
+                        .get(format!("{}/commits/{sha}/check-runs", self.repo_url()))
+                        .query(&[(self.per_page(), "100")])
+                        .send()
+                        .await?
+                        .successful_status()
+                        .await?
+                        .json()
+                        .await
+                        .context("can't parse check runs")?;
+                    checks.extend(
+                        check_runs
+                            .check_runs
+                            .iter()
+                            .map(|c| CommitCheck::new(&c.name, check_run_state(c))),
+                    );
+                }
+            }
+            ForgeType::Gitlab => {
+                let statuses: Vec<GitLabStatus> = self
+                    .client
+                    .get(format!(
+                        "{}/repository/commits/{sha}/statuses",
+                        self.repo_url()
+                    ))
+                    .query(&[(self.per_page(), "100")])
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?
+                    .json()
+                    .await
+                    .context("can't parse commit statuses")?;
+                checks.extend(
+                    statuses
+                        .into_iter()
+                        .map(|s| CommitCheck::new(s.name, gitlab_state(&s.status))),
+                );
+            }
+        }
+        Ok(checks)
+    }
+
+    /// Report a successful check named `name` on the commit `sha`.
+    /// On GitHub, it's a check run showing `summary` as markdown.
+    /// Gitea and GitLab don't have check runs, so it's a commit status with `title`
+    /// as description.
+    pub async fn report_check(
+        &self,
+        sha: &str,
+        name: &str,
+        title: &str,
+        summary: &str,
+    ) -> anyhow::Result<()> {
+        self.ensure_not_job_token("report commit statuses")?;
+        let request = match self.forge {
+            ForgeType::Github => self
+                .client
+                .post(format!("{}/check-runs", self.repo_url()))
+                .json(&json!({
+                    "name": name,
+                    "head_sha": sha,
+                    "status": "completed",
+                    "conclusion": "neutral",
+                    "output": { "title": title, "summary": summary },
+                })),
+            ForgeType::Gitea | ForgeType::Gitlab => {
+                let description: String = title.chars().take(STATUS_DESCRIPTION_MAX_LEN).collect();
+                let name_field = if self.forge == ForgeType::Gitea {
+                    "context"
+                } else {
+                    "name"
+                };
+                self.client
+                    .post(format!("{}/statuses/{sha}", self.repo_url()))
+                    .json(&json!({
+                        "state": "success",
+                        name_field: name,
+                        "description": description,
+                    }))
+            }
+        };
+        request
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to report check {name} on commit {sha}"))?;
+        Ok(())
+    }
+}
+
+/// Name of the CI job running k-releaser, read from the environment.
+fn current_ci_job() -> Option<String> {
+    std::env::var("GITHUB_JOB")
+        .or_else(|_| std::env::var("CI_JOB_NAME"))
+        .ok()
+}
+
+/// Outcome of the `required` checks. If `required` is empty, all the checks are required,
+/// except the ones named `ignored`.
+fn evaluate_checks(
+    checks: &[CommitCheck],
+    required: &[String],
+    ignored: Option<&str>,
+) -> ChecksOutcome {
+    let mut pending = vec![];
+    let mut failed = vec![];
+    let mut record = |name: &str, states: &[CheckState]| {
+        if states.contains(&CheckState::Failure) {
+            failed.push(name.to_string());
+        } else if states.is_empty() || states.contains(&CheckState::Pending) {
+            pending.push(name.to_string());
+        }
+    };
+    if required.is_empty() {
+        for check in checks.iter().filter(|c| Some(c.name.as_str()) != ignored) {
+            record(&check.name, &[check.state]);
+        }
+    } else {
+        for name in required {
+            let states: Vec<CheckState> = checks
+                .iter()
+                .filter(|c| &c.name == name)
+                .map(|c| c.state)
+                .collect();
+            record(name, &states);
+        }
+    }
+    if !failed.is_empty() {
+        ChecksOutcome::Failed(failed)
+    } else if !pending.is_empty() {
+        ChecksOutcome::Pending(pending)
+    } else {
+        ChecksOutcome::Passed
+    }
+}
+
+/// Outcome of the `required` checks of `sha`, ignoring the check of the CI job running
+/// k-releaser, which can't pass before k-releaser finishes.
+/// `ci_job` is the name of that check, and defaults to the one of the environment.
+pub(crate) async fn commit_checks_outcome(
+    git_client: &GitClient,
+    sha: &str,
+    required: &[String],
+    ci_job: Option<&str>,
+) -> anyhow::Result<ChecksOutcome> {
+    let checks = git_client
+        .commit_checks(sha)
+        .await
+        .with_context(|| format!("can't read the checks of commit {sha}"))?;
+    let ci_job = ci_job.map(String::from).or_else(current_ci_job);
+    Ok(evaluate_checks(&checks, required, ci_job.as_deref()))
+}
+
+/// Wait until the `required` checks of `sha` pass.
+/// Fails if a check fails or if the checks don't finish within `timeout`.
+pub(crate) async fn wait_for_checks(
+    git_client: &GitClient,
+    sha: &str,
+    required: &[String],
+    ci_job: Option<&str>,
+    timeout: Duration,
+) -> anyhow::Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match commit_checks_outcome(git_client, sha, required, ci_job).await? {
+            ChecksOutcome::Passed => {
+                info!("checks of commit {sha} passed");
+                return Ok(());
+            }
+            ChecksOutcome::Failed(failed) => {
+                anyhow::bail!("checks of commit {sha} failed: {}", failed.join(", "))
+            }
+            ChecksOutcome::Pending(pending) => {
+                anyhow::ensure!(
+                    Instant::now() + POLL_INTERVAL <= deadline,
+                    "timeout while waiting for the checks of commit {sha}: {}",
+                    pending.join(", ")
+                );
+                info!(
+                    "waiting for the checks of commit {sha}: {}",
+                    pending.join(", ")
+                );
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks() -> Vec<CommitCheck> {
+        vec![
+            CommitCheck::new("test", CheckState::Success),
+            CommitCheck::new("lint", CheckState::Pending),
+            CommitCheck::new("release", CheckState::Pending),
+        ]
+    }
+
+    #[test]
+    fn required_checks_are_evaluated() {
+        let required = ["test".to_string()];
+        assert_eq!(
+            evaluate_checks(&checks(), &required, None),
+            ChecksOutcome::Passed
+        );
+        let required = ["test".to_string(), "lint".to_string()];
+        assert_eq!(
+            evaluate_checks(&checks(), &required, None),
+            ChecksOutcome::Pending(vec!["lint".to_string()])
+        );
+        // Required checks not reported yet are pending.
+        let required = ["build".to_string()];
+        assert_eq!(
+            evaluate_checks(&checks(), &required, None),
+            ChecksOutcome::Pending(vec!["build".to_string()])
+        );
+    }
+
+    #[test]
+    fn all_checks_are_required_by_default_except_the_current_job() {
+        assert_eq!(
+            evaluate_checks(&checks(), &[], Some("release")),
+            ChecksOutcome::Pending(vec!["lint".to_string()])
+        );
+        let mut checks = checks();
+        checks[1].state = CheckState::Failure;
+        assert_eq!(
+            evaluate_checks(&checks, &[], Some("release")),
+            ChecksOutcome::Failed(vec!["lint".to_string()])
+        );
+        checks[1].state = CheckState::Success;
+        assert_eq!(
+            evaluate_checks(&checks, &[], Some("release")),
+            ChecksOutcome::Passed
+        );
+    }
+
+    #[test]
+    fn check_run_state_is_parsed() {
+        let check_run = |status: &str, conclusion: Option<&str>| CheckRun {
+            name: "test".to_string(),
+            status: status.to_string(),
+            conclusion: conclusion.map(String::from),
+        };
+        assert_eq!(
+            check_run_state(&check_run("in_progress", None)),
+            CheckState::Pending
+        );
+        assert_eq!(
+            check_run_state(&check_run("completed", Some("skipped"))),
+            CheckState::Success
+        );
+        assert_eq!(
+            check_run_state(&check_run("completed", Some("timed_out"))),
+            CheckState::Failure
+        );
+    }
+}
@@ -1,8 +1,14 @@
-use crate::git::{gitea_client::Gitea, gitlab_client::GitLab};
+use crate::git::gitea_client::{self, Gitea};
+use crate::git::github_app::GitHubAppAuth;
+use crate::git::github_graphql;
+use crate::git::gitlab_client::GitLab;
+use crate::git::rate_limit::RateLimitRetry;
 use crate::{GitHub, GitReleaseInfo};
+use cargo_metadata::camino::Utf8Path;
 use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
-use crate::pr::Pr;
+use crate::pr::{MergeMethod, Pr, marker_branch_prefix};
 use crate::response_ext::ResponseExt;
 use anyhow::Context;
 use http::StatusCode;
@@ -12,9 +18,12 @@ use reqwest::{Response, Url};
 use reqwest_middleware::ClientBuilder;
 use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
 use secrecy::SecretString;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::json;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
+
+/// Number of items requested for each page of paginated lists.
+const PAGE_SIZE: usize = 50;
 
 #[derive(Debug, Clone)]
 pub enum GitForge {
@@ -45,6 +54,13 @@ pub struct GitClient {
     pub forge: ForgeType,
     pub remote: Remote,
     pub client: reqwest_middleware::ClientWithMiddleware,
+    /// If `true`, the token is a GitLab CI/CD job token,
+    /// which can only call a subset of the API.
+    /// Docs: https://docs.gitlab.com/ci/jobs/ci_job_token/#job-token-access
+    pub(crate) job_token: bool,
+    /// GitLab users already looked up by email.
+    /// The same authors appear in many commits.
+    gitlab_users: Mutex<HashMap<String, Option<GitLabUser>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -74,16 +90,26 @@ pub struct Author {
 }
 
 // https://docs.gitlab.com/ee/api/merge_requests.html#get-single-merge-request-commits
+// https://docs.gitlab.com/ee/api/commits.html#get-a-single-commit
 #[derive(Deserialize, Clone, Debug)]
-pub struct GitLabMrCommit {
+pub struct GitLabCommit {
     pub id: String,
+    /// GitLab doesn't return the username of the author, only its email.
+    pub author_email: Option<String>,
+}
+
+// https://docs.gitlab.com/ee/api/users.html#list-users
+#[derive(Deserialize, Clone, Debug)]
+pub struct GitLabUser {
+    pub id: i32,
+    pub username: String,
 }
 
-impl From<GitLabMrCommit> for PrCommit {
-    fn from(value: GitLabMrCommit) -> Self {
+impl From<GitLabUser> for Author {
+    fn from(value: GitLabUser) -> Self {
         Self {
-            author: None,
-            sha: value.id,
+            id: value.id,
+            login: value.username,
         }
     }
 }
@@ -95,9 +121,22 @@ pub struct CreateReleaseOption<'a> {
     name: &'a str,
     draft: &'a bool,
     prerelease: &'a bool,
-    /// Only supported by GitHub.
+    /// Supported by GitHub, Forgejo and recent Gitea versions.
     #[serde(skip_serializing_if = "Option::is_none")]
     make_latest: Option<String>,
+    /// Only supported by GitHub.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    generate_release_notes: bool,
+}
+
+/// Release created on the forge.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ForgeRelease {
+    /// Web page of the release.
+    pub html_url: String,
+    /// GitLab identifies releases by their tag, so they don't have an id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -106,9 +145,27 @@ pub struct GitPr {
     pub number: u64,
     pub html_url: Url,
     pub head: Commit,
+    /// Branch the PR is merged into.
+    /// Only its repository is read, to recognize the PRs opened from forks.
+    #[serde(default)]
+    pub base: Option<PrBase>,
     pub title: String,
     pub body: Option<String>,
     pub labels: Vec<Label>,
+    /// On GitLab, the draft prefix is removed from the `title`.
+    #[serde(default)]
+    pub draft: bool,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PrBase {
+    /// [`None`] if the repository was deleted.
+    pub repo: Option<PrRepo>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PrRepo {
+    pub id: u64,
 }
 
 /// Pull request.
@@ -120,6 +177,46 @@ impl GitPr {
     pub fn label_names(&self) -> Vec<&str> {
         self.labels.iter().map(|l| l.name.as_str()).collect()
     }
+
+    /// Whether this looks like a release PR of `branch_prefix`: either its branch starts with the
+    /// prefix, or its body contains the hidden marker of a release PR whose prefix starts with it.
+    /// The marker recognizes release PRs whose branch was renamed.
+    /// Anyone can write the marker in the body of a PR: use [`GitPr::is_release_pr`]
+    /// before changing the PR.
+    pub fn looks_like_release_pr(&self, branch_prefix: &str) -> bool {
+        self.branch().starts_with(branch_prefix) || self.has_release_pr_marker(branch_prefix)
+    }
+
+    /// Whether this is a release PR of `branch_prefix`. See [`GitPr::looks_like_release_pr`].
+    /// The marker is only trusted for the PRs opened by `token_user`, the user of the git token,
+    /// from a branch of the repository.
+    pub fn is_release_pr(&self, branch_prefix: &str, token_user: Option<&str>) -> bool {
+        self.branch().starts_with(branch_prefix)
+            || (self.has_release_pr_marker(branch_prefix)
+                && self.is_from_same_repo()
+                && token_user.is_some_and(|user| same_login(user, &self.user.login)))
+    }
+
+    fn has_release_pr_marker(&self, branch_prefix: &str) -> bool {
+        self.body
+            .as_deref()
+            .and_then(marker_branch_prefix)
+            .is_some_and(|prefix| prefix.starts_with(branch_prefix))
+    }
+
+    /// Whether the branch of the PR is in the repository of the PR, rather than in a fork.
+    fn is_from_same_repo(&self) -> bool {
+        let base_repo = self.base.as_ref().and_then(|base| base.repo.as_ref());
+        self.head.repo.is_some() && self.head.repo.as_ref() == base_repo
+    }
+}
+
+/// Whether the two logins belong to the same user.
+/// The GitHub GraphQL API returns the logins of the bots without the `[bot]` suffix.
+fn same_login(login: &str, other: &str) -> bool {
+    login
+        .trim_end_matches("[bot]")
+        .eq_ignore_ascii_case(other.trim_end_matches("[bot]"))
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -127,7 +224,7 @@ pub struct Label {
     pub name: String,
     /// ID of the label.
     /// Used by Gitea and GitHub. Not present in GitLab responses.
-    id: Option<u64>,
+    pub(crate) id: Option<u64>,
 }
 
 impl From<GitLabMr> for GitPr {
@@ -150,14 +247,23 @@ impl From<GitLabMr> for GitPr {
             head: Commit {
                 ref_field: value.source_branch,
                 sha: value.sha,
+                repo: Some(PrRepo {
+                    id: value.source_project_id,
+                }),
             },
-            title: value.title,
+            base: Some(PrBase {
+                repo: Some(PrRepo {
+                    id: value.target_project_id,
+                }),
+            }),
+            title: strip_gitlab_draft_prefix(&value.title).to_string(),
             body,
             user: Author {
                 id: value.author.id,
                 login: value.author.username,
             },
             labels,
+            draft: value.draft,
         }
     }
 }
@@ -170,9 +276,13 @@ pub struct GitLabMr {
     pub web_url: Url,
     pub sha: String,
     pub source_branch: String,
+    pub source_project_id: u64,
+    pub target_project_id: u64,
     pub title: String,
     pub description: String,
     pub labels: Vec<String>,
+    #[serde(default)]
+    pub draft: bool,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -185,6 +295,12 @@ impl From<GitPr> for GitLabMr {
     fn from(value: GitPr) -> Self {
         let desc = value.body.unwrap_or_default();
         let labels: Vec<String> = value.labels.into_iter().map(|l| l.name).collect();
+        let source_project_id = value.head.repo.map(|repo| repo.id).unwrap_or_default();
+        let target_project_id = value
+            .base
+            .and_then(|base| base.repo)
+            .map(|repo| repo.id)
+            .unwrap_or_default();
 
         Self {
             author: GitLabAuthor {
@@ -195,9 +311,12 @@ impl From<GitPr> for GitLabMr {
             web_url: value.html_url,
             sha: value.head.sha,
             source_branch: value.head.ref_field,
-            title: value.title,
+            source_project_id,
+            target_project_id,
+            title: gitlab_mr_title(&value.title, value.draft),
             description: desc,
             labels,
+            draft: value.draft,
         }
     }
 }
@@ -207,6 +326,9 @@ pub struct Commit {
     #[serde(rename = "ref")]
     pub ref_field: String,
     pub sha: String,
+    /// Repository of the branch. [`None`] if it was deleted.
+    #[serde(default)]
+    pub repo: Option<PrRepo>,
 }
 
 /// Representation of a remote contributor.
@@ -234,18 +356,52 @@ pub struct PrEdit {
     body: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     state: Option<String>,
+    /// Only used by GitLab, which marks drafts with a prefix in the title.
+    #[serde(skip)]
+    draft: Option<bool>,
 }
 
 impl From<PrEdit> for GitLabMrEdit {
     fn from(value: PrEdit) -> Self {
+        let title = match value.draft {
+            Some(draft) => value.title.map(|title| gitlab_mr_title(&title, draft)),
+            None => value.title,
+        };
         Self {
-            title: value.title,
+            title,
             description: value.body,
             state_event: value.state,
         }
     }
 }
 
+/// Prefixes of the titles of GitLab draft merge requests. GitLab ignores their case.
+const GITLAB_DRAFT_PREFIXES: &[&str] = &["draft:", "[draft]", "(draft)"];
+
+/// Title of a GitLab merge request without the draft prefix.
+fn strip_gitlab_draft_prefix(title: &str) -> &str {
+    GITLAB_DRAFT_PREFIXES
+        .iter()
+        .find_map(|prefix| {
+            let start = title.get(..prefix.len())?;
+            start
+                .eq_ignore_ascii_case(prefix)
+                .then(|| title[prefix.len()..].trim_start())
+        })
+        .unwrap_or(title)
+}
+
+/// Title of a GitLab merge request, which is a draft if it starts with `Draft:`.
+/// Docs: https://docs.gitlab.com/user/project/merge_requests/drafts/
+fn gitlab_mr_title(title: &str, draft: bool) -> String {
+    let title = strip_gitlab_draft_prefix(title);
+    if draft {
+        format!("Draft: {title}")
+    } else {
+        title.to_string()
+    }
+}
+
 impl PrEdit {
     pub fn new() -> Self {
         Self::default()
@@ -266,6 +422,13 @@ impl PrEdit {
         self
     }
 
+    /// Mark the PR as draft or ready when editing its title.
+    /// Only GitLab supports it, because it marks drafts with a prefix in the title.
+    pub fn with_draft(mut self, draft: bool) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
     pub fn contains_edit(&self) -> bool {
         self.title.is_some() || self.body.is_some() || self.state.is_some()
     }
@@ -281,12 +444,22 @@ impl GitClient {
                 .context("can't build Git client")?;
 
             let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
-            ClientBuilder::new(reqwest_client)
+            let builder = ClientBuilder::new(reqwest_client)
                 // Retry failed requests.
                 .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-                .build()
+                // Wait for the rate limit to reset instead of failing.
+                .with(RateLimitRetry);
+            match &forge {
+                GitForge::Github(GitHub {
+                    remote,
+                    app: Some(app),
+                }) => builder.with(GitHubAppAuth::new(app.clone(), remote)?),
+                _ => builder,
+            }
+            .build()
         };
 
+        let job_token = matches!(&forge, GitForge::Gitlab(g) if g.is_job_token());
         let (forge, remote) = match forge {
             GitForge::Github(g) => (ForgeType::Github, g.remote),
             GitForge::Gitea(g) => (ForgeType::Gitea, g.remote),
@@ -296,9 +469,20 @@ impl GitClient {
             forge,
             remote,
             client,
+            job_token,
+            gitlab_users: Mutex::default(),
         })
     }
 
+    /// Fail if the token is a GitLab CI/CD job token, which can't `action`.
+    pub(crate) fn ensure_not_job_token(&self, action: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.job_token,
+            "GitLab CI/CD job tokens (CI_JOB_TOKEN) can't {action}: set GITLAB_TOKEN to a project access token with the `api` scope"
+        );
+        Ok(())
+    }
+
     pub fn per_page(&self) -> &str {
         match self.forge {
             ForgeType::Github | ForgeType::Gitlab => "per_page",
@@ -306,8 +490,58 @@ impl GitClient {
         }
     }
 
-    /// Creates a GitHub/Gitea release.
-    pub async fn create_release(&self, release_info: &GitReleaseInfo) -> anyhow::Result<()> {
+    /// Get all the items of the paginated list at `url`.
+    pub(crate) async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> anyhow::Result<Vec<T>> {
+        let mut page = 1;
+        let mut items = vec![];
+        loop {
+            let mut page_url = Url::parse(url).with_context(|| format!("invalid URL {url}"))?;
+            page_url
+                .query_pairs_mut()
+                .extend_pairs(query)
+                .append_pair("page", &page.to_string())
+                .append_pair(self.per_page(), &PAGE_SIZE.to_string());
+            let page_items: Vec<T> = self
+                .client
+                .get(page_url)
+                .send()
+                .await?
+                .successful_status()
+                .await?
+                .json()
+                .await
+                .with_context(|| format!("can't parse response of {url}"))?;
+            let page_len = page_items.len();
+            items.extend(page_items);
+            if page_len < PAGE_SIZE {
+                break;
+            }
+            page += 1;
+        }
+        Ok(items)
+    }
+
+    /// Check that the token can read the repository.
+    pub async fn check_repo_access(&self) -> anyhow::Result<()> {
+        self.client
+            .get(self.repo_url())
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .context("can't read the repository with the given token")?;
+        Ok(())
+    }
+
+    /// Creates a GitHub/Gitea/GitLab release.
+    pub async fn create_release(
+        &self,
+        release_info: &GitReleaseInfo,
+    ) -> anyhow::Result<ForgeRelease> {
         match self.forge {
             ForgeType::Github | ForgeType::Gitea => self.create_github_release(release_info).await,
             ForgeType::Gitlab => self.create_gitlab_release(release_info).await,
@@ -316,19 +550,41 @@ impl GitClient {
     }
 
     /// Same as Gitea.
-    pub async fn create_github_release(&self, release_info: &GitReleaseInfo) -> anyhow::Result<()> {
-        if release_info.latest.is_some() && self.forge == ForgeType::Gitea {
-            anyhow::bail!("Gitea does not support the `git_release_latest` option");
+    pub async fn create_github_release(
+        &self,
+        release_info: &GitReleaseInfo,
+    ) -> anyhow::Result<ForgeRelease> {
+        let mut latest = release_info.latest;
+        if latest.is_some()
+            && self.forge == ForgeType::Gitea
+            && !self.gitea_supports_make_latest().await
+        {
+            warn!(
+                "this Gitea version doesn't support the `git_release_latest` option: ignoring it"
+            );
+            latest = None;
+        }
+        let generate_release_notes =
+            release_info.generate_release_notes && self.forge == ForgeType::Github;
+        if release_info.generate_release_notes && !generate_release_notes {
+            warn!("Gitea can't generate release notes: using the changelog as release body");
         }
         let create_release_options = CreateReleaseOption {
             tag_name: &release_info.git_tag,
-            body: &release_info.release_body,
+            // GitHub appends the generated notes to the body.
+            body: if generate_release_notes {
+                ""
+            } else {
+                &release_info.release_body
+            },
             name: &release_info.release_name,
             draft: &release_info.draft,
             prerelease: &release_info.pre_release,
-            make_latest: release_info.latest.map(|l| l.to_string()),
+            make_latest: latest.map(|l| l.to_string()),
+            generate_release_notes,
         };
-        self.client
+        let response = self
+            .client
             .post(format!("{}/releases", self.repo_url()))
             .json(&create_release_options)
             .send()
@@ -344,22 +600,181 @@ impl GitClient {
                 }
                 anyhow::anyhow!(e)
             })?;
+
+        let release: CreatedRelease = response
+            .json()
+            .await
+            .context("can't parse created release")?;
+        for asset in &release_info.assets {
+            self.upload_github_release_asset(&release, asset)
+                .await
+                .with_context(|| format!("failed to upload release asset {asset}"))?;
+        }
+        Ok(ForgeRelease {
+            html_url: release.html_url,
+            id: Some(release.id),
+        })
+    }
+
+    /// Whether the Gitea or Forgejo server supports marking a release as latest.
+    /// Returns `false` if the version of the server can't be read.
+    async fn gitea_supports_make_latest(&self) -> bool {
+        #[derive(Deserialize)]
+        struct ServerVersion {
+            version: String,
+        }
+        let version = async {
+            let version: ServerVersion = self
+                .client
+                .get(format!("{}version", self.remote.base_url))
+                .send()
+                .await?
+                .successful_status()
+                .await?
+                .json()
+                .await
+                .context("can't parse server version")?;
+            anyhow::Ok(version.version)
+        };
+        match version.await {
+            Ok(version) => {
+                debug!("Gitea server version: {version}");
+                gitea_client::supports_make_latest(&version)
+            }
+            Err(e) => {
+                warn!("can't read the Gitea server version: {e:?}");
+                false
+            }
+        }
+    }
+
+    /// Same as Gitea.
+    async fn upload_github_release_asset(
+        &self,
+        release: &CreatedRelease,
+        asset: &Utf8Path,
+    ) -> anyhow::Result<()> {
+        let file_name = asset_file_name(asset)?;
+        let content = fs_err::tokio::read(asset).await?;
+        let request = match self.forge {
+            ForgeType::Github => {
+                // GitHub returns a hypermedia url, e.g. `https://uploads.github.com/repos/o/r/releases/1/assets{?name,label}`.
+                let upload_url = release
+                    .upload_url
+                    .as_deref()
+                    .context("GitHub didn't return the upload url of the release")?;
+                let upload_url = upload_url.split('{').next().unwrap_or(upload_url);
+                self.client
+                    .post(upload_url)
+                    .query(&[("name", file_name)])
+                    .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                    .body(content)
+            }
+            ForgeType::Gitea => {
+                let (content_type, body) = multipart_file_body("attachment", file_name, &content);
+                self.client
+                    .post(format!(
+                        "{}/releases/{}/assets",
+                        self.repo_url(),
+                        release.id
+                    ))
+                    .query(&[("name", file_name)])
+                    .header(reqwest::header::CONTENT_TYPE, content_type)
+                    .body(body)
+            }
+            ForgeType::Gitlab => {
+                anyhow::bail!("GitLab releases don't have an upload url")
+            }
+        };
+        request.send().await?.successful_status().await?;
+        debug!("uploaded release asset {asset}");
+        Ok(())
+    }
+
+    /// GitLab doesn't store files in releases: the file is uploaded to the project
+    /// and then linked to the release.
+    async fn upload_gitlab_release_asset(
+        &self,
+        git_tag: &str,
+        asset: &Utf8Path,
+    ) -> anyhow::Result<()> {
+        #[derive(Deserialize)]
+        struct GitLabUpload {
+            full_path: String,
+        }
+
+        let file_name = asset_file_name(asset)?;
+        let content = fs_err::tokio::read(asset).await?;
+        let (content_type, body) = multipart_file_body("file", file_name, &content);
+        let upload: GitLabUpload = self
+            .client
+            .post(format!("{}/uploads", self.remote.base_url))
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await?
+            .successful_status()
+            .await?
+            .json()
+            .await
+            .context("can't parse GitLab upload")?;
+
+        let asset_url = self.gitlab_instance_url(&upload.full_path)?;
+        self.client
+            .post(format!(
+                "{}/releases/{}/assets/links",
+                self.remote.base_url,
+                urlencoding::encode(git_tag)
+            ))
+            .json(&json!({
+                "name": file_name,
+                "url": asset_url.as_str(),
+            }))
+            .send()
+            .await?
+            .successful_status()
+            .await?;
+        debug!("uploaded release asset {asset}");
         Ok(())
     }
 
-    pub async fn create_gitlab_release(&self, release_info: &GitReleaseInfo) -> anyhow::Result<()> {
+    pub async fn create_gitlab_release(
+        &self,
+        release_info: &GitReleaseInfo,
+    ) -> anyhow::Result<ForgeRelease> {
         #[derive(Serialize)]
         pub struct GitlabReleaseOption<'a> {
             name: &'a str,
             tag_name: &'a str,
             description: &'a str,
+            /// If present, the tag is created from this commit.
+            #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+            tag_ref: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            tag_message: Option<&'a str>,
+        }
+        #[derive(Deserialize)]
+        struct GitlabRelease {
+            #[serde(rename = "_links")]
+            links: GitlabReleaseLinks,
+        }
+        #[derive(Deserialize)]
+        struct GitlabReleaseLinks {
+            #[serde(rename = "self")]
+            html_url: String,
+        }
+        if release_info.generate_release_notes {
+            warn!("GitLab can't generate release notes: using the changelog as release body");
         }
         let gitlab_release_options = GitlabReleaseOption {
             name: &release_info.release_name,
             tag_name: &release_info.git_tag,
             description: &release_info.release_body,
+            tag_ref: release_info.tag_ref.as_deref(),
+            tag_message: release_info.tag_message.as_deref(),
         };
-        self.client
+        let release: GitlabRelease = self
+            .client
             .post(format!("{}/releases", self.remote.base_url))
             .json(&gitlab_release_options)
             .send()
@@ -374,8 +789,137 @@ impl GitClient {
                     }
 
                 anyhow::anyhow!(e)
-            })?;
-        Ok(())
+            })?
+            .json()
+            .await
+            .context("can't parse created release")?;
+        for asset in &release_info.assets {
+            self.upload_gitlab_release_asset(&release_info.git_tag, asset)
+                .await
+                .with_context(|| format!("failed to upload release asset {asset}"))?;
+        }
+        Ok(ForgeRelease {
+            html_url: release.links.html_url,
+            id: None,
+        })
+    }
+
+    /// Delete the release of the given tag, even if it's a draft.
+    /// Returns `false` if the release doesn't exist.
+    pub async fn delete_release(&self, git_tag: &str) -> anyhow::Result<bool> {
+        let url = match self.forge {
+            // The endpoints that get a release by tag don't return the draft releases,
+            // so look for the release in the list of all the releases.
+            ForgeType::Github | ForgeType::Gitea => {
+                let releases = self.list_releases().await?;
+                let Some(release_id) = releases
+                    .into_iter()
+                    .find(|r| r.tag_name == git_tag)
+                    .and_then(|r| r.id)
+                else {
+                    return Ok(false);
+                };
+                format!("{}/releases/{release_id}", self.repo_url())
+            }
+            ForgeType::Gitlab => format!(
+                "{}/releases/{}",
+                self.repo_url(),
+                urlencoding::encode(git_tag)
+            ),
+        };
+        let response = self.client.delete(url).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        response
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to delete release of tag {git_tag}"))?;
+        Ok(true)
+    }
+
+    /// Get the tags of all the releases of the repository.
+    pub async fn release_tags(&self) -> anyhow::Result<Vec<String>> {
+        let releases = self.list_releases().await?;
+        Ok(releases.into_iter().map(|r| r.tag_name).collect())
+    }
+
+    /// Draft releases of the repository.
+    /// GitLab doesn't have draft releases.
+    pub async fn draft_releases(&self) -> anyhow::Result<Vec<DraftRelease>> {
+        anyhow::ensure!(
+            self.forge != ForgeType::Gitlab,
+            "GitLab doesn't support draft releases"
+        );
+        let releases = self.list_releases().await?;
+        Ok(releases
+            .into_iter()
+            .filter(|r| r.draft)
+            .filter_map(|r| {
+                Some(DraftRelease {
+                    id: r.id?,
+                    tag_name: r.tag_name,
+                })
+            })
+            .collect())
+    }
+
+    /// Publish the draft release `release`. Same for GitHub and Gitea.
+    pub async fn publish_draft_release(
+        &self,
+        release: &DraftRelease,
+    ) -> anyhow::Result<ForgeRelease> {
+        let published: CreatedRelease = self
+            .client
+            .patch(format!("{}/releases/{}", self.repo_url(), release.id))
+            .json(&json!({ "draft": false }))
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to publish the release of tag {}", release.tag_name))?
+            .json()
+            .await
+            .context("can't parse published release")?;
+        Ok(ForgeRelease {
+            html_url: published.html_url,
+            id: Some(published.id),
+        })
+    }
+
+    async fn list_releases(&self) -> anyhow::Result<Vec<ListedRelease>> {
+        let mut page = 1;
+        let page_size = 50;
+        let mut all_releases = vec![];
+        loop {
+            debug!(
+                "Loading releases from {}, page {page}",
+                self.remote.owner_slash_repo()
+            );
+            let mut url = Url::parse(&format!("{}/releases", self.repo_url()))
+                .context("invalid releases URL")?;
+            url.query_pairs_mut()
+                .append_pair("page", &page.to_string())
+                .append_pair(self.per_page(), &page_size.to_string());
+            let releases: Vec<ListedRelease> = self
+                .client
+                .get(url)
+                .send()
+                .await?
+                .successful_status()
+                .await
+                .context("failed to retrieve releases")?
+                .json()
+                .await
+                .context("can't parse releases")?;
+            let releases_len = releases.len();
+            all_releases.extend(releases);
+            if releases_len < page_size {
+                break;
+            }
+            page += 1;
+        }
+        Ok(all_releases)
     }
 
     pub fn pulls_url(&self) -> String {
@@ -400,7 +944,7 @@ impl GitClient {
         }
     }
 
-    fn repo_url(&self) -> String {
+    pub(crate) fn repo_url(&self) -> String {
         match self.forge {
             ForgeType::Github | ForgeType::Gitea => {
                 format!(
@@ -413,11 +957,36 @@ impl GitClient {
         }
     }
 
-    /// Get all opened Prs which branch starts with the given `branch_prefix`.
+    /// Get all opened release PRs of the given `branch_prefix`. See [`GitPr::is_release_pr`].
     pub async fn opened_prs(&self, branch_prefix: &str) -> anyhow::Result<Vec<GitPr>> {
+        let prs = if self.forge == ForgeType::Github {
+            github_graphql::opened_prs(self).await?
+        } else {
+            self.all_opened_prs().await?
+        };
+        // Only read the user of the token if a PR is recognized by its marker.
+        let token_user = if prs.iter().any(|pr| {
+            !pr.branch().starts_with(branch_prefix) && pr.has_release_pr_marker(branch_prefix)
+        }) {
+            self.token_user()
+                .await
+                .inspect_err(|e| {
+                    warn!("can't read the user of the git token, so the release PRs recognized by their marker are ignored: {e:?}");
+                })
+                .ok()
+        } else {
+            None
+        };
+        Ok(prs
+            .into_iter()
+            .filter(|pr| pr.is_release_pr(branch_prefix, token_user.as_deref()))
+            .collect())
+    }
+
+    async fn all_opened_prs(&self) -> anyhow::Result<Vec<GitPr>> {
         let mut page = 1;
         let page_size = 30;
-        let mut release_prs: Vec<GitPr> = vec![];
+        let mut opened_prs: Vec<GitPr> = vec![];
         loop {
             debug!(
                 "Loading prs from {}, page {page}",
@@ -428,17 +997,58 @@ impl GitClient {
                 .await
                 .context("Failed to retrieve open PRs")?;
             let prs_len = prs.len();
-            let current_release_prs: Vec<GitPr> = prs
-                .into_iter()
-                .filter(|pr| pr.head.ref_field.starts_with(branch_prefix))
-                .collect();
-            release_prs.extend(current_release_prs);
+            opened_prs.extend(prs);
             if prs_len < page_size {
                 break;
             }
             page += 1;
         }
-        Ok(release_prs)
+        Ok(opened_prs)
+    }
+
+    /// Login of the user authenticated by the git token.
+    async fn token_user(&self) -> anyhow::Result<String> {
+        #[derive(Deserialize)]
+        struct GiteaUser {
+            login: String,
+        }
+
+        match self.forge {
+            ForgeType::Github => github_graphql::viewer_login(self).await,
+            ForgeType::Gitea => {
+                let user: GiteaUser = self
+                    .client
+                    .get(format!("{}user", self.remote.base_url))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?
+                    .json()
+                    .await
+                    .context("can't parse Gitea user")?;
+                Ok(user.login)
+            }
+            ForgeType::Gitlab => {
+                self.ensure_not_job_token("read the current user")?;
+                // The base url points to the project, e.g. `https://gitlab.com/api/v4/projects/<id>`.
+                let url = self
+                    .remote
+                    .base_url
+                    .join("../user")
+                    .context("invalid GitLab user url")?;
+                let user: GitLabUser = self
+                    .client
+                    .get(url)
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?
+                    .json()
+                    .await
+                    .context("can't parse GitLab user")?;
+                Ok(user.username)
+            }
+        }
     }
 
     async fn opened_prs_page(&self, page: i32, page_size: usize) -> anyhow::Result<Vec<GitPr>> {
@@ -494,6 +1104,61 @@ impl GitClient {
         Ok(())
     }
 
+    /// Merge the PR with `merge_method` once its checks pass.
+    pub async fn enable_auto_merge(
+        &self,
+        pr_number: u64,
+        merge_method: MergeMethod,
+    ) -> anyhow::Result<()> {
+        match self.forge {
+            ForgeType::Github => {
+                github_graphql::enable_auto_merge(self, pr_number, merge_method).await?;
+            }
+            // Docs: https://docs.gitea.com/api/1.22/#tag/repository/operation/repoMergePullRequest
+            ForgeType::Gitea => {
+                let merge_style = match merge_method {
+                    MergeMethod::Merge => "merge",
+                    MergeMethod::Squash => "squash",
+                    MergeMethod::Rebase => "rebase",
+                };
+                self.client
+                    .post(format!("{}/{pr_number}/merge", self.pulls_url()))
+                    .json(&json!({
+                        "Do": merge_style,
+                        "merge_when_checks_succeed": true,
+                        "delete_branch_after_merge": true,
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await
+                    .with_context(|| format!("cannot schedule the merge of pr #{pr_number}"))?;
+            }
+            // Docs: https://docs.gitlab.com/api/merge_requests/#merge-a-merge-request
+            ForgeType::Gitlab => {
+                if merge_method == MergeMethod::Rebase {
+                    warn!("GitLab sets the rebase merge method in the project settings");
+                }
+                self.client
+                    .put(format!("{}/{pr_number}/merge", self.pulls_url()))
+                    .json(&json!({
+                        "merge_when_pipeline_succeeds": true,
+                        "squash": merge_method == MergeMethod::Squash,
+                        "should_remove_source_branch": true,
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await
+                    .with_context(|| {
+                        format!("cannot set merge request !{pr_number} to auto-merge")
+                    })?;
+            }
+        }
+        info!("pr #{pr_number} will be merged when its checks pass");
+        Ok(())
+    }
+
     fn closed_pr_state(&self) -> &'static str {
         match self.forge {
             ForgeType::Github | ForgeType::Gitea => "closed",
@@ -525,6 +1190,7 @@ impl GitClient {
 
     #[instrument(skip(self, pr))]
     pub async fn open_pr(&self, pr: &Pr) -> anyhow::Result<GitPr> {
+        self.ensure_not_job_token("open merge requests")?;
         debug!("Opening PR in {}", self.remote.owner_slash_repo());
 
         let json_body = match self.forge {
@@ -536,16 +1202,20 @@ impl GitClient {
                 "draft": pr.draft,
             }),
             // Docs: https://docs.gitlab.com/api/merge_requests/#create-mr
-            ForgeType::Gitlab => json!({
-                "title": pr.title,
-                "description": pr.body,
-                "target_branch": pr.base_branch,
-                "source_branch": pr.branch,
-                "draft": pr.draft,
-                // By default, remove the source branch when merging the PR.
-                // The checkbox can be unchecked in the UI before merging.
-                "remove_source_branch": true
-            }),
+            ForgeType::Gitlab => {
+                let assignees: Vec<&str> = pr.assignees.iter().map(String::as_str).collect();
+                json!({
+                    "title": gitlab_mr_title(&pr.title, pr.draft),
+                    "description": pr.body,
+                    "target_branch": pr.base_branch,
+                    "source_branch": pr.branch,
+                    "assignee_ids": self.gitlab_user_ids(&assignees).await?,
+                    "reviewer_ids": self.gitlab_reviewer_ids(&pr.reviewers).await?,
+                    // By default, remove the source branch when merging the PR.
+                    // The checkbox can be unchecked in the UI before merging.
+                    "remove_source_branch": true
+                })
+            }
         };
 
         let rep = self
@@ -573,9 +1243,133 @@ impl GitClient {
         self.add_labels(&pr.labels, git_pr.number)
             .await
             .context("Failed to add labels")?;
+        // GitLab sets the reviewers and the assignees when creating the MR.
+        if self.forge != ForgeType::Gitlab {
+            self.request_reviewers(&pr.reviewers, git_pr.number)
+                .await
+                .context("Failed to request reviewers")?;
+            self.add_assignees(&pr.assignees, git_pr.number)
+                .await
+                .context("Failed to add assignees")?;
+        }
+        // The PR is open: don't fail if its optional settings can't be applied.
+        if let Some(approvals) = pr.required_approvals
+            && let Err(e) = self
+                .require_approvals(git_pr.number, approvals, &pr.reviewers)
+                .await
+        {
+            warn!("failed to set the required approvals of the PR: {e:?}");
+        }
+        if let Some(milestone) = &pr.milestone
+            && let Err(e) = self.set_pr_milestone(git_pr.number, milestone).await
+        {
+            warn!("failed to set the milestone of the PR: {e:?}");
+        }
+        if let Some(merge_method) = pr.auto_merge
+            && let Err(e) = self.enable_auto_merge(git_pr.number, merge_method).await
+        {
+            warn!("failed to enable auto-merge on the PR: {e:?}");
+        }
+        if let Some(project) = &pr.project {
+            if self.forge == ForgeType::Github {
+                if let Err(e) =
+                    github_graphql::add_pr_to_project(self, git_pr.number, project).await
+                {
+                    warn!("failed to add the PR to the project: {e:?}");
+                }
+            } else {
+                warn!("projects are only supported on GitHub: not adding the PR to a project");
+            }
+        }
         Ok(git_pr)
     }
 
+    /// Request a review of the PR.
+    /// Reviewers in the `org/team` format are teams, the others are users.
+    #[instrument(skip(self))]
+    pub async fn request_reviewers(
+        &self,
+        reviewers: &[String],
+        pr_number: u64,
+    ) -> anyhow::Result<()> {
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+        match self.forge {
+            // Docs: https://docs.github.com/en/rest/pulls/review-requests#request-reviewers-for-a-pull-request
+            ForgeType::Github | ForgeType::Gitea => {
+                let (users, teams) = split_users_and_teams(reviewers);
+                self.client
+                    .post(format!(
+                        "{}/{}/requested_reviewers",
+                        self.pulls_url(),
+                        pr_number
+                    ))
+                    .json(&json!({
+                        "reviewers": users,
+                        "team_reviewers": teams,
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+            }
+            // Docs: https://docs.gitlab.com/api/merge_requests/#update-mr
+            ForgeType::Gitlab => {
+                let reviewer_ids = self.gitlab_reviewer_ids(reviewers).await?;
+                self.client
+                    .put(format!("{}/{}", self.pulls_url(), pr_number))
+                    .json(&json!({ "reviewer_ids": reviewer_ids }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assign the PR to the given users.
+    #[instrument(skip(self))]
+    pub async fn add_assignees(&self, assignees: &[String], pr_number: u64) -> anyhow::Result<()> {
+        if assignees.is_empty() {
+            return Ok(());
+        }
+        match self.forge {
+            // Docs: https://docs.github.com/en/rest/issues/assignees#add-assignees-to-an-issue
+            ForgeType::Github => {
+                self.client
+                    .post(format!("{}/{}/assignees", self.issues_url(), pr_number))
+                    .json(&json!({ "assignees": assignees }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+            }
+            ForgeType::Gitea => {
+                self.client
+                    .patch(format!("{}/{}", self.pulls_url(), pr_number))
+                    .json(&json!({ "assignees": assignees }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+            }
+            ForgeType::Gitlab => {
+                let usernames: Vec<&str> = assignees.iter().map(String::as_str).collect();
+                let assignee_ids = self.gitlab_user_ids(&usernames).await?;
+                self.client
+                    .put(format!("{}/{}", self.pulls_url(), pr_number))
+                    .json(&json!({ "assignee_ids": assignee_ids }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     pub async fn add_labels(&self, labels: &[String], pr_number: u64) -> anyhow::Result<()> {
         if labels.is_empty() {
@@ -589,6 +1383,52 @@ impl GitClient {
         }
     }
 
+    /// Add and remove the labels of the PR, so that they are exactly `labels`.
+    #[instrument(skip(self, pr), fields(pr_number = pr.number))]
+    pub async fn sync_labels(&self, labels: &[String], pr: &GitPr) -> anyhow::Result<()> {
+        let (to_add, to_remove) = labels_diff(&pr.labels, labels);
+        self.add_labels(&to_add, pr.number).await?;
+        if to_remove.is_empty() {
+            return Ok(());
+        }
+        debug!("removing labels {to_remove:?} from pr #{}", pr.number);
+        match self.forge {
+            ForgeType::Github | ForgeType::Gitea => {
+                for label in to_remove {
+                    let label_ref = match self.forge {
+                        ForgeType::Gitea => label
+                            .id
+                            .with_context(|| {
+                                format!("failed to extract id from label '{}'", label.name)
+                            })?
+                            .to_string(),
+                        _ => urlencoding::encode(&label.name).into_owned(),
+                    };
+                    self.client
+                        .delete(format!("{}/{label_ref}", self.pr_labels_url(pr.number)))
+                        .send()
+                        .await?
+                        .successful_status()
+                        .await
+                        .with_context(|| format!("failed to remove label '{}'", label.name))?;
+                }
+            }
+            ForgeType::Gitlab => {
+                self.client
+                    .put(format!("{}/{}", self.pulls_url(), pr.number))
+                    .json(&json!({
+                        "remove_labels": to_remove.iter().map(|l| &l.name).join(",")
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await
+                    .context("failed to remove labels")?;
+            }
+        }
+        Ok(())
+    }
+
     fn pr_labels_url(&self, pr_number: u64) -> String {
         format!("{}/{}/labels", self.issues_url(), pr_number)
     }
@@ -762,6 +1602,9 @@ impl GitClient {
     }
 
     pub async fn pr_commits(&self, pr_number: u64) -> anyhow::Result<Vec<PrCommit>> {
+        if self.forge == ForgeType::Github {
+            return github_graphql::pr_commits(self, pr_number).await;
+        }
         let resp = self
             .client
             .get(format!("{}/{}/commits", self.pulls_url(), pr_number))
@@ -778,12 +1621,19 @@ impl GitClient {
                 resp.json().await.context("failed to parse pr commits")
             }
             ForgeType::Gitlab => {
-                let gitlab_commits: Vec<GitLabMrCommit> =
+                let gitlab_commits: Vec<GitLabCommit> =
                     resp.json().await.context("failed to parse gitlab mr")?;
-                let pr_commits = gitlab_commits
-                    .into_iter()
-                    .map(|commit| commit.into())
-                    .collect();
+                let mut pr_commits = vec![];
+                for commit in gitlab_commits {
+                    let author = match commit.author_email {
+                        Some(email) => self.gitlab_user_by_email(&email).await.map(Author::from),
+                        None => None,
+                    };
+                    pr_commits.push(PrCommit {
+                        author,
+                        sha: commit.id,
+                    });
+                }
                 Ok(pr_commits)
             }
         }
@@ -793,6 +1643,10 @@ impl GitClient {
     /// From my tests, Gitea doesn't work yet,
     /// but this implementation should be correct.
     pub async fn associated_prs(&self, commit: &str) -> anyhow::Result<Vec<GitPr>> {
+        if self.job_token {
+            debug!("GitLab CI/CD job tokens can't read merge requests: no associated PRs");
+            return Ok(vec![]);
+        }
         let url = match self.forge {
             ForgeType::Github => {
                 format!("{}/commits/{}/pulls", self.repo_url(), commit)
@@ -863,6 +1717,9 @@ impl GitClient {
     }
 
     pub async fn get_prs_info(&self, pr_numbers: &[u64]) -> anyhow::Result<Vec<GitPr>> {
+        if self.forge == ForgeType::Github {
+            return github_graphql::prs_info(self, pr_numbers).await;
+        }
         let mut prs = vec![];
         for pr_number in pr_numbers {
             let pr = self.get_pr_info(*pr_number).await?;
@@ -884,14 +1741,25 @@ impl GitClient {
             return Ok(RemoteCommit { username: None });
         }
 
-        let remote_commit: GitHubCommit = response
-            .successful_status()
-            .await?
-            .json()
-            .await
-            .context("can't parse commits")?;
-
-        let username = remote_commit.author.and_then(|author| author.login);
+        let response = response.successful_status().await?;
+        let username = match self.forge {
+            ForgeType::Github | ForgeType::Gitea => {
+                let remote_commit: GitHubCommit =
+                    response.json().await.context("can't parse commits")?;
+                remote_commit.author.and_then(|author| author.login)
+            }
+            ForgeType::Gitlab => {
+                let remote_commit: GitLabCommit =
+                    response.json().await.context("can't parse gitlab commit")?;
+                match remote_commit.author_email {
+                    Some(email) => self
+                        .gitlab_user_by_email(&email)
+                        .await
+                        .map(|user| user.username),
+                    None => None,
+                }
+            }
+        };
         Ok(RemoteCommit { username })
     }
 
@@ -902,13 +1770,136 @@ impl GitClient {
                 format!("git/{commits_path}")
             }
             ForgeType::Github => commits_path.to_string(),
-            ForgeType::Gitlab => {
-                unimplemented!("Gitlab support for `k-releaser release-pr` is not implemented yet")
-            }
+            ForgeType::Gitlab => format!("repository/{commits_path}"),
         };
         format!("{}/{commits_api_path}{commit}", self.repo_url())
     }
 
+    /// Find the GitLab user with the given email.
+    /// GitLab only matches public emails, so the user might not be found.
+    /// Lookups are cached, and a failed lookup leaves the commit with its git author only.
+    async fn gitlab_user_by_email(&self, email: &str) -> Option<GitLabUser> {
+        if let Some(user) = self.gitlab_users.lock().unwrap().get(email) {
+            return user.clone();
+        }
+        let user = self
+            .fetch_gitlab_user_by_email(email)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("can't find the GitLab user with email {email}: {e:?}");
+                None
+            });
+        self.gitlab_users
+            .lock()
+            .unwrap()
+            .insert(email.to_string(), user.clone());
+        user
+    }
+
+    async fn fetch_gitlab_user_by_email(&self, email: &str) -> anyhow::Result<Option<GitLabUser>> {
+        let users: Vec<GitLabUser> = self
+            .client
+            .get(self.gitlab_users_url()?)
+            .query(&[("search", email)])
+            .send()
+            .await?
+            .successful_status()
+            .await?
+            .json()
+            .await
+            .context("can't parse GitLab users")?;
+        if users.is_empty() {
+            debug!("No GitLab user found with email {email}");
+        }
+        Ok(users.into_iter().next())
+    }
+
+    /// Ids of the GitLab users among the `reviewers`.
+    async fn gitlab_reviewer_ids(&self, reviewers: &[String]) -> anyhow::Result<Vec<i32>> {
+        let (users, teams) = split_users_and_teams(reviewers);
+        if !teams.is_empty() {
+            warn!("GitLab doesn't support team reviewers, ignoring {teams:?}");
+        }
+        self.gitlab_user_ids(&users).await
+    }
+
+    /// Require `approvals` approvals to merge the PR, from the `reviewers` if any.
+    /// Only GitLab supports it, with a merge request approval rule.
+    /// GitHub and Gitea require approvals through the branch protection rules.
+    pub async fn require_approvals(
+        &self,
+        pr_number: u64,
+        approvals: u32,
+        reviewers: &[String],
+    ) -> anyhow::Result<()> {
+        if self.forge != ForgeType::Gitlab {
+            warn!(
+                "required approvals are only supported on GitLab: use the branch protection rules"
+            );
+            return Ok(());
+        }
+        // Docs: https://docs.gitlab.com/api/merge_request_approvals/#create-a-merge-request-level-rule
+        let user_ids = self.gitlab_reviewer_ids(reviewers).await?;
+        self.client
+            .post(format!("{}/{pr_number}/approval_rules", self.pulls_url()))
+            .json(&json!({
+                "name": "k-releaser",
+                "approvals_required": approvals,
+                "user_ids": user_ids,
+            }))
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("cannot add approval rule to merge request !{pr_number}"))?;
+        debug!("merge request !{pr_number} requires {approvals} approvals");
+        Ok(())
+    }
+
+    /// Ids of the GitLab users with the given usernames.
+    async fn gitlab_user_ids(&self, usernames: &[&str]) -> anyhow::Result<Vec<i32>> {
+        let mut ids = vec![];
+        for username in usernames {
+            let users: Vec<GitLabUser> = self
+                .client
+                .get(self.gitlab_users_url()?)
+                .query(&[("username", username)])
+                .send()
+                .await?
+                .successful_status()
+                .await?
+                .json()
+                .await
+                .context("can't parse GitLab users")?;
+            let user = users
+                .into_iter()
+                .next()
+                .with_context(|| format!("GitLab user `{username}` not found"))?;
+            ids.push(user.id);
+        }
+        Ok(ids)
+    }
+
+    fn gitlab_users_url(&self) -> anyhow::Result<Url> {
+        // The base url points to the project, e.g. `https://gitlab.com/api/v4/projects/<id>`.
+        self.remote
+            .base_url
+            .join("../users")
+            .context("invalid GitLab users url")
+    }
+
+    /// Url of `path` in the GitLab instance, e.g. the `full_path` of an upload.
+    /// `path` is relative to the root of the instance, which can be a relative url,
+    /// e.g. `https://example.com/gitlab`.
+    fn gitlab_instance_url(&self, path: &str) -> anyhow::Result<Url> {
+        // The base url points to the project, e.g. `https://gitlab.com/api/v4/projects/<id>`.
+        self.remote
+            .base_url
+            .join("../../../")
+            .and_then(|root| root.join(path.trim_start_matches('/')))
+            .with_context(|| format!("invalid GitLab path {path}"))
+    }
+
     /// Create a new branch from the given SHA.
     pub async fn create_branch(&self, branch_name: &str, sha: &str) -> anyhow::Result<()> {
         match self.forge {
@@ -1024,15 +2015,21 @@ Please push your local commits and run k-releaser again.\nResponse body: {body}"
         Ok(())
     }
 
-    /// Creates an annotated tag.
+    /// Creates an annotated tag, or a lightweight one if `message` is [`Option::None`].
     pub async fn create_tag(
         &self,
         tag_name: &str,
-        message: &str,
+        message: Option<&str>,
         sha: &str,
     ) -> Result<(), anyhow::Error> {
         match self.forge {
-            ForgeType::Github => self.create_github_tag(tag_name, message, sha).await,
+            ForgeType::Github => match message {
+                Some(message) => self.create_github_tag(tag_name, message, sha).await,
+                None => {
+                    self.post_github_ref(&format!("refs/tags/{tag_name}"), sha)
+                        .await
+                }
+            },
             ForgeType::Gitlab => self.create_gitlab_tag(tag_name, message, sha).await,
             ForgeType::Gitea => self.create_gitea_tag(tag_name, message, sha).await,
         }
@@ -1072,16 +2069,21 @@ Please push your local commits and run k-releaser again.\nResponse body: {body}"
     async fn create_gitlab_tag(
         &self,
         tag_name: &str,
-        message: &str,
+        message: Option<&str>,
         sha: &str,
     ) -> Result<(), anyhow::Error> {
+        self.ensure_not_job_token("create tags")?;
+        let mut body = json!({
+            "tag_name": tag_name,
+            "ref": sha
+        });
+        // Without a message, GitLab creates a lightweight tag.
+        if let Some(message) = message {
+            body["message"] = json!(message);
+        }
         self.client
             .post(format!("{}/repository/tags", self.repo_url()))
-            .json(&json!({
-                "tag_name": tag_name,
-                "ref": sha,
-                "message": message
-            }))
+            .json(&body)
             .send()
             .await?
             .successful_status()
@@ -1090,19 +2092,70 @@ Please push your local commits and run k-releaser again.\nResponse body: {body}"
         Ok(())
     }
 
+    /// Point the lightweight tag `tag_name` to `sha`, creating the tag if it doesn't exist.
+    /// Used for floating tags like `v1`.
+    pub async fn force_update_tag(&self, tag_name: &str, sha: &str) -> anyhow::Result<()> {
+        match self.forge {
+            ForgeType::Github => {
+                let response = self
+                    .client
+                    .get(format!("{}/git/ref/tags/{tag_name}", self.repo_url()))
+                    .send()
+                    .await?;
+                if response.status() == StatusCode::NOT_FOUND {
+                    self.post_github_ref(&format!("refs/tags/{tag_name}"), sha)
+                        .await
+                } else {
+                    self.patch_github_ref(&format!("tags/{tag_name}"), sha)
+                        .await
+                }
+            }
+            // GitLab and Gitea can't move tags, so the tag is recreated.
+            ForgeType::Gitlab => {
+                let tag_url = format!(
+                    "{}/repository/tags/{}",
+                    self.repo_url(),
+                    urlencoding::encode(tag_name)
+                );
+                self.delete_tag_if_exists(&tag_url, tag_name).await?;
+                self.create_gitlab_tag(tag_name, None, sha).await
+            }
+            ForgeType::Gitea => {
+                let tag_url = format!("{}/tags/{}", self.repo_url(), urlencoding::encode(tag_name));
+                self.delete_tag_if_exists(&tag_url, tag_name).await?;
+                self.create_gitea_tag(tag_name, None, sha).await
+            }
+        }
+    }
+
+    async fn delete_tag_if_exists(&self, tag_url: &str, tag_name: &str) -> anyhow::Result<()> {
+        let response = self.client.delete(tag_url).send().await?;
+        if response.status() != StatusCode::NOT_FOUND {
+            response
+                .successful_status()
+                .await
+                .with_context(|| format!("failed to delete git tag '{tag_name}'"))?;
+        }
+        Ok(())
+    }
+
     async fn create_gitea_tag(
         &self,
         tag_name: &str,
-        message: &str,
+        message: Option<&str>,
         sha: &str,
     ) -> Result<(), anyhow::Error> {
+        let mut body = json!({
+            "tag_name": tag_name,
+            "target": sha
+        });
+        // Without a message, Gitea creates a lightweight tag.
+        if let Some(message) = message {
+            body["message"] = json!(message);
+        }
         self.client
             .post(format!("{}/tags", self.repo_url()))
-            .json(&json!({
-                "tag_name": tag_name,
-                "target": sha,
-                "message": message
-            }))
+            .json(&body)
             .send()
             .await?
             .successful_status()
@@ -1145,6 +2198,61 @@ pub fn validate_labels(labels: &[String]) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Release returned when listing the releases of the repository.
+#[derive(Deserialize, Debug)]
+struct ListedRelease {
+    tag_name: String,
+    /// GitLab identifies releases by their tag, so they don't have an id.
+    id: Option<u64>,
+    /// GitLab doesn't have draft releases.
+    #[serde(default)]
+    draft: bool,
+}
+
+/// Draft release of the repository, not visible to the users yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DraftRelease {
+    pub id: u64,
+    pub tag_name: String,
+}
+
+/// Release returned by GitHub and Gitea after creating it.
+#[derive(Deserialize, Debug)]
+struct CreatedRelease {
+    id: u64,
+    html_url: String,
+    /// Only returned by GitHub.
+    upload_url: Option<String>,
+}
+
+fn asset_file_name(asset: &Utf8Path) -> anyhow::Result<&str> {
+    asset
+        .file_name()
+        .with_context(|| format!("release asset {asset} is not a file"))
+}
+
+/// Build a `multipart/form-data` body containing a single file.
+/// Returns the content type and the body.
+fn multipart_file_body(field: &str, file_name: &str, content: &[u8]) -> (String, Vec<u8>) {
+    let boundary = format!("k-releaser-{:016x}", rand::random::<u64>());
+    let file_name = escape_multipart_name(file_name);
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"{field}\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    (format!("multipart/form-data; boundary={boundary}"), body)
+}
+
+/// Escape a name of a `multipart/form-data` header as browsers do,
+/// so that quotes and line breaks can't end the header.
+fn escape_multipart_name(name: &str) -> String {
+    name.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
 /// Representation of a single commit.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GitHubCommit {
@@ -1161,6 +2269,9 @@ pub struct GitHubCommitAuthor {
     pub login: Option<String>,
 }
 
+/// Accounts of Gitea Actions and Forgejo Actions, which push the commits of the CI jobs.
+const ACTIONS_BOT_LOGINS: &[&str] = &["gitea-actions", "forgejo-actions"];
+
 /// Returns the list of contributors for the given commits,
 /// excluding the PR author and bots.
 pub fn contributors_from_commits(commits: &[PrCommit], forge: ForgeType) -> Vec<String> {
@@ -1169,7 +2280,9 @@ pub fn contributors_from_commits(commits: &[PrCommit], forge: ForgeType) -> Vec<
         .skip(1) // skip pr author
         .flat_map(|commit| &commit.author)
         .filter(|author| {
-            let is_gitea_actions_account = forge == ForgeType::Gitea && author.id == -2;
+            // Forgejo doesn't always resolve the actions account to its id.
+            let is_gitea_actions_account = forge == ForgeType::Gitea
+                && (author.id == -2 || ACTIONS_BOT_LOGINS.contains(&author.login.as_str()));
             let is_bot = author.login.ends_with("[bot]") || is_gitea_actions_account;
             !is_bot
         })
@@ -1179,9 +2292,141 @@ pub fn contributors_from_commits(commits: &[PrCommit], forge: ForgeType) -> Vec<
     contributors
 }
 
+/// Split reviewers in users and teams.
+/// Teams are written as `org/team`: only the team name is returned.
+fn split_users_and_teams(reviewers: &[String]) -> (Vec<&str>, Vec<&str>) {
+    let mut users = vec![];
+    let mut teams = vec![];
+    for reviewer in reviewers {
+        match reviewer.split_once('/') {
+            Some((_org, team)) => teams.push(team),
+            None => users.push(reviewer.as_str()),
+        }
+    }
+    (users, teams)
+}
+
+/// Labels to add to and remove from a PR with the `current` labels,
+/// so that it has exactly the `wanted` labels.
+fn labels_diff<'a>(current: &'a [Label], wanted: &[String]) -> (Vec<String>, Vec<&'a Label>) {
+    let to_add = wanted
+        .iter()
+        .filter(|label| !current.iter().any(|c| &c.name == *label))
+        .unique()
+        .cloned()
+        .collect();
+    let to_remove = current
+        .iter()
+        .filter(|label| !wanted.contains(&label.name))
+        .collect();
+    (to_add, to_remove)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::RepoUrl;
+
+    fn gitlab_client() -> GitClient {
+        let repo_url = RepoUrl::new("https://gitlab.com/owner/repo").unwrap();
+        let gitlab = GitLab::new(repo_url, SecretString::from("token".to_string())).unwrap();
+        GitClient::new(GitForge::Gitlab(gitlab)).unwrap()
+    }
+
+    #[test]
+    fn multipart_body_contains_file() {
+        let (content_type, body) = multipart_file_body("file", "app.tar.gz", b"content");
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = String::from_utf8(body).unwrap();
+        assert_eq!(
+            body,
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"app.tar.gz\"\r\nContent-Type: application/octet-stream\r\n\r\ncontent\r\n--{boundary}--\r\n"
+            )
+        );
+    }
+
+    fn renamed_release_pr(login: &str, head_repo_id: u64) -> GitPr {
+        serde_json::from_value(json!({
+            "user": { "id": 1, "login": login },
+            "number": 1,
+            "html_url": "https://github.com/owner/repo/pull/1",
+            "head": { "ref": "renamed", "sha": "abc", "repo": { "id": head_repo_id } },
+            "base": { "repo": { "id": 1 } },
+            "title": "chore: release",
+            "body": "<!-- k-releaser-release-pr branch-prefix=\"k-releaser-\" -->",
+            "labels": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn release_pr_marker_is_trusted_only_for_the_prs_of_the_token_user() {
+        let pr = renamed_release_pr("github-actions[bot]", 1);
+        assert!(pr.is_release_pr("k-releaser-", Some("github-actions")));
+        assert!(!pr.is_release_pr("k-releaser-", Some("someone")));
+        assert!(!pr.is_release_pr("k-releaser-", None));
+        // PR from a fork.
+        let pr = renamed_release_pr("github-actions[bot]", 2);
+        assert!(!pr.is_release_pr("k-releaser-", Some("github-actions")));
+    }
+
+    #[test]
+    fn multipart_file_name_is_escaped() {
+        let (_, body) = multipart_file_body("file", "a\"b\r\nc.tar.gz", b"content");
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains("filename=\"a%22b%0D%0Ac.tar.gz\"\r\n"));
+    }
+
+    #[test]
+    fn gitlab_commits_api_path_is_correct() {
+        assert_eq!(
+            gitlab_client().commits_api_path("abc"),
+            "https://gitlab.com/api/v4/projects/owner%2Frepo/repository/commits/abc"
+        );
+    }
+
+    #[test]
+    fn reviewers_are_split_in_users_and_teams() {
+        let reviewers = vec!["alice".to_string(), "my-org/maintainers".to_string()];
+        let (users, teams) = split_users_and_teams(&reviewers);
+        assert_eq!(users, vec!["alice"]);
+        assert_eq!(teams, vec!["maintainers"]);
+    }
+
+    #[test]
+    fn gitlab_instance_url_keeps_the_relative_url_root() {
+        let mut client = gitlab_client();
+        let url = client
+            .gitlab_instance_url("/-/project/1/uploads/abc/file.tar.gz")
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://gitlab.com/-/project/1/uploads/abc/file.tar.gz"
+        );
+        client.remote.base_url = "https://example.com/gitlab/api/v4/projects/group%2Fsub%2Fproj"
+            .parse()
+            .unwrap();
+        let url = client
+            .gitlab_instance_url("/-/project/1/uploads/abc/file.tar.gz")
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://example.com/gitlab/-/project/1/uploads/abc/file.tar.gz"
+        );
+        assert_eq!(
+            client.remote.base_url.join("../users").unwrap().as_str(),
+            "https://example.com/gitlab/api/v4/users"
+        );
+    }
+
+    #[test]
+    fn gitlab_users_url_is_relative_to_api_root() {
+        let users_url = gitlab_client().remote.base_url.join("../users").unwrap();
+        assert_eq!(users_url.as_str(), "https://gitlab.com/api/v4/users");
+    }
 
     #[test]
     fn contributors_are_extracted_from_commits() {
@@ -1214,6 +2459,13 @@ mod tests {
                 }),
                 sha: "abc".to_string(),
             },
+            PrCommit {
+                author: Some(Author {
+                    id: 0,
+                    login: "forgejo-actions".to_string(),
+                }),
+                sha: "abc".to_string(),
+            },
             PrCommit {
                 author: None,
                 sha: "abc".to_string(),
@@ -1222,4 +2474,63 @@ mod tests {
         let contributors = contributors_from_commits(&commits, ForgeType::Gitea);
         assert_eq!(contributors, vec!["marco"]);
     }
+
+    #[test]
+    fn labels_are_diffed() {
+        let label = |name: &str| Label {
+            name: name.to_string(),
+            id: None,
+        };
+        let current = [label("release"), label("old")];
+        let wanted = ["release".to_string(), "automated".to_string()];
+        let (to_add, to_remove) = labels_diff(&current, &wanted);
+        assert_eq!(to_add, vec!["automated".to_string()]);
+        assert_eq!(
+            to_remove
+                .iter()
+                .map(|l| l.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["old"]
+        );
+    }
+
+    #[test]
+    fn job_tokens_cant_call_restricted_endpoints() {
+        let mut client = gitlab_client();
+        assert!(client.ensure_not_job_token("open merge requests").is_ok());
+        client.job_token = true;
+        let err = client
+            .ensure_not_job_token("open merge requests")
+            .unwrap_err();
+        assert!(err.to_string().contains("can't open merge requests"));
+    }
+
+    #[test]
+    fn gitlab_drafts_are_marked_in_the_title() {
+        assert_eq!(
+            gitlab_mr_title("chore: release", true),
+            "Draft: chore: release"
+        );
+        assert_eq!(
+            gitlab_mr_title("Draft: chore: release", true),
+            "Draft: chore: release"
+        );
+        assert_eq!(
+            gitlab_mr_title("[Draft] chore: release", false),
+            "chore: release"
+        );
+        assert_eq!(
+            strip_gitlab_draft_prefix("draft:chore: release"),
+            "chore: release"
+        );
+        assert_eq!(
+            strip_gitlab_draft_prefix("chore: release"),
+            "chore: release"
+        );
+        let edit: GitLabMrEdit = PrEdit::new()
+            .with_title("chore: release")
+            .with_draft(true)
+            .into();
+        assert_eq!(edit.title.as_deref(), Some("Draft: chore: release"));
+    }
 }
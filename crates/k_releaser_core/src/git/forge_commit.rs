@@ -0,0 +1,273 @@
+use anyhow::{Context, Result};
+use base64::prelude::*;
+use cargo_metadata::camino::Utf8Path;
+use git_cmd::Repo;
+use serde_json::{Value, json};
+use tracing::{debug, trace};
+
+use crate::GitClient;
+use crate::git::forge::ForgeType;
+use crate::response_ext::ResponseExt;
+
+/// Commit all the changes (except typechanges) that are present in the repository
+/// using the commit API of GitLab or Gitea.
+/// The forge creates the commit, so it's signed with the key of the forge instance
+/// and it's accepted by branches that require signed commits.
+///
+/// The `branch` must already exist. If `force` is `true`, the branch is reset to the
+/// local `HEAD` before committing (only supported by GitLab).
+/// Returns the sha of the new commit.
+pub async fn commit_changes(
+    client: &GitClient,
+    repo: &Repo,
+    message: &str,
+    branch: &str,
+    force: bool,
+) -> Result<String> {
+    let changes = file_changes(repo)?;
+    let changes = with_contents(repo.directory(), changes).await?;
+    match client.forge {
+        ForgeType::Gitlab => gitlab_commit(client, repo, message, branch, force, &changes).await,
+        ForgeType::Gitea => {
+            anyhow::ensure!(!force, "Gitea doesn't support force-pushing via the API");
+            gitea_commit(client, repo, message, branch, &changes).await
+        }
+        ForgeType::Github => {
+            anyhow::bail!("use the GraphQL API to create commits on GitHub")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Create,
+    Update,
+    Delete,
+}
+
+impl Operation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct FileChange {
+    operation: Operation,
+    path: String,
+    /// Base64 encoded content of the file. Empty for deletions.
+    content: String,
+}
+
+// get the list of changes in repository excluding typechanges
+fn file_changes(repo: &Repo) -> Result<Vec<(Operation, String)>> {
+    let deleted = repo.changes(|line| status(line).contains('D'))?;
+    let created = repo.changes(|line| status(line) == "??" || status(line).contains('A'))?;
+    let updated = repo.changes(|line| {
+        let status = status(line);
+        status.contains('M') && !status.contains('A') && !status.contains('D')
+    })?;
+    let changes = deleted
+        .into_iter()
+        .map(|path| (Operation::Delete, path))
+        .chain(created.into_iter().map(|path| (Operation::Create, path)))
+        .chain(updated.into_iter().map(|path| (Operation::Update, path)))
+        .collect();
+    Ok(changes)
+}
+
+/// Status code of a line of `git status --porcelain`.
+fn status<'a>(line: &&'a str) -> &'a str {
+    line.get(..2).unwrap_or_default()
+}
+
+async fn with_contents(
+    repo_dir: &Utf8Path,
+    changes: Vec<(Operation, String)>,
+) -> Result<Vec<FileChange>> {
+    let mut file_changes = vec![];
+    for (operation, path) in changes {
+        let content = if operation == Operation::Delete {
+            String::new()
+        } else {
+            let realpath = repo_dir.join(&path);
+            // Git submodules are reported as directories.
+            if realpath.is_dir() {
+                debug!("skipping directory `{realpath}` in git changes");
+                continue;
+            }
+            BASE64_STANDARD.encode(fs_err::tokio::read(realpath).await?)
+        };
+        file_changes.push(FileChange {
+            operation,
+            path,
+            content,
+        });
+    }
+    Ok(file_changes)
+}
+
+fn gitlab_commit_json(
+    message: &str,
+    branch: &str,
+    start_sha: Option<&str>,
+    changes: &[FileChange],
+) -> Value {
+    let actions: Vec<Value> = changes
+        .iter()
+        .map(|change| match change.operation {
+            Operation::Delete => json!({
+                "action": "delete",
+                "file_path": change.path,
+            }),
+            operation => json!({
+                "action": operation.as_str(),
+                "file_path": change.path,
+                "content": change.content,
+                "encoding": "base64",
+            }),
+        })
+        .collect();
+    let mut body = json!({
+        "branch": branch,
+        "commit_message": message,
+        "actions": actions,
+    });
+    if let Some(start_sha) = start_sha {
+        body["start_sha"] = json!(start_sha);
+        body["force"] = json!(true);
+    }
+    body
+}
+
+/// Create a commit with the GitLab [commits API](https://docs.gitlab.com/ee/api/commits.html#create-a-commit-with-multiple-files-and-actions).
+async fn gitlab_commit(
+    client: &GitClient,
+    repo: &Repo,
+    message: &str,
+    branch: &str,
+    force: bool,
+    changes: &[FileChange],
+) -> Result<String> {
+    let head = force.then(|| repo.current_commit_hash()).transpose()?;
+    let body = gitlab_commit_json(message, branch, head.as_deref(), changes);
+    debug!("creating commit on branch `{branch}` via the GitLab API");
+    trace!("{body}");
+    let res: Value = client
+        .client
+        .post(format!("{}/repository/commits", client.repo_url()))
+        .json(&body)
+        .send()
+        .await?
+        .successful_status()
+        .await
+        .with_context(|| format!("failed to create commit on branch `{branch}`"))?
+        .json()
+        .await?;
+    commit_sha(&res, "/id")
+}
+
+/// Create a commit with the Gitea [contents API](https://gitea.com/api/swagger#/repository/repoChangeFiles).
+async fn gitea_commit(
+    client: &GitClient,
+    repo: &Repo,
+    message: &str,
+    branch: &str,
+    changes: &[FileChange],
+) -> Result<String> {
+    let mut files = vec![];
+    for change in changes {
+        let mut file = json!({
+            "operation": change.operation.as_str(),
+            "path": change.path,
+        });
+        if change.operation != Operation::Delete {
+            file["content"] = json!(change.content);
+        }
+        // Gitea requires the sha of the blob that is updated or deleted.
+        if change.operation != Operation::Create {
+            let sha = repo.git(&["rev-parse", &format!("HEAD:{}", change.path)])?;
+            file["sha"] = json!(sha);
+        }
+        files.push(file);
+    }
+    let body = json!({
+        "branch": branch,
+        "message": message,
+        "files": files,
+    });
+    debug!("creating commit on branch `{branch}` via the Gitea API");
+    trace!("{body}");
+    let res: Value = client
+        .client
+        .post(format!("{}/contents", client.repo_url()))
+        .json(&body)
+        .send()
+        .await?
+        .successful_status()
+        .await
+        .with_context(|| format!("failed to create commit on branch `{branch}`"))?
+        .json()
+        .await?;
+    commit_sha(&res, "/commit/sha")
+}
+
+fn commit_sha(res: &Value, pointer: &str) -> Result<String> {
+    let sha = res
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .with_context(|| format!("commit API did not return the commit sha: {res}"))?;
+    Ok(sha.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn gitlab_commit_contains_all_changes() {
+        let temporary = tempdir().unwrap();
+        let repo_dir = temporary.as_ref();
+        let repo = Repo::init(repo_dir);
+        fs_err::write(repo_dir.join("changed.txt"), b"changed").unwrap();
+        fs_err::write(repo_dir.join("removed.txt"), b"removed").unwrap();
+        repo.add_all_and_commit("initial commit").unwrap();
+
+        fs_err::write(repo_dir.join("changed.txt"), b"file changed").unwrap();
+        fs_err::remove_file(repo_dir.join("removed.txt")).unwrap();
+        fs_err::write(repo_dir.join("added.txt"), b"added").unwrap();
+
+        let changes = with_contents(repo.directory(), file_changes(&repo).unwrap())
+            .await
+            .unwrap();
+        let body = gitlab_commit_json("message", "release", Some("abc"), &changes);
+        let expected = json!({
+            "branch": "release",
+            "commit_message": "message",
+            "start_sha": "abc",
+            "force": true,
+            "actions": [
+                {"action": "delete", "file_path": "removed.txt"},
+                {
+                    "action": "create",
+                    "file_path": "added.txt",
+                    "content": BASE64_STANDARD.encode(b"added"),
+                    "encoding": "base64",
+                },
+                {
+                    "action": "update",
+                    "file_path": "changed.txt",
+                    "content": BASE64_STANDARD.encode(b"file changed"),
+                    "encoding": "base64",
+                },
+            ],
+        });
+        assert_eq!(body, expected);
+    }
+}
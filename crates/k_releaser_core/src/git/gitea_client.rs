@@ -1,10 +1,15 @@
 use crate::RepoUrl;
 use crate::git::forge::Remote;
 use anyhow::{Context, bail};
+use cargo_metadata::semver::Version;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderValue;
 use secrecy::{ExposeSecret, SecretString};
 
+/// First Gitea version accepting `make_latest` when creating a release.
+const MAKE_LATEST_MIN_VERSION: Version = Version::new(1, 24, 0);
+
+/// Gitea or Forgejo forge, which has the same API.
 #[derive(Debug, Clone)]
 pub struct Gitea {
     pub remote: Remote,
@@ -12,10 +17,11 @@ pub struct Gitea {
 
 impl Gitea {
     pub fn new(url: RepoUrl, token: SecretString) -> anyhow::Result<Self> {
+        // The API of ssh remotes is reached over https.
         match url.scheme.as_str() {
-            "http" | "https" => {}
+            "http" | "https" | "ssh" => {}
             _ => bail!(
-                "invalid scheme for gitea url, only `http` and `https` are supported: {url:?}"
+                "invalid scheme for gitea url, only `http`, `https` and `ssh` are supported: {url:?}"
             ),
         }
 
@@ -43,3 +49,29 @@ impl Gitea {
         Ok(headers)
     }
 }
+
+/// Whether the server with the given `version` (returned by `/api/v1/version`)
+/// supports `make_latest` when creating a release.
+/// Forgejo reports the Gitea version it's compatible with as build metadata,
+/// e.g. `11.0.1+gitea-1.22.0`, and supports `make_latest` in all its versions.
+pub(crate) fn supports_make_latest(version: &str) -> bool {
+    let Ok(version) = Version::parse(version.trim_start_matches('v')) else {
+        return false;
+    };
+    version.build.starts_with("gitea-") || version >= MAKE_LATEST_MIN_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_latest_support_depends_on_the_server_version() {
+        assert!(supports_make_latest("1.24.0"));
+        assert!(supports_make_latest("v1.25.1"));
+        assert!(supports_make_latest("11.0.1+gitea-1.22.0"));
+        assert!(!supports_make_latest("1.22.3"));
+        assert!(!supports_make_latest("1.23.0+dev-120-g5b5f4c3"));
+        assert!(!supports_make_latest("development"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Authentication as a [GitHub App](https://docs.github.com/en/apps/creating-github-apps/authenticating-with-a-github-app/authenticating-as-a-github-app-installation).
+//!
+//! k-releaser signs a JWT with the private key of the app, exchanges it for an
+//! installation access token of the repository and refreshes the token before it expires.
+
+use std::sync::Mutex;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use http::Extensions;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::header::{AUTHORIZATION, HeaderValue};
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use url::Url;
+
+use crate::git::forge::Remote;
+use crate::response_ext::ResponseExt;
+
+/// Installation tokens are refreshed when they expire in less than this time.
+const REFRESH_MARGIN_MINUTES: i64 = 5;
+
+/// Credentials of a GitHub App installed in the repository.
+#[derive(Debug, Clone)]
+pub struct GitHubApp {
+    app_id: String,
+    /// PEM encoded private key of the app.
+    private_key: SecretString,
+}
+
+impl GitHubApp {
+    pub fn new(app_id: impl Into<String>, private_key: SecretString) -> Self {
+        Self {
+            app_id: app_id.into(),
+            private_key,
+        }
+    }
+
+    /// JWT used to authenticate as the app.
+    fn jwt(&self, now: DateTime<Utc>) -> anyhow::Result<String> {
+        // Backdate the token to allow for clock drift, as recommended by GitHub.
+        let claims = AppClaims {
+            iat: (now - Duration::seconds(60)).timestamp(),
+            exp: (now + Duration::minutes(9)).timestamp(),
+            iss: self.app_id.clone(),
+        };
+        let key = EncodingKey::from_rsa_pem(self.private_key.expose_secret().as_bytes())
+            .context("invalid GitHub App private key")?;
+        jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)
+            .context("can't sign GitHub App JWT")
+    }
+}
+
+#[derive(Serialize)]
+struct AppClaims {
+    iat: i64,
+    exp: i64,
+    iss: String,
+}
+
+#[derive(Deserialize)]
+struct Installation {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct AccessTokenResponse {
+    token: String,
+    expires_at: String,
+}
+
+#[derive(Clone)]
+struct InstallationToken {
+    token: SecretString,
+    expires_at: DateTime<Utc>,
+}
+
+impl InstallationToken {
+    fn is_expiring(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - now < Duration::minutes(REFRESH_MARGIN_MINUTES)
+    }
+}
+
+/// Middleware that authenticates the requests with an installation token of the app.
+pub(crate) struct GitHubAppAuth {
+    app: GitHubApp,
+    base_url: Url,
+    owner_slash_repo: String,
+    client: reqwest::Client,
+    token: Mutex<Option<InstallationToken>>,
+}
+
+impl GitHubAppAuth {
+    pub fn new(app: GitHubApp, remote: &Remote) -> anyhow::Result<Self> {
+        let client = crate::http_client::http_client_builder()
+            .build()
+            .context("can't build GitHub App client")?;
+        Ok(Self {
+            app,
+            base_url: remote.base_url.clone(),
+            owner_slash_repo: remote.owner_slash_repo(),
+            client,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// Return the cached installation token, or mint a new one if it's about to expire.
+    async fn installation_token(&self) -> anyhow::Result<SecretString> {
+        let now = Utc::now();
+        let cached = self.token.lock().unwrap().clone();
+        if let Some(token) = cached.filter(|t| !t.is_expiring(now)) {
+            return Ok(token.token);
+        }
+        let token = self.mint_installation_token(now).await?;
+        *self.token.lock().unwrap() = Some(token.clone());
+        Ok(token.token)
+    }
+
+    async fn mint_installation_token(
+        &self,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<InstallationToken> {
+        let jwt = self.app.jwt(now)?;
+        let installation: Installation = self
+            .client
+            .get(format!(
+                "{}repos/{}/installation",
+                self.base_url, self.owner_slash_repo
+            ))
+            .bearer_auth(&jwt)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| {
+                format!(
+                    "can't find the GitHub App installation of {}",
+                    self.owner_slash_repo
+                )
+            })?
+            .json()
+            .await?;
+        let response: AccessTokenResponse = self
+            .client
+            .post(format!(
+                "{}app/installations/{}/access_tokens",
+                self.base_url, installation.id
+            ))
+            .bearer_auth(&jwt)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .context("can't create GitHub App installation token")?
+            .json()
+            .await?;
+        let expires_at = DateTime::parse_from_rfc3339(&response.expires_at)
+            .context("invalid expiration date of GitHub App installation token")?
+            .with_timezone(&Utc);
+        debug!("minted GitHub App installation token expiring at {expires_at}");
+        Ok(InstallationToken {
+            token: response.token.into(),
+            expires_at,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for GitHubAppAuth {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let token = self
+            .installation_token()
+            .await
+            .map_err(reqwest_middleware::Error::Middleware)?;
+        let mut auth_header = HeaderValue::from_str(&format!("Bearer {}", token.expose_secret()))
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        auth_header.set_sensitive(true);
+        req.headers_mut().insert(AUTHORIZATION, auth_header);
+        next.run(req, extensions).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_refreshed_before_expiration() {
+        let now = Utc::now();
+        let token = |minutes| InstallationToken {
+            token: SecretString::from("token"),
+            expires_at: now + Duration::minutes(minutes),
+        };
+        assert!(!token(60).is_expiring(now));
+        assert!(token(2).is_expiring(now));
+        assert!(token(-1).is_expiring(now));
+    }
+}
@@ -4,10 +4,13 @@ use secrecy::{ExposeSecret, SecretString};
 use url::Url;
 
 use crate::git::forge::Remote;
+use crate::git::github_app::GitHubApp;
 
 #[derive(Debug, Clone)]
 pub struct GitHub {
     pub remote: Remote,
+    /// If present, requests are authenticated as this GitHub App instead of with the token.
+    pub app: Option<GitHubApp>,
 }
 
 impl GitHub {
@@ -19,15 +22,33 @@ impl GitHub {
                 token,
                 base_url: "https://api.github.com".parse().unwrap(),
             },
+            app: None,
         }
     }
 
-    pub fn with_base_url(self, base_url: Url) -> Self {
+    /// Authenticate as a GitHub App installed in the repository.
+    /// Installation tokens are created and refreshed automatically.
+    pub fn from_app(owner: String, repo: String, app: GitHubApp) -> Self {
+        Self {
+            app: Some(app),
+            ..Self::new(owner, repo, SecretString::default())
+        }
+    }
+
+    /// Use the GitHub API at `base_url`, e.g. `https://ghe.example.com/api/v3`
+    /// for GitHub Enterprise Server.
+    pub fn with_base_url(self, mut base_url: Url) -> Self {
+        // The urls of the endpoints are appended to the base url.
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
         Self {
             remote: Remote {
                 base_url,
                 ..self.remote
             },
+            ..self
         }
     }
 
@@ -37,6 +58,10 @@ impl GitHub {
             reqwest::header::ACCEPT,
             HeaderValue::from_static("application/vnd.github+json"),
         );
+        if self.app.is_some() {
+            // The installation token is added by the GitHub App middleware.
+            return Ok(headers);
+        }
         let mut auth_header: HeaderValue = format!("Bearer {}", self.remote.token.expose_secret())
             .parse()
             .context("invalid GitHub token")?;
@@ -45,3 +70,22 @@ impl GitHub {
         Ok(headers)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enterprise_base_url_ends_with_slash() {
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token".to_string()),
+        )
+        .with_base_url("https://ghe.example.com/api/v3".parse().unwrap());
+        assert_eq!(
+            github.remote.base_url.as_str(),
+            "https://ghe.example.com/api/v3/"
+        );
+    }
+}
@@ -2,12 +2,14 @@ use anyhow::{Context, Result};
 use base64::prelude::*;
 use cargo_metadata::camino::Utf8PathBuf;
 use git_cmd::Repo;
+use serde::Deserialize;
 use serde_json::{Value, json};
 use tracing::{debug, trace};
 use url::Url;
 
-use crate::GitClient;
-use crate::git::forge::Remote;
+use crate::git::forge::{Author, Commit, GitPr, Label, PrBase, PrCommit, PrRepo, Remote};
+use crate::response_ext::ResponseExt;
+use crate::{GitClient, MergeMethod, PrProject};
 
 /// Commit all the changes (except typestates) that are present in the repository
 /// using GitHub's [GraphQL api](https://docs.github.com/en/graphql/reference/mutations#createcommitonbranch).
@@ -53,9 +55,14 @@ pub async fn commit_changes(
     Ok(commit_sha)
 }
 
+/// GraphQL endpoint of the GitHub API: `https://api.github.com/graphql` on GitHub.com and
+/// `https://<host>/api/graphql` on GitHub Enterprise Server, whose REST API is under `/api/v3`.
 fn get_graphql_endpoint(remote: &Remote) -> Url {
     let mut base_url = remote.base_url.clone();
-    base_url.set_path("graphql");
+    let rest_path = base_url.path().trim_end_matches('/');
+    let api_path = rest_path.strip_suffix("/v3").unwrap_or(rest_path);
+    let graphql_path = format!("{api_path}/graphql");
+    base_url.set_path(&graphql_path);
 
     base_url
 }
@@ -162,6 +169,480 @@ fn mutation() -> String {
     MUTATION.replace(|c: char| c.is_whitespace(), "")
 }
 
+/// Number of pull requests requested in a single query.
+const PRS_PER_QUERY: usize = 50;
+
+const PR_FIELDS: &str = r"
+fragment PrFields on PullRequest {
+  number
+  url
+  title
+  body
+  headRefName
+  headRefOid
+  headRepository { databaseId }
+  baseRepository { databaseId }
+  isDraft
+  author {
+    login
+    ... on User { databaseId }
+    ... on Bot { databaseId }
+  }
+  labels(first: 100) { nodes { name } }
+}";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlPr {
+    number: u64,
+    url: Url,
+    title: String,
+    body: String,
+    head_ref_name: String,
+    head_ref_oid: String,
+    /// [`Option::None`] if the repository was deleted.
+    head_repository: Option<GraphQlRepository>,
+    base_repository: Option<GraphQlRepository>,
+    #[serde(default)]
+    is_draft: bool,
+    /// [`Option::None`] if the account of the author was deleted.
+    author: Option<GraphQlActor>,
+    labels: Nodes<GraphQlLabel>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlActor {
+    login: String,
+    database_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlRepository {
+    database_id: u64,
+}
+
+impl From<GraphQlRepository> for PrRepo {
+    fn from(repository: GraphQlRepository) -> Self {
+        Self {
+            id: repository.database_id,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GraphQlLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Page<T> {
+    nodes: Vec<T>,
+    page_info: PageInfo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlPrCommit {
+    commit: GraphQlCommit,
+}
+
+#[derive(Deserialize)]
+struct GraphQlCommit {
+    oid: String,
+    author: Option<GraphQlCommitAuthor>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlCommitAuthor {
+    /// [`Option::None`] if the email of the commit isn't associated with a GitHub account.
+    user: Option<GraphQlActor>,
+}
+
+impl From<GraphQlActor> for Author {
+    fn from(actor: GraphQlActor) -> Self {
+        Self {
+            id: actor.database_id.unwrap_or_default(),
+            login: actor.login,
+        }
+    }
+}
+
+impl From<GraphQlPr> for GitPr {
+    fn from(pr: GraphQlPr) -> Self {
+        // Same user returned by the REST API for deleted accounts.
+        let user = pr.author.map_or_else(
+            || Author {
+                id: 10137,
+                login: "ghost".to_string(),
+            },
+            Author::from,
+        );
+        Self {
+            user,
+            number: pr.number,
+            html_url: pr.url,
+            head: Commit {
+                ref_field: pr.head_ref_name,
+                sha: pr.head_ref_oid,
+                repo: pr.head_repository.map(PrRepo::from),
+            },
+            base: Some(PrBase {
+                repo: pr.base_repository.map(PrRepo::from),
+            }),
+            title: pr.title,
+            // Like the REST API, return no body instead of an empty one.
+            body: Some(pr.body).filter(|body| !body.is_empty()),
+            labels: pr
+                .labels
+                .nodes
+                .into_iter()
+                .map(|label| Label {
+                    name: label.name,
+                    id: None,
+                })
+                .collect(),
+            draft: pr.is_draft,
+        }
+    }
+}
+
+/// Send a query to the GitHub GraphQL API and return its `data`.
+async fn query(client: &GitClient, query: &str, variables: Value) -> Result<Value> {
+    let mut res: Value = client
+        .client
+        .post(get_graphql_endpoint(&client.remote))
+        .json(&json!({"query": query, "variables": variables}))
+        .send()
+        .await?
+        .successful_status()
+        .await?
+        .json()
+        .await?;
+    if let Some(errors) = res.get("errors").and_then(Value::as_array) {
+        anyhow::bail!(
+            "GraphQL query returned errors: {}",
+            serde_json::to_string(errors)?
+        );
+    }
+    Ok(res["data"].take())
+}
+
+fn repo_variables(remote: &Remote) -> Value {
+    json!({"owner": remote.owner, "name": remote.repo})
+}
+
+/// Login of the user authenticated by the token.
+pub async fn viewer_login(client: &GitClient) -> Result<String> {
+    let mut data = query(client, "query { viewer { login } }", json!({}))
+        .await
+        .context("failed to retrieve the user of the token")?;
+    serde_json::from_value(data["viewer"]["login"].take()).context("failed to parse viewer login")
+}
+
+/// Get all opened PRs.
+pub async fn opened_prs(client: &GitClient) -> Result<Vec<GitPr>> {
+    let opened_prs_query = format!(
+        r"
+query($owner: String!, $name: String!, $cursor: String) {{
+  repository(owner: $owner, name: $name) {{
+    pullRequests(states: OPEN, first: 100, after: $cursor) {{
+      nodes {{ ...PrFields }}
+      pageInfo {{ hasNextPage endCursor }}
+    }}
+  }}
+}}{PR_FIELDS}"
+    );
+    let mut variables = repo_variables(&client.remote);
+    let mut opened_prs = vec![];
+    loop {
+        debug!(
+            "Loading opened prs from {}",
+            client.remote.owner_slash_repo()
+        );
+        let mut data = query(client, &opened_prs_query, variables.clone())
+            .await
+            .context("Failed to retrieve open PRs")?;
+        let page: Page<GraphQlPr> =
+            serde_json::from_value(data["repository"]["pullRequests"].take())
+                .context("failed to parse opened PRs")?;
+        opened_prs.extend(page.nodes.into_iter().map(GitPr::from));
+        match page.page_info {
+            PageInfo {
+                has_next_page: true,
+                end_cursor: Some(cursor),
+            } => variables["cursor"] = json!(cursor),
+            _ => break,
+        }
+    }
+    Ok(opened_prs)
+}
+
+fn prs_info_query(pr_numbers: &[u64]) -> String {
+    let prs: String = pr_numbers
+        .iter()
+        .map(|number| format!("    pr{number}: pullRequest(number: {number}) {{ ...PrFields }}\n"))
+        .collect();
+    format!(
+        "query($owner: String!, $name: String!) {{\n  repository(owner: $owner, name: $name) {{\n{prs}  }}\n}}{PR_FIELDS}"
+    )
+}
+
+/// Get the PRs with the given numbers, requesting many PRs in a single query.
+pub async fn prs_info(client: &GitClient, pr_numbers: &[u64]) -> Result<Vec<GitPr>> {
+    let mut prs = vec![];
+    for chunk in pr_numbers.chunks(PRS_PER_QUERY) {
+        let mut data = query(
+            client,
+            &prs_info_query(chunk),
+            repo_variables(&client.remote),
+        )
+        .await
+        .context("failed to retrieve PRs")?;
+        for number in chunk {
+            let pr: GraphQlPr =
+                serde_json::from_value(data["repository"][format!("pr{number}")].take())
+                    .with_context(|| format!("failed to parse PR #{number}"))?;
+            prs.push(pr.into());
+        }
+    }
+    Ok(prs)
+}
+
+/// Get all the commits of a PR.
+pub async fn pr_commits(client: &GitClient, pr_number: u64) -> Result<Vec<PrCommit>> {
+    const PR_COMMITS_QUERY: &str = r"
+query($owner: String!, $name: String!, $number: Int!, $cursor: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      commits(first: 100, after: $cursor) {
+        nodes { commit { oid author { user { login databaseId } } } }
+        pageInfo { hasNextPage endCursor }
+      }
+    }
+  }
+}";
+    let mut variables = repo_variables(&client.remote);
+    variables["number"] = json!(pr_number);
+    let mut commits = vec![];
+    loop {
+        let mut data = query(client, PR_COMMITS_QUERY, variables.clone())
+            .await
+            .with_context(|| format!("failed to retrieve commits of PR #{pr_number}"))?;
+        let page: Page<GraphQlPrCommit> =
+            serde_json::from_value(data["repository"]["pullRequest"]["commits"].take())
+                .context("failed to parse pr commits")?;
+        commits.extend(page.nodes.into_iter().map(|node| {
+            PrCommit {
+                author: node
+                    .commit
+                    .author
+                    .and_then(|author| author.user)
+                    .map(Author::from),
+                sha: node.commit.oid,
+            }
+        }));
+        match page.page_info {
+            PageInfo {
+                has_next_page: true,
+                end_cursor: Some(cursor),
+            } => variables["cursor"] = json!(cursor),
+            _ => break,
+        }
+    }
+    Ok(commits)
+}
+
+#[derive(Deserialize)]
+struct GraphQlProject {
+    id: String,
+    /// [`Option::None`] if the project doesn't have a `Status` field.
+    field: Option<GraphQlStatusField>,
+}
+
+/// `Status` field of a project. The fields are missing if it isn't a single select field.
+#[derive(Deserialize)]
+struct GraphQlStatusField {
+    id: Option<String>,
+    #[serde(default)]
+    options: Vec<GraphQlFieldOption>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlFieldOption {
+    id: String,
+    name: String,
+}
+
+/// Ids of the `Status` field and of its option named `status`.
+fn status_option(project: &GraphQlProject, status: &str) -> Result<(String, String)> {
+    let field = project
+        .field
+        .as_ref()
+        .context("the project doesn't have a `Status` field")?;
+    let field_id = field
+        .id
+        .as_ref()
+        .context("the `Status` field of the project isn't a single select field")?;
+    let option = field
+        .options
+        .iter()
+        .find(|option| option.name == status)
+        .with_context(|| {
+            let names = field.options.iter().map(|option| option.name.as_str());
+            format!(
+                "status `{status}` not found in the project. Available statuses: {}",
+                names.collect::<Vec<_>>().join(", ")
+            )
+        })?;
+    Ok((field_id.clone(), option.id.clone()))
+}
+
+/// Enable auto-merge on the PR, so that GitHub merges it with `merge_method` once the
+/// required checks pass. Auto-merge must be allowed in the settings of the repository.
+/// Docs: <https://docs.github.com/en/graphql/reference/mutations#enablepullrequestautomerge>
+pub async fn enable_auto_merge(
+    client: &GitClient,
+    pr_number: u64,
+    merge_method: MergeMethod,
+) -> Result<()> {
+    const PR_ID_QUERY: &str = r"
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) { pullRequest(number: $number) { id } }
+}";
+    const AUTO_MERGE_MUTATION: &str = r"
+mutation($pr: ID!, $method: PullRequestMergeMethod!) {
+  enablePullRequestAutoMerge(input: {pullRequestId: $pr, mergeMethod: $method}) {
+    pullRequest { number }
+  }
+}";
+    let mut variables = repo_variables(&client.remote);
+    variables["number"] = json!(pr_number);
+    let data = query(client, PR_ID_QUERY, variables)
+        .await
+        .with_context(|| format!("failed to retrieve PR #{pr_number}"))?;
+    let pr_id = data["repository"]["pullRequest"]["id"]
+        .as_str()
+        .with_context(|| format!("failed to retrieve the id of PR #{pr_number}"))?;
+    let method = match merge_method {
+        MergeMethod::Merge => "MERGE",
+        MergeMethod::Squash => "SQUASH",
+        MergeMethod::Rebase => "REBASE",
+    };
+    query(
+        client,
+        AUTO_MERGE_MUTATION,
+        json!({"pr": pr_id, "method": method}),
+    )
+    .await
+    .with_context(|| format!("failed to enable auto-merge on PR #{pr_number}"))?;
+    Ok(())
+}
+
+/// Add the PR to the GitHub Project (v2) `project`, and set its status if configured.
+/// Docs: <https://docs.github.com/en/issues/planning-and-tracking-with-projects/automating-your-project/using-the-api-to-manage-projects>
+pub async fn add_pr_to_project(
+    client: &GitClient,
+    pr_number: u64,
+    project: &PrProject,
+) -> Result<()> {
+    const PROJECT_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!, $projectOwner: String!, $project: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) { id }
+  }
+  repositoryOwner(login: $projectOwner) {
+    ... on ProjectV2Owner {
+      projectV2(number: $project) {
+        id
+        field(name: "Status") {
+          ... on ProjectV2SingleSelectField { id options { id name } }
+        }
+      }
+    }
+  }
+}"#;
+    const ADD_ITEM_MUTATION: &str = r"
+mutation($project: ID!, $content: ID!) {
+  addProjectV2ItemById(input: {projectId: $project, contentId: $content}) { item { id } }
+}";
+    const SET_STATUS_MUTATION: &str = r"
+mutation($project: ID!, $item: ID!, $field: ID!, $option: String!) {
+  updateProjectV2ItemFieldValue(
+    input: {projectId: $project, itemId: $item, fieldId: $field, value: {singleSelectOptionId: $option}}
+  ) { projectV2Item { id } }
+}";
+    let project_owner = project
+        .owner
+        .as_deref()
+        .unwrap_or(client.remote.owner.as_str());
+    let mut variables = repo_variables(&client.remote);
+    variables["number"] = json!(pr_number);
+    variables["projectOwner"] = json!(project_owner);
+    variables["project"] = json!(project.number);
+    let mut data = query(client, PROJECT_QUERY, variables)
+        .await
+        .with_context(|| {
+            format!(
+                "failed to retrieve project {project_owner}/{}",
+                project.number
+            )
+        })?;
+    let pr_id = data["repository"]["pullRequest"]["id"]
+        .as_str()
+        .with_context(|| format!("failed to retrieve the id of PR #{pr_number}"))?
+        .to_owned();
+    let github_project: GraphQlProject =
+        serde_json::from_value(data["repositoryOwner"]["projectV2"].take())
+            .with_context(|| format!("project {project_owner}/{} not found", project.number))?;
+
+    let data = query(
+        client,
+        ADD_ITEM_MUTATION,
+        json!({"project": github_project.id, "content": pr_id}),
+    )
+    .await
+    .context("failed to add the PR to the project")?;
+    let item_id = data
+        .pointer("/addProjectV2ItemById/item/id")
+        .and_then(Value::as_str)
+        .context("addProjectV2ItemById did not return the project item")?;
+    debug!(
+        "added PR #{pr_number} to project {project_owner}/{}",
+        project.number
+    );
+
+    if let Some(status) = &project.status {
+        let (field_id, option_id) = status_option(&github_project, status)?;
+        query(
+            client,
+            SET_STATUS_MUTATION,
+            json!({
+                "project": github_project.id,
+                "item": item_id,
+                "field": field_id,
+                "option": option_id,
+            }),
+        )
+        .await
+        .with_context(|| format!("failed to set the status of PR #{pr_number} to {status}"))?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
@@ -170,6 +651,27 @@ mod tests {
 
     use crate::copy_dir::create_symlink;
 
+    fn remote(base_url: &str) -> Remote {
+        Remote {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: secrecy::SecretString::from("token".to_string()),
+            base_url: base_url.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn graphql_endpoint_is_next_to_the_rest_api() {
+        assert_eq!(
+            get_graphql_endpoint(&remote("https://api.github.com")).as_str(),
+            "https://api.github.com/graphql"
+        );
+        assert_eq!(
+            get_graphql_endpoint(&remote("https://ghe.example.com/api/v3/")).as_str(),
+            "https://ghe.example.com/api/graphql"
+        );
+    }
+
     #[tokio::test]
     async fn github_commit_query() {
         let temporary = tempdir().unwrap();
@@ -259,4 +761,54 @@ mod tests {
         expect_test::expect![[r#""mutation($input:CreateCommitOnBranchInput!){createCommitOnBranch(input:$input){commit{oid}}}""#]]
         .assert_eq(&query["query"].to_string());
     }
+
+    #[test]
+    fn prs_are_requested_in_a_single_query() {
+        let query = prs_info_query(&[1, 5]);
+        assert!(query.contains("pr1: pullRequest(number: 1) { ...PrFields }"));
+        assert!(query.contains("pr5: pullRequest(number: 5) { ...PrFields }"));
+        assert!(query.contains("fragment PrFields on PullRequest"));
+    }
+
+    #[test]
+    fn graphql_pr_is_converted_to_git_pr() {
+        let pr: GraphQlPr = serde_json::from_value(json!({
+            "number": 3,
+            "url": "https://github.com/owner/repo/pull/3",
+            "title": "chore: release",
+            "body": "",
+            "headRefName": "k-releaser-2024",
+            "headRefOid": "abc",
+            "author": {"login": "bot", "databaseId": 42},
+            "labels": {"nodes": [{"name": "release"}]},
+        }))
+        .unwrap();
+        let pr = GitPr::from(pr);
+        assert_eq!(pr.number, 3);
+        assert_eq!(pr.branch(), "k-releaser-2024");
+        assert_eq!(pr.head.sha, "abc");
+        assert_eq!(pr.body, None);
+        assert_eq!(pr.user.id, 42);
+        assert_eq!(pr.label_names(), vec!["release"]);
+    }
+
+    #[test]
+    fn project_status_option_is_found_by_name() {
+        let project: GraphQlProject = serde_json::from_value(json!({
+            "id": "PVT_1",
+            "field": {
+                "id": "PVTSSF_1",
+                "options": [{"id": "a", "name": "Todo"}, {"id": "b", "name": "Release"}],
+            },
+        }))
+        .unwrap();
+        let (field_id, option_id) = status_option(&project, "Release").unwrap();
+        assert_eq!(field_id, "PVTSSF_1");
+        assert_eq!(option_id, "b");
+        let err = status_option(&project, "Done").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Available statuses: Todo, Release")
+        );
+    }
 }
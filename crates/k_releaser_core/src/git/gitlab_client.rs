@@ -8,6 +8,8 @@ use crate::{RepoUrl, git::forge::Remote};
 #[derive(Debug, Clone)]
 pub struct GitLab {
     pub remote: Remote,
+    /// If `true`, the token is a [CI/CD job token](https://docs.gitlab.com/ee/ci/jobs/ci_job_token.html).
+    job_token: bool,
 }
 
 impl GitLab {
@@ -26,9 +28,18 @@ impl GitLab {
                 repo: url.name,
                 token,
             },
+            job_token: false,
         })
     }
 
+    pub fn with_job_token(self, job_token: bool) -> Self {
+        Self { job_token, ..self }
+    }
+
+    pub(crate) fn is_job_token(&self) -> bool {
+        self.job_token
+    }
+
     pub fn default_headers(&self) -> anyhow::Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -43,7 +54,12 @@ impl GitLab {
             .parse()
             .context("Invalid Gitlab token")?;
         private_token.set_sensitive(true);
-        headers.insert("PRIVATE-TOKEN", private_token);
+        let token_header = if self.job_token {
+            "JOB-TOKEN"
+        } else {
+            "PRIVATE-TOKEN"
+        };
+        headers.insert(token_header, private_token);
 
         Ok(headers)
     }
@@ -0,0 +1,107 @@
+//! Comments and state of issues and PRs.
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::debug;
+
+use crate::GitClient;
+use crate::git::forge::ForgeType;
+use crate::response_ext::ResponseExt as _;
+
+/// Comment of an issue or PR. GitLab calls them notes.
+#[derive(Deserialize)]
+struct ForgeComment {
+    id: u64,
+    #[serde(default)]
+    body: String,
+}
+
+impl GitClient {
+    /// Post the comment `body` on the issue `number`.
+    pub async fn comment_issue(&self, number: u64, body: &str) -> anyhow::Result<()> {
+        self.ensure_not_job_token("comment on issues")?;
+        let url = match self.forge {
+            ForgeType::Github | ForgeType::Gitea => {
+                format!("{}/issues/{number}/comments", self.repo_url())
+            }
+            ForgeType::Gitlab => format!("{}/issues/{number}/notes", self.repo_url()),
+        };
+        self.client
+            .post(url)
+            .json(&json!({ "body": body }))
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to comment on issue #{number}"))?;
+        Ok(())
+    }
+
+    /// Create or update the comment of the PR `pr_number` containing `marker`,
+    /// so that the PR has a single up-to-date comment instead of one per run.
+    /// `body` must contain `marker`.
+    pub async fn upsert_pr_comment(
+        &self,
+        pr_number: u64,
+        marker: &str,
+        body: &str,
+    ) -> anyhow::Result<()> {
+        self.ensure_not_job_token("comment on merge requests")?;
+        let comments_url = match self.forge {
+            ForgeType::Github | ForgeType::Gitea => {
+                format!("{}/issues/{pr_number}/comments", self.repo_url())
+            }
+            ForgeType::Gitlab => {
+                format!("{}/merge_requests/{pr_number}/notes", self.repo_url())
+            }
+        };
+        let comments: Vec<ForgeComment> = self
+            .get_all_pages(&comments_url, &[])
+            .await
+            .with_context(|| format!("failed to retrieve the comments of PR #{pr_number}"))?;
+        let request = match comments.iter().find(|c| c.body.contains(marker)) {
+            Some(comment) => {
+                debug!("updating comment {} of PR #{pr_number}", comment.id);
+                match self.forge {
+                    ForgeType::Github | ForgeType::Gitea => self.client.patch(format!(
+                        "{}/issues/comments/{}",
+                        self.repo_url(),
+                        comment.id
+                    )),
+                    ForgeType::Gitlab => self.client.put(format!("{comments_url}/{}", comment.id)),
+                }
+            }
+            None => self.client.post(comments_url),
+        };
+        request
+            .json(&json!({ "body": body }))
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to comment on PR #{pr_number}"))?;
+        Ok(())
+    }
+
+    pub async fn close_issue(&self, number: u64) -> anyhow::Result<()> {
+        self.ensure_not_job_token("close issues")?;
+        let url = format!("{}/issues/{number}", self.repo_url());
+        let request = match self.forge {
+            ForgeType::Github | ForgeType::Gitea => {
+                self.client.patch(url).json(&json!({ "state": "closed" }))
+            }
+            ForgeType::Gitlab => self
+                .client
+                .put(url)
+                .json(&json!({ "state_event": "close" })),
+        };
+        request
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to close issue #{number}"))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+//! Lock that prevents concurrent k-releaser runs on the same repository.
+//!
+//! The lock is a branch of the remote repository. The forge refuses to create a branch
+//! that already exists, so only one run at a time can hold the lock.
+
+use anyhow::Context as _;
+use tracing::{debug, info, warn};
+
+use crate::GitClient;
+
+/// Branch that holds the lock of the repository.
+pub const LOCK_BRANCH: &str = "k-releaser-lock";
+
+/// Whether k-releaser locks the repository while it runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LockMode {
+    /// Don't lock the repository. *(Default)*.
+    #[default]
+    Disabled,
+    /// Lock the repository, failing if another run holds the lock.
+    Enabled,
+    /// Remove the lock left by a previous run, then lock the repository.
+    /// Use it when a run crashed without releasing the lock.
+    ForceUnlock,
+}
+
+/// Run `f` while holding the lock of the repository, if `mode` enables it.
+/// The lock is created at the commit `sha` and released when `f` completes, even if it fails.
+pub(crate) async fn with_lock<T>(
+    mode: LockMode,
+    git_client: &GitClient,
+    sha: &str,
+    f: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    if mode == LockMode::Disabled {
+        return f.await;
+    }
+    if mode == LockMode::ForceUnlock {
+        match git_client.delete_branch(LOCK_BRANCH).await {
+            Ok(()) => warn!("removed the lock branch `{LOCK_BRANCH}`"),
+            Err(e) => debug!("no lock to remove: {e:?}"),
+        }
+    }
+    git_client
+        .create_branch(LOCK_BRANCH, sha)
+        .await
+        .with_context(|| {
+            format!(
+                "can't lock the repository: another k-releaser run might be in progress. \
+If no other run is in progress, the branch `{LOCK_BRANCH}` was left by a run that crashed: \
+delete it or rerun with `--force-unlock`"
+            )
+        })?;
+    info!("locked the repository with the branch `{LOCK_BRANCH}`");
+    let result = f.await;
+    if let Err(e) = git_client.delete_branch(LOCK_BRANCH).await {
+        warn!("can't release the lock. Delete the branch `{LOCK_BRANCH}` manually: {e:?}");
+    }
+    result
+}
@@ -0,0 +1,198 @@
+//! Milestones of the forge and the issues assigned to them.
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, info};
+
+use crate::GitClient;
+use crate::git::forge::ForgeType;
+use crate::response_ext::ResponseExt as _;
+
+/// Milestone of the forge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Milestone {
+    /// Identifier used in the API: the number on GitHub, the id on GitLab and Gitea.
+    pub id: u64,
+    pub title: String,
+}
+
+#[derive(Deserialize)]
+struct ForgeMilestone {
+    id: u64,
+    /// Only returned by GitHub, which uses it instead of the id in the API.
+    number: Option<u64>,
+    title: String,
+}
+
+impl From<ForgeMilestone> for Milestone {
+    fn from(milestone: ForgeMilestone) -> Self {
+        Self {
+            id: milestone.number.unwrap_or(milestone.id),
+            title: milestone.title,
+        }
+    }
+}
+
+/// Issue or PR. GitLab identifies issues in the project by their `iid`.
+#[derive(Deserialize)]
+struct ForgeIssue {
+    #[serde(alias = "iid")]
+    number: u64,
+}
+
+impl GitClient {
+    /// Open milestones of the repository.
+    pub async fn open_milestones(&self) -> anyhow::Result<Vec<Milestone>> {
+        self.ensure_not_job_token("manage milestones")?;
+        let state = match self.forge {
+            ForgeType::Github | ForgeType::Gitea => "open",
+            ForgeType::Gitlab => "active",
+        };
+        let milestones: Vec<ForgeMilestone> = self
+            .get_all_pages(
+                &format!("{}/milestones", self.repo_url()),
+                &[("state", state)],
+            )
+            .await
+            .context("failed to retrieve milestones")?;
+        Ok(milestones.into_iter().map(Milestone::from).collect())
+    }
+
+    pub async fn close_milestone(&self, milestone: &Milestone) -> anyhow::Result<()> {
+        let url = format!("{}/milestones/{}", self.repo_url(), milestone.id);
+        let request = match self.forge {
+            ForgeType::Github | ForgeType::Gitea => {
+                self.client.patch(url).json(&json!({ "state": "closed" }))
+            }
+            ForgeType::Gitlab => self
+                .client
+                .put(url)
+                .json(&json!({ "state_event": "close" })),
+        };
+        request
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to close milestone {}", milestone.title))?;
+        Ok(())
+    }
+
+    pub async fn create_milestone(&self, title: &str) -> anyhow::Result<Milestone> {
+        let milestone: ForgeMilestone = self
+            .client
+            .post(format!("{}/milestones", self.repo_url()))
+            .json(&json!({ "title": title }))
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to create milestone {title}"))?
+            .json()
+            .await
+            .context("can't parse created milestone")?;
+        Ok(milestone.into())
+    }
+
+    /// Open milestone titled `title`, created if missing.
+    pub async fn milestone_or_create(&self, title: &str) -> anyhow::Result<Milestone> {
+        let milestones = self.open_milestones().await?;
+        match milestones.into_iter().find(|m| m.title == title) {
+            Some(milestone) => Ok(milestone),
+            None => {
+                info!("creating milestone {title}");
+                self.create_milestone(title).await
+            }
+        }
+    }
+
+    /// Assign the PR `pr_number` to the milestone titled `title`, creating the milestone
+    /// if missing.
+    pub async fn set_pr_milestone(&self, pr_number: u64, title: &str) -> anyhow::Result<()> {
+        let milestone = self.milestone_or_create(title).await?;
+        // GitHub can only set the milestone of a PR via the issues API.
+        let request = match self.forge {
+            ForgeType::Github | ForgeType::Gitea => self
+                .client
+                .patch(format!("{}/{pr_number}", self.issues_url()))
+                .json(&json!({ "milestone": milestone.id })),
+            ForgeType::Gitlab => self
+                .client
+                .put(format!("{}/{pr_number}", self.pulls_url()))
+                .json(&json!({ "milestone_id": milestone.id })),
+        };
+        request
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("failed to set milestone {title} on pr #{pr_number}"))?;
+        debug!("set milestone {title} on pr #{pr_number}");
+        Ok(())
+    }
+
+    /// Assign the open issues and PRs of the milestone `from` to the milestone `to`.
+    /// Returns the number of moved issues.
+    pub async fn move_open_issues(
+        &self,
+        from: &Milestone,
+        to: &Milestone,
+    ) -> anyhow::Result<usize> {
+        let from_id = from.id.to_string();
+        let query = match self.forge {
+            ForgeType::Github => [("milestone", from_id.as_str()), ("state", "open")],
+            ForgeType::Gitea => [("milestones", from.title.as_str()), ("state", "open")],
+            ForgeType::Gitlab => [("milestone", from.title.as_str()), ("state", "opened")],
+        };
+        let issues: Vec<ForgeIssue> = self
+            .get_all_pages(&format!("{}/issues", self.repo_url()), &query)
+            .await
+            .with_context(|| {
+                format!("failed to retrieve the issues of milestone {}", from.title)
+            })?;
+        for issue in &issues {
+            let url = format!("{}/issues/{}", self.repo_url(), issue.number);
+            let request = match self.forge {
+                ForgeType::Github | ForgeType::Gitea => {
+                    self.client.patch(url).json(&json!({ "milestone": to.id }))
+                }
+                ForgeType::Gitlab => self.client.put(url).json(&json!({ "milestone_id": to.id })),
+            };
+            request
+                .send()
+                .await?
+                .successful_status()
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to move issue #{} to milestone {}",
+                        issue.number, to.title
+                    )
+                })?;
+            debug!("moved issue #{} to milestone {}", issue.number, to.title);
+        }
+        Ok(issues.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_milestones_are_identified_by_their_number() {
+        let milestone: ForgeMilestone =
+            serde_json::from_str(r#"{"id":1002604,"number":3,"title":"v1.2.0"}"#).unwrap();
+        assert_eq!(
+            Milestone::from(milestone),
+            Milestone {
+                id: 3,
+                title: "v1.2.0".to_string()
+            }
+        );
+        let milestone: ForgeMilestone =
+            serde_json::from_str(r#"{"id":12,"iid":3,"title":"v1.2.0"}"#).unwrap();
+        assert_eq!(Milestone::from(milestone).id, 12);
+    }
+}
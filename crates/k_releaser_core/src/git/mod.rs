@@ -1,5 +1,14 @@
+pub mod commit_checks;
 pub mod forge;
+pub mod forge_commit;
 pub mod gitea_client;
+pub mod github_app;
 pub mod github_client;
 pub mod github_graphql;
 pub mod gitlab_client;
+pub mod issues;
+pub mod lock;
+pub mod milestones;
+pub mod rate_limit;
+pub mod shallow;
+pub mod token_scopes;
@@ -0,0 +1,149 @@
+//! Throttling of the forge requests that hit the rate limit.
+//!
+//! When the forge answers with `403` or `429` because the rate limit is exhausted,
+//! k-releaser waits for the time advertised in the `Retry-After` or
+//! `X-RateLimit-Reset` headers and retries the request, instead of failing.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use http::Extensions;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Request, Response, StatusCode};
+use reqwest_middleware::{Middleware, Next};
+use tracing::warn;
+
+/// Maximum number of retries of a rate limited request.
+const MAX_RETRIES: u32 = 3;
+/// Requests that would need to wait longer than this fail immediately.
+const MAX_WAIT: Duration = Duration::from_secs(15 * 60);
+
+/// Middleware that pauses and retries the requests rejected by the rate limit.
+pub(crate) struct RateLimitRetry;
+
+#[async_trait::async_trait]
+impl Middleware for RateLimitRetry {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let mut retries = 0;
+        loop {
+            // Requests with a streaming body can't be retried.
+            let Some(retry_req) = req.try_clone() else {
+                return next.run(req, extensions).await;
+            };
+            let response = next.clone().run(req, extensions).await?;
+            let wait = rate_limit_wait(response.status(), response.headers(), Utc::now());
+            match wait {
+                Some(wait) if retries < MAX_RETRIES && wait <= MAX_WAIT => {
+                    warn!(
+                        "rate limit of {} exceeded, retrying in {}s",
+                        response.url().host_str().unwrap_or_default(),
+                        wait.as_secs()
+                    );
+                    tokio::time::sleep(wait).await;
+                    retries += 1;
+                    req = retry_req;
+                }
+                _ => return Ok(response),
+            }
+        }
+    }
+}
+
+/// Time to wait before retrying a request rejected by the rate limit.
+/// Returns [`Option::None`] if the response wasn't rejected by the rate limit.
+///
+/// GitHub and Gitea use the `X-RateLimit-*` headers, GitLab the `RateLimit-*` ones.
+fn rate_limit_wait(
+    status: StatusCode,
+    headers: &HeaderMap,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    if let Some(seconds) = header_number(headers, RETRY_AFTER.as_str()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    let remaining = header_number(headers, "x-ratelimit-remaining")
+        .or_else(|| header_number(headers, "ratelimit-remaining"))?;
+    if remaining > 0 {
+        // E.g. the token doesn't have the required permissions.
+        return None;
+    }
+    let reset = header_number(headers, "x-ratelimit-reset")
+        .or_else(|| header_number(headers, "ratelimit-reset"))?;
+    let seconds = reset.saturating_sub(u64::try_from(now.timestamp()).unwrap_or_default());
+    // Wait at least one second, in case the clocks are out of sync.
+    Some(Duration::from_secs(seconds.max(1)))
+}
+
+fn header_number(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(values: &[(&'static str, &str)]) -> HeaderMap {
+        values
+            .iter()
+            .map(|(name, value)| {
+                (
+                    reqwest::header::HeaderName::from_static(name),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wait_is_read_from_rate_limit_headers() {
+        let now = Utc::now();
+        let reset = (now.timestamp() + 30).to_string();
+
+        let retry_after = headers(&[("retry-after", "60")]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::TOO_MANY_REQUESTS, &retry_after, now),
+            Some(Duration::from_secs(60))
+        );
+
+        let exhausted = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", &reset),
+        ]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &exhausted, now),
+            Some(Duration::from_secs(30))
+        );
+
+        let gitlab = headers(&[("ratelimit-remaining", "0"), ("ratelimit-reset", &reset)]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::TOO_MANY_REQUESTS, &gitlab, now),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn requests_not_rejected_by_rate_limit_are_not_retried() {
+        let now = Utc::now();
+        let available = headers(&[("x-ratelimit-remaining", "10")]);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &available, now),
+            None
+        );
+        let retry_after = headers(&[("retry-after", "60")]);
+        assert_eq!(rate_limit_wait(StatusCode::OK, &retry_after, now), None);
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &HeaderMap::new(), now),
+            None
+        );
+    }
+}
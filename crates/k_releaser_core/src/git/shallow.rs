@@ -0,0 +1,43 @@
+//! Shallow clones, e.g. the default checkout of CI jobs, miss the commits and the tags
+//! needed to determine the next versions.
+
+use anyhow::Context as _;
+use git_cmd::Repo;
+use tracing::{info, warn};
+
+/// What k-releaser does when the repository is a shallow clone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FetchDepthStrategy {
+    /// Fetch the full history and the tags from the remote. *(Default)*.
+    #[default]
+    Unshallow,
+    /// Fail, asking to fetch the full history.
+    Fail,
+    /// Use the history of the shallow clone, e.g. when the missing commits don't matter.
+    Ignore,
+}
+
+/// Make sure `repo` contains the full history, according to `strategy`.
+pub(crate) fn ensure_full_history(repo: &Repo, strategy: FetchDepthStrategy) -> anyhow::Result<()> {
+    if !repo.is_shallow()? {
+        return Ok(());
+    }
+    match strategy {
+        FetchDepthStrategy::Unshallow => {
+            info!(
+                "the repository is a shallow clone: fetching the full history from `{}`",
+                repo.original_remote()
+            );
+            repo.unshallow().context(
+                "can't fetch the full history: fetch it before running k-releaser, e.g. with `fetch-depth: 0` in actions/checkout",
+            )
+        }
+        FetchDepthStrategy::Fail => anyhow::bail!(
+            "the repository is a shallow clone, so the commits and the tags of the previous releases may be missing: fetch the full history, e.g. with `fetch-depth: 0` in actions/checkout, or set `fetch_depth_strategy = \"unshallow\"`"
+        ),
+        FetchDepthStrategy::Ignore => {
+            warn!("the repository is a shallow clone: the next versions may be wrong");
+            Ok(())
+        }
+    }
+}
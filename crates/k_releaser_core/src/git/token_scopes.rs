@@ -0,0 +1,133 @@
+//! Check of the permissions of the forge token.
+//!
+//! k-releaser checks the token before changing the repository, so that it fails early
+//! with the list of the missing scopes, instead of with a `403` in the middle of the run.
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{GitClient, git::forge::ForgeType, response_ext::ResponseExt as _};
+
+/// Header where GitHub lists the scopes of classic personal access tokens.
+const GITHUB_SCOPES_HEADER: &str = "x-oauth-scopes";
+
+#[derive(Deserialize)]
+struct Repository {
+    /// Permissions of the token on the repository.
+    /// Missing if the forge doesn't report them, e.g. for GitHub App tokens.
+    permissions: Option<RepositoryPermissions>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryPermissions {
+    push: bool,
+}
+
+/// <https://docs.gitlab.com/api/personal_access_tokens/#get-details-on-a-personal-access-token>
+#[derive(Deserialize)]
+struct GitLabToken {
+    scopes: Vec<String>,
+}
+
+/// Check that the token can access the repository and has the scopes k-releaser needs
+/// to push branches and tags, and to open PRs and releases.
+pub async fn check_token_scopes(client: &GitClient) -> anyhow::Result<()> {
+    match client.forge {
+        ForgeType::Github | ForgeType::Gitea => check_repository_permissions(client).await,
+        ForgeType::Gitlab => check_gitlab_scopes(client).await,
+    }
+}
+
+async fn check_repository_permissions(client: &GitClient) -> anyhow::Result<()> {
+    let response = client
+        .client
+        .get(client.repo_url())
+        .send()
+        .await?
+        .successful_status()
+        .await
+        .context("can't read the repository with the given token")?;
+    // Only the classic personal access tokens of GitHub have scopes.
+    if let Some(scopes) = response.headers().get(GITHUB_SCOPES_HEADER) {
+        let scopes = scopes.to_str().unwrap_or_default();
+        ensure_scopes(&missing_github_scopes(scopes))?;
+    }
+    let repository: Repository = response.json().await.context("can't parse repository")?;
+    if let Some(permissions) = repository.permissions {
+        anyhow::ensure!(
+            permissions.push,
+            "the git token can't push to {}: grant it write access to the repository",
+            client.remote.owner_slash_repo()
+        );
+    }
+    Ok(())
+}
+
+async fn check_gitlab_scopes(client: &GitClient) -> anyhow::Result<()> {
+    if client.job_token {
+        // Job tokens don't have scopes, and can't read the project.
+        debug!("not checking the scopes of the GitLab CI/CD job token");
+        return Ok(());
+    }
+    client.check_repo_access().await?;
+    // The base url points to the project, e.g. `https://gitlab.com/api/v4/projects/<id>`.
+    let url = client
+        .remote
+        .base_url
+        .join("../personal_access_tokens/self")
+        .context("invalid GitLab token url")?;
+    let response = client.client.get(url).send().await?;
+    if !response.status().is_success() {
+        // E.g. the scopes of group access tokens can't be read.
+        debug!(
+            "can't read the scopes of the GitLab token: {}",
+            response.status()
+        );
+        return Ok(());
+    }
+    let token: GitLabToken = response.json().await.context("can't parse GitLab token")?;
+    ensure_scopes(&missing_gitlab_scopes(&token.scopes))
+}
+
+/// Scopes missing from the comma separated scopes of a GitHub classic token.
+fn missing_github_scopes(scopes: &str) -> Vec<&'static str> {
+    let has_repo_scope = scopes
+        .split(',')
+        .map(str::trim)
+        .any(|scope| scope == "repo" || scope == "public_repo");
+    if has_repo_scope { vec![] } else { vec!["repo"] }
+}
+
+fn missing_gitlab_scopes(scopes: &[String]) -> Vec<&'static str> {
+    if scopes.iter().any(|scope| scope == "api") {
+        vec![]
+    } else {
+        vec!["api"]
+    }
+}
+
+fn ensure_scopes(missing: &[&str]) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        missing.is_empty(),
+        "the git token is missing the scopes: {}. Add them to the token and retry",
+        missing.join(", ")
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_scopes_are_detected() {
+        assert!(missing_github_scopes("repo, workflow").is_empty());
+        assert!(missing_github_scopes("public_repo").is_empty());
+        assert_eq!(missing_github_scopes("read:org, gist"), ["repo"]);
+        assert_eq!(missing_github_scopes(""), ["repo"]);
+
+        assert!(missing_gitlab_scopes(&["api".to_string()]).is_empty());
+        assert_eq!(missing_gitlab_scopes(&["read_api".to_string()]), ["api"]);
+    }
+}
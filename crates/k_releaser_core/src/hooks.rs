@@ -0,0 +1,120 @@
+use std::process::Command;
+
+use anyhow::Context as _;
+use cargo_metadata::camino::Utf8Path;
+use tracing::{debug, info};
+
+/// Shell commands run before and after the stages of the release pipeline.
+///
+/// The commands run in the workspace root and receive the following environment variables:
+/// - `PACKAGE`: name of the package (`workspace` when packages share the workspace version).
+/// - `NEXT_VERSION`: version that is being released.
+/// - `TAG`: git tag of the release. Only available in the release hooks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hooks {
+    /// Run after the next versions are determined, before manifests and changelogs are updated.
+    pub pre_update: Option<String>,
+    /// Run after manifests, changelogs and Cargo.lock are updated.
+    pub post_update: Option<String>,
+    /// Run before `cargo publish`.
+    pub pre_publish: Option<String>,
+    /// Run after the package is available in the cargo registry.
+    pub post_publish: Option<String>,
+    /// Run before the git tag is created.
+    pub pre_release: Option<String>,
+    /// Run after the git tag and the git release are created.
+    pub post_release: Option<String>,
+}
+
+/// Environment variables passed to a hook.
+#[derive(Debug, Default)]
+pub(crate) struct HookEnv<'a> {
+    pub package: &'a str,
+    pub next_version: String,
+    pub tag: Option<&'a str>,
+}
+
+/// Run the hook `command`, if present. Fails if the command exits with an error.
+pub(crate) fn run_hook(
+    name: &str,
+    command: Option<&str>,
+    dir: &Utf8Path,
+    env: &HookEnv,
+) -> anyhow::Result<()> {
+    let Some(command) = command else {
+        return Ok(());
+    };
+    info!("{}: running {name} hook: {command}", env.package);
+    let mut cmd = shell_command(command);
+    cmd.current_dir(dir)
+        .env("PACKAGE", env.package)
+        .env("NEXT_VERSION", &env.next_version);
+    if let Some(tag) = env.tag {
+        cmd.env("TAG", tag);
+    }
+    let output = cmd
+        .output()
+        .with_context(|| format!("cannot run {name} hook `{command}`"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    debug!("{name} hook stdout: {stdout}");
+    debug!("{name} hook stderr: {stderr}");
+    anyhow::ensure!(
+        output.status.success(),
+        "{name} hook `{command}` failed with {}. stdout: {stdout}; stderr: {stderr}",
+        output.status
+    );
+    Ok(())
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use cargo_metadata::camino::Utf8PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn hook_receives_env_vars() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        let env = HookEnv {
+            package: "my_crate",
+            next_version: "1.2.3".to_string(),
+            tag: Some("v1.2.3"),
+        };
+        run_hook(
+            "post_release",
+            Some(r#"echo "$PACKAGE $NEXT_VERSION $TAG" > out.txt"#),
+            &dir,
+            &env,
+        )
+        .unwrap();
+        let out = fs_err::read_to_string(dir.join("out.txt")).unwrap();
+        assert_eq!(out, "my_crate 1.2.3 v1.2.3\n");
+    }
+
+    #[test]
+    fn failing_hook_returns_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        let error = run_hook("pre_publish", Some("exit 3"), &dir, &HookEnv::default())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("pre_publish hook `exit 3` failed"),
+            "{error}"
+        );
+    }
+}
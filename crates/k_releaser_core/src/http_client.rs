@@ -1,7 +1,99 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use anyhow::Context as _;
+use reqwest::{Certificate, Identity, NoProxy, Proxy};
+
+/// Options applied to every http client built with [`http_client_builder`],
+/// e.g. to reach a self-hosted forge or registry that uses an internal CA.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpConfig {
+    /// PEM file with additional root certificates to trust.
+    pub ca_cert_path: Option<PathBuf>,
+    /// Don't verify the certificates of the servers.
+    /// This is insecure: use it only for testing.
+    pub accept_invalid_certs: bool,
+    /// PEM file with the client certificate and its private key,
+    /// used to authenticate with mutual TLS.
+    pub client_cert_path: Option<PathBuf>,
+    /// Url of the proxy used for all the requests.
+    /// Hosts listed in the `NO_PROXY` environment variable are reached directly.
+    /// If [`Option::None`], the `HTTP_PROXY` and `HTTPS_PROXY` environment variables are used.
+    pub proxy: Option<String>,
+}
+
+/// [`HttpConfig`] with the certificates loaded from disk.
+#[derive(Default)]
+struct ClientOptions {
+    ca_certs: Vec<Certificate>,
+    accept_invalid_certs: bool,
+    identity: Option<Identity>,
+    proxy: Option<Proxy>,
+}
+
+static CLIENT_OPTIONS: OnceLock<ClientOptions> = OnceLock::new();
+
+/// Configure the http clients of k-releaser.
+/// Call it once, before any client is built.
+pub fn configure(config: &HttpConfig) -> anyhow::Result<()> {
+    let ca_certs = match &config.ca_cert_path {
+        Some(path) => {
+            let pem = fs_err::read(path).context("can't read CA certificate")?;
+            Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("invalid CA certificate {}", path.display()))?
+        }
+        None => vec![],
+    };
+    let identity = config
+        .client_cert_path
+        .as_ref()
+        .map(|path| {
+            let pem = fs_err::read(path).context("can't read client certificate")?;
+            Identity::from_pem(&pem)
+                .with_context(|| format!("invalid client certificate {}", path.display()))
+        })
+        .transpose()?;
+    if config.accept_invalid_certs {
+        tracing::warn!("TLS certificate verification is disabled");
+    }
+    let proxy = config
+        .proxy
+        .as_deref()
+        .map(|url| {
+            Proxy::all(url)
+                .map(|proxy| proxy.no_proxy(NoProxy::from_env()))
+                .with_context(|| format!("invalid proxy url {url}"))
+        })
+        .transpose()?;
+    let options = ClientOptions {
+        ca_certs,
+        accept_invalid_certs: config.accept_invalid_certs,
+        identity,
+        proxy,
+    };
+    CLIENT_OPTIONS
+        .set(options)
+        .map_err(|_| anyhow::anyhow!("http clients are already configured"))
+}
+
 /// Client builder using the k-releaser user agent, used
 /// to identify k-releaser to external http servers,
 /// such as GitHub and Gitea.
+/// The options set with [`configure`] are applied.
 pub fn http_client_builder() -> reqwest::ClientBuilder {
     let user_agent = format!("k-releaser/{}", env!("CARGO_PKG_VERSION"));
-    reqwest::Client::builder().user_agent(user_agent)
+    let mut builder = reqwest::Client::builder().user_agent(user_agent);
+    let Some(options) = CLIENT_OPTIONS.get() else {
+        return builder;
+    };
+    builder = builder
+        .tls_certs_merge(options.ca_certs.iter().cloned())
+        .tls_danger_accept_invalid_certs(options.accept_invalid_certs);
+    if let Some(identity) = &options.identity {
+        builder = builder.identity(identity.clone());
+    }
+    if let Some(proxy) = &options.proxy {
+        builder = builder.proxy(proxy.clone());
+    }
+    builder
 }
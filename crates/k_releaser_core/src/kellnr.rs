@@ -0,0 +1,110 @@
+//! Client of the web API of [Kellnr](https://kellnr.io), a self-hosted cargo registry.
+//!
+//! Kellnr doesn't issue short-lived tokens like crates.io's trusted publishing,
+//! so publishing always needs a registry token.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use cargo_metadata::Package;
+use reqwest::{StatusCode, header};
+use secrecy::{ExposeSecret, SecretString};
+use url::Url;
+
+use crate::{
+    cargo::{CargoIndex, index_config},
+    response_ext::ResponseExt as _,
+};
+
+fn api_endpoint(url: &Url, path: &str) -> String {
+    format!("{}/api/v1/{path}", url.as_str().trim_end_matches('/'))
+}
+
+fn request(
+    method: reqwest::Method,
+    endpoint: &str,
+    timeout: Duration,
+    token: &Option<SecretString>,
+) -> anyhow::Result<reqwest::RequestBuilder> {
+    let client = crate::http_client::http_client_builder()
+        .timeout(timeout)
+        .build()?;
+    let mut req = client.request(method, endpoint);
+    if let Some(token) = token {
+        req = req.header(header::AUTHORIZATION, token.expose_secret());
+    }
+    Ok(req)
+}
+
+/// Check if the package is published by looking for its download, at the `dl` url
+/// of the `config.json` of the index.
+/// Kellnr answers `HEAD` requests without sending the crate file.
+pub(crate) async fn is_published(
+    index: &CargoIndex,
+    package: &Package,
+    timeout: Duration,
+    token: &Option<SecretString>,
+) -> anyhow::Result<bool> {
+    let config = index_config(index, timeout, token)
+        .await
+        .context("can't read the download url of the Kellnr registry")?;
+    let endpoint = config
+        .download_url(&package.name, &package.version.to_string())
+        .with_context(|| format!("invalid download url of package {}", package.name))?;
+    let res = request(reqwest::Method::HEAD, &endpoint, timeout, token)?
+        .send()
+        .await
+        .with_context(|| format!("failed to call Kellnr api {endpoint}"))?;
+    match res.status() {
+        StatusCode::NOT_FOUND => Ok(false),
+        _ => {
+            res.successful_status()
+                .await
+                .with_context(|| format!("unexpected response from Kellnr api {endpoint}"))?;
+            Ok(true)
+        }
+    }
+}
+
+/// Check that Kellnr accepts the token, so that an invalid token fails before
+/// running `cargo publish`.
+pub(crate) async fn validate_token(
+    url: &Url,
+    package: &Package,
+    timeout: Duration,
+    token: &Option<SecretString>,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        token.is_some(),
+        "no token found to publish {} to Kellnr. Kellnr doesn't support trusted publishing",
+        package.name
+    );
+    let endpoint = api_endpoint(url, &format!("crates/{}/owners", package.name));
+    let res = request(reqwest::Method::GET, &endpoint, timeout, token)?
+        .send()
+        .await
+        .with_context(|| format!("failed to call Kellnr api {endpoint}"))?;
+    anyhow::ensure!(
+        !matches!(
+            res.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ),
+        "Kellnr rejected the token used to publish {}",
+        package.name
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_endpoint_ignores_trailing_slash() {
+        let expected = "https://kellnr.example.com/api/v1/crates/my-crate/owners";
+        for url in ["https://kellnr.example.com", "https://kellnr.example.com/"] {
+            let url = Url::parse(url).unwrap();
+            assert_eq!(api_endpoint(&url, "crates/my-crate/owners"), expected);
+        }
+    }
+}
@@ -4,13 +4,18 @@ mod changelog;
 mod changelog_filler;
 mod changelog_parser;
 mod clone;
+mod codeowners;
 mod command;
 mod copy_dir;
+mod crates_io;
 mod diff;
+mod docs_rs;
 mod download;
 pub mod fs_utils;
 mod git;
+mod hooks;
 pub mod http_client;
+mod kellnr;
 mod next_ver;
 mod package_compare;
 mod package_path;
@@ -24,16 +29,23 @@ pub mod semver_check;
 mod tera;
 mod tmp_repo;
 
+pub use cargo::RegistryKind;
 pub use changelog::*;
+pub use changelog_parser::{ChangelogRelease, last_changes, release_from_str};
 pub use command::*;
 pub use download::{PackageDownloader, read_package};
-pub use git::forge::{GitClient, GitForge, GitPr};
+pub use git::forge::{ForgeRelease, GitClient, GitForge, GitPr};
 pub use git::gitea_client::Gitea;
+pub use git::github_app::GitHubApp;
 pub use git::github_client::GitHub;
 pub use git::gitlab_client::GitLab;
+pub use git::lock::{LOCK_BRANCH, LockMode};
+pub use git::shallow::FetchDepthStrategy;
+pub use git::token_scopes::check_token_scopes;
+pub use hooks::Hooks;
 pub use next_ver::*;
 pub use package_compare::*;
 pub use package_path::*;
-pub use pr::{DEFAULT_BRANCH_PREFIX, Pr};
+pub use pr::{DEFAULT_BRANCH_PREFIX, MergeMethod, Pr, PrProject};
 pub use project::*;
 pub use repo_url::*;
@@ -3,8 +3,9 @@ use crate::updater::Updater;
 use crate::{
     PackagesUpdate, Project,
     changelog_parser::{self, ChangelogRelease},
-    copy_dir::copy_dir,
+    copy_dir::{copy_dir, detach_worktree_copy},
     fs_utils::{Utf8TempDir, strip_prefix},
+    git::shallow::ensure_full_history,
     package_path::manifest_dir,
     semver_check::SemverCheck,
     tmp_repo::TempRepo,
@@ -17,6 +18,7 @@ use cargo_metadata::{
     semver::Version,
 };
 use chrono::NaiveDate;
+use git_cmd::Repo;
 use std::path::PathBuf;
 use toml_edit::TableLike;
 use tracing::{instrument, trace};
@@ -32,6 +34,8 @@ pub struct ReleaseMetadata {
     pub tag_name_template: Option<String>,
     /// Template for the git release name created by k-releaser.
     pub release_name_template: Option<String>,
+    /// Templates of the additional git tags created for the release.
+    pub extra_tag_name_templates: Vec<String>,
 }
 
 pub trait ReleaseMetadataBuilder {
@@ -43,14 +47,17 @@ pub struct ChangelogRequest {
     /// When the new release is published. If unspecified, current date is used.
     pub release_date: Option<NaiveDate>,
     pub changelog_config: Option<git_cliff_core::config::Config>,
+    /// With unified versioning, group the commits of the changelog by the package they changed.
+    pub group_by_package: bool,
 }
 
 impl ReleaseMetadataBuilder for UpdateRequest {
     fn get_release_metadata(&self, package_name: &str) -> Option<ReleaseMetadata> {
         let config = self.get_package_config(package_name);
-        Some(ReleaseMetadata {
+        config.generic.release.then(|| ReleaseMetadata {
             tag_name_template: config.generic.tag_name_template.clone(),
             release_name_template: None,
+            extra_tag_name_templates: vec![],
         })
     }
 }
@@ -59,21 +66,49 @@ impl ReleaseMetadataBuilder for UpdateRequest {
 #[instrument(skip_all)]
 pub async fn next_versions(input: &UpdateRequest) -> anyhow::Result<(PackagesUpdate, TempRepo)> {
     let overrides = input.packages_config().overridden_packages();
-    let local_project = Project::new(
+    // Packages of a version group are always updated together.
+    let version_group = input
+        .single_package()
+        .and_then(|package| input.get_package_config(package).version_group);
+    let single_package = match version_group {
+        Some(_) => None,
+        None => input.single_package(),
+    };
+    let mut local_project = Project::new(
         input.local_manifest(),
-        input.single_package(),
+        single_package,
         &overrides,
         input.cargo_metadata(),
         input,
-    )?;
+    )?
+    .with_version_mode(input.version_mode())
+    .with_branch(input.branch().map(str::to_string))
+    .with_workspace_tag_name(input.workspace_tag_name().map(str::to_string));
+    if let Some(version_group) = &version_group {
+        local_project = local_project.retain_packages(|p| {
+            input.get_package_config(&p.name).version_group.as_ref() == Some(version_group)
+        });
+    }
     let updater = Updater {
         project: &local_project,
         req: input,
     };
 
-    let repository = local_project
+    // The temporary repository is a copy of the local one, so the local one needs the full history.
+    let mut local_repo = Repo::new(root_repo_path_from_manifest_dir(
+        input.local_manifest_dir()?,
+    )?)?;
+    if let Some(remote) = input.git_remote() {
+        local_repo = local_repo.with_remote(remote);
+    }
+    ensure_full_history(&local_repo, input.fetch_depth_strategy())?;
+
+    let mut repository = local_project
         .get_repo()
         .context("failed to determine local project repository")?;
+    if let Some(remote) = input.git_remote() {
+        repository.repo = repository.repo.with_remote(remote);
+    }
 
     let repo_is_clean_result = repository.repo.is_clean();
     if !input.allow_dirty() {
@@ -181,6 +216,11 @@ pub fn copy_to_temp_dir(target: &Utf8Path) -> anyhow::Result<Utf8TempDir> {
     let tmp_dir = Utf8TempDir::new().context("cannot create temporary directory")?;
     copy_dir(target, tmp_dir.path())
         .with_context(|| format!("cannot copy directory {target:?} to {tmp_dir:?}"))?;
+    if target.join(".git").is_file() {
+        let copy = crate::new_project_root(target, tmp_dir.path())?;
+        detach_worktree_copy(target, &copy)
+            .with_context(|| format!("cannot detach the copy of the git worktree {target:?}"))?;
+    }
     Ok(tmp_dir)
 }
 
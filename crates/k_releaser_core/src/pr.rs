@@ -1,11 +1,14 @@
 use crate::{
     PackagesUpdate, ReleaseInfo,
-    tera::{RELEASES_VAR, VERSION_VAR, render_template},
+    tera::{PACKAGES_VAR, RELEASES_VAR, TIMESTAMP_VAR, VERSION_VAR, render_template},
 };
 use chrono::SecondsFormat;
 
 pub const DEFAULT_BRANCH_PREFIX: &str = "k-releaser-";
 pub const OLD_BRANCH_PREFIX: &str = "release-plz/";
+/// Start of the hidden marker added to the body of the release PRs.
+const RELEASE_PR_MARKER_START: &str = "<!-- k-releaser-release-pr branch-prefix=\"";
+const RELEASE_PR_MARKER_END: &str = "\" -->";
 pub const DEFAULT_PR_BODY_TEMPLATE: &str = r#"
 ## New release v{{ releases[0].next_version }}
 
@@ -32,6 +35,25 @@ This release updates all workspace packages to version **{{ releases[0].next_ver
 {%- endfor %}
 {% endif %}
 
+---
+Generated by [k-releaser](https://github.com/secana/k-releaser/)
+"#;
+/// PR body used when packages are versioned independently.
+pub const DEFAULT_INDEPENDENT_PR_BODY_TEMPLATE: &str = r#"
+## New release
+
+This release updates the following packages:
+{% for release in releases %}
+* `{{ release.package }}`: {{ release.previous_version }} -> {{ release.next_version }}
+{%- endfor %}
+{% for release in releases %}{% if release.changelog %}
+<details><summary><i><b>`{{ release.package }}` changelog</b></i></summary>
+
+{{ release.changelog }}
+
+</details>
+{% endif %}{% endfor %}
+
 ---
 Generated by [k-releaser](https://github.com/secana/k-releaser/)
 "#;
@@ -44,6 +66,39 @@ pub struct Pr {
     pub body: String,
     pub draft: bool,
     pub labels: Vec<String>,
+    /// Users and teams (`org/team`) requested to review the PR.
+    pub reviewers: Vec<String>,
+    /// Users assigned to the PR.
+    pub assignees: Vec<String>,
+    /// Title of the milestone of the PR. It's created if missing.
+    pub milestone: Option<String>,
+    /// GitHub Project where the PR is added.
+    pub project: Option<PrProject>,
+    /// Approvals required to merge the PR. Only supported by GitLab.
+    pub required_approvals: Option<u32>,
+    /// If set, the PR is merged with this method once its checks pass.
+    pub auto_merge: Option<MergeMethod>,
+}
+
+/// How the release PR is merged when auto-merge is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+/// GitHub Project (v2) where the release PR is added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrProject {
+    /// User or organization owning the project.
+    /// If [`Option::None`], the owner of the repository.
+    pub owner: Option<String>,
+    /// Number of the project, as shown in its url.
+    pub number: u64,
+    /// Value of the `Status` field of the PR in the project.
+    /// If [`Option::None`], the default status of the project is kept.
+    pub status: Option<String>,
 }
 
 impl Pr {
@@ -52,20 +107,27 @@ impl Pr {
         packages_to_update: &PackagesUpdate,
         project_contains_multiple_pub_packages: bool,
         branch_prefix: &str,
+        branch_template: Option<&str>,
         title_template: Option<String>,
         body_template: Option<&str>,
     ) -> anyhow::Result<Self> {
         let pr = Self {
-            branch: release_branch(branch_prefix),
+            branch: release_branch(branch_prefix, branch_template, packages_to_update)?,
             base_branch: default_branch.to_string(),
             title: pr_title(
                 packages_to_update,
                 project_contains_multiple_pub_packages,
                 title_template,
             )?,
-            body: pr_body(packages_to_update, body_template)?,
+            body: pr_body(packages_to_update, body_template, branch_prefix)?,
             draft: false,
             labels: vec![],
+            reviewers: vec![],
+            assignees: vec![],
+            milestone: None,
+            project: None,
+            required_approvals: None,
+            auto_merge: None,
         };
         Ok(pr)
     }
@@ -79,15 +141,105 @@ impl Pr {
         self.labels = labels;
         self
     }
+
+    pub fn with_reviewers(mut self, reviewers: Vec<String>) -> Self {
+        self.reviewers = reviewers;
+        self
+    }
+
+    pub fn with_assignees(mut self, assignees: Vec<String>) -> Self {
+        self.assignees = assignees;
+        self
+    }
+
+    pub fn with_milestone(mut self, milestone: Option<String>) -> Self {
+        self.milestone = milestone;
+        self
+    }
+
+    pub fn with_project(mut self, project: Option<PrProject>) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_required_approvals(mut self, required_approvals: Option<u32>) -> Self {
+        self.required_approvals = required_approvals;
+        self
+    }
+
+    pub fn with_auto_merge(mut self, auto_merge: Option<MergeMethod>) -> Self {
+        self.auto_merge = auto_merge;
+        self
+    }
+}
+
+/// Context with the `version` and the `packages` of the release.
+fn release_context(packages_to_update: &PackagesUpdate) -> tera::Context {
+    let updates = packages_to_update.updates();
+    let version = match (packages_to_update.workspace_version(), updates) {
+        (Some(version), _) => Some(version),
+        // The version is only meaningful when a single package is released
+        (None, [(_, update)]) => Some(&update.version),
+        (None, _) => None,
+    };
+    let mut context = tera::Context::new();
+    if let Some(version) = version {
+        context.insert(VERSION_VAR, &version.to_string());
+    }
+    let packages: Vec<&str> = updates.iter().map(|(p, _)| p.name.as_str()).collect();
+    context.insert(PACKAGES_VAR, &packages);
+    context
 }
 
-fn release_branch(prefix: &str) -> String {
+/// Title of the milestone of the release PR, rendered from `milestone_template`.
+pub fn milestone_title(
+    milestone_template: &str,
+    packages_to_update: &PackagesUpdate,
+) -> anyhow::Result<String> {
+    let context = release_context(packages_to_update);
+    let title = render_template(milestone_template, &context, "pr_milestone")?;
+    Ok(title.trim().to_string())
+}
+
+/// Name of the release branch: the prefix followed by the rendered `branch_template`.
+/// The prefix is kept so that [`crate::GitClient::opened_prs`] recognizes the branch.
+fn release_branch(
+    prefix: &str,
+    branch_template: Option<&str>,
+    packages_to_update: &PackagesUpdate,
+) -> anyhow::Result<String> {
     let now = chrono::offset::Utc::now();
     // Convert to a string of format "2018-01-26T18:30:09Z".
     let now = now.to_rfc3339_opts(SecondsFormat::Secs, true);
     // ':' is not a valid character for a branch name.
     let now = now.replace(':', "-");
-    format!("{prefix}{now}")
+    let Some(branch_template) = branch_template else {
+        return Ok(format!("{prefix}{now}"));
+    };
+    let mut context = release_context(packages_to_update);
+    context.insert(TIMESTAMP_VAR, &now);
+    let name = render_template(branch_template, &context, "pr_branch_name")?;
+    let name = name.trim();
+    let branch = format!("{prefix}{name}");
+    anyhow::ensure!(
+        !name.is_empty() && is_valid_branch_name(&branch),
+        "invalid release branch name `{branch}`: `pr_branch_name` must render to a name accepted by git, without spaces or any of `~^:?*[\\`, `..` and `@{{`"
+    );
+    Ok(branch)
+}
+
+/// Whether git accepts `name` as a branch name. See `git check-ref-format`.
+fn is_valid_branch_name(name: &str) -> bool {
+    const FORBIDDEN: [&str; 3] = ["..", "@{", "//"];
+    name != "@"
+        && !name.starts_with(['/', '-'])
+        && !name.ends_with(['/', '.'])
+        && !FORBIDDEN.iter().any(|s| name.contains(s))
+        && !name
+            .contains(|c: char| c.is_whitespace() || c.is_ascii_control() || "~^:?*[\\".contains(c))
+        && !name
+            .split('/')
+            .any(|component| component.starts_with('.') || component.ends_with(".lock"))
 }
 
 fn pr_title(
@@ -97,6 +249,11 @@ fn pr_title(
 ) -> anyhow::Result<String> {
     let updates = packages_to_update.updates();
 
+    // Without a workspace version, packages are versioned independently
+    if packages_to_update.workspace_version().is_none() {
+        return independent_pr_title(packages_to_update, title_template);
+    }
+
     // With unified workspace versioning, all packages always have the same version
     let workspace_version = &updates[0].1.version;
 
@@ -120,51 +277,186 @@ fn pr_title(
     Ok(title)
 }
 
+fn independent_pr_title(
+    packages_to_update: &PackagesUpdate,
+    title_template: Option<String>,
+) -> anyhow::Result<String> {
+    let updates = packages_to_update.updates();
+    let single_update = match updates {
+        [(package, update)] => Some((package.name.as_str(), &update.version)),
+        _ => None,
+    };
+
+    let title = if let Some(title_template) = title_template {
+        let mut context = tera::Context::new();
+        // The version is only meaningful when a single package is released
+        if let Some((package_name, version)) = single_update {
+            context.insert(VERSION_VAR, version.to_string().as_str());
+            context.insert("package", package_name);
+        } else {
+            context.insert("package", "workspace");
+        }
+        render_template(&title_template, &context, "pr_name")?
+    } else if let Some((package_name, version)) = single_update {
+        format!("chore({package_name}): release v{version}")
+    } else {
+        "chore: release".to_string()
+    };
+    Ok(title)
+}
+
 /// The Github API allows a max of 65536 characters in the body field when trying to create a new PR
 const MAX_BODY_LEN: usize = 65536;
 
+/// Hidden marker of the release PRs, so that they are recognized even if their branch
+/// was renamed. It contains the branch prefix, to tell apart the PRs of different
+/// packages and branches.
+fn release_pr_marker(branch_prefix: &str) -> String {
+    format!("{RELEASE_PR_MARKER_START}{branch_prefix}{RELEASE_PR_MARKER_END}")
+}
+
+/// Branch prefix of the release PR marker in `body`, if any.
+pub(crate) fn marker_branch_prefix(body: &str) -> Option<&str> {
+    let (_, marker) = body.split_once(RELEASE_PR_MARKER_START)?;
+    marker
+        .split_once(RELEASE_PR_MARKER_END)
+        .map(|(prefix, _)| prefix)
+}
+
+/// `body` without the release PR marker.
+pub(crate) fn strip_release_pr_marker(body: &str) -> String {
+    body.lines()
+        .filter(|line| !line.contains(RELEASE_PR_MARKER_START))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
 fn pr_body(
     packages_to_update: &PackagesUpdate,
     body_template: Option<&str>,
+    branch_prefix: &str,
 ) -> anyhow::Result<String> {
-    let body_template = body_template.unwrap_or(DEFAULT_PR_BODY_TEMPLATE);
+    let default_template = if packages_to_update.workspace_version().is_some() {
+        DEFAULT_PR_BODY_TEMPLATE
+    } else {
+        DEFAULT_INDEPENDENT_PR_BODY_TEMPLATE
+    };
+    let body_template = body_template.unwrap_or(default_template);
+
+    let marker = release_pr_marker(branch_prefix);
+    // Leave room for the marker, appended on a new line.
+    let max_len = MAX_BODY_LEN - marker.chars().count() - 1;
 
     let mut releases = packages_to_update.releases();
-    let first_render = render_pr_body(&releases, body_template)?;
+    let first_render = render_pr_body(&releases, body_template, max_len)?;
 
-    if first_render.chars().count() > MAX_BODY_LEN {
-        tracing::info!(
-            "PR body is longer than {MAX_BODY_LEN} characters. Omitting full changelog."
-        );
+    let body = if first_render.chars().count() > max_len {
+        tracing::info!("PR body is longer than {max_len} characters. Omitting full changelog.");
 
         releases.iter_mut().for_each(|release| {
             release.changelog = None;
             release.title = None;
         });
 
-        render_pr_body(&releases, body_template)
+        render_pr_body(&releases, body_template, max_len)?
     } else {
-        Ok(first_render)
-    }
+        first_render
+    };
+    Ok(format!("{body}\n{marker}"))
 }
 
-fn render_pr_body(releases: &[ReleaseInfo], body_template: &str) -> anyhow::Result<String> {
+fn render_pr_body(
+    releases: &[ReleaseInfo],
+    body_template: &str,
+    max_len: usize,
+) -> anyhow::Result<String> {
     let mut context = tera::Context::new();
     context.insert(RELEASES_VAR, releases);
 
     let rendered_body = render_template(body_template, &context, "pr_body")?;
-    Ok(trim_pr_body(rendered_body))
+    Ok(trim_pr_body(rendered_body, max_len))
 }
 
-fn trim_pr_body(body: String) -> String {
+fn trim_pr_body(body: String, max_len: usize) -> String {
     // Make extra sure the body is short enough.
     // If it's not, give up trying to fail gracefully by truncating it to the nearest valid UTF-8 boundary.
     // A grapheme cluster may be cut in half in the process.
 
-    if body.chars().count() > MAX_BODY_LEN {
-        tracing::warn!("PR body is still longer than {MAX_BODY_LEN} characters. Truncating as is.");
-        body.chars().take(MAX_BODY_LEN).collect()
+    if body.chars().count() > max_len {
+        tracing::warn!("PR body is still longer than {max_len} characters. Truncating as is.");
+        body.chars().take(max_len).collect()
     } else {
         body
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::UpdateResult;
+    use crate::semver_check::SemverCheck;
+
+    use super::*;
+
+    #[test]
+    fn release_branch_name_is_rendered() {
+        let update = UpdateResult {
+            version: "0.2.0".parse().unwrap(),
+            changelog: None,
+            semver_check: SemverCheck::Skipped,
+            new_changelog_entry: None,
+        };
+        let packages_update =
+            PackagesUpdate::new(vec![(fake_package::FakePackage::new("aaa").into(), update)]);
+        let branch = release_branch(
+            "release/",
+            Some("{{ packages | join(sep='-') }}-v{{ version }}"),
+            &packages_update,
+        )
+        .unwrap();
+        assert_eq!(branch, "release/aaa-v0.2.0");
+        let branch = release_branch("k-releaser-", None, &packages_update).unwrap();
+        assert!(branch.starts_with("k-releaser-") && !branch.contains(':'));
+        assert!(release_branch("release/", Some("{{ version }} x"), &packages_update).is_err());
+        for invalid in [
+            "v..{{ version }}",
+            "v{{ version }}~1",
+            "a:{{ version }}",
+            "x^",
+            "x.lock",
+        ] {
+            assert!(
+                release_branch("release/", Some(invalid), &packages_update).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn milestone_title_is_rendered() {
+        let update = UpdateResult {
+            version: "0.2.0".parse().unwrap(),
+            changelog: None,
+            semver_check: SemverCheck::Skipped,
+            new_changelog_entry: None,
+        };
+        let packages_update =
+            PackagesUpdate::new(vec![(fake_package::FakePackage::new("aaa").into(), update)]);
+        let title = milestone_title("v{{ version }}", &packages_update).unwrap();
+        assert_eq!(title, "v0.2.0");
+        let title = milestone_title("Release", &packages_update).unwrap();
+        assert_eq!(title, "Release");
+    }
+
+    #[test]
+    fn release_pr_marker_contains_the_branch_prefix() {
+        let body = format!(
+            "## New release\n\nchanges\n{}",
+            release_pr_marker("k-releaser-foo/")
+        );
+        assert_eq!(marker_branch_prefix(&body), Some("k-releaser-foo/"));
+        assert_eq!(marker_branch_prefix("## New release"), None);
+        assert_eq!(strip_release_pr_marker(&body), "## New release\n\nchanges");
+    }
+}
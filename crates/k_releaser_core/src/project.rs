@@ -4,13 +4,15 @@ use anyhow::Context as _;
 use cargo_metadata::{
     DependencyKind, Metadata, Package,
     camino::{Utf8Path, Utf8PathBuf},
+    semver::Version,
 };
 use cargo_utils::CARGO_TOML;
+use git_cmd::Repo;
 use tracing::debug;
 
 use crate::{
     PackagePath as _,
-    tera::{PACKAGE_VAR, VERSION_VAR, tera_context, tera_var},
+    tera::{BRANCH_VAR, PACKAGE_VAR, VERSION_VAR, tera_context, tera_var},
 };
 use crate::{
     Publishable as _, ReleaseMetadata, ReleaseMetadataBuilder, copy_to_temp_dir,
@@ -35,10 +37,36 @@ pub struct Project {
     /// The project contains more than one public package.
     /// Not affected by `single_package` option.
     contains_multiple_pub_packages: bool,
+    /// How the versions of the packages are calculated.
+    version_mode: VersionMode,
+    /// Branch the project is released from, available as `{{ branch }}` in the templates.
+    branch: Option<String>,
+    /// Template of the workspace git tag with unified versioning.
+    /// If [`Option::None`], the tag is `v{version}`.
+    workspace_tag_name: Option<String>,
+}
+
+/// How k-releaser versions the packages of a workspace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionMode {
+    /// All packages share the same version, changelog and `v{version}` git tag.
+    #[default]
+    Unified,
+    /// Every package has its own version, changelog and git tag,
+    /// calculated from the commits that touched the package.
+    /// The default git tag is `{package}-v{version}`.
+    Independent,
+}
+
+impl VersionMode {
+    pub fn is_independent(self) -> bool {
+        self == Self::Independent
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
 enum TemplateField {
+    GitTagName,
     ReleaseName,
 }
 
@@ -98,6 +126,9 @@ impl Project {
             root,
             manifest_dir,
             contains_multiple_pub_packages,
+            version_mode: VersionMode::default(),
+            branch: None,
+            workspace_tag_name: None,
         })
     }
 
@@ -145,9 +176,40 @@ impl Project {
             root,
             manifest_dir,
             contains_multiple_pub_packages,
+            version_mode: VersionMode::default(),
+            branch: None,
+            workspace_tag_name: None,
         })
     }
 
+    pub fn with_version_mode(self, version_mode: VersionMode) -> Self {
+        Self {
+            version_mode,
+            ..self
+        }
+    }
+
+    pub fn version_mode(&self) -> VersionMode {
+        self.version_mode
+    }
+
+    pub fn with_branch(self, branch: Option<String>) -> Self {
+        Self { branch, ..self }
+    }
+
+    pub fn with_workspace_tag_name(self, workspace_tag_name: Option<String>) -> Self {
+        Self {
+            workspace_tag_name,
+            ..self
+        }
+    }
+
+    /// Keep only the packages that satisfy the predicate.
+    pub(crate) fn retain_packages(mut self, f: impl FnMut(&Package) -> bool) -> Self {
+        self.packages.retain(f);
+        self
+    }
+
     pub fn root(&self) -> &Utf8Path {
         &self.root
     }
@@ -182,11 +244,77 @@ impl Project {
         Ok(repository)
     }
 
-    /// Generate git tag for the workspace version.
-    /// Always uses format: v{version} (unified workspace tag, not per-package)
-    pub fn git_tag(&self, version: &str) -> anyhow::Result<String> {
-        // For unified workspace versioning, always use v{version} format
-        Ok(format!("v{version}"))
+    /// Generate the git tag of a release.
+    /// - With unified versioning, the tag is `v{version}` (one tag for the workspace),
+    ///   unless a workspace tag template is set, e.g. for a maintenance branch.
+    /// - With independent versioning, the tag is rendered from the `git_tag_name`
+    ///   template of the package and defaults to `{package}-v{version}`.
+    pub fn git_tag(&self, package_name: &str, version: &str) -> anyhow::Result<String> {
+        match self.version_mode {
+            VersionMode::Unified => match &self.workspace_tag_name {
+                Some(template) => {
+                    let context = self.template_context(package_name, version);
+                    crate::tera::render_template(template, &context, "tag_name")
+                }
+                None => Ok(format!("v{version}")),
+            },
+            VersionMode::Independent => {
+                self.render_template(package_name, version, TemplateField::GitTagName)
+            }
+        }
+    }
+
+    /// Additional git tags of a release, rendered from the `git_tag_extra_names` templates
+    /// of the package. Tags equal to [`Project::git_tag`] are skipped.
+    pub fn extra_git_tags(&self, package_name: &str, version: &str) -> anyhow::Result<Vec<String>> {
+        let Some(release_metadata) = self.release_metadata.get(package_name) else {
+            return Ok(vec![]);
+        };
+        let git_tag = self.git_tag(package_name, version)?;
+        let context = self.template_context(package_name, version);
+        let mut tags: Vec<String> = vec![];
+        for template in &release_metadata.extra_tag_name_templates {
+            let tag = crate::tera::render_template(template, &context, "extra_tag_name")?;
+            if tag != git_tag && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Glob patterns matching the git tags of the releases of the project,
+    /// e.g. `v*` or `my-crate-v*`, used to fetch only the relevant tags.
+    /// Returns [`None`] if a tag template doesn't render to a pattern with exactly one `*`,
+    /// e.g. because it doesn't contain the version.
+    pub fn git_tag_patterns(&self) -> anyhow::Result<Option<Vec<String>>> {
+        let mut patterns: Vec<String> = vec![];
+        for package in self.publishable_packages() {
+            let mut tags = vec![self.git_tag(&package.name, "*")?];
+            tags.extend(self.extra_git_tags(&package.name, "*")?);
+            for tag in tags {
+                if tag.matches('*').count() != 1 {
+                    debug!(
+                        "tag template of {} renders to {tag:?}, which isn't a valid pattern",
+                        package.name
+                    );
+                    return Ok(None);
+                }
+                if !patterns.contains(&tag) {
+                    patterns.push(tag);
+                }
+            }
+        }
+        Ok(Some(patterns))
+    }
+
+    /// Fetch the tags of the releases of the project from the remote of `repo`.
+    /// Only the tags matching [`Project::git_tag_patterns`] are fetched,
+    /// which is faster than fetching all the tags in repositories with many tags.
+    pub(crate) fn fetch_release_tags(&self, repo: &Repo) -> anyhow::Result<()> {
+        match self.git_tag_patterns()? {
+            Some(patterns) if !patterns.is_empty() => repo.fetch_tags_matching(&patterns),
+            _ => repo.fetch_tags(),
+        }
     }
 
     pub fn release_name(&self, package_name: &str, version: &str) -> anyhow::Result<String> {
@@ -202,6 +330,10 @@ impl Project {
         let release_metadata = self.release_metadata.get(package_name);
 
         let (template_name, template) = match field {
+            TemplateField::GitTagName => (
+                "tag_name",
+                release_metadata.and_then(|m| m.tag_name_template.clone()),
+            ),
             TemplateField::ReleaseName => (
                 "release_name",
                 release_metadata.and_then(|m| m.release_name_template.clone()),
@@ -209,17 +341,25 @@ impl Project {
         };
 
         let template = template.unwrap_or({
-            if self.contains_multiple_pub_packages {
+            if self.contains_multiple_pub_packages || self.version_mode.is_independent() {
                 format!("{}-v{}", tera_var(PACKAGE_VAR), tera_var(VERSION_VAR))
             } else {
                 format!("v{}", tera_var(VERSION_VAR))
             }
         });
 
-        let context = tera_context(package_name, version);
+        let context = self.template_context(package_name, version);
         crate::tera::render_template(&template, &context, template_name)
     }
 
+    fn template_context(&self, package_name: &str, version: &str) -> tera::Context {
+        let mut context = tera_context(package_name, version);
+        if let Some(branch) = &self.branch {
+            context.insert(BRANCH_VAR, branch);
+        }
+        context
+    }
+
     pub fn cargo_lock_path(&self) -> Utf8PathBuf {
         self.manifest_dir.join("Cargo.lock")
     }
@@ -274,6 +414,14 @@ See https://doc.rust-lang.org/cargo/reference/manifest.html\n",
     }
 }
 
+/// Version contained in a tag like `v1.2.3` or `my-package-v1.2.3`, or the version itself.
+pub(crate) fn version_from_tag(tag: &str) -> Option<Version> {
+    Version::parse(tag).ok().or_else(|| {
+        tag.match_indices('v')
+            .find_map(|(i, _)| Version::parse(&tag[i + 1..]).ok())
+    })
+}
+
 fn ordered_packages(packages: &[Package]) -> anyhow::Result<Vec<Package>> {
     let packages_refs: Vec<&Package> = packages.iter().collect();
     let ordered = release_order(&packages_refs)
@@ -377,6 +525,19 @@ mod tests {
     use super::*;
     use cargo_utils::get_manifest_metadata;
 
+    #[test]
+    fn version_is_read_from_tag() {
+        let v = |s: &str| Some(Version::parse(s).unwrap());
+        assert_eq!(version_from_tag("1.2.3"), v("1.2.3"));
+        assert_eq!(version_from_tag("v1.2.3"), v("1.2.3"));
+        assert_eq!(version_from_tag("my-package-v0.4.0"), v("0.4.0"));
+        assert_eq!(version_from_tag("my-pkg-v1.2.3-dev.1"), v("1.2.3-dev.1"));
+        assert_eq!(version_from_tag("dev-v0.1.0"), v("0.1.0"));
+        assert_eq!(version_from_tag("HEAD"), None);
+        assert_eq!(version_from_tag("main"), None);
+        assert_eq!(version_from_tag("latest"), None);
+    }
+
     struct ReleaseMetadataBuilderStub {
         release: bool,
         tag_name: Option<String>,
@@ -398,6 +559,7 @@ mod tests {
             self.release.then(|| ReleaseMetadata {
                 tag_name_template: self.tag_name.clone(),
                 release_name_template: self.release_name.clone(),
+                extra_tag_name_templates: vec![],
             })
         }
     }
@@ -485,10 +647,77 @@ mod tests {
         let local_manifest = Utf8Path::new("../../tests/fixtures/typo-in-overrides/Cargo.toml");
         let project = get_project(local_manifest, None, &HashSet::default(), true, None, None)
             .expect("Should ok");
-        let git_tag = project.git_tag("0.1.0").unwrap();
+        let git_tag = project.git_tag("typo_test", "0.1.0").unwrap();
         assert_eq!(git_tag, "v0.1.0");
     }
 
+    #[test]
+    fn project_tag_template_none_independent() {
+        let local_manifest = Utf8Path::new("../../tests/fixtures/typo-in-overrides/Cargo.toml");
+        let project = get_project(local_manifest, None, &HashSet::default(), true, None, None)
+            .expect("Should ok")
+            .with_version_mode(VersionMode::Independent);
+        let git_tag = project.git_tag("typo_test", "0.1.0").unwrap();
+        assert_eq!(git_tag, "typo_test-v0.1.0");
+    }
+
+    #[test]
+    fn project_tag_template_some_independent() {
+        let local_manifest = Utf8Path::new("../../tests/fixtures/typo-in-overrides/Cargo.toml");
+        let project = get_project(
+            local_manifest,
+            None,
+            &HashSet::default(),
+            true,
+            Some("prefix-{{ package }}-middle-{{ version }}-postfix".to_string()),
+            None,
+        )
+        .expect("Should ok")
+        .with_version_mode(VersionMode::Independent);
+        assert_eq!(
+            project.git_tag("typo_test", "0.1.0").unwrap(),
+            "prefix-typo_test-middle-0.1.0-postfix"
+        );
+    }
+
+    #[test]
+    fn tag_patterns_are_rendered_from_templates() {
+        let local_manifest = Utf8Path::new("../../tests/fixtures/typo-in-overrides/Cargo.toml");
+        let project = get_project(local_manifest, None, &HashSet::default(), true, None, None)
+            .expect("Should ok");
+        assert_eq!(
+            project.git_tag_patterns().unwrap(),
+            Some(vec!["v*".to_string()])
+        );
+
+        let project = get_project(
+            local_manifest,
+            None,
+            &HashSet::default(),
+            true,
+            Some("{{ package }}-v{{ version }}".to_string()),
+            None,
+        )
+        .expect("Should ok")
+        .with_version_mode(VersionMode::Independent);
+        assert_eq!(
+            project.git_tag_patterns().unwrap(),
+            Some(vec!["typo_test-v*".to_string()])
+        );
+
+        let project = get_project(
+            local_manifest,
+            None,
+            &HashSet::default(),
+            true,
+            Some("{{ package }}-latest".to_string()),
+            None,
+        )
+        .expect("Should ok")
+        .with_version_mode(VersionMode::Independent);
+        assert_eq!(project.git_tag_patterns().unwrap(), None);
+    }
+
     #[test]
     fn project_release_and_tag_template_some() {
         let local_manifest = Utf8Path::new("../../tests/fixtures/typo-in-overrides/Cargo.toml");
@@ -503,10 +732,58 @@ mod tests {
         .expect("Should ok");
         // With unified workspace versioning, git tags always use v{version} format
         // Custom tag templates are no longer supported (monorepo uses single version)
-        assert_eq!(project.git_tag("0.1.0").unwrap(), "v0.1.0");
+        assert_eq!(project.git_tag("typo_test", "0.1.0").unwrap(), "v0.1.0");
         assert_eq!(
             project.release_name("typo_test", "0.1.0").unwrap(),
             "release-prefix-typo_test-middle-0.1.0-postfix"
         );
     }
+
+    #[test]
+    fn project_extra_tags_are_rendered() {
+        struct ExtraTags;
+        impl ReleaseMetadataBuilder for ExtraTags {
+            fn get_release_metadata(&self, _package_name: &str) -> Option<ReleaseMetadata> {
+                Some(ReleaseMetadata {
+                    tag_name_template: None,
+                    release_name_template: None,
+                    extra_tag_name_templates: vec![
+                        "v{{ version }}".to_string(),
+                        "{{ package }}-v{{ version }}".to_string(),
+                    ],
+                })
+            }
+        }
+        let local_manifest = Utf8Path::new("../../tests/fixtures/typo-in-overrides/Cargo.toml");
+        let metadata = get_manifest_metadata(local_manifest).unwrap();
+        let project = Project::new(
+            local_manifest,
+            None,
+            &HashSet::default(),
+            &metadata,
+            &ExtraTags,
+        )
+        .unwrap()
+        .with_version_mode(VersionMode::Independent);
+        // The primary tag isn't repeated.
+        assert_eq!(
+            project.extra_git_tags("typo_test", "0.1.0").unwrap(),
+            ["v0.1.0"]
+        );
+    }
+
+    #[test]
+    fn project_tag_template_uses_branch() {
+        let local_manifest = Utf8Path::new("../../tests/fixtures/typo-in-overrides/Cargo.toml");
+        let project = get_project(local_manifest, None, &HashSet::default(), true, None, None)
+            .expect("Should ok")
+            .with_branch(Some("release/1.x".to_string()))
+            .with_workspace_tag_name(Some(
+                r#"v{{ version }}{% if branch != "main" %}-lts{% endif %}"#.to_string(),
+            ));
+        assert_eq!(project.git_tag("typo_test", "1.4.3").unwrap(), "v1.4.3-lts");
+
+        let project = project.with_branch(Some("main".to_string()));
+        assert_eq!(project.git_tag("typo_test", "2.0.0").unwrap(), "v2.0.0");
+    }
 }
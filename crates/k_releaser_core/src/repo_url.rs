@@ -37,6 +37,24 @@ impl RepoUrl {
         format!("https://{}/{}/{}", self.host, self.owner, self.name)
     }
 
+    /// Whether the remote is served over http(s), as opposed to ssh or the git protocol.
+    fn is_web_url(&self) -> bool {
+        matches!(self.scheme.as_str(), "http" | "https")
+    }
+
+    /// Root of the web server of the forge, e.g. `https://gitlab.example.com:8443`.
+    /// The web server of ssh remotes is assumed to be served over https on the same host.
+    /// The port of ssh urls is the port of the ssh server, so it's dropped.
+    fn web_root(&self) -> String {
+        if !self.is_web_url() {
+            return format!("https://{}", self.host);
+        }
+        match self.port {
+            Some(port) => format!("{}://{}:{port}", self.scheme, self.host),
+            None => format!("{}://{}", self.scheme, self.host),
+        }
+    }
+
     /// Get GitHub/Gitea release link
     pub fn git_release_link(&self, prev_tag: &str, new_tag: &str) -> String {
         let host = self.full_host();
@@ -55,27 +73,46 @@ impl RepoUrl {
     }
 
     pub fn gitea_api_url(&self) -> String {
-        let v1 = "api/v1/";
-        if let Some(port) = self.port {
-            format!("{}://{}:{}/{v1}", self.scheme, self.host, port)
-        } else {
-            format!("{}://{}/{v1}", self.scheme, self.host)
-        }
+        format!("{}/api/v1/", self.web_root())
     }
 
+    /// Url of the project in the GitLab API, e.g.
+    /// `https://gitlab.com/api/v4/projects/group%2Fsubgroup%2Fproject`.
+    ///
+    /// In GitLab CI, the root of the instance is read from `CI_SERVER_URL`, so that
+    /// self-managed instances served under a relative url (e.g. `https://example.com/gitlab`)
+    /// and on custom ports are supported, even if the remote is an ssh url.
     pub fn gitlab_api_url(&self) -> String {
-        let v4 = "api/v4/projects";
-        let prj_path = urlencoding::encode(self.path.strip_prefix('/').unwrap_or(&self.path));
-        let scheme = if self.scheme == "ssh" {
-            "https"
-        } else {
-            self.scheme.as_str()
+        let server_url = std::env::var("CI_SERVER_URL").ok();
+        self.gitlab_api_url_with_server(server_url.as_deref())
+    }
+
+    fn gitlab_api_url_with_server(&self, server_url: Option<&str>) -> String {
+        let path = self.path.trim_start_matches('/');
+        let server = server_url
+            .and_then(|url| url::Url::parse(url).ok())
+            .filter(|url| url.host_str() == Some(self.host.as_str()));
+        let (root, project_path) = match &server {
+            Some(server) => {
+                let relative_root = server.path().trim_matches('/');
+                // Https remotes contain the relative root, ssh remotes don't.
+                let project_path = if relative_root.is_empty() {
+                    path
+                } else {
+                    path.strip_prefix(relative_root)
+                        .and_then(|p| p.strip_prefix('/'))
+                        .unwrap_or(path)
+                };
+                (
+                    server.as_str().trim_end_matches('/').to_string(),
+                    project_path,
+                )
+            }
+            None => (self.web_root(), path),
         };
-        if let Some(port) = self.port {
-            format!("{scheme}://{}:{port}/{v4}/{prj_path}", self.host)
-        } else {
-            format!("{scheme}://{}/{v4}/{prj_path}", self.host)
-        }
+        // The project path is a single segment of the url, so its slashes are encoded.
+        let project_path = urlencoding::encode(project_path);
+        format!("{root}/api/v4/projects/{project_path}")
     }
 }
 
@@ -138,13 +175,73 @@ mod tests {
         let git_repo = RepoUrl::new("git@host.example.com:ab/cd/myproj.git").unwrap();
         assert_eq!(
             "https://host.example.com/api/v4/projects/ab%2Fcd%2Fmyproj",
-            git_repo.gitlab_api_url()
+            git_repo.gitlab_api_url_with_server(None)
         );
 
         let http_repo = RepoUrl::new("https://host.example.com/ab/cd/myproj.git").unwrap();
         assert_eq!(
             "https://host.example.com/api/v4/projects/ab%2Fcd%2Fmyproj",
-            http_repo.gitlab_api_url()
+            http_repo.gitlab_api_url_with_server(None)
+        );
+    }
+
+    #[test]
+    fn ssh_remotes_are_parsed() {
+        for url in [
+            "git@codeberg.org:owner/repo.git",
+            "ssh://git@codeberg.org/owner/repo.git",
+            "ssh://git@codeberg.org:2222/owner/repo",
+        ] {
+            let repo = RepoUrl::new(url).unwrap();
+            assert_eq!(repo.owner, "owner");
+            assert_eq!(repo.name, "repo");
+            assert_eq!(repo.full_host(), "https://codeberg.org/owner/repo");
+            assert_eq!(repo.gitea_api_url(), "https://codeberg.org/api/v1/");
+        }
+    }
+
+    #[test]
+    fn gitea_api_url_keeps_the_http_port() {
+        let repo = RepoUrl::new("http://localhost:3000/owner/repo").unwrap();
+        assert_eq!(repo.gitea_api_url(), "http://localhost:3000/api/v1/");
+    }
+
+    #[test]
+    fn gitlab_api_url_of_ssh_remote_ignores_the_ssh_port() {
+        let repo = RepoUrl::new("ssh://git@host.example.com:2222/group/sub/proj.git").unwrap();
+        assert_eq!(
+            "https://host.example.com/api/v4/projects/group%2Fsub%2Fproj",
+            repo.gitlab_api_url_with_server(None)
+        );
+    }
+
+    #[test]
+    fn gitlab_api_url_keeps_the_http_port() {
+        let repo = RepoUrl::new("https://host.example.com:8443/group/sub/proj").unwrap();
+        assert_eq!(
+            "https://host.example.com:8443/api/v4/projects/group%2Fsub%2Fproj",
+            repo.gitlab_api_url_with_server(None)
+        );
+    }
+
+    #[test]
+    fn gitlab_api_url_supports_relative_url_root() {
+        let server = Some("https://host.example.com:8443/gitlab");
+        let repo = RepoUrl::new("https://host.example.com:8443/gitlab/group/sub/proj").unwrap();
+        assert_eq!(
+            "https://host.example.com:8443/gitlab/api/v4/projects/group%2Fsub%2Fproj",
+            repo.gitlab_api_url_with_server(server)
+        );
+        let repo = RepoUrl::new("git@host.example.com:group/sub/proj.git").unwrap();
+        assert_eq!(
+            "https://host.example.com:8443/gitlab/api/v4/projects/group%2Fsub%2Fproj",
+            repo.gitlab_api_url_with_server(server)
+        );
+        // The server of another host is ignored.
+        let server = Some("https://other.example.com/gitlab");
+        assert_eq!(
+            "https://host.example.com/api/v4/projects/group%2Fsub%2Fproj",
+            repo.gitlab_api_url_with_server(server)
         );
     }
 }
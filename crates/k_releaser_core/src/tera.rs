@@ -7,6 +7,9 @@ pub const VERSION_VAR: &str = "version";
 pub const CHANGELOG_VAR: &str = "changelog";
 pub const REMOTE_VAR: &str = "remote";
 pub const RELEASES_VAR: &str = "releases";
+pub const BRANCH_VAR: &str = "branch";
+pub const TIMESTAMP_VAR: &str = "timestamp";
+pub const PACKAGES_VAR: &str = "packages";
 
 pub fn tera_var(var_name: &str) -> String {
     format!("{{{{ {var_name} }}}}")
@@ -0,0 +1,17 @@
+use k_releaser_core::{GitClient, GitForge, Gitea, MergeMethod, RepoUrl};
+use secrecy::SecretString;
+
+use crate::helpers::gitea_mock_server::GiteaMockServer;
+
+#[tokio::test]
+async fn gitea_release_pr_is_merged_when_checks_succeed() {
+    let server = GiteaMockServer::start("owner", "repo").await;
+    server.expect_scheduled_merge(3, "squash").await;
+    let url = RepoUrl::new(&format!("{}owner/repo", server.base_url())).unwrap();
+    let gitea = Gitea::new(url, SecretString::from("token".to_string())).unwrap();
+    let client = GitClient::new(GitForge::Gitea(gitea)).unwrap();
+    client
+        .enable_auto_merge(3, MergeMethod::Squash)
+        .await
+        .unwrap();
+}
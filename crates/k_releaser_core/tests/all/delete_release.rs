@@ -0,0 +1,29 @@
+use k_releaser_core::{GitClient, GitForge, Gitea, RepoUrl};
+use secrecy::SecretString;
+
+use crate::helpers::gitea_mock_server::GiteaMockServer;
+
+fn gitea_client(server: &GiteaMockServer) -> GitClient {
+    let url = RepoUrl::new(&format!("{}owner/repo", server.base_url())).unwrap();
+    let gitea = Gitea::new(url, SecretString::from("token".to_string())).unwrap();
+    GitClient::new(GitForge::Gitea(gitea)).unwrap()
+}
+
+#[tokio::test]
+async fn gitea_draft_release_is_deleted() {
+    let server = GiteaMockServer::start("owner", "repo").await;
+    server
+        .releases(&[(7, "v1.1.0", true), (5, "v1.0.0", false)])
+        .await;
+    server.expect_deleted_release(7).await;
+    let client = gitea_client(&server);
+    assert!(client.delete_release("v1.1.0").await.unwrap());
+}
+
+#[tokio::test]
+async fn missing_release_is_not_deleted() {
+    let server = GiteaMockServer::start("owner", "repo").await;
+    server.releases(&[(5, "v1.0.0", false)]).await;
+    let client = gitea_client(&server);
+    assert!(!client.delete_release("v1.1.0").await.unwrap());
+}
@@ -53,6 +53,7 @@ impl ComparisonTest {
             .with_changelog_req(ChangelogRequest {
                 release_date: NaiveDate::from_ymd_opt(2015, 5, 15),
                 changelog_config: None,
+                group_by_package: false,
             })
             .with_registry_manifest_path(&self.registry_project_manfifest())
             .unwrap()
@@ -1,7 +1,7 @@
 use url::Url;
 use wiremock::{
     Mock, MockServer, ResponseTemplate,
-    matchers::{method, path},
+    matchers::{body_partial_json, method, path},
 };
 
 pub struct GiteaMockServer {
@@ -47,6 +47,53 @@ impl GiteaMockServer {
             .await;
     }
 
+    /// Expect the merge of the PR `pr_number` to be scheduled for when its checks succeed.
+    pub async fn expect_scheduled_merge(&self, pr_number: u64, merge_style: &str) {
+        Mock::given(method("POST"))
+            .and(path(format!("{}/{pr_number}/merge", self.pulls_path())))
+            .and(body_partial_json(serde_json::json!({
+                "Do": merge_style,
+                "merge_when_checks_succeed": true,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Return the given releases, as `(id, tag, draft)`, when listing the releases.
+    pub async fn releases(&self, releases: &[(u64, &str, bool)]) {
+        let releases: Vec<_> = releases
+            .iter()
+            .map(|(id, tag, draft)| {
+                serde_json::json!({
+                    "id": id,
+                    "tag_name": tag,
+                    "draft": draft,
+                })
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path(self.releases_path()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(releases))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Expect the release `release_id` to be deleted.
+    pub async fn expect_deleted_release(&self, release_id: u64) {
+        Mock::given(method("DELETE"))
+            .and(path(format!("{}/{release_id}", self.releases_path())))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    fn releases_path(&self) -> String {
+        format!("/api/v1/repos/{}/{}/releases", self.owner, self.repo)
+    }
+
     fn pulls_path(&self) -> String {
         format!("/api/v1/repos/{}/{}/pulls", self.owner, self.repo)
     }
@@ -33,14 +33,24 @@ impl GitHubMockServer {
             .await;
     }
 
-    /// Return an empty list of PRs.
+    /// Return an empty list of opened PRs.
+    /// PRs are queried with the GraphQL API.
     pub async fn _no_prs(&self) {
         let no_prs = {
-            let empty_body = serde_json::Value::Array(vec![]);
+            let empty_body = serde_json::json!({
+                "data": {
+                    "repository": {
+                        "pullRequests": {
+                            "nodes": [],
+                            "pageInfo": {"hasNextPage": false, "endCursor": null}
+                        }
+                    }
+                }
+            });
             ResponseTemplate::new(200).set_body_json(empty_body)
         };
-        Mock::given(method("GET"))
-            .and(path(self.pulls_path()))
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
             .respond_with(no_prs)
             .expect(1)
             .mount(&self.server)
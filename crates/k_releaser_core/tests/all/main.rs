@@ -1,3 +1,5 @@
+mod auto_merge;
+mod delete_release;
 mod helpers;
 mod release_pr;
 mod update;
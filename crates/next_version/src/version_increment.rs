@@ -4,7 +4,7 @@ use semver::Version;
 
 use crate::{NextVersion, VersionUpdater};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VersionIncrement {
     Major,
     Minor,